@@ -0,0 +1,112 @@
+import crypto from "crypto";
+import fs from "fs";
+import path from "path";
+import { fileURLToPath } from "url";
+
+// Pins the SHA-256 of every bundled model in the catalog so downloads are
+// verified. Each model is downloaded from its primary url, and every mirror
+// must serve the same bytes. Needs network access and several GB of transfer.
+//
+//   bun scripts/pin-model-checksums.ts          download, hash and pin
+//   bun scripts/pin-model-checksums.ts --check  fail if a checksum is missing
+
+const __dirname = path.dirname(fileURLToPath(import.meta.url));
+
+const CATALOG_PATH = path.join(
+  __dirname,
+  "..",
+  "src-tauri",
+  "resources",
+  "model_catalog.json",
+);
+
+interface CatalogModel {
+  id: string;
+  url?: string;
+  mirrors?: string[];
+  sha256?: string;
+}
+
+interface Catalog {
+  models: CatalogModel[];
+}
+
+async function sha256OfUrl(url: string): Promise<string> {
+  const response = await fetch(url);
+  if (!response.ok || !response.body) {
+    throw new Error(`${url}: HTTP ${response.status}`);
+  }
+  const hash = crypto.createHash("sha256");
+  for await (const chunk of response.body) {
+    hash.update(chunk);
+  }
+  return hash.digest("hex");
+}
+
+// Sets the model's "sha256" line in place so the rest of the hand-formatted
+// catalog is left untouched.
+function pinChecksum(source: string, id: string, sha256: string): string {
+  const start = source.indexOf(`"id": "${id}"`);
+  const end = source.indexOf("}", start);
+  if (start === -1 || end === -1) {
+    throw new Error(`Model ${id} not found in catalog`);
+  }
+  let block = source.slice(start, end);
+  if (/"sha256": "[^"]*"/.test(block)) {
+    block = block.replace(/"sha256": "[^"]*"/, `"sha256": "${sha256}"`);
+  } else {
+    block = block.replace(
+      /("url": "[^"]*",\n)(\s*)/,
+      `$1$2"sha256": "${sha256}",\n$2`,
+    );
+  }
+  return source.slice(0, start) + block + source.slice(end);
+}
+
+async function main() {
+  const checkOnly = process.argv.includes("--check");
+  let source = fs.readFileSync(CATALOG_PATH, "utf8");
+  const catalog = JSON.parse(source) as Catalog;
+  const downloadable = catalog.models.filter((model) => model.url);
+
+  if (checkOnly) {
+    const missing = downloadable.filter((model) => !model.sha256);
+    for (const model of missing) {
+      console.log(`✗ ${model.id}: no sha256`);
+    }
+    if (missing.length > 0) {
+      console.log("Run `bun scripts/pin-model-checksums.ts` to pin them.");
+      process.exit(1);
+    }
+    console.log(`✓ All ${downloadable.length} models have a sha256`);
+    return;
+  }
+
+  for (const model of downloadable) {
+    console.log(`Hashing ${model.id} from ${model.url}`);
+    const sha256 = await sha256OfUrl(model.url!);
+    for (const mirror of model.mirrors ?? []) {
+      console.log(`  checking mirror ${mirror}`);
+      const mirrored = await sha256OfUrl(mirror);
+      if (mirrored !== sha256) {
+        throw new Error(
+          `${model.id}: mirror ${mirror} serves ${mirrored}, expected ${sha256}`,
+        );
+      }
+    }
+    if (model.sha256 && model.sha256.toLowerCase() !== sha256) {
+      console.log(`  ! pinned ${model.sha256} changes to ${sha256}`);
+    }
+    console.log(`  ${sha256}`);
+    source = pinChecksum(source, model.id, sha256);
+  }
+
+  JSON.parse(source);
+  fs.writeFileSync(CATALOG_PATH, source);
+  console.log(`Pinned ${downloadable.length} checksums in ${CATALOG_PATH}`);
+}
+
+main().catch((error) => {
+  console.error(error instanceof Error ? error.message : error);
+  process.exit(1);
+});
@@ -5,11 +5,12 @@ use crate::audio_feedback::{play_feedback_sound, play_feedback_sound_blocking, S
 use crate::growth::{self, FeatureName};
 use crate::managers::audio::{
     AudioRecordingManager, RecordingPrearmSource, RecordingStartFailure, RecordingStartOutcome,
+    StoppedRecording,
 };
-use crate::managers::history::{HistoryEntry, HistoryManager};
+use crate::managers::history::{HistoryEntry, HistoryManager, RecordingAudio};
 #[cfg(all(target_os = "macos", target_arch = "aarch64"))]
 use crate::managers::mlx::MlxModelManager;
-use crate::managers::transcription::TranscriptionManager;
+use crate::managers::transcription::{TranscriptionManager, TranscriptionOutput};
 #[cfg(all(target_os = "macos", target_arch = "aarch64"))]
 use crate::settings::LOCAL_MLX_PROVIDER_ID;
 use crate::settings::{get_settings, AppSettings, APPLE_INTELLIGENCE_PROVIDER_ID};
@@ -655,6 +656,30 @@ fn ensure_microphone_permission_or_notify(app: &AppHandle) -> bool {
     true
 }
 
+/// Diarize a saved recording and attach the speaker turns to its history
+/// entry. Runs after the paste so dictation never waits on diarization; the
+/// recording (and its spool, if any) is dropped once the turns are stored.
+fn store_speaker_turns_in_background(
+    tm: Arc<TranscriptionManager>,
+    hm: Arc<HistoryManager>,
+    entry_id: i64,
+    audio: RecordingAudio,
+    settings: AppSettings,
+) {
+    tauri::async_runtime::spawn_blocking(move || {
+        let turns = tm.diarize_recording(&audio, &settings);
+        if turns.is_empty() {
+            return;
+        }
+        if let Err(e) = hm.set_speaker_turns(entry_id, &turns) {
+            error!(
+                "Failed to store speaker turns for history entry {}: {}",
+                entry_id, e
+            );
+        }
+    });
+}
+
 fn emit_transcription_delivery_events(app: &AppHandle, source_action: &str) {
     let _ = app.emit("transcription-inserted", source_action.to_string());
 
@@ -764,6 +789,9 @@ impl ShortcutAction for TranscribeAction {
             "Recording startup entered prepare state"
         );
 
+        // Resolve the mode and app profile before any overlay can take focus.
+        crate::app_profiles::begin_session(app, binding_id, &session_id);
+
         let pre_ready_phase_for_overlay = Arc::clone(&pre_ready_phase);
         let app_for_overlay_check = app.clone();
         let session_id_for_overlay = session_id.clone();
@@ -819,7 +847,7 @@ impl ShortcutAction for TranscribeAction {
             change_tray_icon(app, TrayIconState::Recording);
 
             // Get the microphone mode to determine audio feedback timing
-            let settings = get_settings(app);
+            let settings = crate::app_profiles::session_settings(app);
             let is_always_on = settings.always_on_microphone;
             let selected_model = settings.selected_model.clone();
             let tm = app.state::<Arc<TranscriptionManager>>();
@@ -1046,10 +1074,16 @@ impl ShortcutAction for TranscribeAction {
                 }
             }
 
+            if !recording_started {
+                crate::app_profiles::end_session(&session_id);
+            }
+
             if recording_started {
                 // Dynamically register the cancel shortcut in a separate task to avoid deadlock
                 shortcut::register_cancel_shortcut(app);
-            } else if binding_id == "transcribe_handsfree" {
+            } else if binding_id == "transcribe_handsfree"
+                || crate::modes::is_mode_binding(binding_id)
+            {
                 // Keep hands-free toggle state consistent when start is rejected
                 // (e.g. maintenance mode flipped on between prepare and stream start).
                 let toggle_state_manager = app.state::<ManagedToggleState>();
@@ -1075,6 +1109,27 @@ impl ShortcutAction for TranscribeAction {
     }
 
     fn stop(&self, app: &AppHandle, binding_id: &str, shortcut_str: &str) {
+        self.finish_recording(app, binding_id, shortcut_str, None);
+    }
+}
+
+/// Audio that was not recorded through start/stop, e.g. taken from the
+/// always-on recent-audio buffer, handed straight to the transcribe pipeline.
+struct CapturedRecording {
+    session_id: String,
+    recording: StoppedRecording,
+}
+
+impl TranscribeAction {
+    /// Stops the active recording, or takes `captured` instead, then
+    /// transcribes, saves and pastes it.
+    fn finish_recording(
+        &self,
+        app: &AppHandle,
+        binding_id: &str,
+        shortcut_str: &str,
+        captured: Option<CapturedRecording>,
+    ) {
         // We delay unregistering cancel shortcut until after transcription or cancellation
         // shortcut::unregister_cancel_shortcut(app);
 
@@ -1097,9 +1152,12 @@ impl ShortcutAction for TranscribeAction {
         play_feedback_sound(app, SoundType::Stop);
 
         // Get session_id BEFORE stopping (it will be cleared after stop)
-        let session_id = rm
-            .get_current_session_id()
-            .unwrap_or_else(|| "unknown".to_string());
+        let session_id = match &captured {
+            Some(captured) => captured.session_id.clone(),
+            None => rm
+                .get_current_session_id()
+                .unwrap_or_else(|| "unknown".to_string()),
+        };
 
         let binding_id = binding_id.to_string(); // Clone binding_id for the async task
         let session_id_for_task = session_id.clone();
@@ -1110,16 +1168,24 @@ impl ShortcutAction for TranscribeAction {
             async move {
                 let _guard = session_span.enter();
                 let _finish_guard = FinishGuard(ah.clone());
+                let _profile_guard =
+                    crate::app_profiles::SessionProfileGuard(session_id_for_task.clone());
                 let _stop_transition_guard = crate::undo::StopTransitionGuard::new(&ah);
                 let binding_id = binding_id.clone();
-                let selected_model = get_settings(&ah).selected_model;
+                let selected_model =
+                    crate::app_profiles::settings_for_session(&ah, &session_id_for_task)
+                        .selected_model;
                 debug!(
                     "Starting async transcription task for binding: {}",
                     binding_id
                 );
 
                 let stop_recording_time = Instant::now();
-                if let Some(stopped_recording) = rm.stop_recording(&binding_id) {
+                let stopped_recording = match captured {
+                    Some(captured) => Some(captured.recording),
+                    None => rm.stop_recording(&binding_id),
+                };
+                if let Some(stopped_recording) = stopped_recording {
                     debug!(
                         "Recording stopped and samples retrieved in {:?}, sample count: {}",
                         stop_recording_time.elapsed(),
@@ -1139,7 +1205,13 @@ impl ShortcutAction for TranscribeAction {
                         );
                     }
 
-                    if stopped_recording.samples_for_transcription.is_empty() {
+                    let has_long_form_audio = tm
+                        .long_form_speech_ms()
+                        .is_some_and(|speech_ms| speech_ms > 0);
+                    if stopped_recording.samples_for_transcription.is_empty()
+                        && !has_long_form_audio
+                    {
+                        tm.cancel_long_form_session();
                         warn!(
                             session = %session_id_for_task,
                             binding = binding_id,
@@ -1178,11 +1250,21 @@ impl ShortcutAction for TranscribeAction {
                         }
 
                     let transcription_time = Instant::now();
-                    let samples_for_history = stopped_recording.samples_for_transcription.clone();
                     let speech_duration_ms = stopped_recording.speech_duration_ms;
                     let recording_duration_ms = stopped_recording.recording_duration_ms;
-                    match tm.transcribe(stopped_recording.samples_for_transcription) {
-                        Ok((transcription, filler_words_removed)) => {
+                    match tm.transcribe_recording(
+                        stopped_recording.samples_for_transcription,
+                        stopped_recording.captured,
+                    ) {
+                        Ok((
+                            TranscriptionOutput {
+                                text: transcription,
+                                filler_words_removed,
+                                timings,
+                                bias_terms,
+                            },
+                            audio_for_history,
+                        )) => {
                             // Check if the session was cancelled during transcription (from llm)
                             if !tm.is_session_active(&session_id_for_task) {
                                 debug!(
@@ -1226,7 +1308,10 @@ impl ShortcutAction for TranscribeAction {
                             );
 
                             if !transcription.trim().is_empty() {
-                                let settings = get_settings(&ah);
+                                let settings = crate::app_profiles::settings_for_session(
+                                    &ah,
+                                    &session_id_for_task,
+                                );
                                 let mut final_text = transcription.clone();
                                 let mut post_processed_text: Option<String> = None;
                                 let mut post_process_prompt: Option<String> = None;
@@ -1287,56 +1372,68 @@ impl ShortcutAction for TranscribeAction {
                                 let transcription_for_history = transcription.clone();
                                 let filler_count = filler_words_removed as i64;
                                 let final_text_for_paste = final_text;
+                                let diarization_settings =
+                                    settings.speaker_diarization_enabled.then(|| settings.clone());
                                 // Keep persistence + paste in this stop task so session-active
                                 // state covers the full write lifecycle. Use the blocking pool
                                 // because save_transcription performs filesystem/SQLite I/O.
+                                // Diarization runs after the paste; see below.
                                 let hm_for_save = Arc::clone(&hm);
-                                let saved_transcription = match tauri::async_runtime::spawn_blocking(
-                                    move || {
-                                        hm_for_save.save_transcription(
-                                            samples_for_history,
+                                let (saved_transcription, audio_for_history) =
+                                    match tauri::async_runtime::spawn_blocking(move || {
+                                        let saved = hm_for_save.save_transcription(
+                                            &audio_for_history,
                                             transcription_for_history,
                                             post_processed_text,
                                             post_process_prompt,
                                             recording_duration_ms,
                                             speech_duration_ms,
                                             filler_count,
-                                        )
-                                    },
-                                )
-                                .await
-                                {
-                                    Ok(Ok(saved)) => {
-                                        crate::undo::register_stats_contribution(
-                                            &ah,
-                                            stats_token,
-                                            source_action,
-                                            saved.contribution.clone(),
-                                        );
-                                        Some(saved)
-                                    }
-                                    Ok(Err(e)) => {
-                                        error!("Failed to save transcription to history: {}", e);
-                                        capture_handled_message(
-                                            &META_SAVE_HISTORY_FAILURE,
-                                            &format!("Failed to save transcription to history: {e}"),
-                                        );
-                                        None
-                                    }
-                                    Err(e) => {
-                                        error!(
-                                            "Failed to save transcription to history: blocking task join failure: {}",
-                                            e
+                                            timings,
+                                            bias_terms,
                                         );
-                                        capture_handled_message(
-                                            &META_SAVE_HISTORY_FAILURE,
-                                            &format!(
-                                                "Failed to save transcription to history: blocking task join failure: {e}"
-                                            ),
-                                        );
-                                        None
-                                    }
-                                };
+                                        (saved, audio_for_history)
+                                    })
+                                    .await
+                                    {
+                                        Ok((Ok(saved), audio)) => {
+                                            crate::undo::register_stats_contribution(
+                                                &ah,
+                                                stats_token,
+                                                source_action,
+                                                saved.contribution.clone(),
+                                            );
+                                            (Some(saved), Some(audio))
+                                        }
+                                        Ok((Err(e), _)) => {
+                                            error!(
+                                                "Failed to save transcription to history: {}",
+                                                e
+                                            );
+                                            capture_handled_message(
+                                                &META_SAVE_HISTORY_FAILURE,
+                                                &format!(
+                                                    "Failed to save transcription to history: {e}"
+                                                ),
+                                            );
+                                            (None, None)
+                                        }
+                                        Err(e) => {
+                                            error!(
+                                                "Failed to save transcription to history: blocking task join failure: {}",
+                                                e
+                                            );
+                                            capture_handled_message(
+                                                &META_SAVE_HISTORY_FAILURE,
+                                                &format!(
+                                                    "Failed to save transcription to history: blocking task join failure: {e}"
+                                                ),
+                                            );
+                                            (None, None)
+                                        }
+                                    };
+                                let saved_entry_id =
+                                    saved_transcription.as_ref().map(|saved| saved.entry_id);
 
                                 let paste_time = Instant::now();
                                 let (paste_tx, paste_rx) = tokio::sync::oneshot::channel();
@@ -1344,11 +1441,13 @@ impl ShortcutAction for TranscribeAction {
                                 let app_for_main_thread = app_for_paste_task.clone();
                                 let app_for_undo_slot = app_for_paste_task.clone();
                                 let suggestion_for_undo = suggestion_text.clone();
+                                let session_id_for_paste = session_id_for_task.clone();
                                 let run_main_thread_result =
                                     app_for_paste_task.run_on_main_thread(move || {
-                                        let paste_result = match utils::paste(
+                                        let paste_result = match utils::paste_transcript(
                                             final_text_for_paste,
                                             app_for_main_thread.clone(),
+                                            &session_id_for_paste,
                                         ) {
                                             Ok(result) => {
                                                 debug!(
@@ -1463,6 +1562,18 @@ impl ShortcutAction for TranscribeAction {
                                         );
                                     }
                                 }
+
+                                if let (Some(entry_id), Some(audio), Some(settings)) =
+                                    (saved_entry_id, audio_for_history, diarization_settings)
+                                {
+                                    store_speaker_turns_in_background(
+                                        Arc::clone(&tm),
+                                        Arc::clone(&hm),
+                                        entry_id,
+                                        audio,
+                                        settings,
+                                    );
+                                }
                             } else {
                                 utils::hide_overlay_after_transcription(&ah);
                                 change_tray_icon(&ah, TrayIconState::Idle);
@@ -1520,6 +1631,67 @@ impl ShortcutAction for TranscribeAction {
     }
 }
 
+// Transcribe Recent Audio Action
+struct TranscribeRecentAudioAction {
+    post_process: bool,
+}
+
+impl ShortcutAction for TranscribeRecentAudioAction {
+    fn start(&self, app: &AppHandle, binding_id: &str, shortcut_str: &str) {
+        if !crate::backup_restore::ensure_transcription_start_allowed(app) {
+            debug!(
+                "Skipped recent audio transcription for '{}' because backup/restore maintenance mode is active",
+                binding_id
+            );
+            return;
+        }
+
+        // The coordinator calls `stop` once no other recording or
+        // transcription is in flight.
+        match app.try_state::<TranscriptionCoordinator>() {
+            Some(coordinator) => coordinator.request_recent_audio(binding_id, shortcut_str),
+            None => warn!("TranscriptionCoordinator not initialized"),
+        }
+    }
+
+    fn stop(&self, app: &AppHandle, binding_id: &str, shortcut_str: &str) {
+        let app = app.clone();
+        let binding_id = binding_id.to_string();
+        let shortcut_str = shortcut_str.to_string();
+        let post_process = self.post_process;
+        // Taking the buffer runs VAD over up to a minute of audio; keep that
+        // off the coordinator thread.
+        std::thread::spawn(move || {
+            let rm = app.state::<Arc<AudioRecordingManager>>();
+            let Some(recording) = rm.take_recent_audio() else {
+                debug!("No recent speech to transcribe for '{}'", binding_id);
+                // Nothing to process; hand the pipeline back to the coordinator.
+                drop(FinishGuard(app.clone()));
+                return;
+            };
+
+            let session_id = uuid::Uuid::new_v4().to_string()[..8].to_string();
+            info!(
+                session = %session_id,
+                binding = binding_id.as_str(),
+                speech_ms = recording.speech_duration_ms,
+                event_code = "recent_audio_transcription_started",
+                "Transcribing recent audio"
+            );
+            crate::app_profiles::begin_session(&app, &binding_id, &session_id);
+            TranscribeAction { post_process }.finish_recording(
+                &app,
+                &binding_id,
+                &shortcut_str,
+                Some(CapturedRecording {
+                    session_id,
+                    recording,
+                }),
+            );
+        });
+    }
+}
+
 // Cancel Action
 struct CancelAction;
 
@@ -1590,7 +1762,7 @@ impl ShortcutAction for PasteLastTranscriptAction {
         tauri::async_runtime::spawn(async move {
             let text = if let Some(history_manager) = app_clone.try_state::<Arc<HistoryManager>>() {
                 let manager = history_manager.inner().clone();
-                match manager.get_history_entries(1, 0, None, false, None).await {
+                match manager.get_history_entries(1, 0, None, false, None, None).await {
                     Ok(entries) => {
                         if let Some(latest) = entries.first() {
                             let (text, suggestion_text) = select_text_for_paste_last(latest);
@@ -1709,7 +1881,7 @@ impl ShortcutAction for RefineLastTranscriptAction {
             let latest_entry =
                 if let Some(history_manager) = app_clone.try_state::<Arc<HistoryManager>>() {
                     let manager = history_manager.inner().clone();
-                    match manager.get_history_entries(1, 0, None, false, None).await {
+                    match manager.get_history_entries(1, 0, None, false, None, None).await {
                         Ok(entries) => {
                             if let Some(latest) = entries.first() {
                                 Some((manager, latest.clone()))
@@ -2002,6 +2174,12 @@ pub static ACTION_MAP: LazyLock<HashMap<String, Arc<dyn ShortcutAction>>> = Lazy
             post_process: false,
         }) as Arc<dyn ShortcutAction>,
     );
+    map.insert(
+        "transcribe_recent_audio".to_string(),
+        Arc::new(TranscribeRecentAudioAction {
+            post_process: false,
+        }) as Arc<dyn ShortcutAction>,
+    );
     map.insert(
         "cancel".to_string(),
         Arc::new(CancelAction) as Arc<dyn ShortcutAction>,
@@ -2029,6 +2207,16 @@ pub static ACTION_MAP: LazyLock<HashMap<String, Arc<dyn ShortcutAction>>> = Lazy
     map
 });
 
+/// Looks up the action for a binding. Mode shortcuts share the hands-free
+/// transcribe action; the mode itself is resolved from the binding id when
+/// the recording session begins.
+pub fn action_for_binding(binding_id: &str) -> Option<&'static Arc<dyn ShortcutAction>> {
+    if crate::modes::is_mode_binding(binding_id) {
+        return ACTION_MAP.get("transcribe_handsfree");
+    }
+    ACTION_MAP.get(binding_id)
+}
+
 #[cfg(test)]
 mod tests {
     use super::{
@@ -2067,6 +2255,11 @@ mod tests {
             duration_ms: 0,
             file_path: "/tmp/codictate-1.wav".to_string(),
             audio_file_exists: true,
+            timings: Vec::new(),
+            tags: Vec::new(),
+            speaker_turns: Vec::new(),
+            speakers: Vec::new(),
+            bias_terms: Vec::new(),
         }
     }
 
@@ -0,0 +1,539 @@
+//! Per-application profiles.
+//!
+//! When a recording starts, the frontmost application is detected and matched
+//! against the configured [`AppProfile`]s. The first enabled match overlays its
+//! settings for that recording session only; the persisted settings are never
+//! modified. The session also captures the [`Mode`](crate::settings::Mode) in
+//! effect: the active mode is applied beneath the profile, while a mode picked
+//! through its own shortcut is applied on top of it. Code on the recording →
+//! transcription → paste path reads settings through [`session_settings`] so it
+//! picks up the active overlay. Overlays are kept per session id, so a session
+//! that is still transcribing keeps its own when the next recording starts.
+
+use crate::settings::{get_settings, AppProfile, AppProfileOverrides, AppSettings, ModeSettings};
+use once_cell::sync::Lazy;
+#[cfg(target_os = "linux")]
+use std::sync::mpsc;
+use std::sync::Mutex;
+#[cfg(target_os = "linux")]
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+#[cfg(target_os = "linux")]
+use tracing::warn;
+use tracing::{debug, info};
+
+#[derive(Debug, Clone)]
+struct SessionOverlay {
+    /// Active mode at recording start, applied before the profile.
+    base_mode: Option<ModeSettings>,
+    profile: Option<AppProfileOverrides>,
+    /// Mode chosen through its dedicated shortcut, applied after the profile.
+    shortcut_mode: Option<ModeSettings>,
+}
+
+impl SessionOverlay {
+    fn is_empty(&self) -> bool {
+        self.base_mode.is_none() && self.profile.is_none() && self.shortcut_mode.is_none()
+    }
+
+    fn apply_to(&self, settings: &mut AppSettings) {
+        if let Some(mode) = &self.base_mode {
+            mode.apply_to(settings);
+        }
+        if let Some(profile) = &self.profile {
+            profile.apply_to(settings);
+        }
+        if let Some(mode) = &self.shortcut_mode {
+            mode.apply_to(settings);
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ActiveProfile {
+    session_id: String,
+    label: String,
+    overlay: SessionOverlay,
+}
+
+/// Overlays of the sessions that have not ended, oldest first.
+static ACTIVE_PROFILES: Lazy<Mutex<Vec<ActiveProfile>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// Returns the first enabled profile whose identifiers match `app_identifier`.
+pub fn match_profile<'a>(
+    profiles: &'a [AppProfile],
+    app_identifier: &str,
+) -> Option<&'a AppProfile> {
+    let needle = app_identifier.trim();
+    if needle.is_empty() {
+        return None;
+    }
+
+    profiles
+        .iter()
+        .filter(|profile| profile.enabled)
+        .find(|profile| {
+            profile
+                .app_identifiers
+                .iter()
+                .map(|id| id.trim())
+                .any(|id| !id.is_empty() && id.eq_ignore_ascii_case(needle))
+        })
+}
+
+fn frontmost_profile(settings: &AppSettings) -> Option<&AppProfile> {
+    if !settings.app_profiles.iter().any(|profile| profile.enabled) {
+        return None;
+    }
+
+    let identifiers = detect_frontmost_app();
+    if identifiers.is_empty() {
+        debug!("App profiles: could not detect the frontmost application");
+        return None;
+    }
+
+    let profile = identifiers
+        .iter()
+        .find_map(|identifier| match_profile(&settings.app_profiles, identifier))?;
+    info!(
+        "App profile '{}' active for this session (frontmost app: {})",
+        profile.name,
+        identifiers.join(", ")
+    );
+    Some(profile)
+}
+
+/// Resolves the mode and application profile for the recording session
+/// `session_id` that `binding_id` is starting. End it with [`end_session`].
+pub fn begin_session(app: &AppHandle, binding_id: &str, session_id: &str) {
+    end_session(session_id);
+
+    let settings = get_settings(app);
+    let shortcut_mode = crate::modes::mode_for_binding(&settings, binding_id);
+    let base_mode = if shortcut_mode.is_none() {
+        settings.active_mode()
+    } else {
+        None
+    };
+    let profile = frontmost_profile(&settings);
+
+    let overlay = SessionOverlay {
+        base_mode: base_mode.map(|mode| mode.settings.clone()),
+        profile: profile.map(|profile| profile.overrides.clone()),
+        shortcut_mode: shortcut_mode.map(|mode| mode.settings.clone()),
+    };
+    if overlay.is_empty() {
+        return;
+    }
+
+    let label = shortcut_mode
+        .or(base_mode)
+        .map(|mode| format!("mode '{}'", mode.name))
+        .into_iter()
+        .chain(profile.map(|profile| format!("profile '{}'", profile.name)))
+        .collect::<Vec<_>>()
+        .join(", ");
+    info!(session = session_id, overlay = %label, "Session settings overlay active");
+
+    if let Ok(mut active) = ACTIVE_PROFILES.lock() {
+        active.push(ActiveProfile {
+            session_id: session_id.to_string(),
+            label,
+            overlay,
+        });
+    }
+}
+
+/// Ends the overlay of `session_id`, if it has one. Other sessions keep theirs.
+pub fn end_session(session_id: &str) {
+    if let Ok(mut active) = ACTIVE_PROFILES.lock() {
+        active.retain(|profile| {
+            let ended = profile.session_id == session_id;
+            if ended {
+                debug!(
+                    session = session_id,
+                    overlay = %profile.label,
+                    "Session settings overlay ended"
+                );
+            }
+            !ended
+        });
+    }
+}
+
+/// Drops every session overlay (used when a recording is cancelled).
+pub fn clear_active_profile() {
+    if let Ok(mut active) = ACTIVE_PROFILES.lock() {
+        active.clear();
+    }
+}
+
+/// Ends the session's overlay when dropped, so every exit path of the
+/// transcription task restores the global settings.
+pub struct SessionProfileGuard(pub String);
+
+impl Drop for SessionProfileGuard {
+    fn drop(&mut self) {
+        end_session(&self.0);
+    }
+}
+
+/// Settings for the current recording session: the persisted settings with
+/// the mode and application profile of the latest session applied on top.
+pub fn session_settings(app: &AppHandle) -> AppSettings {
+    let mut settings = get_settings(app);
+    if let Ok(active) = ACTIVE_PROFILES.lock() {
+        if let Some(profile) = active.last() {
+            profile.overlay.apply_to(&mut settings);
+        }
+    }
+    settings
+}
+
+/// Like [`session_settings`], for a session that may no longer be the latest
+/// (e.g. one that is transcribing while the next recording runs).
+pub fn settings_for_session(app: &AppHandle, session_id: &str) -> AppSettings {
+    let mut settings = get_settings(app);
+    if let Ok(active) = ACTIVE_PROFILES.lock() {
+        if let Some(profile) = active
+            .iter()
+            .find(|profile| profile.session_id == session_id)
+        {
+            profile.overlay.apply_to(&mut settings);
+        }
+    }
+    settings
+}
+
+/// Identifiers of the frontmost application, most specific first.
+///
+/// - macOS: bundle identifier (e.g. `com.apple.Terminal`)
+/// - Linux/Wayland: app-id reported by sway or Hyprland
+/// - Linux/X11: `WM_CLASS` instance and class names
+/// - Windows: executable file name (e.g. `code.exe`)
+#[cfg(target_os = "macos")]
+pub fn detect_frontmost_app() -> Vec<String> {
+    use objc2::runtime::AnyObject;
+    use objc2::{class, msg_send};
+    use objc2_foundation::NSString;
+
+    unsafe {
+        let workspace: *mut AnyObject = msg_send![class!(NSWorkspace), sharedWorkspace];
+        if workspace.is_null() {
+            return Vec::new();
+        }
+        let application: *mut AnyObject = msg_send![workspace, frontmostApplication];
+        if application.is_null() {
+            return Vec::new();
+        }
+        let bundle_id: *mut NSString = msg_send![application, bundleIdentifier];
+        if bundle_id.is_null() {
+            return Vec::new();
+        }
+        vec![(*bundle_id).to_string()]
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn detect_frontmost_app() -> Vec<String> {
+    let deadline = Instant::now() + FRONTMOST_APP_TIMEOUT;
+    if crate::utils::is_wayland() {
+        if let Some(app_id) = wayland_focused_app_id(deadline) {
+            return vec![app_id];
+        }
+    }
+    x11_active_window_class(deadline)
+}
+
+/// Longest recording start waits for the frontmost application. Linux asks
+/// the compositor or X server through helper programs, and a hung one must
+/// not hold up the hotkey.
+#[cfg(target_os = "linux")]
+const FRONTMOST_APP_TIMEOUT: Duration = Duration::from_millis(300);
+
+/// Stdout of `program`, or `None` when it cannot run or is still running at
+/// `deadline`, in which case it is killed.
+#[cfg(target_os = "linux")]
+fn command_stdout(program: &str, args: &[&str], deadline: Instant) -> Option<Vec<u8>> {
+    use std::io::Read;
+    use std::process::{Command, Stdio};
+
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+    let mut stdout = child.stdout.take()?;
+    // Read on another thread so a large reply cannot fill the pipe and stall
+    // the child while this one waits.
+    let (output_tx, output_rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let mut output = Vec::new();
+        if stdout.read_to_end(&mut output).is_ok() {
+            let _ = output_tx.send(output);
+        }
+    });
+
+    let output = output_rx
+        .recv_timeout(deadline.saturating_duration_since(Instant::now()))
+        .ok();
+    if output.is_none() {
+        warn!(program, "Frontmost application lookup timed out");
+        let _ = child.kill();
+    }
+    let _ = child.wait();
+    output
+}
+
+#[cfg(target_os = "windows")]
+pub fn detect_frontmost_app() -> Vec<String> {
+    use windows::Win32::Foundation::CloseHandle;
+    use windows::Win32::System::Threading::{
+        OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32,
+        PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+    use windows::Win32::UI::WindowsAndMessaging::{GetForegroundWindow, GetWindowThreadProcessId};
+
+    unsafe {
+        let hwnd = GetForegroundWindow();
+        let mut pid = 0u32;
+        GetWindowThreadProcessId(hwnd, Some(&mut pid));
+        if pid == 0 {
+            return Vec::new();
+        }
+        let Ok(process) = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid) else {
+            return Vec::new();
+        };
+        let mut buffer = [0u16; 1024];
+        let mut len = buffer.len() as u32;
+        let result = QueryFullProcessImageNameW(
+            process,
+            PROCESS_NAME_WIN32,
+            windows::core::PWSTR(buffer.as_mut_ptr()),
+            &mut len,
+        );
+        let _ = CloseHandle(process);
+        if result.is_err() {
+            return Vec::new();
+        }
+        let path = String::from_utf16_lossy(&buffer[..len as usize]);
+        std::path::Path::new(&path)
+            .file_name()
+            .map(|name| vec![name.to_string_lossy().to_string()])
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+pub fn detect_frontmost_app() -> Vec<String> {
+    Vec::new()
+}
+
+#[cfg(target_os = "linux")]
+fn wayland_focused_app_id(deadline: Instant) -> Option<String> {
+    if std::env::var("HYPRLAND_INSTANCE_SIGNATURE").is_ok() {
+        let output = command_stdout("hyprctl", &["activewindow", "-j"], deadline)?;
+        let window: serde_json::Value = serde_json::from_slice(&output).ok()?;
+        return window
+            .get("class")
+            .and_then(|class| class.as_str())
+            .filter(|class| !class.is_empty())
+            .map(ToOwned::to_owned);
+    }
+
+    if std::env::var("SWAYSOCK").is_ok() {
+        let output = command_stdout("swaymsg", &["-t", "get_tree", "-r"], deadline)?;
+        let tree: serde_json::Value = serde_json::from_slice(&output).ok()?;
+        return find_focused_sway_app_id(&tree);
+    }
+
+    None
+}
+
+#[cfg(target_os = "linux")]
+fn find_focused_sway_app_id(node: &serde_json::Value) -> Option<String> {
+    if node.get("focused").and_then(|f| f.as_bool()) == Some(true) {
+        let app_id = node.get("app_id").and_then(|id| id.as_str()).or_else(|| {
+            node.pointer("/window_properties/class")
+                .and_then(|class| class.as_str())
+        });
+        if let Some(app_id) = app_id.filter(|id| !id.is_empty()) {
+            return Some(app_id.to_string());
+        }
+    }
+
+    ["nodes", "floating_nodes"]
+        .iter()
+        .filter_map(|key| node.get(key).and_then(|children| children.as_array()))
+        .flatten()
+        .find_map(find_focused_sway_app_id)
+}
+
+#[cfg(target_os = "linux")]
+fn x11_active_window_class(deadline: Instant) -> Vec<String> {
+    let Some(output) = command_stdout("xprop", &["-root", "_NET_ACTIVE_WINDOW"], deadline) else {
+        return Vec::new();
+    };
+    let stdout = String::from_utf8_lossy(&output);
+    let Some(window_id) = stdout
+        .split_whitespace()
+        .last()
+        .filter(|id| id.starts_with("0x") && *id != "0x0")
+    else {
+        return Vec::new();
+    };
+
+    let Some(output) = command_stdout("xprop", &["-id", window_id, "WM_CLASS"], deadline) else {
+        return Vec::new();
+    };
+    parse_wm_class(&String::from_utf8_lossy(&output))
+}
+
+/// Parses `WM_CLASS(STRING) = "instance", "Class"` into `["instance", "Class"]`.
+#[cfg(any(target_os = "linux", test))]
+fn parse_wm_class(output: &str) -> Vec<String> {
+    let Some((_, values)) = output.split_once('=') else {
+        return Vec::new();
+    };
+    values
+        .split(',')
+        .map(|value| value.trim().trim_matches('"').to_string())
+        .filter(|value| !value.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::{get_default_settings, PasteMethod};
+
+    fn profile(id: &str, identifiers: &[&str], enabled: bool) -> AppProfile {
+        AppProfile {
+            id: id.to_string(),
+            name: id.to_string(),
+            app_identifiers: identifiers.iter().map(|s| s.to_string()).collect(),
+            enabled,
+            overrides: AppProfileOverrides::default(),
+        }
+    }
+
+    #[test]
+    fn match_profile_is_case_insensitive_and_skips_disabled() {
+        let profiles = vec![
+            profile("disabled", &["code"], false),
+            profile("editor", &["com.microsoft.VSCode", "Code"], true),
+            profile("later", &["code"], true),
+        ];
+
+        assert_eq!(
+            match_profile(&profiles, "CODE").map(|p| p.id.as_str()),
+            Some("editor")
+        );
+        assert_eq!(
+            match_profile(&profiles, "com.microsoft.vscode").map(|p| p.id.as_str()),
+            Some("editor")
+        );
+        assert!(match_profile(&profiles, "firefox").is_none());
+        assert!(match_profile(&profiles, "  ").is_none());
+    }
+
+    #[test]
+    fn overrides_only_replace_set_fields() {
+        let mut settings = get_default_settings();
+        settings.selected_model = "global-model".to_string();
+        settings.selected_language = "en".to_string();
+        let paste_method = settings.paste_method;
+
+        let overrides = AppProfileOverrides {
+            selected_model: Some("profile-model".to_string()),
+            selected_language: Some(String::new()),
+            translate_to_english: Some(true),
+            post_process_selected_prompt_id: Some("prompt_1".to_string()),
+            ..Default::default()
+        };
+        overrides.apply_to(&mut settings);
+
+        assert_eq!(settings.selected_model, "profile-model");
+        assert_eq!(settings.selected_language, "en");
+        assert!(settings.translate_to_english);
+        assert_eq!(settings.paste_method, paste_method);
+        assert_eq!(
+            settings.post_process_selected_prompt_id.as_deref(),
+            Some("prompt_1")
+        );
+
+        let overrides = AppProfileOverrides {
+            paste_method: Some(PasteMethod::None),
+            ..Default::default()
+        };
+        overrides.apply_to(&mut settings);
+        assert_eq!(settings.paste_method, PasteMethod::None);
+    }
+
+    #[test]
+    fn session_overlay_layers_modes_around_profile() {
+        let mode_settings = |model: &str| ModeSettings {
+            selected_model: Some(model.to_string()),
+            ..Default::default()
+        };
+        let profile = AppProfileOverrides {
+            selected_model: Some("profile-model".to_string()),
+            ..Default::default()
+        };
+
+        let mut settings = get_default_settings();
+        SessionOverlay {
+            base_mode: Some(mode_settings("active-mode-model")),
+            profile: Some(profile.clone()),
+            shortcut_mode: None,
+        }
+        .apply_to(&mut settings);
+        assert_eq!(settings.selected_model, "profile-model");
+
+        let mut settings = get_default_settings();
+        SessionOverlay {
+            base_mode: None,
+            profile: Some(profile),
+            shortcut_mode: Some(mode_settings("shortcut-mode-model")),
+        }
+        .apply_to(&mut settings);
+        assert_eq!(settings.selected_model, "shortcut-mode-model");
+    }
+
+    #[test]
+    fn ending_a_session_keeps_other_sessions_overlays() {
+        let overlay = SessionOverlay {
+            base_mode: None,
+            profile: Some(AppProfileOverrides::default()),
+            shortcut_mode: None,
+        };
+        for session_id in ["transcribing", "recording"] {
+            ACTIVE_PROFILES.lock().unwrap().push(ActiveProfile {
+                session_id: session_id.to_string(),
+                label: String::new(),
+                overlay: overlay.clone(),
+            });
+        }
+
+        end_session("transcribing");
+        let remaining: Vec<String> = ACTIVE_PROFILES
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|profile| profile.session_id.clone())
+            .collect();
+        assert_eq!(remaining, vec!["recording".to_string()]);
+        clear_active_profile();
+    }
+
+    #[test]
+    fn parses_xprop_wm_class_output() {
+        assert_eq!(
+            parse_wm_class("WM_CLASS(STRING) = \"navigator\", \"firefox\"\n"),
+            vec!["navigator".to_string(), "firefox".to_string()]
+        );
+        assert!(parse_wm_class("WM_CLASS:  not found.\n").is_empty());
+    }
+}
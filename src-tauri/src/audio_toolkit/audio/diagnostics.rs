@@ -0,0 +1,253 @@
+use std::{
+    sync::mpsc,
+    time::{Duration, Instant},
+};
+
+use cpal::{
+    traits::{DeviceTrait, StreamTrait},
+    Sample, SizedSample,
+};
+
+use super::{resample_to_whisper_rate, AudioRecorder};
+use crate::audio_toolkit::vad::VoiceActivityDetector;
+
+/// Samples at or above this magnitude count as clipped.
+pub const CLIP_LEVEL: f32 = 0.999;
+/// Level reported for digital silence, so dB values stay finite.
+pub const SILENCE_DBFS: f32 = -120.0;
+
+/// VAD frame length at 16 kHz (30 ms).
+const VAD_FRAME: usize = 480;
+/// How long past the requested duration to wait for a device that stalls.
+const CAPTURE_GRACE: Duration = Duration::from_secs(2);
+
+/// Raw device audio captured for diagnostics, before downmix, resampling or
+/// any of the recorder's processing.
+pub struct DiagnosticCapture {
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub sample_format: cpal::SampleFormat,
+    pub interleaved: Vec<f32>,
+}
+
+/// Clipping and DC offset of a capture, measured per sample on every channel.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LevelStats {
+    pub peak: f32,
+    pub clipping_ratio: f32,
+    /// Mean of the channel furthest from zero.
+    pub dc_offset: f32,
+}
+
+impl DiagnosticCapture {
+    /// Records `duration` from `device` with the config the recorder would pick.
+    pub fn record(
+        device: &cpal::Device,
+        duration: Duration,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let config = AudioRecorder::get_preferred_config(device)?;
+        let sample_rate = config.sample_rate().0;
+        let channels = config.channels();
+        let wanted = (duration.as_secs_f64() * sample_rate as f64) as usize * channels as usize;
+
+        let (sample_tx, sample_rx) = mpsc::channel::<Vec<f32>>();
+        let stream = match config.sample_format() {
+            cpal::SampleFormat::U8 => build_raw_stream::<u8>(device, &config, sample_tx),
+            cpal::SampleFormat::I8 => build_raw_stream::<i8>(device, &config, sample_tx),
+            cpal::SampleFormat::I16 => build_raw_stream::<i16>(device, &config, sample_tx),
+            cpal::SampleFormat::I32 => build_raw_stream::<i32>(device, &config, sample_tx),
+            cpal::SampleFormat::F32 => build_raw_stream::<f32>(device, &config, sample_tx),
+            other => return Err(format!("Unsupported sample format: {}", other).into()),
+        }?;
+        stream.play()?;
+
+        let deadline = Instant::now() + duration + CAPTURE_GRACE;
+        let mut interleaved = Vec::with_capacity(wanted);
+        while interleaved.len() < wanted {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            match sample_rx.recv_timeout(remaining) {
+                Ok(packet) => interleaved.extend(packet),
+                Err(_) => break,
+            }
+        }
+        drop(stream);
+
+        if interleaved.is_empty() {
+            return Err("The microphone did not deliver any audio".into());
+        }
+        interleaved.truncate(wanted);
+
+        Ok(Self {
+            sample_rate,
+            channels,
+            sample_format: config.sample_format(),
+            interleaved,
+        })
+    }
+
+    /// Channels averaged into one, as the recorder does.
+    pub fn mono(&self) -> Vec<f32> {
+        let channels = self.channels.max(1) as usize;
+        self.interleaved
+            .chunks_exact(channels)
+            .map(|frame| frame.iter().sum::<f32>() / channels as f32)
+            .collect()
+    }
+
+    /// The mono mix at 16 kHz, as the VAD sees it.
+    pub fn mono_16k(&self) -> Vec<f32> {
+        resample_to_whisper_rate(&self.mono(), self.sample_rate)
+    }
+
+    /// RMS level of each 30 ms frame of the mono mix, in dBFS.
+    pub fn frame_levels_dbfs(&self) -> Vec<f32> {
+        let frame_len = (self.sample_rate as usize * 30 / 1000).max(1);
+        frame_levels_dbfs(&self.mono(), frame_len)
+    }
+
+    pub fn levels(&self) -> LevelStats {
+        level_stats(&self.interleaved, self.channels.max(1) as usize)
+    }
+}
+
+fn build_raw_stream<T>(
+    device: &cpal::Device,
+    config: &cpal::SupportedStreamConfig,
+    sample_tx: mpsc::Sender<Vec<f32>>,
+) -> Result<cpal::Stream, cpal::BuildStreamError>
+where
+    T: Sample + SizedSample + Send + 'static,
+    f32: cpal::FromSample<T>,
+{
+    device.build_input_stream(
+        &config.clone().into(),
+        move |data: &[T], _: &cpal::InputCallbackInfo| {
+            let _ = sample_tx.send(data.iter().map(|&s| s.to_sample::<f32>()).collect());
+        },
+        |err| tracing::error!("Diagnostic stream error: {}", err),
+        None,
+    )
+}
+
+fn level_stats(interleaved: &[f32], channels: usize) -> LevelStats {
+    if interleaved.is_empty() || channels == 0 {
+        return LevelStats {
+            peak: 0.0,
+            clipping_ratio: 0.0,
+            dc_offset: 0.0,
+        };
+    }
+
+    let peak = interleaved.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+    let clipped = interleaved.iter().filter(|s| s.abs() >= CLIP_LEVEL).count();
+
+    let mut sums = vec![0.0f64; channels];
+    for frame in interleaved.chunks_exact(channels) {
+        for (sum, &sample) in sums.iter_mut().zip(frame) {
+            *sum += sample as f64;
+        }
+    }
+    let frames = (interleaved.len() / channels).max(1) as f64;
+    let dc_offset = sums
+        .iter()
+        .map(|sum| (sum / frames) as f32)
+        .fold(0.0f32, |worst, mean| {
+            if mean.abs() > worst.abs() {
+                mean
+            } else {
+                worst
+            }
+        });
+
+    LevelStats {
+        peak,
+        clipping_ratio: clipped as f32 / interleaved.len() as f32,
+        dc_offset,
+    }
+}
+
+/// Converts a linear amplitude to dBFS, floored at [`SILENCE_DBFS`].
+pub fn amplitude_to_dbfs(amplitude: f32) -> f32 {
+    if amplitude <= 0.0 {
+        return SILENCE_DBFS;
+    }
+    (20.0 * amplitude.log10()).max(SILENCE_DBFS)
+}
+
+/// RMS level of each whole `frame_len` frame, in dBFS.
+fn frame_levels_dbfs(mono: &[f32], frame_len: usize) -> Vec<f32> {
+    mono.chunks_exact(frame_len.max(1))
+        .map(|frame| {
+            let mean_square = frame.iter().map(|s| s * s).sum::<f32>() / frame.len() as f32;
+            amplitude_to_dbfs(mean_square.sqrt())
+        })
+        .collect()
+}
+
+/// Nearest-rank percentile (`fraction` in 0.0..=1.0) of `values`.
+pub fn percentile(values: &[f32], fraction: f32) -> Option<f32> {
+    if values.is_empty() {
+        return None;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let rank = ((sorted.len() - 1) as f32 * fraction.clamp(0.0, 1.0)).round() as usize;
+    Some(sorted[rank])
+}
+
+/// Fraction of 30 ms frames of 16 kHz mono audio that `vad` keeps as speech.
+/// The detector is not reset, so adaptive detectors carry over what they
+/// learned from earlier audio.
+pub fn vad_speech_ratio(vad: &mut dyn VoiceActivityDetector, mono_16k: &[f32]) -> f32 {
+    let mut frames = 0usize;
+    let mut speech = 0usize;
+    for frame in mono_16k.chunks_exact(VAD_FRAME) {
+        frames += 1;
+        if vad.is_voice(frame).unwrap_or(false) {
+            speech += 1;
+        }
+    }
+    if frames == 0 {
+        0.0
+    } else {
+        speech as f32 / frames as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio_toolkit::vad::test_signals::{voiced, FRAME};
+    use crate::audio_toolkit::vad::EnergyVad;
+
+    #[test]
+    fn level_stats_reports_clipping_and_worst_channel_offset() {
+        // Left channel clips twice, right channel sits at +0.1 DC.
+        let interleaved = [1.0, 0.1, -1.0, 0.1, 0.5, 0.1, -0.5, 0.1];
+        let stats = level_stats(&interleaved, 2);
+        assert_eq!(stats.peak, 1.0);
+        assert_eq!(stats.clipping_ratio, 0.25);
+        assert!((stats.dc_offset - 0.1).abs() < 1e-6);
+    }
+
+    #[test]
+    fn frame_levels_and_percentiles() {
+        let mut mono = vec![0.0; 4];
+        mono.extend([0.5; 4]);
+        let levels = frame_levels_dbfs(&mono, 4);
+        assert_eq!(levels[0], SILENCE_DBFS);
+        assert!((levels[1] - -6.0206).abs() < 1e-3);
+        assert_eq!(percentile(&levels, 0.0), Some(SILENCE_DBFS));
+        assert_eq!(percentile(&levels, 1.0), Some(levels[1]));
+        assert_eq!(percentile(&[], 0.5), None);
+    }
+
+    #[test]
+    fn vad_speech_ratio_counts_voiced_frames() {
+        let mut audio = vec![0.0; FRAME * 10];
+        audio.extend(voiced(0.5, FRAME * 10, 0));
+        let mut vad = EnergyVad::new(0.5);
+        let ratio = vad_speech_ratio(&mut vad, &audio);
+        assert!((0.4..=0.5).contains(&ratio), "ratio {}", ratio);
+    }
+}
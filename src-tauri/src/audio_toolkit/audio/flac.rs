@@ -0,0 +1,411 @@
+//! Minimal FLAC encoder for 16-bit mono recordings.
+//!
+//! Uses fixed linear predictors (orders 0-4) with partitioned Rice coding,
+//! which typically halves the size of speech recordings while staying
+//! bit-exact. Decoding goes through symphonia like every other format.
+
+use std::io::{self, Write};
+
+const BLOCK_SIZE: usize = 4096;
+const BITS_PER_SAMPLE: u32 = 16;
+const MAX_FIXED_ORDER: usize = 4;
+const MAX_PARTITION_ORDER: u32 = 8;
+/// Rice parameter 15 is the escape code for 4-bit parameters.
+const MAX_RICE_PARAMETER: u32 = 14;
+
+/// Encode mono 16-bit samples as a complete FLAC stream.
+pub fn encode_flac(samples: &[i16], sample_rate: u32) -> Vec<u8> {
+    let mut writer = FlacWriter::new(Vec::new(), sample_rate, samples.len() as u64)
+        .expect("writing to a Vec cannot fail");
+    writer
+        .write_samples(samples)
+        .expect("writing to a Vec cannot fail");
+    writer.finish().expect("writing to a Vec cannot fail")
+}
+
+/// Incremental FLAC encoder, for recordings too long to hold in memory.
+///
+/// The total sample count goes into STREAMINFO up front, so callers must know
+/// it before writing.
+pub struct FlacWriter<W: Write> {
+    out: W,
+    sample_rate: u32,
+    block: Vec<i16>,
+    frame_number: u64,
+}
+
+impl<W: Write> FlacWriter<W> {
+    pub fn new(mut out: W, sample_rate: u32, total_samples: u64) -> io::Result<Self> {
+        let mut writer = BitWriter::default();
+        writer.write_bytes(b"fLaC");
+
+        // STREAMINFO is the only (and therefore last) metadata block.
+        let block_size = total_samples.clamp(16, BLOCK_SIZE as u64) as u32;
+        writer.write(1, 1);
+        writer.write(0, 7);
+        writer.write(34, 24);
+        writer.write(block_size, 16);
+        writer.write(block_size, 16);
+        writer.write(0, 24); // minimum frame size unknown
+        writer.write(0, 24); // maximum frame size unknown
+        writer.write(sample_rate, 20);
+        writer.write(0, 3); // one channel
+        writer.write(BITS_PER_SAMPLE - 1, 5);
+        writer.write((total_samples >> 32) as u32, 4);
+        writer.write(total_samples as u32, 32);
+        writer.write_bytes(&[0; 16]); // MD5 signature not computed
+
+        out.write_all(&writer.into_bytes())?;
+        Ok(Self {
+            out,
+            sample_rate,
+            block: Vec::with_capacity(BLOCK_SIZE),
+            frame_number: 0,
+        })
+    }
+
+    pub fn write_samples(&mut self, samples: &[i16]) -> io::Result<()> {
+        for &sample in samples {
+            self.block.push(sample);
+            if self.block.len() == BLOCK_SIZE {
+                self.flush_block()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Encode the final, possibly short, block and return the inner writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        if !self.block.is_empty() {
+            self.flush_block()?;
+        }
+        self.out.flush()?;
+        Ok(self.out)
+    }
+
+    fn flush_block(&mut self) -> io::Result<()> {
+        let frame = encode_frame(&self.block, self.frame_number, self.sample_rate);
+        self.out.write_all(&frame)?;
+        self.block.clear();
+        self.frame_number += 1;
+        Ok(())
+    }
+}
+
+fn encode_frame(block: &[i16], frame_number: u64, sample_rate: u32) -> Vec<u8> {
+    let mut writer = BitWriter::default();
+    writer.write(0b111_1111_1111_1100, 15); // sync code
+    writer.write(0, 1); // fixed block size stream
+    writer.write(0b0111, 4); // block size - 1 follows as 16 bits
+    let (rate_code, rate_tail) = sample_rate_code(sample_rate);
+    writer.write(rate_code, 4);
+    writer.write(0, 4); // mono
+    writer.write(0b100, 3); // 16 bits per sample
+    writer.write(0, 1);
+    writer.write_bytes(&utf8_coded(frame_number));
+    writer.write(block.len() as u32 - 1, 16);
+    if let Some((value, bits)) = rate_tail {
+        writer.write(value, bits);
+    }
+    let header_crc = crc8(writer.bytes());
+    writer.write(header_crc as u32, 8);
+
+    encode_subframe(&mut writer, block);
+    writer.align();
+    let frame_crc = crc16(writer.bytes());
+    writer.write(frame_crc as u32, 16);
+    writer.into_bytes()
+}
+
+fn sample_rate_code(sample_rate: u32) -> (u32, Option<(u32, u32)>) {
+    match sample_rate {
+        8000 => (0b0100, None),
+        16000 => (0b0101, None),
+        22050 => (0b0110, None),
+        24000 => (0b0111, None),
+        32000 => (0b1000, None),
+        44100 => (0b1001, None),
+        48000 => (0b1010, None),
+        96000 => (0b1011, None),
+        rate if rate <= u16::MAX as u32 => (0b1101, Some((rate, 16))),
+        // Fall back to the rate stored in STREAMINFO.
+        _ => (0b0000, None),
+    }
+}
+
+fn encode_subframe(writer: &mut BitWriter, block: &[i16]) {
+    let samples: Vec<i32> = block.iter().map(|&s| s as i32).collect();
+
+    if samples.iter().all(|&s| s == samples[0]) {
+        writer.write(0, 1);
+        writer.write(0b000000, 6);
+        writer.write(0, 1);
+        writer.write_signed(samples[0], BITS_PER_SAMPLE);
+        return;
+    }
+
+    let verbatim_bits = samples.len() as u64 * BITS_PER_SAMPLE as u64;
+    let best = (0..=MAX_FIXED_ORDER.min(samples.len() - 1))
+        .map(|order| {
+            let residual = fixed_residual(&samples, order);
+            let rice = choose_rice_partitions(&residual, samples.len(), order);
+            let bits = order as u64 * BITS_PER_SAMPLE as u64 + rice.bits;
+            (order, residual, rice, bits)
+        })
+        .min_by_key(|(_, _, _, bits)| *bits);
+
+    match best {
+        Some((order, residual, rice, bits)) if bits < verbatim_bits => {
+            writer.write(0, 1);
+            writer.write(0b001000 | order as u32, 6);
+            writer.write(0, 1);
+            for &warmup in &samples[..order] {
+                writer.write_signed(warmup, BITS_PER_SAMPLE);
+            }
+            write_residual(writer, &residual, &rice, order);
+        }
+        _ => {
+            writer.write(0, 1);
+            writer.write(0b000001, 6);
+            writer.write(0, 1);
+            for &sample in &samples {
+                writer.write_signed(sample, BITS_PER_SAMPLE);
+            }
+        }
+    }
+}
+
+/// Residual of the fixed polynomial predictor; excludes the warm-up samples.
+fn fixed_residual(samples: &[i32], order: usize) -> Vec<i32> {
+    (order..samples.len())
+        .map(|i| {
+            let s = |k: usize| samples[i - k];
+            match order {
+                0 => s(0),
+                1 => s(0) - s(1),
+                2 => s(0) - 2 * s(1) + s(2),
+                3 => s(0) - 3 * s(1) + 3 * s(2) - s(3),
+                _ => s(0) - 4 * s(1) + 6 * s(2) - 4 * s(3) + s(4),
+            }
+        })
+        .collect()
+}
+
+struct RicePartitions {
+    order: u32,
+    parameters: Vec<u32>,
+    bits: u64,
+}
+
+fn zigzag(value: i32) -> u32 {
+    ((value << 1) ^ (value >> 31)) as u32
+}
+
+fn rice_bits(values: &[i32], parameter: u32) -> u64 {
+    values
+        .iter()
+        .map(|&v| (zigzag(v) >> parameter) as u64 + 1 + parameter as u64)
+        .sum()
+}
+
+fn best_rice_parameter(values: &[i32]) -> (u32, u64) {
+    // The optimum sits next to log2 of the mean magnitude; only check neighbours.
+    let sum: u64 = values.iter().map(|&v| zigzag(v) as u64).sum();
+    let mean = sum / values.len().max(1) as u64;
+    let estimate = (u64::BITS - mean.leading_zeros()).min(MAX_RICE_PARAMETER);
+    (estimate.saturating_sub(1)..=(estimate + 1).min(MAX_RICE_PARAMETER))
+        .map(|parameter| (parameter, rice_bits(values, parameter)))
+        .min_by_key(|(_, bits)| *bits)
+        .unwrap_or((0, 0))
+}
+
+fn choose_rice_partitions(
+    residual: &[i32],
+    block_size: usize,
+    predictor_order: usize,
+) -> RicePartitions {
+    let mut best: Option<RicePartitions> = None;
+    for order in 0..=MAX_PARTITION_ORDER {
+        let partitions = 1usize << order;
+        if block_size.trailing_zeros() < order || block_size / partitions <= predictor_order {
+            break;
+        }
+        let partition_len = block_size / partitions;
+        let mut parameters = Vec::with_capacity(partitions);
+        // Coding method and partition order.
+        let mut bits = 2 + 4;
+        let mut start = 0;
+        for partition in 0..partitions {
+            // The first partition is shorter by the predictor's warm-up.
+            let len = if partition == 0 {
+                partition_len - predictor_order
+            } else {
+                partition_len
+            };
+            let (parameter, partition_bits) = best_rice_parameter(&residual[start..start + len]);
+            parameters.push(parameter);
+            bits += 4 + partition_bits;
+            start += len;
+        }
+        if best.as_ref().is_none_or(|b| bits < b.bits) {
+            best = Some(RicePartitions {
+                order,
+                parameters,
+                bits,
+            });
+        }
+    }
+    best.expect("partition order 0 is always valid")
+}
+
+fn write_residual(
+    writer: &mut BitWriter,
+    residual: &[i32],
+    rice: &RicePartitions,
+    predictor_order: usize,
+) {
+    writer.write(0b00, 2);
+    writer.write(rice.order, 4);
+    let partition_len = (residual.len() + predictor_order) >> rice.order;
+    let mut start = 0;
+    for (partition, &parameter) in rice.parameters.iter().enumerate() {
+        let len = if partition == 0 {
+            partition_len - predictor_order
+        } else {
+            partition_len
+        };
+        writer.write(parameter, 4);
+        for &value in &residual[start..start + len] {
+            let value = zigzag(value);
+            writer.write_unary(value >> parameter);
+            if parameter > 0 {
+                writer.write(value & ((1 << parameter) - 1), parameter);
+            }
+        }
+        start += len;
+    }
+}
+
+fn utf8_coded(value: u64) -> Vec<u8> {
+    if value < 0x80 {
+        return vec![value as u8];
+    }
+    let mut continuation = Vec::new();
+    let mut rest = value;
+    let mut payload_bits = 6;
+    while rest >= 1 << payload_bits {
+        continuation.push(0x80 | (rest & 0x3F) as u8);
+        rest >>= 6;
+        payload_bits -= 1;
+    }
+    let count = continuation.len() as u32 + 1;
+    let prefix = !(0xFFu8 >> count);
+    let mut bytes = vec![prefix | rest as u8];
+    bytes.extend(continuation.into_iter().rev());
+    bytes
+}
+
+fn crc8(bytes: &[u8]) -> u8 {
+    bytes.iter().fold(0u8, |mut crc, &byte| {
+        crc ^= byte;
+        for _ in 0..8 {
+            crc = if crc & 0x80 != 0 {
+                (crc << 1) ^ 0x07
+            } else {
+                crc << 1
+            };
+        }
+        crc
+    })
+}
+
+fn crc16(bytes: &[u8]) -> u16 {
+    bytes.iter().fold(0u16, |mut crc, &byte| {
+        crc ^= (byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x8005
+            } else {
+                crc << 1
+            };
+        }
+        crc
+    })
+}
+
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    accumulator: u64,
+    pending_bits: u32,
+}
+
+impl BitWriter {
+    fn write(&mut self, value: u32, bits: u32) {
+        if bits == 0 {
+            return;
+        }
+        let masked = value as u64 & ((1u64 << bits) - 1);
+        self.accumulator = (self.accumulator << bits) | masked;
+        self.pending_bits += bits;
+        while self.pending_bits >= 8 {
+            self.pending_bits -= 8;
+            self.bytes
+                .push((self.accumulator >> self.pending_bits) as u8);
+        }
+    }
+
+    fn write_signed(&mut self, value: i32, bits: u32) {
+        self.write(value as u32, bits);
+    }
+
+    fn write_unary(&mut self, zeros: u32) {
+        let mut remaining = zeros;
+        while remaining >= 32 {
+            self.write(0, 32);
+            remaining -= 32;
+        }
+        self.write(1, remaining + 1);
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.write(byte as u32, 8);
+        }
+    }
+
+    fn align(&mut self) {
+        if self.pending_bits > 0 {
+            self.write(0, 8 - self.pending_bits);
+        }
+    }
+
+    /// Completed bytes; only meaningful when byte-aligned.
+    fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    fn into_bytes(mut self) -> Vec<u8> {
+        self.align();
+        self.bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn utf8_coding_matches_flac_frame_numbers() {
+        assert_eq!(utf8_coded(0), vec![0x00]);
+        assert_eq!(utf8_coded(0x7F), vec![0x7F]);
+        assert_eq!(utf8_coded(0x80), vec![0xC2, 0x80]);
+        assert_eq!(utf8_coded(0x800), vec![0xE0, 0xA0, 0x80]);
+    }
+
+    #[test]
+    fn fixed_residual_of_a_ramp_vanishes_at_order_two() {
+        let ramp: Vec<i32> = (0..32).map(|i| 3 * i - 10).collect();
+        assert!(fixed_residual(&ramp, 2).iter().all(|&r| r == 0));
+    }
+}
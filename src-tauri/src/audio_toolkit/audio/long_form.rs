@@ -0,0 +1,157 @@
+//! Chunking policy and transcript stitching for long-form recordings.
+//!
+//! Long recordings are cut into chunks while they run so each chunk can be
+//! spooled to disk and transcribed in the background. Cuts normally land on a
+//! VAD pause; uninterrupted speech is cut hard and the next chunk repeats a
+//! short overlap, whose duplicated words are removed when stitching.
+
+use crate::audio_toolkit::constants::WHISPER_SAMPLE_RATE;
+
+/// Preferred chunk length; the first pause after it ends the chunk.
+const TARGET_CHUNK_SECS: usize = 30;
+/// Hard limit for a chunk of uninterrupted speech.
+const MAX_CHUNK_SECS: usize = 60;
+/// Audio repeated at the start of the next chunk after a hard cut, so a word
+/// split by the cut is heard whole at least once.
+const FORCED_CUT_OVERLAP_MS: usize = 1500;
+/// Longest run of words compared when removing duplicated overlap text.
+const MAX_OVERLAP_WORDS: usize = 12;
+
+/// Speech audio cut from a long-form recording while it is still running.
+#[derive(Clone, Debug)]
+pub struct RecordedChunk {
+    /// 16 kHz mono speech samples.
+    pub samples: Vec<f32>,
+    /// Trailing samples that are repeated at the start of the next chunk.
+    pub carried_over: usize,
+    /// Everything captured since the previous chunk, before the VAD dropped
+    /// the pauses. This is what history keeps.
+    pub capture: Vec<f32>,
+}
+
+/// Decides where the recorder cuts a long-form recording into chunks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LongFormChunker {
+    target_samples: usize,
+    max_samples: usize,
+    overlap_samples: usize,
+}
+
+impl Default for LongFormChunker {
+    fn default() -> Self {
+        let rate = WHISPER_SAMPLE_RATE as usize;
+        Self::new(
+            TARGET_CHUNK_SECS * rate,
+            MAX_CHUNK_SECS * rate,
+            FORCED_CUT_OVERLAP_MS * rate / 1000,
+        )
+    }
+}
+
+impl LongFormChunker {
+    pub fn new(target_samples: usize, max_samples: usize, overlap_samples: usize) -> Self {
+        Self {
+            target_samples,
+            max_samples: max_samples.max(target_samples),
+            overlap_samples: overlap_samples.min(target_samples / 2),
+        }
+    }
+
+    /// Called after every recorded frame with the number of buffered speech
+    /// samples. Returns how many of them to carry into the next chunk when the
+    /// buffer should be cut now.
+    pub fn cut(&self, buffered: usize, frame_was_speech: bool) -> Option<usize> {
+        if !frame_was_speech && buffered >= self.target_samples {
+            return Some(0);
+        }
+        if buffered >= self.max_samples {
+            return Some(self.overlap_samples);
+        }
+        None
+    }
+}
+
+fn normalize_word(word: &str) -> String {
+    word.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Appends the transcript of the next chunk to `transcript`.
+///
+/// When the chunks share audio, the longest run of words that both ends
+/// `transcript` and starts `next` is dropped from `next`; punctuation and case
+/// are ignored for the comparison.
+pub fn stitch_chunk_text(transcript: &mut String, next: &str, overlaps_previous: bool) {
+    let next_words: Vec<&str> = next.split_whitespace().collect();
+    let mut skip = 0;
+    if overlaps_previous {
+        let words: Vec<&str> = transcript.split_whitespace().collect();
+        let previous: Vec<String> = words[words.len().saturating_sub(MAX_OVERLAP_WORDS)..]
+            .iter()
+            .map(|word| normalize_word(word))
+            .collect();
+        let upcoming: Vec<String> = next_words
+            .iter()
+            .take(MAX_OVERLAP_WORDS)
+            .map(|word| normalize_word(word))
+            .collect();
+        skip = (1..=previous.len().min(upcoming.len()))
+            .rev()
+            .find(|&len| previous[previous.len() - len..] == upcoming[..len])
+            .unwrap_or(0);
+    }
+
+    let rest = next_words[skip..].join(" ");
+    if rest.is_empty() {
+        return;
+    }
+    if !transcript.is_empty() {
+        transcript.push(' ');
+    }
+    transcript.push_str(&rest);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cuts_at_the_first_pause_after_the_target_length() {
+        let chunker = LongFormChunker::new(100, 200, 20);
+        assert_eq!(chunker.cut(99, false), None);
+        assert_eq!(chunker.cut(150, true), None);
+        assert_eq!(chunker.cut(150, false), Some(0));
+        assert_eq!(
+            chunker.cut(200, true),
+            Some(20),
+            "hard cut keeps an overlap"
+        );
+    }
+
+    #[test]
+    fn overlap_never_exceeds_half_a_chunk() {
+        let chunker = LongFormChunker::new(100, 200, 500);
+        assert_eq!(chunker.cut(200, true), Some(50));
+    }
+
+    #[test]
+    fn stitching_drops_words_repeated_by_the_overlap() {
+        let mut transcript = "We will now look at the".to_string();
+        stitch_chunk_text(&mut transcript, "Look at the results, then stop.", true);
+        assert_eq!(transcript, "We will now look at the results, then stop.");
+    }
+
+    #[test]
+    fn stitching_keeps_repeats_across_a_pause_cut() {
+        let mut transcript = "Thanks.".to_string();
+        stitch_chunk_text(&mut transcript, "thanks again", false);
+        assert_eq!(transcript, "Thanks. thanks again");
+
+        let mut transcript = String::new();
+        stitch_chunk_text(&mut transcript, "  first chunk ", true);
+        stitch_chunk_text(&mut transcript, "", true);
+        assert_eq!(transcript, "first chunk");
+    }
+}
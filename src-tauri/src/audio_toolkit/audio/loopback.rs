@@ -0,0 +1,237 @@
+use std::{
+    io::{self, Read},
+    process::{Child, Stdio},
+    thread::JoinHandle,
+};
+
+/// PulseAudio / PipeWire alias for the monitor of the default output.
+pub const DEFAULT_MONITOR_SOURCE: &str = "@DEFAULT_MONITOR@";
+
+/// A monitor source that plays back what an output device is playing.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LoopbackSource {
+    /// Source name as understood by `parec --device`.
+    pub name: String,
+    pub description: String,
+}
+
+/// Lists the monitor sources of the running PulseAudio / PipeWire server.
+/// Empty on other platforms, where system audio cannot be captured yet.
+pub fn list_loopback_sources() -> Result<Vec<LoopbackSource>, Box<dyn std::error::Error>> {
+    #[cfg(target_os = "linux")]
+    {
+        let output = std::process::Command::new("pactl")
+            .args(["list", "sources"])
+            .env("LC_ALL", "C")
+            .output()?;
+        if !output.status.success() {
+            return Err(format!(
+                "pactl list sources failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )
+            .into());
+        }
+        Ok(parse_monitor_sources(&String::from_utf8_lossy(
+            &output.stdout,
+        )))
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        Ok(Vec::new())
+    }
+}
+
+/// Picks the monitor sources out of `pactl list sources`.
+#[cfg(any(target_os = "linux", test))]
+fn parse_monitor_sources(output: &str) -> Vec<LoopbackSource> {
+    let mut sources = Vec::new();
+    let mut name: Option<String> = None;
+    let mut description: Option<String> = None;
+    let mut is_monitor = false;
+
+    let mut flush = |name: &mut Option<String>, description: &mut Option<String>, is_monitor| {
+        if let (Some(name), true) = (name.take(), is_monitor) {
+            let description = description.take().unwrap_or_else(|| name.clone());
+            sources.push(LoopbackSource { name, description });
+        }
+        description.take();
+    };
+
+    for line in output.lines() {
+        if line.starts_with("Source #") {
+            flush(&mut name, &mut description, is_monitor);
+            is_monitor = false;
+            continue;
+        }
+        let Some((key, value)) = line.trim().split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match key {
+            "Name" => name = Some(value.to_string()),
+            "Description" => description = Some(value.to_string()),
+            "Monitor of Sink" => is_monitor = value != "n/a",
+            _ => {}
+        }
+    }
+    flush(&mut name, &mut description, is_monitor);
+
+    sources
+}
+
+/// Turns a raw little-endian f32 byte stream into samples, carrying partial
+/// samples over between reads.
+#[derive(Default)]
+struct PcmF32Decoder {
+    carry: Vec<u8>,
+}
+
+impl PcmF32Decoder {
+    fn decode(&mut self, bytes: &[u8]) -> Vec<f32> {
+        self.carry.extend_from_slice(bytes);
+        let whole = self.carry.len() / 4 * 4;
+        let samples = self.carry[..whole]
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect();
+        self.carry.drain(..whole);
+        samples
+    }
+}
+
+/// Records a monitor source through `parec` as mono f32 packets at the given
+/// rate. The capture stops when this is dropped.
+pub struct LoopbackCapture {
+    child: Child,
+    reader: Option<JoinHandle<()>>,
+}
+
+impl LoopbackCapture {
+    pub fn spawn(
+        source: &str,
+        sample_rate: u32,
+        mut deliver: impl FnMut(Vec<f32>) -> bool + Send + 'static,
+    ) -> io::Result<Self> {
+        if !cfg!(target_os = "linux") {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "System audio capture is only supported on Linux",
+            ));
+        }
+
+        let mut child = std::process::Command::new("parec")
+            .arg(format!("--device={}", source))
+            .arg(format!("--rate={}", sample_rate))
+            .args([
+                "--format=float32le",
+                "--channels=1",
+                "--raw",
+                "--latency-msec=20",
+            ])
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+        let mut stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::BrokenPipe, "parec has no stdout"))?;
+
+        tracing::info!(
+            source = source,
+            sample_rate = sample_rate,
+            event_code = "loopback_capture_started",
+            "Capturing system audio"
+        );
+
+        // ~10ms per read keeps packets as small as the microphone's.
+        let read_size = (sample_rate as usize / 100).max(1) * 4;
+        let reader = std::thread::spawn(move || {
+            let mut decoder = PcmF32Decoder::default();
+            let mut buf = vec![0u8; read_size];
+            loop {
+                match stdout.read(&mut buf) {
+                    Ok(0) => break,
+                    Ok(n) => {
+                        let samples = decoder.decode(&buf[..n]);
+                        if !samples.is_empty() && !deliver(samples) {
+                            break;
+                        }
+                    }
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(e) => {
+                        tracing::error!("System audio capture failed: {}", e);
+                        break;
+                    }
+                }
+            }
+        });
+
+        Ok(Self {
+            child,
+            reader: Some(reader),
+        })
+    }
+}
+
+impl Drop for LoopbackCapture {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+        if let Some(reader) = self.reader.take() {
+            let _ = reader.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PACTL_SOURCES: &str = "\
+Source #0
+\tState: SUSPENDED
+\tName: alsa_output.pci-0000_00_1f.3.analog-stereo.monitor
+\tDescription: Monitor of Built-in Audio Analog Stereo
+\tDriver: PipeWire
+\tMonitor of Sink: alsa_output.pci-0000_00_1f.3.analog-stereo
+\tFlags: DECIBEL_VOLUME LATENCY
+Source #1
+\tState: RUNNING
+\tName: alsa_input.pci-0000_00_1f.3.analog-stereo
+\tDescription: Built-in Audio Analog Stereo
+\tMonitor of Sink: n/a
+Source #2
+\tName: null.monitor
+\tMonitor of Sink: null
+";
+
+    #[test]
+    fn only_monitor_sources_are_listed() {
+        assert_eq!(
+            parse_monitor_sources(PACTL_SOURCES),
+            vec![
+                LoopbackSource {
+                    name: "alsa_output.pci-0000_00_1f.3.analog-stereo.monitor".to_string(),
+                    description: "Monitor of Built-in Audio Analog Stereo".to_string(),
+                },
+                LoopbackSource {
+                    name: "null.monitor".to_string(),
+                    description: "null.monitor".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn decoder_carries_partial_samples_across_reads() {
+        let bytes: Vec<u8> = [0.5f32, -0.25]
+            .iter()
+            .flat_map(|s| s.to_le_bytes())
+            .collect();
+        let mut decoder = PcmF32Decoder::default();
+        assert_eq!(decoder.decode(&bytes[..3]), Vec::<f32>::new());
+        assert_eq!(decoder.decode(&bytes[3..6]), vec![0.5]);
+        assert_eq!(decoder.decode(&bytes[6..]), vec![-0.25]);
+    }
+}
@@ -0,0 +1,146 @@
+use std::collections::VecDeque;
+
+/// Microphone and system audio of a mixed recording, kept apart.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SourceChannels {
+    pub microphone: Vec<f32>,
+    pub system: Vec<f32>,
+}
+
+/// Lines system-audio packets up with microphone packets of the same rate.
+/// The microphone is the clock: each microphone packet takes as many system
+/// samples as it holds, with silence filling in when the loopback lags. The
+/// samples that silence stood in for are skipped once they arrive, so a late
+/// loopback does not push system audio behind the microphone.
+pub struct SourceMixer {
+    system: VecDeque<f32>,
+    max_backlog: usize,
+    /// System samples already paired as silence and not yet skipped.
+    owed: usize,
+}
+
+impl SourceMixer {
+    /// `max_backlog` bounds how far system audio may run ahead of the
+    /// microphone before the oldest samples are dropped.
+    pub fn new(max_backlog: usize) -> Self {
+        Self {
+            system: VecDeque::with_capacity(max_backlog),
+            max_backlog,
+            owed: 0,
+        }
+    }
+
+    pub fn push_system(&mut self, samples: &[f32]) {
+        let skip = self.owed.min(samples.len());
+        self.owed -= skip;
+        self.system.extend(&samples[skip..]);
+        let excess = self.system.len().saturating_sub(self.max_backlog);
+        self.system.drain(..excess);
+    }
+
+    /// Pairs `microphone` with system audio as interleaved
+    /// `[microphone, system]` samples.
+    pub fn interleave(&mut self, microphone: &[f32]) -> Vec<f32> {
+        let mut out = Vec::with_capacity(microphone.len() * 2);
+        for &mic in microphone {
+            out.push(mic);
+            out.push(self.system.pop_front().unwrap_or_else(|| {
+                self.owed += 1;
+                0.0
+            }));
+        }
+        // Audio that arrives later than the backlog allows is stale anyway.
+        self.owed = self.owed.min(self.max_backlog);
+        out
+    }
+
+    /// Forget buffered and owed system audio, e.g. while the microphone is
+    /// paused and there is nothing to line it up with.
+    pub fn reset(&mut self) {
+        self.system.clear();
+        self.owed = 0;
+    }
+
+    /// Pairs `microphone` with system audio summed into one channel.
+    pub fn downmix(&mut self, microphone: &[f32]) -> Vec<f32> {
+        let interleaved = self.interleave(microphone);
+        downmix(&interleaved)
+    }
+}
+
+/// Sums interleaved `[microphone, system]` pairs, clamped to full scale.
+pub fn downmix(interleaved: &[f32]) -> Vec<f32> {
+    interleaved
+        .chunks_exact(2)
+        .map(|pair| (pair[0] + pair[1]).clamp(-1.0, 1.0))
+        .collect()
+}
+
+/// Splits interleaved `[microphone, system]` pairs into the two sources.
+pub fn deinterleave(interleaved: &[f32]) -> (Vec<f32>, Vec<f32>) {
+    interleaved
+        .chunks_exact(2)
+        .map(|pair| (pair[0], pair[1]))
+        .unzip()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lagging_system_audio_is_filled_with_silence() {
+        let mut mixer = SourceMixer::new(16);
+        mixer.push_system(&[0.5]);
+        assert_eq!(mixer.interleave(&[0.1, 0.2]), vec![0.1, 0.5, 0.2, 0.0]);
+    }
+
+    #[test]
+    fn late_system_audio_is_skipped_to_stay_in_sync() {
+        let mut mixer = SourceMixer::new(16);
+        mixer.push_system(&[0.5]);
+        assert_eq!(mixer.interleave(&[0.1, 0.2]), vec![0.1, 0.5, 0.2, 0.0]);
+        // The sample that was paired as silence arrives late and is dropped.
+        mixer.push_system(&[0.6, 0.7]);
+        assert_eq!(mixer.interleave(&[0.3, 0.4]), vec![0.3, 0.7, 0.4, 0.0]);
+    }
+
+    #[test]
+    fn owed_system_audio_is_bounded_by_the_backlog() {
+        let mut mixer = SourceMixer::new(2);
+        mixer.interleave(&[0.0; 8]);
+        mixer.push_system(&[0.1, 0.2, 0.3]);
+        assert_eq!(mixer.interleave(&[0.0]), vec![0.0, 0.3]);
+    }
+
+    #[test]
+    fn reset_drops_buffered_and_owed_system_audio() {
+        let mut mixer = SourceMixer::new(16);
+        mixer.interleave(&[0.0; 4]);
+        mixer.push_system(&[0.1]);
+        mixer.reset();
+        mixer.push_system(&[0.2]);
+        assert_eq!(mixer.interleave(&[0.0]), vec![0.0, 0.2]);
+    }
+
+    #[test]
+    fn system_backlog_drops_oldest_samples() {
+        let mut mixer = SourceMixer::new(2);
+        mixer.push_system(&[0.1, 0.2, 0.3]);
+        assert_eq!(mixer.interleave(&[0.0, 0.0]), vec![0.0, 0.2, 0.0, 0.3]);
+    }
+
+    #[test]
+    fn downmix_sums_and_clamps_sources() {
+        let mut mixer = SourceMixer::new(16);
+        mixer.push_system(&[0.25, 0.75]);
+        assert_eq!(mixer.downmix(&[0.5, 0.5]), vec![0.75, 1.0]);
+    }
+
+    #[test]
+    fn deinterleave_splits_microphone_and_system() {
+        let (microphone, system) = deinterleave(&[0.1, 0.2, 0.3, 0.4]);
+        assert_eq!(microphone, vec![0.1, 0.3]);
+        assert_eq!(system, vec![0.2, 0.4]);
+    }
+}
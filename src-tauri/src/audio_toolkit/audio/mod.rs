@@ -1,12 +1,37 @@
 // Re-export all audio components
 mod device;
+mod diagnostics;
+mod flac;
+mod long_form;
+mod loopback;
+mod mixer;
+mod processing;
 mod recorder;
 mod resampler;
+mod silence_stop;
+mod speech_map;
 mod utils;
 mod visualizer;
 
 pub use device::{list_input_devices, list_output_devices, CpalDeviceInfo};
-pub use recorder::{AudioRecorder, RecorderStartError, RecorderStartWait, RecorderStopWait};
+pub use diagnostics::{
+    amplitude_to_dbfs, percentile, vad_speech_ratio, DiagnosticCapture, LevelStats, SILENCE_DBFS,
+};
+pub use long_form::{stitch_chunk_text, LongFormChunker, RecordedChunk};
+pub use loopback::{
+    list_loopback_sources, LoopbackCapture, LoopbackSource, DEFAULT_MONITOR_SOURCE,
+};
+pub use mixer::{SourceChannels, SourceMixer};
+pub use processing::{AudioProcessingConfig, AudioProcessor};
+pub use recorder::{
+    AudioRecorder, CaptureInput, RecorderStartError, RecorderStartWait, RecorderStopWait,
+    SpeechSegment,
+};
 pub use resampler::FrameResampler;
-pub use utils::save_wav_file;
+pub use silence_stop::{validate_auto_stop, TrailingSilenceDetector};
+pub use speech_map::{CapturedAudio, SpeechMap};
+pub use utils::{
+    concat_recording_files, convert_recording_file, load_audio_file_for_transcription,
+    read_audio_file, resample_to_whisper_rate, save_flac_file, save_wav_file,
+};
 pub use visualizer::AudioVisualiser;
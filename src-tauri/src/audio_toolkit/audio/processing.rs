@@ -0,0 +1,501 @@
+//! Optional DSP stage for the recorder's 30 ms frames.
+//!
+//! Runs between `FrameResampler` and the VAD, in this order: a high-pass filter
+//! against rumble and DC, spectral-gating noise suppression against steady
+//! background noise (fans, hum), and automatic gain control with a peak limiter
+//! for quiet speakers. Every stage is off by default.
+
+use rustfft::{num_complex::Complex32, Fft, FftPlanner};
+use std::sync::Arc;
+
+const HIGH_PASS_CUTOFF_HZ: f32 = 80.0;
+
+/// Frames averaged to seed the noise estimate (~240 ms).
+const NOISE_INIT_FRAMES: usize = 8;
+/// A bin is treated as noise while its power stays below this multiple of the
+/// noise estimate (~8 dB).
+const NOISE_GATE_RATIO: f32 = 6.0;
+/// How quickly the estimate follows bins classified as noise.
+const NOISE_ADAPT_RATE: f32 = 0.05;
+/// Per-frame rise of the estimate in bins above the gate (~1.3 dB/s), so a new
+/// steady noise such as a fan switching on is learned within seconds.
+const NOISE_RISE_FACTOR: f32 = 1.01;
+/// Attenuation of gated bins (-20 dB).
+const NOISE_FLOOR_GAIN: f32 = 0.1;
+/// Smoothing of falling bin gains, which keeps "musical noise" down.
+const NOISE_GAIN_RELEASE: f32 = 0.6;
+
+/// Output level of the loudest recent frames (-20 dBFS RMS).
+const AGC_TARGET_RMS: f32 = 0.1;
+/// Frames quieter than this (~-46 dBFS) never raise the gain.
+const AGC_GATE_RMS: f32 = 0.005;
+const AGC_MAX_GAIN: f32 = 16.0;
+const AGC_MIN_GAIN: f32 = 0.25;
+/// Per-frame decay of the tracked speech level (~2.6 dB/s).
+const AGC_LEVEL_DECAY: f32 = 0.99;
+/// Peak ceiling enforced after gain (~-1 dBFS).
+const LIMITER_CEILING: f32 = 0.89;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AudioProcessingConfig {
+    pub high_pass_filter: bool,
+    pub noise_suppression: bool,
+    pub auto_gain: bool,
+}
+
+impl AudioProcessingConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.high_pass_filter || self.noise_suppression || self.auto_gain
+    }
+}
+
+pub struct AudioProcessor {
+    sample_rate: u32,
+    config: AudioProcessingConfig,
+    high_pass: HighPassFilter,
+    noise_suppressor: Option<NoiseSuppressor>,
+    auto_gain: AutoGainControl,
+    out: Vec<f32>,
+}
+
+impl AudioProcessor {
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            sample_rate,
+            config: AudioProcessingConfig::default(),
+            high_pass: HighPassFilter::new(sample_rate as f32, HIGH_PASS_CUTOFF_HZ),
+            noise_suppressor: None,
+            auto_gain: AutoGainControl::new(),
+            out: Vec::new(),
+        }
+    }
+
+    /// Processes one frame with `config`. Filter state is reset whenever the
+    /// configuration changes. With noise suppression enabled the output lags the
+    /// input by one frame; see [`AudioProcessor::flush`].
+    pub fn process<'a>(&'a mut self, frame: &'a [f32], config: AudioProcessingConfig) -> &'a [f32] {
+        if config != self.config {
+            self.config = config;
+            self.reset();
+        }
+        if !config.is_enabled() {
+            return frame;
+        }
+
+        self.out.clear();
+        self.out.extend_from_slice(frame);
+
+        if config.high_pass_filter {
+            self.high_pass.process(&mut self.out);
+        }
+        if config.noise_suppression {
+            let frame_len = self.out.len();
+            if self
+                .noise_suppressor
+                .as_ref()
+                .is_none_or(|suppressor| suppressor.hop != frame_len)
+            {
+                self.noise_suppressor = Some(NoiseSuppressor::new(frame_len));
+            }
+            if let Some(suppressor) = self.noise_suppressor.as_mut() {
+                suppressor.process(&mut self.out);
+            }
+        }
+        if config.auto_gain {
+            self.auto_gain.process(&mut self.out);
+        }
+
+        &self.out
+    }
+
+    /// Returns the frame still held back by noise suppression, if any. Call once
+    /// after the last [`AudioProcessor::process`] of a recording.
+    pub fn flush(&mut self) -> Option<&[f32]> {
+        if !self.config.noise_suppression {
+            return None;
+        }
+        let suppressor = self.noise_suppressor.as_mut()?;
+
+        self.out.clear();
+        self.out.resize(suppressor.hop, 0.0);
+        suppressor.process(&mut self.out);
+        suppressor.clear_overlap();
+        if self.config.auto_gain {
+            self.auto_gain.process(&mut self.out);
+        }
+
+        Some(&self.out)
+    }
+
+    fn reset(&mut self) {
+        self.high_pass = HighPassFilter::new(self.sample_rate as f32, HIGH_PASS_CUTOFF_HZ);
+        self.noise_suppressor = None;
+        self.auto_gain = AutoGainControl::new();
+    }
+}
+
+/// Second-order Butterworth high-pass (RBJ biquad, direct form I).
+struct HighPassFilter {
+    b0: f32,
+    b1: f32,
+    b2: f32,
+    a1: f32,
+    a2: f32,
+    x1: f32,
+    x2: f32,
+    y1: f32,
+    y2: f32,
+}
+
+impl HighPassFilter {
+    fn new(sample_rate: f32, cutoff_hz: f32) -> Self {
+        let w0 = 2.0 * std::f32::consts::PI * cutoff_hz / sample_rate;
+        let cos_w0 = w0.cos();
+        let alpha = w0.sin() / (2.0 * std::f32::consts::FRAC_1_SQRT_2);
+        let a0 = 1.0 + alpha;
+
+        Self {
+            b0: (1.0 + cos_w0) / 2.0 / a0,
+            b1: -(1.0 + cos_w0) / a0,
+            b2: (1.0 + cos_w0) / 2.0 / a0,
+            a1: -2.0 * cos_w0 / a0,
+            a2: (1.0 - alpha) / a0,
+            x1: 0.0,
+            x2: 0.0,
+            y1: 0.0,
+            y2: 0.0,
+        }
+    }
+
+    fn process(&mut self, samples: &mut [f32]) {
+        for sample in samples.iter_mut() {
+            let x0 = *sample;
+            let y0 = self.b0 * x0 + self.b1 * self.x1 + self.b2 * self.x2
+                - self.a1 * self.y1
+                - self.a2 * self.y2;
+            self.x2 = self.x1;
+            self.x1 = x0;
+            self.y2 = self.y1;
+            self.y1 = y0;
+            *sample = y0;
+        }
+    }
+}
+
+/// Spectral gating over a 50% overlapped sqrt-Hann STFT whose hop is the frame
+/// length, so every input frame yields exactly one output frame.
+struct NoiseSuppressor {
+    hop: usize,
+    fft: Arc<dyn Fft<f32>>,
+    ifft: Arc<dyn Fft<f32>>,
+    window: Vec<f32>,
+    spectrum: Vec<Complex32>,
+    previous_input: Vec<f32>,
+    overlap: Vec<f32>,
+    noise_power: Vec<f32>,
+    gains: Vec<f32>,
+    frames_seen: usize,
+}
+
+impl NoiseSuppressor {
+    fn new(hop: usize) -> Self {
+        let fft_len = hop * 2;
+        let mut planner = FftPlanner::<f32>::new();
+        let bins = fft_len / 2 + 1;
+
+        // Periodic sqrt-Hann: applied at analysis and synthesis, the squared
+        // windows sum to one at 50% overlap.
+        let window = (0..fft_len)
+            .map(|i| {
+                (0.5 * (1.0 - (2.0 * std::f32::consts::PI * i as f32 / fft_len as f32).cos()))
+                    .sqrt()
+            })
+            .collect();
+
+        Self {
+            hop,
+            fft: planner.plan_fft_forward(fft_len),
+            ifft: planner.plan_fft_inverse(fft_len),
+            window,
+            spectrum: vec![Complex32::new(0.0, 0.0); fft_len],
+            previous_input: vec![0.0; hop],
+            overlap: vec![0.0; hop],
+            noise_power: vec![0.0; bins],
+            gains: vec![1.0; bins],
+            frames_seen: 0,
+        }
+    }
+
+    fn clear_overlap(&mut self) {
+        self.previous_input.fill(0.0);
+        self.overlap.fill(0.0);
+    }
+
+    /// Replaces `frame` with the denoised previous frame.
+    fn process(&mut self, frame: &mut [f32]) {
+        let fft_len = self.hop * 2;
+        for i in 0..fft_len {
+            let sample = if i < self.hop {
+                self.previous_input[i]
+            } else {
+                frame[i - self.hop]
+            };
+            self.spectrum[i] = Complex32::new(sample * self.window[i], 0.0);
+        }
+        self.previous_input.copy_from_slice(frame);
+
+        self.fft.process(&mut self.spectrum);
+        self.update_gains();
+        for (i, bin) in self.spectrum.iter_mut().enumerate() {
+            let gain_index = if i <= self.hop { i } else { fft_len - i };
+            *bin *= self.gains[gain_index];
+        }
+        self.ifft.process(&mut self.spectrum);
+
+        let scale = 1.0 / fft_len as f32;
+        let (head, tail) = self.spectrum.split_at(self.hop);
+        let (head_window, tail_window) = self.window.split_at(self.hop);
+        for (i, (sample, overlap)) in frame.iter_mut().zip(&mut self.overlap).enumerate() {
+            *sample = *overlap + head[i].re * scale * head_window[i];
+            *overlap = tail[i].re * scale * tail_window[i];
+        }
+    }
+
+    fn update_gains(&mut self) {
+        self.frames_seen += 1;
+        let seeding = self.frames_seen <= NOISE_INIT_FRAMES;
+
+        for (k, noise) in self.noise_power.iter_mut().enumerate() {
+            let power = self.spectrum[k].norm_sqr();
+
+            if seeding {
+                *noise += (power - *noise) / self.frames_seen as f32;
+                self.gains[k] = 1.0;
+                continue;
+            }
+
+            let target = if power < NOISE_GATE_RATIO * *noise {
+                *noise += NOISE_ADAPT_RATE * (power - *noise);
+                NOISE_FLOOR_GAIN
+            } else {
+                *noise *= NOISE_RISE_FACTOR;
+                (1.0 - *noise / power).max(NOISE_FLOOR_GAIN)
+            };
+
+            let previous = self.gains[k];
+            self.gains[k] = if target >= previous {
+                target
+            } else {
+                NOISE_GAIN_RELEASE * previous + (1.0 - NOISE_GAIN_RELEASE) * target
+            };
+        }
+    }
+}
+
+/// Level-tracking gain control followed by a per-frame peak limiter. The level
+/// follows loud frames at once and decays slowly, so pauses between words do
+/// not pump the background noise up.
+struct AutoGainControl {
+    level: Option<f32>,
+    gain: f32,
+}
+
+impl AutoGainControl {
+    fn new() -> Self {
+        Self {
+            level: None,
+            gain: 1.0,
+        }
+    }
+
+    fn process(&mut self, samples: &mut [f32]) {
+        if samples.is_empty() {
+            return;
+        }
+
+        let rms = (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt();
+        if rms >= AGC_GATE_RMS {
+            self.level = Some(match self.level {
+                Some(level) => rms.max(level * AGC_LEVEL_DECAY),
+                None => rms,
+            });
+        }
+        let target_gain = self
+            .level
+            .map(|level| (AGC_TARGET_RMS / level).clamp(AGC_MIN_GAIN, AGC_MAX_GAIN))
+            .unwrap_or(self.gain);
+
+        // Ramp across the frame to avoid zipper noise.
+        let start_gain = self.gain;
+        let step = (target_gain - start_gain) / samples.len() as f32;
+        let mut peak = 0.0f32;
+        for (i, sample) in samples.iter_mut().enumerate() {
+            *sample *= start_gain + step * (i + 1) as f32;
+            peak = peak.max(sample.abs());
+        }
+        self.gain = target_gain;
+
+        if peak > LIMITER_CEILING {
+            let reduction = LIMITER_CEILING / peak;
+            for sample in samples.iter_mut() {
+                *sample *= reduction;
+            }
+            self.gain *= reduction;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: u32 = 16_000;
+    const FRAME: usize = 480;
+
+    fn sine(freq: f32, amplitude: f32, len: usize, offset: usize) -> Vec<f32> {
+        (0..len)
+            .map(|i| {
+                let t = (i + offset) as f32 / SAMPLE_RATE as f32;
+                amplitude * (2.0 * std::f32::consts::PI * freq * t).sin()
+            })
+            .collect()
+    }
+
+    /// Deterministic white noise in [-amplitude, amplitude].
+    fn noise(amplitude: f32, len: usize, seed: &mut u32) -> Vec<f32> {
+        (0..len)
+            .map(|_| {
+                *seed ^= *seed << 13;
+                *seed ^= *seed >> 17;
+                *seed ^= *seed << 5;
+                (*seed as f32 / u32::MAX as f32 * 2.0 - 1.0) * amplitude
+            })
+            .collect()
+    }
+
+    fn rms(samples: &[f32]) -> f32 {
+        (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+    }
+
+    fn run(
+        processor: &mut AudioProcessor,
+        input: &[f32],
+        config: AudioProcessingConfig,
+    ) -> Vec<f32> {
+        input
+            .chunks(FRAME)
+            .flat_map(|frame| processor.process(frame, config).to_vec())
+            .collect()
+    }
+
+    #[test]
+    fn disabled_config_passes_frames_through() {
+        let mut processor = AudioProcessor::new(SAMPLE_RATE);
+        let frame = sine(440.0, 0.3, FRAME, 0);
+        let output = processor.process(&frame, AudioProcessingConfig::default());
+        assert_eq!(output, frame.as_slice());
+        assert!(processor.flush().is_none());
+    }
+
+    #[test]
+    fn high_pass_removes_dc_and_rumble_but_keeps_speech_band() {
+        let config = AudioProcessingConfig {
+            high_pass_filter: true,
+            ..Default::default()
+        };
+        let len = FRAME * 50;
+        let speech = sine(1000.0, 0.5, len, 0);
+        let rumble = sine(20.0, 0.3, len, 0);
+        let input: Vec<f32> = speech
+            .iter()
+            .zip(&rumble)
+            .map(|(s, r)| s + r + 0.3)
+            .collect();
+
+        let mut processor = AudioProcessor::new(SAMPLE_RATE);
+        let output = run(&mut processor, &input, config);
+        let settled = &output[FRAME * 20..];
+
+        let mean = settled.iter().sum::<f32>() / settled.len() as f32;
+        assert!(mean.abs() < 0.01, "mean {mean}");
+        assert!((rms(settled) - rms(&speech)).abs() < 0.02);
+    }
+
+    #[test]
+    fn noise_suppression_attenuates_steady_noise_and_keeps_tone() {
+        let config = AudioProcessingConfig {
+            noise_suppression: true,
+            ..Default::default()
+        };
+        let mut seed = 0x1234_5678;
+        let noise_only = noise(0.05, FRAME * 40, &mut seed);
+        let tone = sine(1000.0, 0.3, FRAME * 10, 0);
+        let tone_noise = noise(0.05, FRAME * 10, &mut seed);
+        let noisy_tone: Vec<f32> = tone.iter().zip(&tone_noise).map(|(t, n)| t + n).collect();
+
+        let mut processor = AudioProcessor::new(SAMPLE_RATE);
+        let noise_output = run(&mut processor, &noise_only, config);
+        let tone_output = run(&mut processor, &noisy_tone, config);
+
+        // Skip the seeding frames; output lags by one frame.
+        let noise_in = rms(&noise_only[FRAME * 19..FRAME * 39]);
+        let noise_out = rms(&noise_output[FRAME * 20..]);
+        let reduction_db = 20.0 * (noise_in / noise_out).log10();
+        assert!(reduction_db > 12.0, "noise reduced by {reduction_db} dB");
+
+        let tone_out = rms(&tone_output[FRAME * 2..]);
+        let tone_ratio = tone_out / rms(&tone);
+        assert!((0.85..1.1).contains(&tone_ratio), "tone ratio {tone_ratio}");
+    }
+
+    #[test]
+    fn flush_returns_the_delayed_frame() {
+        let config = AudioProcessingConfig {
+            noise_suppression: true,
+            ..Default::default()
+        };
+        let mut processor = AudioProcessor::new(SAMPLE_RATE);
+        let frame = sine(1000.0, 0.3, FRAME, 0);
+        let first = processor.process(&frame, config).to_vec();
+        assert!(rms(&first) < rms(&frame));
+
+        let tail = processor.flush().expect("delayed frame").to_vec();
+        assert_eq!(tail.len(), FRAME);
+        assert!(rms(&tail) > 0.1);
+    }
+
+    #[test]
+    fn auto_gain_raises_quiet_speech_to_target() {
+        let config = AudioProcessingConfig {
+            auto_gain: true,
+            ..Default::default()
+        };
+        let input = sine(300.0, 0.01, FRAME * 30, 0);
+        let mut processor = AudioProcessor::new(SAMPLE_RATE);
+        let output = run(&mut processor, &input, config);
+
+        let level = rms(&output[FRAME * 20..]);
+        assert!((0.08..0.12).contains(&level), "level {level}");
+    }
+
+    #[test]
+    fn auto_gain_leaves_silence_alone_and_limits_sudden_peaks() {
+        let config = AudioProcessingConfig {
+            auto_gain: true,
+            ..Default::default()
+        };
+        let mut processor = AudioProcessor::new(SAMPLE_RATE);
+
+        let silence = sine(300.0, 0.001, FRAME, 0);
+        assert_eq!(processor.process(&silence, config), silence.as_slice());
+
+        let quiet = sine(300.0, 0.01, FRAME * 10, 0);
+        let loud = sine(300.0, 0.8, FRAME * 5, 0);
+        let mut input = quiet;
+        input.extend(loud);
+        let output = run(&mut processor, &input, config);
+
+        let peak = output.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+        assert!(peak <= LIMITER_CEILING + 1e-4, "peak {peak}");
+    }
+}
@@ -1,8 +1,9 @@
 use std::{
+    collections::VecDeque,
     fmt,
     io::Error,
     sync::{
-        atomic::{AtomicU64, Ordering},
+        atomic::{AtomicU64, AtomicUsize, Ordering},
         mpsc, Arc, Mutex,
     },
     time::{Duration, Instant},
@@ -14,18 +15,35 @@ use cpal::{
 };
 
 use crate::audio_toolkit::{
-    audio::{AudioVisualiser, FrameResampler},
+    audio::{
+        mixer::{deinterleave, downmix},
+        speech_map::CaptureTracker,
+        AudioProcessingConfig, AudioProcessor, AudioVisualiser, CapturedAudio, FrameResampler,
+        LongFormChunker, LoopbackCapture, RecordedChunk, SourceChannels, SourceMixer,
+    },
     constants,
     vad::{self, VadFrame},
     VoiceActivityDetector,
 };
 
+/// Length of the resampled frames fed to the DSP stage and VAD.
+const FRAME_MS: usize = 30;
+
+/// System-audio packets (~10ms each) queued for the mixer before the
+/// loopback starts dropping them.
+const SYSTEM_AUDIO_QUEUE_PACKETS: usize = 32;
+
+/// A gap this long between microphone packets means the stream is paused.
+const MIC_PAUSE: Duration = Duration::from_millis(50);
+
 enum Cmd {
     Start {
         start_id: u64,
         ready_tx: Option<mpsc::Sender<StartReadyAck>>,
     },
     Stop(mpsc::Sender<Vec<f32>>),
+    /// Hand over the speech in the recent-audio buffer and clear it.
+    TakeRecentAudio(mpsc::Sender<Vec<f32>>),
     Shutdown,
 }
 
@@ -133,6 +151,91 @@ impl RecorderStopWait {
     }
 }
 
+/// Speech audio handed to the segment callback while a recording is running.
+#[derive(Clone, Debug)]
+pub struct SpeechSegment {
+    /// 16 kHz mono speech samples for the current utterance so far.
+    pub samples: Vec<f32>,
+    /// True once VAD has seen the pause that ends this utterance.
+    pub utterance_complete: bool,
+}
+
+/// Cuts the VAD-filtered sample stream into utterances for incremental transcription.
+///
+/// An interim segment (the utterance so far) is emitted every `interim_samples` of
+/// new speech; a complete segment is emitted when VAD drops back to noise or the
+/// utterance reaches `max_utterance_samples`.
+struct SpeechSegmenter {
+    interim_samples: usize,
+    max_utterance_samples: usize,
+    utterance_start: usize,
+    last_emit_len: usize,
+    in_speech: bool,
+}
+
+impl SpeechSegmenter {
+    fn new(interim_samples: usize, max_utterance_samples: usize) -> Self {
+        Self {
+            interim_samples,
+            max_utterance_samples,
+            utterance_start: 0,
+            last_emit_len: 0,
+            in_speech: false,
+        }
+    }
+
+    fn reset(&mut self, processed_len: usize) {
+        self.utterance_start = processed_len;
+        self.last_emit_len = processed_len;
+        self.in_speech = false;
+    }
+
+    fn observe(&mut self, frame_was_speech: bool, processed: &[f32]) -> Option<SpeechSegment> {
+        if frame_was_speech {
+            self.in_speech = true;
+            if processed.len() - self.utterance_start >= self.max_utterance_samples {
+                return Some(self.complete(processed));
+            }
+            if processed.len() - self.last_emit_len >= self.interim_samples {
+                self.last_emit_len = processed.len();
+                return Some(SpeechSegment {
+                    samples: processed[self.utterance_start..].to_vec(),
+                    utterance_complete: false,
+                });
+            }
+            return None;
+        }
+
+        if self.in_speech {
+            self.in_speech = false;
+            if processed.len() > self.utterance_start {
+                return Some(self.complete(processed));
+            }
+        }
+        None
+    }
+
+    /// Shift buffer positions after the first `drained` samples were handed off
+    /// as a long-form chunk.
+    fn rebase(&mut self, drained: usize) {
+        self.utterance_start = self.utterance_start.saturating_sub(drained);
+        self.last_emit_len = self.last_emit_len.saturating_sub(drained);
+    }
+
+    fn complete(&mut self, processed: &[f32]) -> SpeechSegment {
+        let segment = SpeechSegment {
+            samples: processed[self.utterance_start..].to_vec(),
+            utterance_complete: true,
+        };
+        self.reset(processed.len());
+        segment
+    }
+}
+
+type SegmentCallback = Arc<dyn Fn(SpeechSegment) + Send + Sync + 'static>;
+type ActivityCallback = Arc<dyn Fn(bool) + Send + Sync + 'static>;
+type ChunkCallback = Arc<dyn Fn(RecordedChunk) + Send + Sync + 'static>;
+
 struct PreRollBuffer {
     slots: Vec<Vec<f32>>,
     head: usize,
@@ -148,6 +251,29 @@ impl PreRollBuffer {
         }
     }
 
+    fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Grows or shrinks the buffer, keeping the newest frames that still fit.
+    fn set_capacity(&mut self, capacity: usize) {
+        if capacity == self.slots.len() {
+            return;
+        }
+
+        let mut frames: Vec<Vec<f32>> = (0..self.len)
+            .map(|offset| {
+                let idx = (self.head + offset) % self.slots.len();
+                std::mem::take(&mut self.slots[idx])
+            })
+            .collect();
+        frames.drain(..frames.len().saturating_sub(capacity));
+        self.len = frames.len();
+        self.head = 0;
+        frames.resize_with(capacity, Vec::new);
+        self.slots = frames;
+    }
+
     fn push_frame(&mut self, frame: &[f32]) {
         if self.slots.is_empty() {
             return;
@@ -168,7 +294,8 @@ impl PreRollBuffer {
         slot.extend_from_slice(frame);
     }
 
-    fn drain_into<F>(&mut self, mut sink: F)
+    /// Feeds the newest `latest` frames to `sink` in order and empties the buffer.
+    fn drain_into<F>(&mut self, latest: usize, mut sink: F)
     where
         F: FnMut(&[f32]),
     {
@@ -176,7 +303,7 @@ impl PreRollBuffer {
             return;
         }
 
-        for offset in 0..self.len {
+        for offset in self.len.saturating_sub(latest)..self.len {
             let idx = (self.head + offset) % self.slots.len();
             sink(&self.slots[idx]);
         }
@@ -185,12 +312,144 @@ impl PreRollBuffer {
     }
 }
 
+/// Per-source audio of a mixed recording, trimmed to the frames the VAD keeps
+/// from the mix so it lines up with the transcribed audio.
+struct SourceTracks {
+    microphone: FrameResampler,
+    system: FrameResampler,
+    /// Resampled frame pairs waiting for their mix frame.
+    pending: VecDeque<(Vec<f32>, Vec<f32>)>,
+    /// The latest frame pairs, for frames the VAD keeps late (pre-roll,
+    /// smoothing prefill).
+    recent: VecDeque<(Vec<f32>, Vec<f32>)>,
+    /// Recent frames the VAD has not kept yet.
+    unkept: usize,
+    kept: SourceChannels,
+}
+
+impl SourceTracks {
+    const RECENT_FRAMES: usize = 64;
+
+    fn new(in_sample_rate: u32) -> Self {
+        let resampler = || {
+            FrameResampler::new(
+                in_sample_rate as usize,
+                constants::WHISPER_SAMPLE_RATE as usize,
+                Duration::from_millis(FRAME_MS as u64),
+            )
+        };
+        Self {
+            microphone: resampler(),
+            system: resampler(),
+            pending: VecDeque::new(),
+            recent: VecDeque::with_capacity(Self::RECENT_FRAMES),
+            unkept: 0,
+            kept: SourceChannels::default(),
+        }
+    }
+
+    /// Queues an interleaved `[microphone, system]` packet and returns its mono
+    /// mix for the main pipeline.
+    fn push_packet(&mut self, interleaved: &[f32]) -> Vec<f32> {
+        let (microphone, system) = deinterleave(interleaved);
+        let mut microphone_frames = Vec::new();
+        self.microphone
+            .push(&microphone, |frame| microphone_frames.push(frame.to_vec()));
+        let mut system_frames = Vec::new();
+        self.system
+            .push(&system, |frame| system_frames.push(frame.to_vec()));
+        self.pending
+            .extend(microphone_frames.into_iter().zip(system_frames));
+        downmix(interleaved)
+    }
+
+    /// Flushes the partial frames left at the end of a recording.
+    fn finish(&mut self) {
+        let mut microphone_frames = Vec::new();
+        self.microphone
+            .finish(|frame| microphone_frames.push(frame.to_vec()));
+        let mut system_frames = Vec::new();
+        self.system
+            .finish(|frame| system_frames.push(frame.to_vec()));
+        self.pending
+            .extend(microphone_frames.into_iter().zip(system_frames));
+    }
+
+    /// Moves on by one frame; call once for every resampled mix frame.
+    fn advance(&mut self) {
+        let pair = self.pending.pop_front().unwrap_or_default();
+        if self.recent.len() == Self::RECENT_FRAMES {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(pair);
+        self.unkept = (self.unkept + 1).min(Self::RECENT_FRAMES);
+    }
+
+    /// Keeps the sources of the newest frames after the VAD appended `samples`
+    /// samples of the mix. Mix audio without source frames, such as the tail
+    /// flushed out of the DSP stage, is matched with silence.
+    fn keep(&mut self, samples: usize) {
+        if samples == 0 {
+            return;
+        }
+        let frame_len = constants::WHISPER_SAMPLE_RATE as usize * FRAME_MS / 1000;
+        let frames = samples.div_ceil(frame_len).min(self.unkept);
+        let target = self.kept.microphone.len() + samples;
+        for (microphone, system) in self.recent.iter().skip(self.recent.len() - frames) {
+            self.kept.microphone.extend_from_slice(microphone);
+            self.kept.system.extend_from_slice(system);
+        }
+        self.kept.microphone.resize(target, 0.0);
+        self.kept.system.resize(target, 0.0);
+        self.unkept = 0;
+    }
+
+    /// Starts collecting a new recording; buffered frames stay for pre-roll.
+    fn restart(&mut self) {
+        self.kept = SourceChannels::default();
+        self.unkept = self.recent.len();
+    }
+
+    fn take(&mut self) -> SourceChannels {
+        std::mem::take(&mut self.kept)
+    }
+}
+
+/// Where the recorder takes its audio from.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum CaptureInput {
+    #[default]
+    Microphone,
+    /// A PulseAudio / PipeWire monitor source instead of the microphone.
+    Loopback { source: String },
+    /// The microphone and a monitor source at once. With `keep_separate` the
+    /// recorder also keeps each source's audio; see `take_source_channels`.
+    Mixed { source: String, keep_separate: bool },
+}
+
+/// The input streams feeding one open recorder.
+struct CaptureStreams {
+    sample_rate: u32,
+    stream: Option<cpal::Stream>,
+    loopback: Option<LoopbackCapture>,
+    source_tracks: Option<SourceTracks>,
+}
+
 pub struct AudioRecorder {
     device: Option<Device>,
     cmd_tx: Option<mpsc::Sender<Cmd>>,
     worker_handle: Option<std::thread::JoinHandle<()>>,
     vad: Option<Arc<Mutex<Box<dyn vad::VoiceActivityDetector>>>>,
     level_cb: Option<Arc<dyn Fn(Vec<f32>) + Send + Sync + 'static>>,
+    segment_cb: Option<SegmentCallback>,
+    activity_cb: Option<ActivityCallback>,
+    chunk_cb: Option<ChunkCallback>,
+    chunking: Arc<Mutex<Option<LongFormChunker>>>,
+    recent_audio_frames: Arc<AtomicUsize>,
+    processing: Arc<Mutex<AudioProcessingConfig>>,
+    capture: CaptureInput,
+    source_channels: Arc<Mutex<Option<SourceChannels>>>,
+    captured_audio: Arc<Mutex<Option<CapturedAudio>>>,
     cached_config: Option<cpal::SupportedStreamConfig>,
     next_start_id: AtomicU64,
 }
@@ -203,6 +462,15 @@ impl AudioRecorder {
             worker_handle: None,
             vad: None,
             level_cb: None,
+            segment_cb: None,
+            activity_cb: None,
+            chunk_cb: None,
+            chunking: Arc::new(Mutex::new(None)),
+            recent_audio_frames: Arc::new(AtomicUsize::new(0)),
+            processing: Arc::new(Mutex::new(AudioProcessingConfig::default())),
+            capture: CaptureInput::Microphone,
+            source_channels: Arc::new(Mutex::new(None)),
+            captured_audio: Arc::new(Mutex::new(None)),
             cached_config: None,
             next_start_id: AtomicU64::new(1),
         })
@@ -213,6 +481,15 @@ impl AudioRecorder {
         self
     }
 
+    /// Swaps the detector used by a running stream; the new detector starts
+    /// from a clean state on the next frame.
+    pub fn set_vad(&mut self, vad: Box<dyn VoiceActivityDetector>) {
+        match &self.vad {
+            Some(existing) => *existing.lock().unwrap() = vad,
+            None => self.vad = Some(Arc::new(Mutex::new(vad))),
+        }
+    }
+
     pub fn with_level_callback<F>(mut self, cb: F) -> Self
     where
         F: Fn(Vec<f32>) + Send + Sync + 'static,
@@ -220,6 +497,107 @@ impl AudioRecorder {
         self.level_cb = Some(Arc::new(cb));
         self
     }
+
+    /// Receive VAD-segmented speech while recording, for streaming partial transcripts.
+    /// The callback runs on the recorder worker thread and must not block.
+    pub fn with_segment_callback<F>(mut self, cb: F) -> Self
+    where
+        F: Fn(SpeechSegment) + Send + Sync + 'static,
+    {
+        self.segment_cb = Some(Arc::new(cb));
+        self
+    }
+
+    /// Receive the VAD decision for every recorded frame (~30 ms), e.g. to
+    /// detect trailing silence. Runs on the recorder worker thread and must
+    /// not block.
+    pub fn with_activity_callback<F>(mut self, cb: F) -> Self
+    where
+        F: Fn(bool) + Send + Sync + 'static,
+    {
+        self.activity_cb = Some(Arc::new(cb));
+        self
+    }
+
+    /// Receive long-form chunks cut from the running recording while chunking
+    /// is enabled. Runs on the recorder worker thread and must not block.
+    pub fn with_chunk_callback<F>(mut self, cb: F) -> Self
+    where
+        F: Fn(RecordedChunk) + Send + Sync + 'static,
+    {
+        self.chunk_cb = Some(Arc::new(cb));
+        self
+    }
+
+    /// Enables or disables cutting the current recording into long-form
+    /// chunks. While enabled, `stop` only returns the audio after the last chunk.
+    pub fn set_chunking(&self, chunker: Option<LongFormChunker>) {
+        *self.chunking.lock().unwrap() = chunker;
+    }
+
+    pub fn with_recent_audio(self, secs: u32) -> Self {
+        self.set_recent_audio(secs);
+        self
+    }
+
+    /// Keeps the last `secs` seconds of audio while not recording, so they can
+    /// be transcribed after the fact with `begin_take_recent_audio`; 0 turns
+    /// it off. While enabled the input stream keeps running between recordings.
+    pub fn set_recent_audio(&self, secs: u32) {
+        let frames = secs as usize * 1000 / FRAME_MS;
+        self.recent_audio_frames.store(frames, Ordering::Relaxed);
+    }
+
+    /// Asks for the speech in the recent-audio buffer, VAD-filtered like a
+    /// recording; wait on the result like a stop. The buffer is cleared, so the
+    /// same audio is never returned twice.
+    pub fn begin_take_recent_audio(&self) -> Result<RecorderStopWait, Box<dyn std::error::Error>> {
+        let tx = self.cmd_tx.as_ref().ok_or_else(|| {
+            Error::new(
+                std::io::ErrorKind::NotConnected,
+                "Recorder command channel not initialized",
+            )
+        })?;
+
+        let (resp_tx, resp_rx) = mpsc::channel();
+        tx.send(Cmd::TakeRecentAudio(resp_tx))?;
+        Ok(RecorderStopWait {
+            samples_rx: resp_rx,
+        })
+    }
+
+    pub fn with_processing(self, config: AudioProcessingConfig) -> Self {
+        self.set_processing(config);
+        self
+    }
+
+    /// Updates the DSP stage; takes effect from the next captured packet.
+    pub fn set_processing(&self, config: AudioProcessingConfig) {
+        *self.processing.lock().unwrap() = config;
+    }
+
+    /// Chooses the input for the next `open`; a running stream keeps its input
+    /// until it is closed.
+    pub fn set_capture_input(&mut self, capture: CaptureInput) {
+        if capture != self.capture {
+            self.capture = capture;
+            self.cached_config = None;
+        }
+    }
+
+    /// The microphone and system audio of the last stopped recording when
+    /// capturing `CaptureInput::Mixed` with `keep_separate`, trimmed to the
+    /// speech the VAD kept. Take it after the stop completes.
+    pub fn take_source_channels(&self) -> Option<SourceChannels> {
+        self.source_channels.lock().unwrap().take()
+    }
+
+    /// The full capture of the last stopped recording, before the VAD dropped
+    /// its pauses, and where the returned speech sits in it. Take it after
+    /// the stop completes.
+    pub fn take_captured_audio(&self) -> Option<CapturedAudio> {
+        self.captured_audio.lock().unwrap().take()
+    }
     
     pub fn reset_cache(&mut self) {
         self.cached_config = None;
@@ -234,69 +612,63 @@ impl AudioRecorder {
         let (sample_tx, sample_rx) = mpsc::channel::<Vec<f32>>();
         let (cmd_tx, cmd_rx) = mpsc::channel::<Cmd>();
 
-        let host = crate::audio_toolkit::get_cpal_host();
-        let device = match device {
-            Some(dev) => dev,
-            None => host
-                .default_input_device()
-                .ok_or_else(|| Error::new(std::io::ErrorKind::NotFound, "No input device found"))?,
+        // System audio on its own does not need a microphone.
+        let device = if matches!(self.capture, CaptureInput::Loopback { .. }) {
+            None
+        } else {
+            let host = crate::audio_toolkit::get_cpal_host();
+            Some(match device {
+                Some(dev) => dev,
+                None => host.default_input_device().ok_or_else(|| {
+                    Error::new(std::io::ErrorKind::NotFound, "No input device found")
+                })?,
+            })
         };
 
         let thread_device = device.clone();
         
         // Use cached config if available, otherwise fetch and cache it
-        let config = if let Some(ref config) = self.cached_config {
-            config.clone()
-        } else {
-            let config = AudioRecorder::get_preferred_config(&thread_device)
-                .expect("failed to fetch preferred config");
-            self.cached_config = Some(config.clone());
-            config
+        let config = match thread_device.as_ref() {
+            None => None,
+            Some(device) => Some(match self.cached_config.clone() {
+                Some(config) => config,
+                None => {
+                    let config = AudioRecorder::get_preferred_config(device)
+                        .expect("failed to fetch preferred config");
+                    self.cached_config = Some(config.clone());
+                    config
+                }
+            }),
         };
         
         let vad = self.vad.clone();
         // Move the optional level callback into the worker thread
         let level_cb = self.level_cb.clone();
+        let segment_cb = self.segment_cb.clone();
+        let activity_cb = self.activity_cb.clone();
+        let chunk_cb = self.chunk_cb.clone();
+        let chunking = self.chunking.clone();
+        let recent_audio_frames = self.recent_audio_frames.clone();
+        let processing = self.processing.clone();
+        let capture = self.capture.clone();
+        let source_channels = self.source_channels.clone();
+        let captured_audio = self.captured_audio.clone();
         
         let (startup_tx, startup_rx) = mpsc::channel::<Result<mpsc::Receiver<()>, Box<dyn std::error::Error + Send + Sync>>>();
 
         let worker = std::thread::spawn(move || {
             let worker_started = Instant::now();
             let (data_started_tx, data_started_rx) = mpsc::channel::<()>();
-            let sample_rate = config.sample_rate().0;
-            let channels = config.channels() as usize;
-
-            tracing::info!(
-                "Using device: {:?}\nSample rate: {}\nChannels: {}\nFormat: {:?}",
-                thread_device.name(),
-                sample_rate,
-                channels,
-                config.sample_format()
-            );
-
-            let stream_result = match config.sample_format() {
-                cpal::SampleFormat::U8 => {
-                    AudioRecorder::build_stream::<u8>(&thread_device, &config, sample_tx, channels)
-                }
-                cpal::SampleFormat::I8 => {
-                    AudioRecorder::build_stream::<i8>(&thread_device, &config, sample_tx, channels)
-                }
-                cpal::SampleFormat::I16 => {
-                    AudioRecorder::build_stream::<i16>(&thread_device, &config, sample_tx, channels)
-                }
-                cpal::SampleFormat::I32 => {
-                    AudioRecorder::build_stream::<i32>(&thread_device, &config, sample_tx, channels)
-                }
-                cpal::SampleFormat::F32 => {
-                    AudioRecorder::build_stream::<f32>(&thread_device, &config, sample_tx, channels)
-                }
-                _ => panic!("unsupported sample format"),
-            };
 
-            let stream = match stream_result {
-                Ok(s) => s,
+            let streams = match AudioRecorder::open_capture(
+                &capture,
+                thread_device.as_ref(),
+                config.as_ref(),
+                sample_tx,
+            ) {
+                Ok(streams) => streams,
                 Err(e) => {
-                    let _ = startup_tx.send(Err(Box::new(e)));
+                    let _ = startup_tx.send(Err(e));
                     return;
                 }
             };
@@ -307,9 +679,11 @@ impl AudioRecorder {
                 "Recorder worker built input stream"
             );
 
-            if let Err(e) = stream.play() {
-                let _ = startup_tx.send(Err(Box::new(e)));
-                return;
+            if let Some(stream) = &streams.stream {
+                if let Err(e) = stream.play() {
+                    let _ = startup_tx.send(Err(Box::new(e)));
+                    return;
+                }
             }
             tracing::debug!(
                 event_code = "stream_open_subphase",
@@ -321,9 +695,32 @@ impl AudioRecorder {
             // Signal success, providing the receiver for data-started signal
             let _ = startup_tx.send(Ok(data_started_rx));
 
+            let CaptureStreams {
+                sample_rate,
+                stream,
+                loopback: _loopback,
+                source_tracks,
+            } = streams;
             // keep the stream alive while we process samples
-            run_consumer(sample_rate, vad, sample_rx, cmd_rx, level_cb, Some(data_started_tx), Some(stream));
-            // stream is dropped here, after run_consumer returns
+            run_consumer(
+                sample_rate,
+                vad,
+                sample_rx,
+                cmd_rx,
+                level_cb,
+                segment_cb,
+                activity_cb,
+                chunk_cb,
+                chunking,
+                recent_audio_frames,
+                processing,
+                source_tracks,
+                source_channels,
+                captured_audio,
+                Some(data_started_tx),
+                stream,
+            );
+            // streams are dropped here, after run_consumer returns
         });
 
         // Wait for the stream to start
@@ -344,7 +741,7 @@ impl AudioRecorder {
                     "Recorder stream received first audio packet"
                 );
 
-                self.device = Some(device);
+                self.device = device;
                 self.cmd_tx = Some(cmd_tx);
                 self.worker_handle = Some(worker);
                 tracing::debug!(
@@ -423,6 +820,128 @@ impl AudioRecorder {
         Ok(())
     }
 
+    /// Starts the streams for `capture`; a microphone stream is returned
+    /// unstarted.
+    fn open_capture(
+        capture: &CaptureInput,
+        device: Option<&Device>,
+        config: Option<&cpal::SupportedStreamConfig>,
+        sample_tx: mpsc::Sender<Vec<f32>>,
+    ) -> Result<CaptureStreams, Box<dyn std::error::Error + Send + Sync>> {
+        if let CaptureInput::Loopback { source } = capture {
+            let sample_rate = constants::WHISPER_SAMPLE_RATE;
+            let loopback = LoopbackCapture::spawn(source, sample_rate, move |samples| {
+                sample_tx.send(samples).is_ok()
+            })?;
+            return Ok(CaptureStreams {
+                sample_rate,
+                stream: None,
+                loopback: Some(loopback),
+                source_tracks: None,
+            });
+        }
+
+        let (device, config) = device.zip(config).ok_or("No input device found")?;
+        let sample_rate = config.sample_rate().0;
+        tracing::info!(
+            "Using device: {:?}\nSample rate: {}\nChannels: {}\nFormat: {:?}",
+            device.name(),
+            sample_rate,
+            config.channels(),
+            config.sample_format()
+        );
+
+        let CaptureInput::Mixed {
+            source,
+            keep_separate,
+        } = capture
+        else {
+            let stream = AudioRecorder::build_input_stream(device, config, sample_tx)?;
+            return Ok(CaptureStreams {
+                sample_rate,
+                stream: Some(stream),
+                loopback: None,
+                source_tracks: None,
+            });
+        };
+
+        // The microphone paces the mix: every microphone packet is paired with
+        // the same number of system-audio samples.
+        let (mic_tx, mic_rx) = mpsc::channel::<Vec<f32>>();
+        let (system_tx, system_rx) = mpsc::sync_channel::<Vec<f32>>(SYSTEM_AUDIO_QUEUE_PACKETS);
+        let stream = AudioRecorder::build_input_stream(device, config, mic_tx)?;
+        // Packets are dropped while the queue is full, so a stalled mixer
+        // cannot make it grow.
+        let loopback = LoopbackCapture::spawn(source, sample_rate, move |samples| {
+            !matches!(
+                system_tx.try_send(samples),
+                Err(mpsc::TrySendError::Disconnected(_))
+            )
+        })?;
+        let keep_separate = *keep_separate;
+        std::thread::spawn(move || {
+            // System audio may run at most 200ms ahead of the microphone.
+            let mut mixer = SourceMixer::new(sample_rate as usize / 5);
+            loop {
+                let microphone = match mic_rx.recv_timeout(MIC_PAUSE) {
+                    Ok(microphone) => microphone,
+                    Err(mpsc::RecvTimeoutError::Timeout) => {
+                        // Paused between recordings: keep the loopback
+                        // drained and drop what cannot be lined up.
+                        while system_rx.try_recv().is_ok() {}
+                        mixer.reset();
+                        continue;
+                    }
+                    Err(mpsc::RecvTimeoutError::Disconnected) => break,
+                };
+                while let Ok(system) = system_rx.try_recv() {
+                    mixer.push_system(&system);
+                }
+                let packet = if keep_separate {
+                    mixer.interleave(&microphone)
+                } else {
+                    mixer.downmix(&microphone)
+                };
+                if sample_tx.send(packet).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(CaptureStreams {
+            sample_rate,
+            stream: Some(stream),
+            loopback: Some(loopback),
+            source_tracks: keep_separate.then(|| SourceTracks::new(sample_rate)),
+        })
+    }
+
+    fn build_input_stream(
+        device: &cpal::Device,
+        config: &cpal::SupportedStreamConfig,
+        sample_tx: mpsc::Sender<Vec<f32>>,
+    ) -> Result<cpal::Stream, cpal::BuildStreamError> {
+        let channels = config.channels() as usize;
+        match config.sample_format() {
+            cpal::SampleFormat::U8 => {
+                AudioRecorder::build_stream::<u8>(device, config, sample_tx, channels)
+            }
+            cpal::SampleFormat::I8 => {
+                AudioRecorder::build_stream::<i8>(device, config, sample_tx, channels)
+            }
+            cpal::SampleFormat::I16 => {
+                AudioRecorder::build_stream::<i16>(device, config, sample_tx, channels)
+            }
+            cpal::SampleFormat::I32 => {
+                AudioRecorder::build_stream::<i32>(device, config, sample_tx, channels)
+            }
+            cpal::SampleFormat::F32 => {
+                AudioRecorder::build_stream::<f32>(device, config, sample_tx, channels)
+            }
+            _ => panic!("unsupported sample format"),
+        }
+    }
+
     fn build_stream<T>(
         device: &cpal::Device,
         config: &cpal::SupportedStreamConfig,
@@ -469,7 +988,7 @@ impl AudioRecorder {
         )
     }
 
-    fn get_preferred_config(
+    pub(crate) fn get_preferred_config(
         device: &cpal::Device,
     ) -> Result<cpal::SupportedStreamConfig, Box<dyn std::error::Error>> {
         let supported_configs = device.supported_input_configs()?;
@@ -514,6 +1033,15 @@ fn run_consumer(
     sample_rx: mpsc::Receiver<Vec<f32>>,
     cmd_rx: mpsc::Receiver<Cmd>,
     level_cb: Option<Arc<dyn Fn(Vec<f32>) + Send + Sync + 'static>>,
+    segment_cb: Option<SegmentCallback>,
+    activity_cb: Option<ActivityCallback>,
+    chunk_cb: Option<ChunkCallback>,
+    chunking: Arc<Mutex<Option<LongFormChunker>>>,
+    recent_audio_frames: Arc<AtomicUsize>,
+    processing: Arc<Mutex<AudioProcessingConfig>>,
+    mut source_tracks: Option<SourceTracks>,
+    source_channels: Arc<Mutex<Option<SourceChannels>>>,
+    captured_audio: Arc<Mutex<Option<CapturedAudio>>>,
     mut data_started_tx: Option<mpsc::Sender<()>>,
     stream: Option<cpal::Stream>,
 ) {
@@ -522,10 +1050,14 @@ fn run_consumer(
     let mut frame_resampler = FrameResampler::new(
         in_sample_rate as usize,
         constants::WHISPER_SAMPLE_RATE as usize,
-        Duration::from_millis(30),
+        Duration::from_millis(FRAME_MS as u64),
     );
+    // Optional noise suppression / AGC on the resampled frames, ahead of VAD.
+    let mut processor = AudioProcessor::new(constants::WHISPER_SAMPLE_RATE);
 
     let mut processed_samples = Vec::<f32>::new();
+    // The whole recording, for history, and where the kept speech sits in it.
+    let mut capture_tracker = CaptureTracker::default();
     let mut recording = false;
     let mut pending_start_ready: Option<PendingStartReady> = None;
     
@@ -541,7 +1073,16 @@ fn run_consumer(
     // Assumes ~30ms resampler frames: 9 frames * 30ms = 270ms pre-roll.
     // Recalibrate this constant if the frame duration changes.
     const PREROLL_FRAMES: usize = 9;
+    // The same ring holds the recent-audio buffer when that is enabled; Start
+    // still only takes the newest PREROLL_FRAMES from it.
     let mut pre_roll_frames = PreRollBuffer::new(PREROLL_FRAMES);
+    // Partial transcripts: refresh the running utterance every ~1s of speech and
+    // force a cut at 8s. A partial pass still running at stop delays the final
+    // pass, so no single partial may grow long.
+    let mut segmenter = SpeechSegmenter::new(
+        constants::WHISPER_SAMPLE_RATE as usize,
+        constants::WHISPER_SAMPLE_RATE as usize * 8,
+    );
 
     // ---------- spectrum visualisation setup ---------------------------- //
     const BUCKETS: usize = 16;
@@ -559,22 +1100,45 @@ fn run_consumer(
         recording: bool,
         vad: &Option<Arc<Mutex<Box<dyn vad::VoiceActivityDetector>>>>,
         out_buf: &mut Vec<f32>,
-    ) {
+    ) -> bool {
         if !recording {
-            return;
+            return false;
         }
 
         if let Some(vad_arc) = vad {
             let mut det = vad_arc.lock().unwrap();
             match det.push_frame(samples).unwrap_or(VadFrame::Speech(samples)) {
-                VadFrame::Speech(buf) => out_buf.extend_from_slice(buf),
-                VadFrame::Noise => {}
+                VadFrame::Speech(buf) => {
+                    out_buf.extend_from_slice(buf);
+                    true
+                }
+                VadFrame::Noise => false,
             }
         } else {
             out_buf.extend_from_slice(samples);
+            true
         }
     }
 
+    /// `handle_frame` for a recording, keeping the full capture and the
+    /// sources of what it kept.
+    fn handle_tracked_frame(
+        samples: &[f32],
+        vad: &Option<Arc<Mutex<Box<dyn vad::VoiceActivityDetector>>>>,
+        out_buf: &mut Vec<f32>,
+        source_tracks: &mut Option<SourceTracks>,
+        capture: &mut CaptureTracker,
+    ) -> bool {
+        let kept_from = out_buf.len();
+        let frame_was_speech = handle_frame(samples, true, vad, out_buf);
+        let kept = out_buf.len() - kept_from;
+        capture.observe(samples, kept);
+        if let Some(tracks) = source_tracks {
+            tracks.keep(kept);
+        }
+        frame_was_speech
+    }
+
     struct PendingStartReady {
         start_id: u64,
         ready_tx: mpsc::Sender<StartReadyAck>,
@@ -590,6 +1154,9 @@ fn run_consumer(
         vad: &Option<Arc<Mutex<Box<dyn vad::VoiceActivityDetector>>>>,
         pending_start_ready: &mut Option<PendingStartReady>,
         pre_roll_frames: &mut PreRollBuffer,
+        segmenter: &mut SpeechSegmenter,
+        source_tracks: &mut Option<SourceTracks>,
+        capture: &mut CaptureTracker,
     ) {
         tracing::debug!(
             event_code = "recorder_start_subphase",
@@ -605,6 +1172,7 @@ fn run_consumer(
         }
 
         processed_samples.clear();
+        segmenter.reset(0);
         *recording = true;
         *warmup_remaining = WARMUP_FRAMES;
         visualizer.reset();
@@ -614,7 +1182,34 @@ fn run_consumer(
             v.lock().unwrap().reset();
         }
 
-        pre_roll_frames.drain_into(|frame| handle_frame(frame, true, vad, processed_samples));
+        if let Some(tracks) = source_tracks.as_mut() {
+            tracks.restart();
+        }
+        capture.restart();
+        pre_roll_frames.drain_into(PREROLL_FRAMES, |frame| {
+            handle_tracked_frame(frame, vad, processed_samples, source_tracks, capture);
+        });
+    }
+
+    fn apply_take_recent_audio_command(
+        reply_tx: mpsc::Sender<Vec<f32>>,
+        recording: bool,
+        vad: &Option<Arc<Mutex<Box<dyn vad::VoiceActivityDetector>>>>,
+        pre_roll_frames: &mut PreRollBuffer,
+    ) {
+        let mut speech = Vec::new();
+        if !recording {
+            if let Some(v) = vad {
+                v.lock().unwrap().reset();
+            }
+            pre_roll_frames.drain_into(usize::MAX, |frame| {
+                handle_frame(frame, true, vad, &mut speech);
+            });
+            if let Some(v) = vad {
+                v.lock().unwrap().reset();
+            }
+        }
+        let _ = reply_tx.send(speech);
     }
 
     fn apply_stop_command(
@@ -623,26 +1218,57 @@ fn run_consumer(
         pending_start_ready: &mut Option<PendingStartReady>,
         sample_rx: &mpsc::Receiver<Vec<f32>>,
         frame_resampler: &mut FrameResampler,
+        processor: &mut AudioProcessor,
+        processing: &Mutex<AudioProcessingConfig>,
         vad: &Option<Arc<Mutex<Box<dyn vad::VoiceActivityDetector>>>>,
         processed_samples: &mut Vec<f32>,
+        source_tracks: &mut Option<SourceTracks>,
+        source_channels: &Mutex<Option<SourceChannels>>,
+        capture: &mut CaptureTracker,
+        captured_audio: &Mutex<Option<CapturedAudio>>,
     ) {
         let was_recording = *recording;
         *recording = false;
         *pending_start_ready = None;
 
         if was_recording {
+            let processing_config = *processing.lock().unwrap();
             // Drain any audio chunks that were captured but not yet consumed.
             while let Ok(remaining) = sample_rx.try_recv() {
+                let remaining = match source_tracks.as_mut() {
+                    Some(tracks) => tracks.push_packet(&remaining),
+                    None => remaining,
+                };
                 frame_resampler.push(&remaining, &mut |frame: &[f32]| {
-                    handle_frame(frame, true, vad, processed_samples)
+                    if let Some(tracks) = source_tracks.as_mut() {
+                        tracks.advance();
+                    }
+                    let frame = processor.process(frame, processing_config);
+                    handle_tracked_frame(frame, vad, processed_samples, source_tracks, capture);
                 });
             }
 
+            if let Some(tracks) = source_tracks.as_mut() {
+                tracks.finish();
+            }
             frame_resampler.finish(&mut |frame: &[f32]| {
-                handle_frame(frame, true, vad, processed_samples)
+                if let Some(tracks) = source_tracks.as_mut() {
+                    tracks.advance();
+                }
+                let frame = processor.process(frame, processing_config);
+                handle_tracked_frame(frame, vad, processed_samples, source_tracks, capture);
             });
+
+            if let Some(frame) = processor.flush() {
+                handle_tracked_frame(frame, vad, processed_samples, source_tracks, capture);
+            }
         }
 
+        *source_channels.lock().unwrap() = source_tracks
+            .as_mut()
+            .filter(|_| was_recording)
+            .map(SourceTracks::take);
+        *captured_audio.lock().unwrap() = was_recording.then(|| capture.finish());
         let _ = reply_tx.send(std::mem::take(processed_samples));
     }
 
@@ -662,7 +1288,26 @@ fn run_consumer(
         }
     }
 
+    /// The recent-audio buffer keeps listening between recordings.
+    fn pause_unless_buffering(stream: &Option<cpal::Stream>, pre_roll_frames: &PreRollBuffer) {
+        if pre_roll_frames.capacity() <= PREROLL_FRAMES {
+            pause_stream(stream);
+        }
+    }
+
     loop {
+        let retained = PREROLL_FRAMES.max(recent_audio_frames.load(Ordering::Relaxed));
+        if retained != pre_roll_frames.capacity() {
+            pre_roll_frames.set_capacity(retained);
+            if !recording {
+                if retained > PREROLL_FRAMES {
+                    resume_stream(&stream);
+                } else {
+                    pause_stream(&stream);
+                }
+            }
+        }
+
         let raw = match sample_rx.recv_timeout(COMMAND_POLL_TIMEOUT) {
             Ok(s) => s,
             Err(mpsc::RecvTimeoutError::Timeout) => {
@@ -681,20 +1326,35 @@ fn run_consumer(
                                 &vad,
                                 &mut pending_start_ready,
                                 &mut pre_roll_frames,
+                                &mut segmenter,
+                                &mut source_tracks,
+                                &mut capture_tracker,
                             )
                         }
                         Cmd::Stop(reply_tx) => {
-                            pause_stream(&stream);
+                            pause_unless_buffering(&stream, &pre_roll_frames);
                             apply_stop_command(
                                 reply_tx,
                                 &mut recording,
                                 &mut pending_start_ready,
                                 &sample_rx,
                                 &mut frame_resampler,
+                                &mut processor,
+                                &processing,
                                 &vad,
                                 &mut processed_samples,
+                                &mut source_tracks,
+                                &source_channels,
+                                &mut capture_tracker,
+                                &captured_audio,
                             )
                         }
+                        Cmd::TakeRecentAudio(reply_tx) => apply_take_recent_audio_command(
+                            reply_tx,
+                            recording,
+                            &vad,
+                            &mut pre_roll_frames,
+                        ),
                         Cmd::Shutdown => return,
                     }
                 }
@@ -708,6 +1368,12 @@ fn run_consumer(
             let _ = tx.send(());
         }
 
+        // Mixed capture with the sources kept apart sends interleaved pairs.
+        let raw = match source_tracks.as_mut() {
+            Some(tracks) => tracks.push_packet(&raw),
+            None => raw,
+        };
+
         // ---------- spectrum processing ---------------------------------- //
         if let Some(buckets) = visualizer.feed(&raw) {
             if let Some(cb) = &level_cb {
@@ -744,6 +1410,9 @@ fn run_consumer(
                         &vad,
                         &mut pending_start_ready,
                         &mut pre_roll_frames,
+                        &mut segmenter,
+                        &mut source_tracks,
+                        &mut capture_tracker,
                     )
                 }
                 other => {
@@ -754,7 +1423,13 @@ fn run_consumer(
         }
 
         // ---------- existing pipeline ------------------------------------ //
+        let processing_config = *processing.lock().unwrap();
+        let chunker = *chunking.lock().unwrap();
         frame_resampler.push(&raw, &mut |frame: &[f32]| {
+            if let Some(tracks) = source_tracks.as_mut() {
+                tracks.advance();
+            }
+            let frame = processor.process(frame, processing_config);
             if !recording {
                 pre_roll_frames.push_frame(frame);
                 return;
@@ -792,24 +1467,62 @@ fn run_consumer(
                 warmup_remaining -= 1;
                 return;
             }
-            handle_frame(frame, recording, &vad, &mut processed_samples)
+            let frame_was_speech =
+                handle_tracked_frame(
+                    frame,
+                    &vad,
+                    &mut processed_samples,
+                    &mut source_tracks,
+                    &mut capture_tracker,
+                );
+            if let Some(cb) = &activity_cb {
+                cb(frame_was_speech);
+            }
+            if let Some(cb) = &segment_cb {
+                if let Some(segment) = segmenter.observe(frame_was_speech, &processed_samples) {
+                    cb(segment);
+                }
+            }
+            // Long-form: hand finished chunks off so the buffer stays bounded.
+            if let (Some(cb), Some(chunker)) = (&chunk_cb, chunker) {
+                if let Some(carried_over) = chunker.cut(processed_samples.len(), frame_was_speech) {
+                    let samples = std::mem::take(&mut processed_samples);
+                    let drained = samples.len() - carried_over;
+                    processed_samples.extend_from_slice(&samples[drained..]);
+                    segmenter.rebase(drained);
+                    cb(RecordedChunk {
+                        samples,
+                        carried_over,
+                        capture: capture_tracker.take_capture(),
+                    });
+                }
+            }
         });
 
         // Handle deferred non-start commands after processing this packet.
         for cmd in deferred_cmds {
             match cmd {
                 Cmd::Stop(reply_tx) => {
-                    pause_stream(&stream);
+                    pause_unless_buffering(&stream, &pre_roll_frames);
                     apply_stop_command(
                         reply_tx,
                         &mut recording,
                         &mut pending_start_ready,
                         &sample_rx,
                         &mut frame_resampler,
+                        &mut processor,
+                        &processing,
                         &vad,
                         &mut processed_samples,
+                        &mut source_tracks,
+                        &source_channels,
+                        &mut capture_tracker,
+                        &captured_audio,
                     )
                 }
+                Cmd::TakeRecentAudio(reply_tx) => {
+                    apply_take_recent_audio_command(reply_tx, recording, &vad, &mut pre_roll_frames)
+                }
                 Cmd::Shutdown => return,
                 Cmd::Start { start_id, ready_tx } => {
                     resume_stream(&stream);
@@ -823,6 +1536,9 @@ fn run_consumer(
                         &vad,
                         &mut pending_start_ready,
                         &mut pre_roll_frames,
+                        &mut segmenter,
+                        &mut source_tracks,
+                        &mut capture_tracker,
                     )
                 }
             }
@@ -843,20 +1559,32 @@ fn run_consumer(
                         &vad,
                         &mut pending_start_ready,
                         &mut pre_roll_frames,
+                        &mut segmenter,
+                        &mut source_tracks,
+                        &mut capture_tracker,
                     )
                 }
                 Cmd::Stop(reply_tx) => {
-                    pause_stream(&stream);
+                    pause_unless_buffering(&stream, &pre_roll_frames);
                     apply_stop_command(
                         reply_tx,
                         &mut recording,
                         &mut pending_start_ready,
                         &sample_rx,
                         &mut frame_resampler,
+                        &mut processor,
+                        &processing,
                         &vad,
                         &mut processed_samples,
+                        &mut source_tracks,
+                        &source_channels,
+                        &mut capture_tracker,
+                        &captured_audio,
                     )
                 }
+                Cmd::TakeRecentAudio(reply_tx) => {
+                    apply_take_recent_audio_command(reply_tx, recording, &vad, &mut pre_roll_frames)
+                }
                 Cmd::Shutdown => return,
             }
         }
@@ -866,11 +1594,13 @@ fn run_consumer(
 #[cfg(test)]
 mod tests {
     use super::{
-        run_consumer, AudioRecorder, Cmd, RecorderStartError, RecorderStopWait, StartReadyStatus,
+        run_consumer, AudioRecorder, Cmd, PreRollBuffer, RecorderStartError, RecorderStopWait,
+        SourceTracks, SpeechSegmenter, StartReadyStatus,
     };
+    use crate::audio_toolkit::audio::AudioProcessingConfig;
     use crate::audio_toolkit::constants;
     use std::sync::{
-        atomic::AtomicU64,
+        atomic::{AtomicU64, AtomicUsize},
         mpsc, Arc, Mutex,
     };
     use std::time::Duration;
@@ -902,6 +1632,15 @@ mod tests {
                 None,
                 None,
                 None,
+                None,
+                Arc::new(Mutex::new(None)),
+                Arc::new(AtomicUsize::new(0)),
+                Arc::new(Mutex::new(AudioProcessingConfig::default())),
+                None,
+                Arc::new(Mutex::new(None)),
+                Arc::new(Mutex::new(None)),
+                None,
+                None,
             );
             let _ = done_tx.send(());
         });
@@ -1315,6 +2054,15 @@ mod tests {
             worker_handle: None,
             vad: None,
             level_cb: None,
+            segment_cb: None,
+            activity_cb: None,
+            chunk_cb: None,
+            chunking: Arc::new(Mutex::new(None)),
+            recent_audio_frames: Arc::new(AtomicUsize::new(0)),
+            processing: Arc::new(Mutex::new(AudioProcessingConfig::default())),
+            capture: CaptureInput::Microphone,
+            source_channels: Arc::new(Mutex::new(None)),
+            captured_audio: Arc::new(Mutex::new(None)),
             cached_config: None,
             next_start_id: AtomicU64::new(1),
         };
@@ -1359,6 +2107,15 @@ mod tests {
             worker_handle: None,
             vad: None,
             level_cb: None,
+            segment_cb: None,
+            activity_cb: None,
+            chunk_cb: None,
+            chunking: Arc::new(Mutex::new(None)),
+            recent_audio_frames: Arc::new(AtomicUsize::new(0)),
+            processing: Arc::new(Mutex::new(AudioProcessingConfig::default())),
+            capture: CaptureInput::Microphone,
+            source_channels: Arc::new(Mutex::new(None)),
+            captured_audio: Arc::new(Mutex::new(None)),
             cached_config: None,
             next_start_id: AtomicU64::new(1),
         };
@@ -1394,4 +2151,139 @@ mod tests {
         let stop_completed = wait_thread.join().expect("stop wait thread should join");
         assert!(stop_completed, "stop wait should complete successfully");
     }
+
+    #[test]
+    fn segmenter_emits_interim_then_complete_utterance() {
+        let mut segmenter = SpeechSegmenter::new(4, 100);
+        let mut processed = Vec::new();
+
+        processed.extend_from_slice(&[0.1; 2]);
+        assert!(segmenter.observe(true, &processed).is_none());
+
+        processed.extend_from_slice(&[0.1; 2]);
+        let interim = segmenter
+            .observe(true, &processed)
+            .expect("interim segment");
+        assert!(!interim.utterance_complete);
+        assert_eq!(interim.samples.len(), 4);
+
+        processed.extend_from_slice(&[0.2; 2]);
+        assert!(segmenter.observe(true, &processed).is_none());
+
+        let complete = segmenter
+            .observe(false, &processed)
+            .expect("complete segment");
+        assert!(complete.utterance_complete);
+        assert_eq!(complete.samples.len(), 6);
+
+        // Further silence does not re-emit the finished utterance.
+        assert!(segmenter.observe(false, &processed).is_none());
+    }
+
+    #[test]
+    fn segmenter_cuts_long_utterances_at_max_length() {
+        let mut segmenter = SpeechSegmenter::new(1000, 6);
+        let mut processed = vec![0.1; 6];
+
+        let forced = segmenter.observe(true, &processed).expect("forced cut");
+        assert!(forced.utterance_complete);
+        assert_eq!(forced.samples.len(), 6);
+
+        processed.extend_from_slice(&[0.3; 2]);
+        assert!(segmenter.observe(true, &processed).is_none());
+        let tail = segmenter
+            .observe(false, &processed)
+            .expect("tail utterance");
+        assert_eq!(tail.samples, vec![0.3; 2]);
+    }
+
+    #[test]
+    fn segmenter_follows_the_buffer_across_a_long_form_cut() {
+        let mut segmenter = SpeechSegmenter::new(1000, 100);
+        let mut processed = vec![0.1; 10];
+        assert!(segmenter.observe(true, &processed).is_none());
+
+        // A long-form cut hands off 8 samples and keeps the last 2 as overlap.
+        processed.drain(..8);
+        segmenter.rebase(8);
+        processed.extend_from_slice(&[0.2; 3]);
+        assert!(segmenter.observe(true, &processed).is_none());
+
+        let complete = segmenter
+            .observe(false, &processed)
+            .expect("complete segment");
+        assert_eq!(complete.samples, vec![0.1, 0.1, 0.2, 0.2, 0.2]);
+    }
+
+    fn drained(buffer: &mut PreRollBuffer, latest: usize) -> Vec<f32> {
+        let mut values = Vec::new();
+        buffer.drain_into(latest, |frame| values.push(frame[0]));
+        values
+    }
+
+    #[test]
+    fn growing_the_ring_keeps_buffered_frames_for_recent_audio() {
+        let mut buffer = PreRollBuffer::new(3);
+        for value in 1..=5 {
+            buffer.push_frame(&[value as f32]);
+        }
+        buffer.set_capacity(10);
+        buffer.push_frame(&[6.0]);
+        assert_eq!(drained(&mut buffer, usize::MAX), vec![3.0, 4.0, 5.0, 6.0]);
+        assert!(
+            drained(&mut buffer, usize::MAX).is_empty(),
+            "draining empties the ring"
+        );
+    }
+
+    #[test]
+    fn pre_roll_takes_only_the_newest_frames_of_a_large_ring() {
+        let mut buffer = PreRollBuffer::new(10);
+        for value in 1..=8 {
+            buffer.push_frame(&[value as f32]);
+        }
+        assert_eq!(drained(&mut buffer, 2), vec![7.0, 8.0]);
+        assert!(
+            drained(&mut buffer, usize::MAX).is_empty(),
+            "older frames are discarded"
+        );
+
+        for value in 1..=8 {
+            buffer.push_frame(&[value as f32]);
+        }
+        buffer.set_capacity(3);
+        assert_eq!(drained(&mut buffer, usize::MAX), vec![6.0, 7.0, 8.0]);
+    }
+
+    #[test]
+    fn source_tracks_follow_the_frames_the_vad_keeps() {
+        const FRAME: usize = 480;
+        let mut tracks = SourceTracks::new(constants::WHISPER_SAMPLE_RATE);
+        let packet: Vec<f32> = (0..3 * FRAME)
+            .flat_map(|i| {
+                let frame = (i / FRAME) as f32;
+                [frame / 10.0, -frame / 10.0]
+            })
+            .collect();
+        let mix = tracks.push_packet(&packet);
+        assert_eq!(mix.len(), 3 * FRAME);
+        assert!(mix.iter().all(|s| *s == 0.0), "opposite sources cancel out");
+
+        tracks.restart();
+        for _ in 0..3 {
+            tracks.advance();
+        }
+        // A smoothing VAD hands back the prefill frame with the current one.
+        tracks.keep(2 * FRAME);
+        // Audio flushed out of the DSP stage has no source frames.
+        tracks.keep(FRAME);
+
+        let kept = tracks.take();
+        assert_eq!(kept.microphone.len(), 3 * FRAME);
+        assert_eq!(kept.system.len(), 3 * FRAME);
+        assert_eq!(kept.microphone[0], 0.1);
+        assert_eq!(kept.microphone[FRAME], 0.2);
+        assert_eq!(kept.system[FRAME], -0.2);
+        assert!(kept.microphone[2 * FRAME..].iter().all(|s| *s == 0.0));
+    }
 }
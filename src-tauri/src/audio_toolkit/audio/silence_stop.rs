@@ -0,0 +1,126 @@
+/// Duration of one recorder frame fed to the VAD.
+const FRAME_MS: u32 = 30;
+
+pub const MIN_AUTO_STOP_SILENCE_MS: u32 = 500;
+pub const MAX_AUTO_STOP_SILENCE_MS: u32 = 10_000;
+pub const MAX_AUTO_STOP_MIN_SPEECH_MS: u32 = 5_000;
+
+/// Checks hands-free auto-stop parameters before they are stored.
+pub fn validate_auto_stop(silence_ms: u32, min_speech_ms: u32) -> Result<(), String> {
+    if !(MIN_AUTO_STOP_SILENCE_MS..=MAX_AUTO_STOP_SILENCE_MS).contains(&silence_ms) {
+        return Err(format!(
+            "Silence duration must be between {} and {} ms, got {}",
+            MIN_AUTO_STOP_SILENCE_MS, MAX_AUTO_STOP_SILENCE_MS, silence_ms
+        ));
+    }
+    if min_speech_ms > MAX_AUTO_STOP_MIN_SPEECH_MS {
+        return Err(format!(
+            "Minimum speech must be at most {} ms, got {}",
+            MAX_AUTO_STOP_MIN_SPEECH_MS, min_speech_ms
+        ));
+    }
+    Ok(())
+}
+
+/// Decides when a hands-free recording should stop, from per-frame VAD
+/// decisions.
+///
+/// Fires once after `silence_ms` of non-speech that follows at least
+/// `min_speech_ms` of speech. Shorter bursts (a cough, a click) are forgotten
+/// once the silence has elapsed, so they never end the recording on their own.
+pub struct TrailingSilenceDetector {
+    silence_ms: u32,
+    min_speech_ms: u32,
+    speech_ms: u32,
+    silence_run_ms: u32,
+    fired: bool,
+}
+
+impl TrailingSilenceDetector {
+    pub fn new(silence_ms: u32, min_speech_ms: u32) -> Self {
+        Self {
+            silence_ms,
+            min_speech_ms,
+            speech_ms: 0,
+            silence_run_ms: 0,
+            fired: false,
+        }
+    }
+
+    /// Feed one frame; returns true exactly once, when the recording should stop.
+    pub fn observe(&mut self, frame_was_speech: bool) -> bool {
+        if self.fired {
+            return false;
+        }
+
+        if frame_was_speech {
+            self.speech_ms = self.speech_ms.saturating_add(FRAME_MS);
+            self.silence_run_ms = 0;
+            return false;
+        }
+
+        if self.speech_ms == 0 {
+            return false;
+        }
+
+        self.silence_run_ms += FRAME_MS;
+        if self.silence_run_ms < self.silence_ms {
+            return false;
+        }
+
+        if self.speech_ms >= self.min_speech_ms {
+            self.fired = true;
+            true
+        } else {
+            self.speech_ms = 0;
+            self.silence_run_ms = 0;
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feed(detector: &mut TrailingSilenceDetector, speech: bool, ms: u32) -> bool {
+        (0..ms.div_ceil(FRAME_MS)).any(|_| detector.observe(speech))
+    }
+
+    #[test]
+    fn stops_after_trailing_silence_following_speech() {
+        let mut detector = TrailingSilenceDetector::new(1500, 600);
+        assert!(
+            !feed(&mut detector, false, 5000),
+            "leading silence never stops"
+        );
+        assert!(!feed(&mut detector, true, 1200));
+        assert!(!feed(&mut detector, false, 1200));
+        assert!(
+            !feed(&mut detector, true, 300),
+            "a pause resets the silence run"
+        );
+        assert!(feed(&mut detector, false, 1500));
+        assert!(!feed(&mut detector, false, 3000), "fires only once");
+    }
+
+    #[test]
+    fn short_bursts_do_not_stop_the_recording() {
+        let mut detector = TrailingSilenceDetector::new(1000, 900);
+        assert!(!feed(&mut detector, true, 450));
+        assert!(!feed(&mut detector, false, 2000));
+        // The cough was forgotten, so this burst alone is also too short.
+        assert!(!feed(&mut detector, true, 600));
+        assert!(!feed(&mut detector, false, 2000));
+        assert!(!feed(&mut detector, true, 900));
+        assert!(feed(&mut detector, false, 1000));
+    }
+
+    #[test]
+    fn auto_stop_bounds_are_enforced() {
+        assert!(validate_auto_stop(2000, 1000).is_ok());
+        assert!(validate_auto_stop(MIN_AUTO_STOP_SILENCE_MS - 1, 0).is_err());
+        assert!(validate_auto_stop(MAX_AUTO_STOP_SILENCE_MS + 1, 0).is_err());
+        assert!(validate_auto_stop(2000, MAX_AUTO_STOP_MIN_SPEECH_MS + 1).is_err());
+    }
+}
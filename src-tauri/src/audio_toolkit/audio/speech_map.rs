@@ -0,0 +1,182 @@
+//! The clock of a recording before and after the VAD trims it.
+//!
+//! The engine only hears the samples the VAD keeps, so its timings count
+//! kept samples. The recorder also keeps the full capture for history and
+//! notes where each run of kept samples came from in it, so those timings
+//! can be moved onto the audio that is stored and played back.
+
+use crate::audio_toolkit::constants::WHISPER_SAMPLE_RATE;
+
+/// A run of kept samples and where it starts in the full capture.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct SpeechSpan {
+    kept_start: usize,
+    capture_start: usize,
+    len: usize,
+}
+
+/// Maps positions in the VAD-trimmed audio to the full capture.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SpeechMap {
+    spans: Vec<SpeechSpan>,
+}
+
+impl SpeechMap {
+    /// Note that `len` kept samples starting at `kept_start` came from the
+    /// capture at `capture_start`. Spans are recorded in order; one that
+    /// continues the previous span extends it.
+    pub fn record(&mut self, kept_start: usize, capture_start: usize, len: usize) {
+        if len == 0 {
+            return;
+        }
+        if let Some(last) = self.spans.last_mut() {
+            if last.kept_start + last.len == kept_start
+                && last.capture_start + last.len == capture_start
+            {
+                last.len += len;
+                return;
+            }
+        }
+        self.spans.push(SpeechSpan {
+            kept_start,
+            capture_start,
+            len,
+        });
+    }
+
+    /// Where the kept sample at `kept` sits in the capture. Positions past
+    /// the last span (e.g. padding added before transcription) continue from
+    /// its end. With `is_end`, a position on the boundary between two spans
+    /// belongs to the earlier one, so the end of a word does not jump across
+    /// the silence that follows it.
+    pub fn capture_position(&self, kept: usize, is_end: bool) -> usize {
+        let index = self.spans.partition_point(|span| {
+            if is_end {
+                span.kept_start + span.len < kept
+            } else {
+                span.kept_start + span.len <= kept
+            }
+        });
+        let span = match self.spans.get(index).or_else(|| self.spans.last()) {
+            Some(span) => span,
+            None => return kept,
+        };
+        if kept < span.kept_start {
+            return span.capture_start.saturating_sub(span.kept_start - kept);
+        }
+        span.capture_start + (kept - span.kept_start)
+    }
+
+    /// `capture_position` for a time in milliseconds.
+    pub fn capture_ms(&self, kept_ms: i64, is_end: bool) -> i64 {
+        if self.spans.is_empty() {
+            return kept_ms;
+        }
+        let rate = WHISPER_SAMPLE_RATE as u64;
+        let kept = (kept_ms.max(0) as u64 * rate / 1000) as usize;
+        (self.capture_position(kept, is_end) as u64 * 1000 / rate) as i64
+    }
+}
+
+/// The full capture of a stopped recording and where its speech sits in it.
+#[derive(Clone, Debug, Default)]
+pub struct CapturedAudio {
+    /// 16 kHz mono capture that has not been taken by a long-form chunk.
+    pub samples: Vec<f32>,
+    /// Kept speech of the whole recording mapped onto the full capture.
+    pub speech_map: SpeechMap,
+}
+
+/// Follows a recording frame by frame while the VAD trims it.
+#[derive(Debug, Default)]
+pub(crate) struct CaptureTracker {
+    capture: Vec<f32>,
+    captured: usize,
+    kept: usize,
+    speech_map: SpeechMap,
+}
+
+impl CaptureTracker {
+    pub(crate) fn restart(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Record a frame of the recording and the number of samples the VAD let
+    /// through for it. Kept samples end with the frame; the VAD's pre-roll
+    /// reaches back into frames it held.
+    pub(crate) fn observe(&mut self, frame: &[f32], kept: usize) {
+        self.capture.extend_from_slice(frame);
+        self.captured += frame.len();
+        let capture_start = self.captured.saturating_sub(kept);
+        self.speech_map.record(self.kept, capture_start, kept);
+        self.kept += kept;
+    }
+
+    /// Hand over the capture recorded since the last call, e.g. when a
+    /// long-form chunk is cut. The speech map keeps covering the recording.
+    pub(crate) fn take_capture(&mut self) -> Vec<f32> {
+        std::mem::take(&mut self.capture)
+    }
+
+    pub(crate) fn finish(&mut self) -> CapturedAudio {
+        let tracker = std::mem::take(self);
+        CapturedAudio {
+            samples: tracker.capture,
+            speech_map: tracker.speech_map,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CaptureTracker, SpeechMap};
+
+    #[test]
+    fn speech_map_moves_kept_positions_past_dropped_silence() {
+        let mut map = SpeechMap::default();
+        map.record(0, 100, 50);
+        map.record(50, 150, 10);
+        map.record(60, 400, 40);
+
+        assert_eq!(map.capture_position(0, false), 100);
+        assert_eq!(map.capture_position(59, false), 159);
+        assert_eq!(map.capture_position(60, false), 400);
+        assert_eq!(map.capture_position(60, true), 160);
+        assert_eq!(map.capture_position(99, false), 439);
+        // Padding past the kept audio continues from the last span.
+        assert_eq!(map.capture_position(120, true), 460);
+    }
+
+    #[test]
+    fn empty_speech_map_is_identity() {
+        let map = SpeechMap::default();
+        assert_eq!(map.capture_position(42, false), 42);
+        assert_eq!(map.capture_ms(1234, true), 1234);
+    }
+
+    #[test]
+    fn capture_tracker_follows_dropped_frames_and_pre_roll() {
+        let mut tracker = CaptureTracker::default();
+        let frame = [0.0f32; 10];
+        tracker.observe(&frame, 0);
+        tracker.observe(&frame, 0);
+        // Speech starts: the VAD releases one held frame with this one.
+        tracker.observe(&frame, 20);
+        tracker.observe(&frame, 10);
+        tracker.observe(&frame, 0);
+        tracker.observe(&frame, 10);
+
+        let chunk = tracker.take_capture();
+        assert_eq!(chunk.len(), 60);
+        tracker.observe(&frame, 10);
+
+        let captured = tracker.finish();
+        assert_eq!(captured.samples.len(), 10);
+        let map = captured.speech_map;
+        assert_eq!(map.capture_position(0, false), 10);
+        assert_eq!(map.capture_position(29, false), 39);
+        assert_eq!(map.capture_position(30, false), 50);
+        assert_eq!(map.capture_position(40, false), 60);
+        assert_eq!(map.capture_position(49, false), 69);
+    }
+}
@@ -1,26 +1,454 @@
+use super::flac::{encode_flac, FlacWriter};
+use super::FrameResampler;
+use crate::audio_toolkit::constants::WHISPER_SAMPLE_RATE;
 use anyhow::Result;
-use hound::{WavSpec, WavWriter};
+use hound::{WavReader, WavSpec, WavWriter};
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{DecoderOptions, CODEC_TYPE_NULL};
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
 use tracing::debug;
-use std::path::Path;
 
-/// Save audio samples as a WAV file
-pub fn save_wav_file<P: AsRef<Path>>(file_path: P, samples: &[f32]) -> Result<()> {
+fn to_pcm16(samples: &[f32]) -> Vec<i16> {
+    samples
+        .iter()
+        .map(|sample| (sample * i16::MAX as f32) as i16)
+        .collect()
+}
+
+fn write_wav_pcm16(file_path: &Path, samples: &[i16], sample_rate: u32) -> Result<()> {
     let spec = WavSpec {
         channels: 1,
-        sample_rate: 16000,
+        sample_rate,
         bits_per_sample: 16,
         sample_format: hound::SampleFormat::Int,
     };
 
-    let mut writer = WavWriter::create(file_path.as_ref(), spec)?;
-
-    // Convert f32 samples to i16 for WAV
-    for sample in samples {
-        let sample_i16 = (sample * i16::MAX as f32) as i16;
-        writer.write_sample(sample_i16)?;
+    let mut writer = WavWriter::create(file_path, spec)?;
+    for &sample in samples {
+        writer.write_sample(sample)?;
     }
-
     writer.finalize()?;
+    Ok(())
+}
+
+fn write_flac_pcm16(file_path: &Path, samples: &[i16], sample_rate: u32) -> Result<()> {
+    std::fs::write(file_path, encode_flac(samples, sample_rate))?;
+    Ok(())
+}
+
+/// Save audio samples as a WAV file
+pub fn save_wav_file<P: AsRef<Path>>(file_path: P, samples: &[f32]) -> Result<()> {
+    write_wav_pcm16(file_path.as_ref(), &to_pcm16(samples), WHISPER_SAMPLE_RATE)?;
     debug!("Saved WAV file: {:?}", file_path.as_ref());
     Ok(())
 }
+
+/// Save 16 kHz audio samples as a lossless 16-bit FLAC file.
+pub fn save_flac_file<P: AsRef<Path>>(file_path: P, samples: &[f32]) -> Result<()> {
+    write_flac_pcm16(file_path.as_ref(), &to_pcm16(samples), WHISPER_SAMPLE_RATE)?;
+    debug!("Saved FLAC file: {:?}", file_path.as_ref());
+    Ok(())
+}
+
+/// Decode a 16-bit recording without going through f32, so converting
+/// between storage formats is bit-exact.
+fn read_pcm16_file(path: &Path) -> Result<(Vec<i16>, u32)> {
+    let file = File::open(path)
+        .map_err(|e| anyhow::anyhow!("Failed to open audio file {:?}: {}", path, e))?;
+    let stream = MediaSourceStream::new(Box::new(file), Default::default());
+    let mut hint = Hint::new();
+    if let Some(extension) = path.extension().and_then(|ext| ext.to_str()) {
+        hint.with_extension(extension);
+    }
+
+    let mut format = symphonia::default::get_probe()
+        .format(
+            &hint,
+            stream,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )
+        .map_err(|e| anyhow::anyhow!("Unsupported audio format {:?}: {}", path, e))?
+        .format;
+    let track = format
+        .tracks()
+        .iter()
+        .find(|track| track.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or_else(|| anyhow::anyhow!("No decodable audio track in {:?}", path))?;
+    let track_id = track.id;
+    let sample_rate = track
+        .codec_params
+        .sample_rate
+        .ok_or_else(|| anyhow::anyhow!("Unknown sample rate for {:?}", path))?;
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .map_err(|e| anyhow::anyhow!("Unsupported audio codec in {:?}: {}", path, e))?;
+
+    let mut samples = Vec::new();
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(SymphoniaError::IoError(ref e))
+                if e.kind() == std::io::ErrorKind::UnexpectedEof =>
+            {
+                break;
+            }
+            Err(e) => return Err(anyhow::anyhow!("Failed to read {:?}: {}", path, e)),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+        // Unlike transcription input, a storage conversion must not silently
+        // drop packets, so decode errors abort.
+        let decoded = decoder
+            .decode(&packet)
+            .map_err(|e| anyhow::anyhow!("Failed to decode {:?}: {}", path, e))?;
+        let spec = *decoded.spec();
+        if spec.channels.count() != 1 {
+            return Err(anyhow::anyhow!("Recording {:?} is not mono", path));
+        }
+        let mut buffer = SampleBuffer::<i16>::new(decoded.capacity() as u64, spec);
+        buffer.copy_interleaved_ref(decoded);
+        samples.extend_from_slice(buffer.samples());
+    }
+    Ok((samples, sample_rate))
+}
+
+/// Re-encode a mono 16-bit recording into the format implied by the
+/// destination extension (`wav` or `flac`). The audio is preserved exactly.
+pub fn convert_recording_file<P: AsRef<Path>, Q: AsRef<Path>>(
+    source: P,
+    destination: Q,
+) -> Result<()> {
+    let (source, destination) = (source.as_ref(), destination.as_ref());
+    let (samples, sample_rate) = read_pcm16_file(source)?;
+    match destination.extension().and_then(|ext| ext.to_str()) {
+        Some(ext) if ext.eq_ignore_ascii_case("flac") => {
+            write_flac_pcm16(destination, &samples, sample_rate)
+        }
+        Some(ext) if ext.eq_ignore_ascii_case("wav") => {
+            write_wav_pcm16(destination, &samples, sample_rate)
+        }
+        _ => Err(anyhow::anyhow!(
+            "Unsupported recording format for {:?}",
+            destination
+        )),
+    }
+}
+
+/// Join mono 16-bit WAV parts into one recording in the format implied by the
+/// destination extension, dropping the given number of leading samples from
+/// each part (audio it shares with the previous one).
+///
+/// Parts are streamed one at a time, so memory use does not grow with the
+/// length of the recording.
+pub fn concat_recording_files<P: AsRef<Path>>(
+    parts: &[(PathBuf, usize)],
+    destination: P,
+) -> Result<()> {
+    enum Sink {
+        Wav(WavWriter<BufWriter<File>>),
+        Flac(FlacWriter<BufWriter<File>>),
+    }
+
+    let destination = destination.as_ref();
+    let mut total_samples = 0u64;
+    let mut sample_rate = WHISPER_SAMPLE_RATE;
+    for (path, skip) in parts {
+        let reader = WavReader::open(path)
+            .map_err(|e| anyhow::anyhow!("Failed to open recording part {:?}: {}", path, e))?;
+        sample_rate = reader.spec().sample_rate;
+        total_samples += (reader.len() as usize).saturating_sub(*skip) as u64;
+    }
+
+    let mut sink = match destination.extension().and_then(|ext| ext.to_str()) {
+        Some(ext) if ext.eq_ignore_ascii_case("flac") => Sink::Flac(FlacWriter::new(
+            BufWriter::new(File::create(destination)?),
+            sample_rate,
+            total_samples,
+        )?),
+        Some(ext) if ext.eq_ignore_ascii_case("wav") => Sink::Wav(WavWriter::create(
+            destination,
+            WavSpec {
+                channels: 1,
+                sample_rate,
+                bits_per_sample: 16,
+                sample_format: hound::SampleFormat::Int,
+            },
+        )?),
+        _ => {
+            return Err(anyhow::anyhow!(
+                "Unsupported recording format for {:?}",
+                destination
+            ))
+        }
+    };
+
+    for (path, skip) in parts {
+        let mut reader = WavReader::open(path)?;
+        let samples = reader
+            .samples::<i16>()
+            .skip(*skip)
+            .collect::<Result<Vec<_>, _>>()?;
+        match &mut sink {
+            Sink::Wav(writer) => {
+                for &sample in &samples {
+                    writer.write_sample(sample)?;
+                }
+            }
+            Sink::Flac(writer) => writer.write_samples(&samples)?,
+        }
+    }
+
+    match sink {
+        Sink::Wav(writer) => writer.finalize()?,
+        Sink::Flac(writer) => {
+            writer.finish()?;
+        }
+    }
+    debug!(
+        "Joined {} recording parts into {:?}",
+        parts.len(),
+        destination
+    );
+    Ok(())
+}
+
+/// Decode an audio file (WAV, FLAC, MP3, Ogg Vorbis) into mono f32 samples.
+///
+/// Returns the samples together with the source sample rate. Multi-channel
+/// audio is downmixed by averaging channels.
+pub fn read_audio_file<P: AsRef<Path>>(file_path: P) -> Result<(Vec<f32>, u32)> {
+    let path = file_path.as_ref();
+    let file = File::open(path)
+        .map_err(|e| anyhow::anyhow!("Failed to open audio file {:?}: {}", path, e))?;
+    let stream = MediaSourceStream::new(Box::new(file), Default::default());
+
+    let mut hint = Hint::new();
+    if let Some(extension) = path.extension().and_then(|ext| ext.to_str()) {
+        hint.with_extension(extension);
+    }
+
+    let probed = symphonia::default::get_probe()
+        .format(
+            &hint,
+            stream,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )
+        .map_err(|e| anyhow::anyhow!("Unsupported audio format {:?}: {}", path, e))?;
+    let mut format = probed.format;
+
+    let track = format
+        .tracks()
+        .iter()
+        .find(|track| track.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or_else(|| anyhow::anyhow!("No decodable audio track in {:?}", path))?;
+    let track_id = track.id;
+    let sample_rate = track
+        .codec_params
+        .sample_rate
+        .ok_or_else(|| anyhow::anyhow!("Unknown sample rate for {:?}", path))?;
+
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .map_err(|e| anyhow::anyhow!("Unsupported audio codec in {:?}: {}", path, e))?;
+
+    let mut samples = Vec::new();
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(SymphoniaError::IoError(ref e))
+                if e.kind() == std::io::ErrorKind::UnexpectedEof =>
+            {
+                break;
+            }
+            Err(SymphoniaError::ResetRequired) => break,
+            Err(e) => return Err(anyhow::anyhow!("Failed to read {:?}: {}", path, e)),
+        };
+
+        if packet.track_id() != track_id {
+            continue;
+        }
+
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            // Skip corrupt packets instead of failing the whole file.
+            Err(SymphoniaError::DecodeError(e)) => {
+                debug!("Skipping undecodable packet in {:?}: {}", path, e);
+                continue;
+            }
+            Err(e) => return Err(anyhow::anyhow!("Failed to decode {:?}: {}", path, e)),
+        };
+
+        let spec = *decoded.spec();
+        let channels = spec.channels.count().max(1);
+        let mut buffer = SampleBuffer::<f32>::new(decoded.capacity() as u64, spec);
+        buffer.copy_interleaved_ref(decoded);
+
+        samples.extend(
+            buffer
+                .samples()
+                .chunks(channels)
+                .map(|frame| frame.iter().sum::<f32>() / channels as f32),
+        );
+    }
+
+    debug!(
+        "Decoded audio file {:?}: {} samples at {} Hz",
+        path,
+        samples.len(),
+        sample_rate
+    );
+    Ok((samples, sample_rate))
+}
+
+/// Resample mono audio to the 16 kHz rate expected by the transcription engines.
+pub fn resample_to_whisper_rate(samples: &[f32], sample_rate: u32) -> Vec<f32> {
+    if sample_rate == WHISPER_SAMPLE_RATE {
+        return samples.to_vec();
+    }
+
+    let mut resampler = FrameResampler::new(
+        sample_rate as usize,
+        WHISPER_SAMPLE_RATE as usize,
+        Duration::from_millis(30),
+    );
+    let expected_len =
+        (samples.len() as u64 * WHISPER_SAMPLE_RATE as u64 / sample_rate as u64) as usize;
+    let mut output = Vec::with_capacity(expected_len);
+    resampler.push(samples, |frame| output.extend_from_slice(frame));
+    resampler.finish(|frame| output.extend_from_slice(frame));
+    output
+}
+
+/// Decode an audio file and resample it to 16 kHz mono for transcription.
+pub fn load_audio_file_for_transcription<P: AsRef<Path>>(file_path: P) -> Result<Vec<f32>> {
+    let (samples, sample_rate) = read_audio_file(file_path)?;
+    Ok(resample_to_whisper_rate(&samples, sample_rate))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn read_audio_file_round_trips_saved_wav() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("tone.wav");
+        let samples: Vec<f32> = (0..16000)
+            .map(|i| (i as f32 * 440.0 * 2.0 * std::f32::consts::PI / 16000.0).sin() * 0.5)
+            .collect();
+        save_wav_file(&path, &samples).unwrap();
+
+        let (decoded, sample_rate) = read_audio_file(&path).unwrap();
+
+        assert_eq!(sample_rate, 16000);
+        assert_eq!(decoded.len(), samples.len());
+        for (original, round_tripped) in samples.iter().zip(decoded.iter()) {
+            assert!((original - round_tripped).abs() < 0.001);
+        }
+    }
+
+    #[test]
+    fn flac_recordings_convert_losslessly_to_and_from_wav() {
+        let temp_dir = TempDir::new().unwrap();
+        let wav = temp_dir.path().join("speech.wav");
+        let flac = temp_dir.path().join("speech.flac");
+        let back = temp_dir.path().join("back.wav");
+        let samples: Vec<f32> = (0..20000)
+            .map(|i| (i as f32 * 0.02).sin() * 0.4 + (i as f32 * 0.37).sin() * 0.05)
+            .collect();
+        save_wav_file(&wav, &samples).unwrap();
+
+        convert_recording_file(&wav, &flac).unwrap();
+        convert_recording_file(&flac, &back).unwrap();
+
+        assert!(std::fs::metadata(&flac).unwrap().len() < std::fs::metadata(&wav).unwrap().len());
+        assert_eq!(
+            read_pcm16_file(&wav).unwrap(),
+            read_pcm16_file(&back).unwrap()
+        );
+        assert_eq!(read_audio_file(&flac).unwrap().0.len(), samples.len());
+    }
+
+    #[test]
+    fn concatenated_parts_drop_their_overlap() {
+        let temp_dir = TempDir::new().unwrap();
+        let first = temp_dir.path().join("chunk-0.wav");
+        let second = temp_dir.path().join("chunk-1.wav");
+        let samples: Vec<f32> = (0..12000).map(|i| (i as f32 * 0.05).sin() * 0.3).collect();
+        save_wav_file(&first, &samples[..8000]).unwrap();
+        // The second part repeats the last 1000 samples of the first.
+        save_wav_file(&second, &samples[7000..]).unwrap();
+        let parts = vec![(first, 0), (second, 1000)];
+
+        let whole = temp_dir.path().join("whole.wav");
+        save_wav_file(&whole, &samples).unwrap();
+        for name in ["joined.wav", "joined.flac"] {
+            let joined = temp_dir.path().join(name);
+            concat_recording_files(&parts, &joined).unwrap();
+            assert_eq!(
+                read_pcm16_file(&joined).unwrap(),
+                read_pcm16_file(&whole).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn read_audio_file_downmixes_stereo() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("stereo.wav");
+        let spec = WavSpec {
+            channels: 2,
+            sample_rate: 16000,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = WavWriter::create(&path, spec).unwrap();
+        for _ in 0..1600 {
+            writer.write_sample(i16::MAX / 2).unwrap();
+            writer.write_sample(0i16).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let (decoded, _) = read_audio_file(&path).unwrap();
+
+        assert_eq!(decoded.len(), 1600);
+        assert!(decoded.iter().all(|sample| (sample - 0.25).abs() < 0.01));
+    }
+
+    #[test]
+    fn read_audio_file_rejects_non_audio() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("notes.wav");
+        std::fs::write(&path, b"definitely not audio").unwrap();
+
+        assert!(read_audio_file(&path).is_err());
+    }
+
+    #[test]
+    fn resample_to_whisper_rate_converts_length() {
+        let samples = vec![0.0f32; 48000];
+
+        let resampled = resample_to_whisper_rate(&samples, 48000);
+
+        // One second of audio, allowing for the final padded 30 ms frame.
+        assert!(resampled.len() >= 15000 && resampled.len() <= 16480);
+    }
+
+    #[test]
+    fn resample_to_whisper_rate_is_identity_at_16k() {
+        let samples = vec![0.1f32, -0.2, 0.3];
+        assert_eq!(resample_to_whisper_rate(&samples, 16000), samples);
+    }
+}
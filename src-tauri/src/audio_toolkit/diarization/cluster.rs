@@ -0,0 +1,231 @@
+/// Merging stops once the closest clusters are this many times further apart
+/// than the average spread inside the looser of the two.
+const SEPARATION_RATIO: f32 = 1.6;
+/// Most windows clustered directly. Longer recordings cluster an evenly spaced
+/// subset and give every other window the label of the nearest centroid, which
+/// keeps the cubic merge loop and the distance matrix small.
+const MAX_CLUSTERED_WINDOWS: usize = 600;
+/// Keeps dimensions that barely vary from dominating after standardisation.
+const MIN_FEATURE_STD: f32 = 1e-3;
+
+/// Standardises each dimension across all windows so no single cepstral
+/// coefficient dominates the distances.
+fn standardize(embeddings: &[Vec<f32>]) -> Vec<Vec<f32>> {
+    let dims = embeddings.first().map_or(0, Vec::len);
+    let count = embeddings.len().max(1) as f32;
+    let mut mean = vec![0.0f32; dims];
+    for embedding in embeddings {
+        for (m, value) in mean.iter_mut().zip(embedding) {
+            *m += value / count;
+        }
+    }
+    let mut std = vec![0.0f32; dims];
+    for embedding in embeddings {
+        for ((s, m), value) in std.iter_mut().zip(&mean).zip(embedding) {
+            *s += (value - m) * (value - m) / count;
+        }
+    }
+    for s in std.iter_mut() {
+        *s = s.sqrt().max(MIN_FEATURE_STD);
+    }
+
+    embeddings
+        .iter()
+        .map(|embedding| {
+            embedding
+                .iter()
+                .zip(&mean)
+                .zip(&std)
+                .map(|((value, m), s)| (value - m) / s)
+                .collect()
+        })
+        .collect()
+}
+
+fn distance(a: &[f32], b: &[f32]) -> f32 {
+    a.iter()
+        .zip(b)
+        .map(|(x, y)| (x - y) * (x - y))
+        .sum::<f32>()
+        .sqrt()
+}
+
+/// Average-linkage agglomerative clustering. Returns one label per point;
+/// labels are dense but otherwise arbitrary.
+fn agglomerate(points: &[Vec<f32>], max_clusters: usize) -> Vec<usize> {
+    let n = points.len();
+    let mut distances = vec![0.0f32; n * n];
+    for i in 0..n {
+        for j in i + 1..n {
+            let d = distance(&points[i], &points[j]);
+            distances[i * n + j] = d;
+            distances[j * n + i] = d;
+        }
+    }
+
+    let mut parent: Vec<usize> = (0..n).collect();
+    let mut active: Vec<usize> = (0..n).collect();
+    let mut sizes = vec![1usize; n];
+    // Sum of pairwise distances inside each cluster.
+    let mut inner_sums = vec![0.0f32; n];
+    let spread = |cluster: usize, sizes: &[usize], inner_sums: &[f32]| {
+        let size = sizes[cluster];
+        (size > 1).then(|| inner_sums[cluster] / (size * (size - 1) / 2) as f32)
+    };
+
+    while active.len() > 1 {
+        let mut closest: Option<(f32, usize, usize)> = None;
+        let mut closest_singleton: Option<(f32, usize, usize)> = None;
+        for (index, &a) in active.iter().enumerate() {
+            for &b in &active[index + 1..] {
+                let d = distances[a * n + b];
+                if closest.is_none_or(|(best, _, _)| d < best) {
+                    closest = Some((d, a, b));
+                }
+                if (sizes[a] == 1 || sizes[b] == 1)
+                    && closest_singleton.is_none_or(|(best, _, _)| d < best)
+                {
+                    closest_singleton = Some((d, a, b));
+                }
+            }
+        }
+        let Some((d, a, b)) = closest else {
+            break;
+        };
+
+        let separated = match (
+            spread(a, &sizes, &inner_sums),
+            spread(b, &sizes, &inner_sums),
+        ) {
+            (Some(x), Some(y)) => d > SEPARATION_RATIO * x.max(y),
+            (Some(x), None) | (None, Some(x)) => d > SEPARATION_RATIO * x,
+            (None, None) => false,
+        };
+        let (d, a, b) = if active.len() > max_clusters.max(1) || !separated {
+            (d, a, b)
+        } else if let Some(singleton) = closest_singleton {
+            // A lone window is not a speaker; fold it into its nearest cluster.
+            singleton
+        } else {
+            break;
+        };
+
+        let (size_a, size_b) = (sizes[a] as f32, sizes[b] as f32);
+        for &k in &active {
+            if k != a && k != b {
+                let merged = (size_a * distances[a * n + k] + size_b * distances[b * n + k])
+                    / (size_a + size_b);
+                distances[a * n + k] = merged;
+                distances[k * n + a] = merged;
+            }
+        }
+        inner_sums[a] += inner_sums[b] + size_a * size_b * d;
+        sizes[a] += sizes[b];
+        parent[b] = a;
+        active.retain(|&cluster| cluster != b);
+    }
+
+    let root = |mut point: usize| {
+        while parent[point] != point {
+            point = parent[point];
+        }
+        point
+    };
+    (0..n)
+        .map(|point| {
+            let cluster = root(point);
+            active.iter().position(|&c| c == cluster).unwrap_or(0)
+        })
+        .collect()
+}
+
+/// Groups window embeddings into at most `max_clusters` speakers.
+pub(super) fn cluster(embeddings: &[Vec<f32>], max_clusters: usize) -> Vec<usize> {
+    if embeddings.len() < 2 {
+        return vec![0; embeddings.len()];
+    }
+    let points = standardize(embeddings);
+    if points.len() <= MAX_CLUSTERED_WINDOWS {
+        return agglomerate(&points, max_clusters);
+    }
+
+    let stride = points.len().div_ceil(MAX_CLUSTERED_WINDOWS);
+    let subset: Vec<Vec<f32>> = points.iter().step_by(stride).cloned().collect();
+    let subset_labels = agglomerate(&subset, max_clusters);
+    let clusters = subset_labels.iter().max().map_or(0, |max| max + 1);
+
+    let dims = points[0].len();
+    let mut centroids = vec![vec![0.0f32; dims]; clusters];
+    let mut counts = vec![0usize; clusters];
+    for (point, &label) in subset.iter().zip(&subset_labels) {
+        counts[label] += 1;
+        for (c, value) in centroids[label].iter_mut().zip(point) {
+            *c += value;
+        }
+    }
+    for (centroid, &count) in centroids.iter_mut().zip(&counts) {
+        for c in centroid.iter_mut() {
+            *c /= count.max(1) as f32;
+        }
+    }
+
+    points
+        .iter()
+        .map(|point| {
+            centroids
+                .iter()
+                .enumerate()
+                .min_by(|(_, a), (_, b)| distance(point, a).total_cmp(&distance(point, b)))
+                .map_or(0, |(label, _)| label)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `count` points scattered uniformly within ±1 of `center` in every dimension.
+    fn blob(center: f32, count: usize, seed: &mut u32) -> Vec<Vec<f32>> {
+        (0..count)
+            .map(|_| {
+                (0..4)
+                    .map(|_| {
+                        *seed ^= *seed << 13;
+                        *seed ^= *seed >> 17;
+                        *seed ^= *seed << 5;
+                        center + (*seed as f32 / u32::MAX as f32 * 2.0 - 1.0)
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn separates_distinct_groups_and_respects_the_cap() {
+        let mut seed = 3;
+        let mut points = blob(0.0, 20, &mut seed);
+        points.extend(blob(5.0, 20, &mut seed));
+        let labels = cluster(&points, 4);
+        assert!(labels[..20].iter().all(|&l| l == labels[0]), "{labels:?}");
+        assert!(labels[20..].iter().all(|&l| l == labels[20]), "{labels:?}");
+        assert_ne!(labels[0], labels[20]);
+
+        let single = blob(0.0, 40, &mut seed);
+        assert!(cluster(&single, 4).iter().all(|&l| l == 0));
+
+        assert!(cluster(&points, 1).iter().all(|&l| l == 0));
+    }
+
+    #[test]
+    fn large_inputs_are_assigned_through_centroids() {
+        let mut seed = 5;
+        let mut points = blob(0.0, MAX_CLUSTERED_WINDOWS, &mut seed);
+        points.extend(blob(5.0, MAX_CLUSTERED_WINDOWS, &mut seed));
+        let labels = cluster(&points, 4);
+        let (first, second) = labels.split_at(MAX_CLUSTERED_WINDOWS);
+        assert!(first.iter().all(|&l| l == first[0]));
+        assert!(second.iter().all(|&l| l == second[0]));
+        assert_ne!(first[0], second[0]);
+    }
+}
@@ -0,0 +1,158 @@
+use rustfft::{num_complex::Complex32, Fft, FftPlanner};
+use std::sync::Arc;
+
+use crate::audio_toolkit::constants;
+
+/// 25 ms analysis frames with a 10 ms hop at 16 kHz.
+const FRAME_LEN: usize = 400;
+const HOP_LEN: usize = 160;
+const FFT_LEN: usize = 512;
+const MEL_BANDS: usize = 26;
+const MIN_HZ: f32 = 60.0;
+const MAX_HZ: f32 = 7600.0;
+/// Cepstra c1..=c19; c0 only tracks loudness, which says little about who speaks.
+const CEPSTRA: usize = 19;
+const PRE_EMPHASIS: f32 = 0.97;
+
+/// Length of the vectors returned by [`MfccEmbedder::embed`].
+pub(super) const EMBEDDING_DIM: usize = 2 * CEPSTRA;
+
+fn hz_to_mel(hz: f32) -> f32 {
+    2595.0 * (1.0 + hz / 700.0).log10()
+}
+
+fn mel_to_hz(mel: f32) -> f32 {
+    700.0 * (10f32.powf(mel / 2595.0) - 1.0)
+}
+
+/// Summarises a stretch of speech as the mean and standard deviation of its
+/// MFCCs, which mostly reflect the speaker's vocal tract rather than the words.
+pub(super) struct MfccEmbedder {
+    fft: Arc<dyn Fft<f32>>,
+    spectrum: Vec<Complex32>,
+    window: Vec<f32>,
+    /// First FFT bin and triangular weights of each mel band.
+    filters: Vec<(usize, Vec<f32>)>,
+    /// DCT-II rows for c1..=c19.
+    dct: Vec<[f32; MEL_BANDS]>,
+}
+
+impl MfccEmbedder {
+    pub(super) fn new() -> Self {
+        let mut planner = FftPlanner::<f32>::new();
+        let window = (0..FRAME_LEN)
+            .map(|i| {
+                0.54 - 0.46 * (2.0 * std::f32::consts::PI * i as f32 / (FRAME_LEN - 1) as f32).cos()
+            })
+            .collect();
+
+        let bin_hz = constants::WHISPER_SAMPLE_RATE as f32 / FFT_LEN as f32;
+        let (min_mel, max_mel) = (hz_to_mel(MIN_HZ), hz_to_mel(MAX_HZ));
+        let edges: Vec<f32> = (0..MEL_BANDS + 2)
+            .map(|i| mel_to_hz(min_mel + (max_mel - min_mel) * i as f32 / (MEL_BANDS + 1) as f32))
+            .map(|hz| hz / bin_hz)
+            .collect();
+        let filters = edges
+            .windows(3)
+            .map(|edge| {
+                let (low, center, high) = (edge[0], edge[1], edge[2]);
+                let start = low.ceil() as usize;
+                let end = (high.floor() as usize).min(FFT_LEN / 2);
+                let weights = (start..=end)
+                    .map(|bin| {
+                        let bin = bin as f32;
+                        if bin <= center {
+                            (bin - low) / (center - low)
+                        } else {
+                            (high - bin) / (high - center)
+                        }
+                        .max(0.0)
+                    })
+                    .collect();
+                (start, weights)
+            })
+            .collect();
+
+        let dct = (1..=CEPSTRA)
+            .map(|k| {
+                let mut row = [0.0; MEL_BANDS];
+                for (n, coefficient) in row.iter_mut().enumerate() {
+                    *coefficient = (std::f32::consts::PI * k as f32 * (n as f32 + 0.5)
+                        / MEL_BANDS as f32)
+                        .cos();
+                }
+                row
+            })
+            .collect();
+
+        Self {
+            fft: planner.plan_fft_forward(FFT_LEN),
+            spectrum: vec![Complex32::new(0.0, 0.0); FFT_LEN],
+            window,
+            filters,
+            dct,
+        }
+    }
+
+    fn cepstra(&mut self, frame: &[f32]) -> [f32; CEPSTRA] {
+        let mut previous = 0.0;
+        for (i, bin) in self.spectrum.iter_mut().enumerate() {
+            let sample = match frame.get(i) {
+                Some(&sample) => {
+                    let emphasized = sample - PRE_EMPHASIS * previous;
+                    previous = sample;
+                    emphasized * self.window[i]
+                }
+                None => 0.0,
+            };
+            *bin = Complex32::new(sample, 0.0);
+        }
+        self.fft.process(&mut self.spectrum);
+
+        let mut log_mel = [0.0; MEL_BANDS];
+        for (energy, (start, weights)) in log_mel.iter_mut().zip(&self.filters) {
+            let power: f32 = weights
+                .iter()
+                .zip(&self.spectrum[*start..])
+                .map(|(weight, bin)| weight * bin.norm_sqr())
+                .sum();
+            *energy = (power + 1e-10).ln();
+        }
+
+        let mut cepstra = [0.0; CEPSTRA];
+        for (value, row) in cepstra.iter_mut().zip(&self.dct) {
+            *value = row.iter().zip(&log_mel).map(|(c, e)| c * e).sum();
+        }
+        cepstra
+    }
+
+    /// Embedding of 16 kHz mono speech: per-coefficient means, then standard
+    /// deviations. Audio shorter than one frame yields zeros.
+    pub(super) fn embed(&mut self, samples: &[f32]) -> Vec<f32> {
+        let mut sum = [0.0f64; CEPSTRA];
+        let mut sum_squares = [0.0f64; CEPSTRA];
+        let mut frames = 0usize;
+        let mut start = 0;
+        while start + FRAME_LEN <= samples.len() {
+            let cepstra = self.cepstra(&samples[start..start + FRAME_LEN]);
+            for (k, &value) in cepstra.iter().enumerate() {
+                sum[k] += value as f64;
+                sum_squares[k] += (value * value) as f64;
+            }
+            frames += 1;
+            start += HOP_LEN;
+        }
+
+        let mut embedding = vec![0.0; EMBEDDING_DIM];
+        if frames == 0 {
+            return embedding;
+        }
+        let count = frames as f64;
+        for k in 0..CEPSTRA {
+            let mean = sum[k] / count;
+            embedding[k] = mean as f32;
+            embedding[CEPSTRA + k] = (sum_squares[k] / count - mean * mean).max(0.0).sqrt() as f32;
+        }
+        embedding
+    }
+}
@@ -0,0 +1,259 @@
+//! Offline speaker diarization. Speech found by a VAD is cut into short
+//! windows, each window is summarised by an MFCC embedding, and the windows are
+//! clustered into speakers. Needs no model files.
+
+mod cluster;
+mod embedding;
+
+use crate::audio_toolkit::constants::WHISPER_SAMPLE_RATE;
+use crate::audio_toolkit::vad::VoiceActivityDetector;
+use embedding::MfccEmbedder;
+
+/// VAD frame length at 16 kHz (30 ms).
+const VAD_FRAME: usize = 480;
+/// Speech is embedded in windows of up to 1.5 s.
+const WINDOW_FRAMES: usize = 50;
+/// Windows shorter than ~0.5 s are too short to embed reliably; they take the
+/// speaker of the nearest embedded window instead.
+const MIN_EMBEDDED_FRAMES: usize = 17;
+
+fn samples_to_ms(samples: usize) -> i64 {
+    (samples as u64 * 1000 / WHISPER_SAMPLE_RATE as u64) as i64
+}
+
+/// A stretch of the recording attributed to one speaker.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SpeakerTurn {
+    pub start_ms: i64,
+    pub end_ms: i64,
+    /// 1-based; speakers are numbered in order of first appearance.
+    pub speaker: u32,
+}
+
+struct SpeechWindow {
+    start: usize,
+    end: usize,
+    embedding: Option<Vec<f32>>,
+}
+
+/// Streaming diarizer: feed 16 kHz mono audio in order with [`Self::push`],
+/// then call [`Self::finish`]. Only one embedding per window is kept, so
+/// memory stays small for long recordings.
+pub struct Diarizer {
+    vad: Box<dyn VoiceActivityDetector>,
+    embedder: MfccEmbedder,
+    max_speakers: usize,
+    pending: Vec<f32>,
+    position: usize,
+    window: Vec<f32>,
+    window_start: usize,
+    windows: Vec<SpeechWindow>,
+}
+
+impl Diarizer {
+    pub fn new(mut vad: Box<dyn VoiceActivityDetector>, max_speakers: usize) -> Self {
+        vad.reset();
+        Self {
+            vad,
+            embedder: MfccEmbedder::new(),
+            max_speakers: max_speakers.max(1),
+            pending: Vec::with_capacity(VAD_FRAME),
+            position: 0,
+            window: Vec::with_capacity(WINDOW_FRAMES * VAD_FRAME),
+            window_start: 0,
+            windows: Vec::new(),
+        }
+    }
+
+    /// Feeds the next stretch of audio; chunks may be any length.
+    pub fn push(&mut self, samples: &[f32]) {
+        self.pending.extend_from_slice(samples);
+        let whole = self.pending.len() - self.pending.len() % VAD_FRAME;
+        let pending = std::mem::take(&mut self.pending);
+        for frame in pending[..whole].chunks_exact(VAD_FRAME) {
+            self.push_frame(frame);
+        }
+        self.pending = pending[whole..].to_vec();
+    }
+
+    fn push_frame(&mut self, frame: &[f32]) {
+        if self.vad.is_voice(frame).unwrap_or(false) {
+            if self.window.is_empty() {
+                self.window_start = self.position;
+            }
+            self.window.extend_from_slice(frame);
+            if self.window.len() >= WINDOW_FRAMES * VAD_FRAME {
+                self.close_window();
+            }
+        } else {
+            self.close_window();
+        }
+        self.position += frame.len();
+    }
+
+    fn close_window(&mut self) {
+        if self.window.is_empty() {
+            return;
+        }
+        let embedding = (self.window.len() >= MIN_EMBEDDED_FRAMES * VAD_FRAME)
+            .then(|| self.embedder.embed(&self.window));
+        self.windows.push(SpeechWindow {
+            start: self.window_start,
+            end: self.window_start + self.window.len(),
+            embedding,
+        });
+        self.window.clear();
+    }
+
+    /// Clusters the speech seen so far into speaker turns. Consecutive windows
+    /// of the same speaker merge into one turn, including the pauses between
+    /// them. Empty when there was no speech long enough to embed.
+    pub fn finish(mut self) -> Vec<SpeakerTurn> {
+        self.close_window();
+
+        let (embedded, embeddings): (Vec<usize>, Vec<Vec<f32>>) = self
+            .windows
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(index, window)| window.embedding.take().map(|e| (index, e)))
+            .unzip();
+        if embedded.is_empty() {
+            return Vec::new();
+        }
+        let labels = cluster::cluster(&embeddings, self.max_speakers);
+
+        // Short windows take the label of the nearest embedded window.
+        let window_labels: Vec<usize> = (0..self.windows.len())
+            .map(|index| {
+                let nearest = match embedded.binary_search(&index) {
+                    Ok(position) | Err(position @ 0) => position,
+                    Err(position) if position == embedded.len() => position - 1,
+                    Err(position) => {
+                        if embedded[position] - index < index - embedded[position - 1] {
+                            position
+                        } else {
+                            position - 1
+                        }
+                    }
+                };
+                labels[nearest]
+            })
+            .collect();
+
+        // Number speakers by first appearance.
+        let mut order: Vec<usize> = Vec::new();
+        let mut turns: Vec<SpeakerTurn> = Vec::new();
+        for (window, label) in self.windows.iter().zip(window_labels) {
+            let speaker = match order.iter().position(|&l| l == label) {
+                Some(position) => position,
+                None => {
+                    order.push(label);
+                    order.len() - 1
+                }
+            } as u32
+                + 1;
+            let (start_ms, end_ms) = (samples_to_ms(window.start), samples_to_ms(window.end));
+            match turns.last_mut() {
+                Some(turn) if turn.speaker == speaker => turn.end_ms = end_ms,
+                _ => turns.push(SpeakerTurn {
+                    start_ms,
+                    end_ms,
+                    speaker,
+                }),
+            }
+        }
+        turns
+    }
+}
+
+/// Diarizes a whole recording held in memory.
+pub fn diarize(
+    samples: &[f32],
+    vad: Box<dyn VoiceActivityDetector>,
+    max_speakers: usize,
+) -> Vec<SpeakerTurn> {
+    let mut diarizer = Diarizer::new(vad, max_speakers);
+    diarizer.push(samples);
+    diarizer.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio_toolkit::vad::test_signals::noise;
+    use crate::audio_toolkit::vad::EnergyVad;
+
+    const SECOND: usize = WHISPER_SAMPLE_RATE as usize;
+
+    /// Harmonic voice with fundamental `f0`; `rolloff` shapes the spectral
+    /// envelope the way a different vocal tract would.
+    fn voice(f0: f32, rolloff: f32, len: usize, seed: &mut u32) -> Vec<f32> {
+        let harmonics = (4000.0 / f0) as usize;
+        noise(0.005, len, seed)
+            .into_iter()
+            .enumerate()
+            .map(|(i, n)| {
+                let t = i as f32 / WHISPER_SAMPLE_RATE as f32;
+                let sum: f32 = (1..=harmonics)
+                    .map(|h| {
+                        (2.0 * std::f32::consts::PI * f0 * h as f32 * t).sin()
+                            / (h as f32).powf(rolloff)
+                    })
+                    .sum();
+                0.2 * sum + n
+            })
+            .collect()
+    }
+
+    fn conversation(turns: &[(f32, f32)], seed: &mut u32) -> Vec<f32> {
+        let mut audio = noise(0.005, SECOND, seed);
+        for &(f0, rolloff) in turns {
+            audio.extend(voice(f0, rolloff, 3 * SECOND, seed));
+            audio.extend(noise(0.005, SECOND / 2, seed));
+        }
+        audio
+    }
+
+    #[test]
+    fn alternating_voices_become_two_speakers() {
+        let mut seed = 7;
+        let (a, b) = ((120.0, 1.0), (230.0, 2.5));
+        let audio = conversation(&[a, b, a, b], &mut seed);
+        let turns = diarize(&audio, Box::new(EnergyVad::new(0.5)), 4);
+
+        let speakers: Vec<u32> = turns.iter().map(|turn| turn.speaker).collect();
+        assert_eq!(speakers, vec![1, 2, 1, 2], "{turns:?}");
+        // The first turn starts after the leading second of silence.
+        assert!((900..=1100).contains(&turns[0].start_ms), "{turns:?}");
+    }
+
+    #[test]
+    fn a_single_voice_stays_one_speaker() {
+        let mut seed = 11;
+        let a = (140.0, 1.2);
+        let audio = conversation(&[a, a, a, a], &mut seed);
+        let turns = diarize(&audio, Box::new(EnergyVad::new(0.5)), 4);
+
+        assert_eq!(turns.len(), 1, "{turns:?}");
+        assert_eq!(turns[0].speaker, 1);
+    }
+
+    #[test]
+    fn speaker_cap_and_chunked_input() {
+        let mut seed = 13;
+        let (a, b, c) = ((120.0, 1.0), (230.0, 2.5), (400.0, 0.5));
+        let audio = conversation(&[a, b, c, a], &mut seed);
+
+        let mut diarizer = Diarizer::new(Box::new(EnergyVad::new(0.5)), 2);
+        for chunk in audio.chunks(1234) {
+            diarizer.push(chunk);
+        }
+        let chunked = diarizer.finish();
+        assert!(chunked.iter().all(|turn| turn.speaker <= 2), "{chunked:?}");
+        assert_eq!(
+            chunked,
+            diarize(&audio, Box::new(EnergyVad::new(0.5)), 2),
+            "chunking must not change the result"
+        );
+    }
+}
@@ -1,13 +1,19 @@
 pub mod audio;
 pub mod constants;
+pub mod diarization;
+pub mod spoken_punctuation;
 pub mod stop_words;
 pub mod text;
 pub mod utils;
 pub mod vad;
+pub mod voice_commands;
 
 pub use audio::{
-    list_input_devices, list_output_devices, save_wav_file, AudioRecorder, CpalDeviceInfo,
+    concat_recording_files, convert_recording_file, list_input_devices, list_output_devices,
+    load_audio_file_for_transcription, save_flac_file, save_wav_file, AudioRecorder,
+    CpalDeviceInfo, SpeechSegment,
 };
+pub use spoken_punctuation::{convert_spoken_punctuation, spoken_punctuation_language};
 pub use text::{
     apply_custom_words, apply_custom_words_with_thresholds, filter_and_count_filler_words,
     filter_hallucinations, filter_transcription_output,
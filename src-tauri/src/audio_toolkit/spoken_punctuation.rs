@@ -0,0 +1,421 @@
+//! Deterministic spoken punctuation.
+//!
+//! Converts dictated punctuation names ("comma", "open paren", "dash dash",
+//! "at sign") into symbols without an LLM. Each language has its own table,
+//! keyed by base language code.
+//!
+//! Coexistence with `smart_insertion::prepare_text_for_paste`:
+//! - Symbols are emitted with conventional spacing, so smart insertion sees
+//!   ordinary text when it adjusts boundary spaces at the cursor.
+//! - The first word of the transcript is never re-cased; sentence-start casing
+//!   at the cursor stays with smart insertion. Only words that follow a spoken
+//!   sentence terminator inside the transcript are capitalized.
+//! - Punctuation the recognizer already placed next to a spoken name
+//!   ("Hello, comma,") is dropped so smart insertion never sees doubled marks.
+
+/// How a symbol joins the surrounding words.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Spacing {
+    /// Attaches to the previous word: `,` `.` `)`.
+    AttachLeft,
+    /// Attaches to the next word: `(` `$`.
+    AttachRight,
+    /// Attaches to both neighbours: `@` `_` `-`.
+    Join,
+    /// Separated by spaces on both sides: `&`.
+    Spaced,
+}
+
+struct SpokenSymbol {
+    phrase: &'static str,
+    symbol: &'static str,
+    spacing: Spacing,
+    /// The phrase is also an ordinary word ("a period of time"). It is only
+    /// converted at the end of the transcript, next to recognizer punctuation,
+    /// or before a capitalized word.
+    ambiguous: bool,
+}
+
+const fn sym(phrase: &'static str, symbol: &'static str, spacing: Spacing) -> SpokenSymbol {
+    SpokenSymbol {
+        phrase,
+        symbol,
+        spacing,
+        ambiguous: false,
+    }
+}
+
+const fn ambiguous(phrase: &'static str, symbol: &'static str, spacing: Spacing) -> SpokenSymbol {
+    SpokenSymbol {
+        phrase,
+        symbol,
+        spacing,
+        ambiguous: true,
+    }
+}
+
+use Spacing::{AttachLeft, AttachRight, Join, Spaced};
+
+const EN_SYMBOLS: &[SpokenSymbol] = &[
+    sym("comma", ",", AttachLeft),
+    ambiguous("period", ".", AttachLeft),
+    sym("full stop", ".", AttachLeft),
+    sym("question mark", "?", AttachLeft),
+    sym("exclamation mark", "!", AttachLeft),
+    sym("exclamation point", "!", AttachLeft),
+    ambiguous("colon", ":", AttachLeft),
+    sym("semicolon", ";", AttachLeft),
+    sym("ellipsis", "…", AttachLeft),
+    sym("open paren", "(", AttachRight),
+    sym("open parenthesis", "(", AttachRight),
+    sym("left paren", "(", AttachRight),
+    sym("close paren", ")", AttachLeft),
+    sym("close parenthesis", ")", AttachLeft),
+    sym("right paren", ")", AttachLeft),
+    sym("open bracket", "[", AttachRight),
+    sym("close bracket", "]", AttachLeft),
+    sym("open brace", "{", AttachRight),
+    sym("close brace", "}", AttachLeft),
+    sym("open quote", "\"", AttachRight),
+    sym("close quote", "\"", AttachLeft),
+    sym("end quote", "\"", AttachLeft),
+    sym("dash dash", "--", AttachRight),
+    sym("em dash", "—", Join),
+    sym("en dash", "–", Join),
+    ambiguous("dash", "-", Join),
+    ambiguous("hyphen", "-", Join),
+    sym("at sign", "@", Join),
+    sym("underscore", "_", Join),
+    ambiguous("slash", "/", Join),
+    sym("forward slash", "/", Join),
+    sym("backslash", "\\", Join),
+    sym("hash sign", "#", AttachRight),
+    sym("dollar sign", "$", AttachRight),
+    sym("percent sign", "%", AttachLeft),
+    sym("ampersand", "&", Spaced),
+    sym("asterisk", "*", Spaced),
+];
+
+const DE_SYMBOLS: &[SpokenSymbol] = &[
+    sym("komma", ",", AttachLeft),
+    ambiguous("punkt", ".", AttachLeft),
+    sym("fragezeichen", "?", AttachLeft),
+    sym("ausrufezeichen", "!", AttachLeft),
+    sym("doppelpunkt", ":", AttachLeft),
+    sym("semikolon", ";", AttachLeft),
+    sym("klammer auf", "(", AttachRight),
+    sym("klammer zu", ")", AttachLeft),
+    sym("anführungszeichen auf", "„", AttachRight),
+    sym("anführungszeichen zu", "“", AttachLeft),
+    sym("gedankenstrich", "–", Spaced),
+    sym("bindestrich", "-", Join),
+    sym("at zeichen", "@", Join),
+    sym("unterstrich", "_", Join),
+    sym("schrägstrich", "/", Join),
+];
+
+const ES_SYMBOLS: &[SpokenSymbol] = &[
+    sym("coma", ",", AttachLeft),
+    sym("punto y coma", ";", AttachLeft),
+    ambiguous("punto", ".", AttachLeft),
+    sym("signo de interrogación", "?", AttachLeft),
+    sym("signo de exclamación", "!", AttachLeft),
+    sym("dos puntos", ":", AttachLeft),
+    sym("abrir paréntesis", "(", AttachRight),
+    sym("cerrar paréntesis", ")", AttachLeft),
+    sym("abrir comillas", "«", AttachRight),
+    sym("cerrar comillas", "»", AttachLeft),
+    sym("guion bajo", "_", Join),
+    ambiguous("guion", "-", Join),
+    sym("arroba", "@", Join),
+    ambiguous("barra", "/", Join),
+];
+
+const FR_SYMBOLS: &[SpokenSymbol] = &[
+    sym("virgule", ",", AttachLeft),
+    sym("point virgule", ";", AttachLeft),
+    sym("point d'interrogation", "?", AttachLeft),
+    sym("point d'exclamation", "!", AttachLeft),
+    ambiguous("point", ".", AttachLeft),
+    sym("deux points", ":", AttachLeft),
+    sym("ouvrir la parenthèse", "(", AttachRight),
+    sym("fermer la parenthèse", ")", AttachLeft),
+    sym("ouvrir les guillemets", "«", AttachRight),
+    sym("fermer les guillemets", "»", AttachLeft),
+    sym("tiret bas", "_", Join),
+    ambiguous("tiret", "-", Join),
+    sym("arobase", "@", Join),
+    sym("barre oblique", "/", Join),
+];
+
+/// Recognizer punctuation that is dropped when it touches a spoken name.
+const RECOGNIZER_PUNCTUATION: &[char] = &[',', '.', ';', ':', '!', '?'];
+
+fn base_language(lang: &str) -> &str {
+    lang.split(&['-', '_'][..]).next().unwrap_or(lang)
+}
+
+fn symbols_for_language(lang: &str) -> &'static [SpokenSymbol] {
+    match base_language(lang).to_lowercase().as_str() {
+        "en" => EN_SYMBOLS,
+        "de" => DE_SYMBOLS,
+        "es" => ES_SYMBOLS,
+        "fr" => FR_SYMBOLS,
+        _ => &[],
+    }
+}
+
+pub fn supports_spoken_punctuation(lang: &str) -> bool {
+    !symbols_for_language(lang).is_empty()
+}
+
+/// The language whose table applies. With automatic detection the app
+/// language is used, matching the filler-word filter.
+pub fn spoken_punctuation_language<'a>(
+    selected_language: &'a str,
+    app_language: &'a str,
+) -> &'a str {
+    if selected_language == "auto" {
+        app_language
+    } else {
+        selected_language
+    }
+}
+
+fn normalize_word(word: &str) -> String {
+    word.trim_matches(|c: char| !c.is_alphanumeric())
+        .replace('’', "'")
+        .to_lowercase()
+}
+
+fn is_sentence_terminator(symbol: &str) -> bool {
+    matches!(symbol, "." | "?" | "!" | "…")
+}
+
+fn capitalize_first(word: &str) -> String {
+    let Some(index) = word.find(char::is_alphabetic) else {
+        return word.to_string();
+    };
+    let (prefix, rest) = word.split_at(index);
+    let mut chars = rest.chars();
+    match chars.next() {
+        Some(first) => format!("{}{}{}", prefix, first.to_uppercase(), chars.as_str()),
+        None => word.to_string(),
+    }
+}
+
+enum Piece {
+    Word(String),
+    Symbol(&'static str, Spacing),
+}
+
+/// Replaces spoken punctuation names in `text` with symbols using the table
+/// for `lang`. Text in languages without a table is returned unchanged.
+pub fn convert_spoken_punctuation(text: &str, lang: &str) -> String {
+    let symbols = symbols_for_language(lang);
+    if symbols.is_empty() {
+        return text.to_string();
+    }
+
+    let mut phrases: Vec<(Vec<String>, &SpokenSymbol)> = symbols
+        .iter()
+        .map(|entry| {
+            (
+                entry
+                    .phrase
+                    .split_whitespace()
+                    .map(normalize_word)
+                    .collect(),
+                entry,
+            )
+        })
+        .collect();
+    // Longest phrase first so "punto y coma" wins over "punto".
+    phrases.sort_by_key(|(phrase, _)| std::cmp::Reverse(phrase.len()));
+
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let normalized: Vec<String> = words.iter().map(|word| normalize_word(word)).collect();
+
+    let mut pieces: Vec<Piece> = Vec::with_capacity(words.len());
+    let mut converted = false;
+    let mut i = 0;
+    while i < words.len() {
+        let matched = phrases.iter().find(|(phrase, entry)| {
+            let end = i + phrase.len();
+            end <= normalized.len()
+                && phrase
+                    .iter()
+                    .zip(&normalized[i..end])
+                    .all(|(expected, actual)| expected == actual)
+                && (!entry.ambiguous || ambiguous_phrase_is_dictated(&words, end))
+        });
+
+        let Some((phrase, entry)) = matched else {
+            pieces.push(Piece::Word(words[i].to_string()));
+            i += 1;
+            continue;
+        };
+
+        if matches!(entry.spacing, AttachLeft | Join) {
+            if let Some(Piece::Word(previous)) = pieces.last_mut() {
+                let trimmed_len = previous.trim_end_matches(RECOGNIZER_PUNCTUATION).len();
+                if trimmed_len > 0 {
+                    previous.truncate(trimmed_len);
+                }
+            }
+        }
+        pieces.push(Piece::Symbol(entry.symbol, entry.spacing));
+        converted = true;
+        i += phrase.len();
+    }
+
+    if !converted {
+        return text.to_string();
+    }
+    render(pieces)
+}
+
+/// Whether an ambiguous phrase ending before `end` reads as dictation rather
+/// than an ordinary word.
+fn ambiguous_phrase_is_dictated(words: &[&str], end: usize) -> bool {
+    let Some(next) = words.get(end) else {
+        return true;
+    };
+    words[end - 1].ends_with(RECOGNIZER_PUNCTUATION)
+        || next.chars().next().is_some_and(char::is_uppercase)
+}
+
+fn render(pieces: Vec<Piece>) -> String {
+    let mut output = String::new();
+    let mut space_allowed = false;
+    let mut capitalize_next = false;
+
+    for piece in pieces {
+        match piece {
+            Piece::Word(word) => {
+                if space_allowed {
+                    output.push(' ');
+                }
+                if capitalize_next {
+                    output.push_str(&capitalize_first(&word));
+                } else {
+                    output.push_str(&word);
+                }
+                space_allowed = true;
+                capitalize_next = false;
+            }
+            Piece::Symbol(symbol, spacing) => {
+                if space_allowed && matches!(spacing, AttachRight | Spaced) {
+                    output.push(' ');
+                }
+                output.push_str(symbol);
+                space_allowed = matches!(spacing, AttachLeft | Spaced);
+                if is_sentence_terminator(symbol) {
+                    capitalize_next = true;
+                }
+            }
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_clause_and_sentence_punctuation() {
+        assert_eq!(
+            convert_spoken_punctuation(
+                "hello comma how are you question mark i am fine period",
+                "en"
+            ),
+            "hello, how are you? I am fine."
+        );
+    }
+
+    #[test]
+    fn drops_recognizer_punctuation_next_to_spoken_names() {
+        assert_eq!(
+            convert_spoken_punctuation("Hello, comma, world. Period.", "en"),
+            "Hello, world."
+        );
+    }
+
+    #[test]
+    fn brackets_and_joined_symbols_attach_to_words() {
+        assert_eq!(
+            convert_spoken_punctuation("call foo open paren bar close paren now", "en"),
+            "call foo (bar) now"
+        );
+        assert_eq!(
+            convert_spoken_punctuation("mail jane at sign example.com", "en"),
+            "mail jane@example.com"
+        );
+        assert_eq!(
+            convert_spoken_punctuation("run it with dash dash verbose", "en"),
+            "run it with --verbose"
+        );
+    }
+
+    #[test]
+    fn ambiguous_words_stay_in_ordinary_speech() {
+        assert_eq!(
+            convert_spoken_punctuation("it took a period of time", "en"),
+            "it took a period of time"
+        );
+        assert_eq!(
+            convert_spoken_punctuation("a dash of salt", "en"),
+            "a dash of salt"
+        );
+        assert_eq!(
+            convert_spoken_punctuation("we are done period", "en"),
+            "we are done."
+        );
+        assert_eq!(
+            convert_spoken_punctuation("well dash Known", "en"),
+            "well-Known"
+        );
+    }
+
+    #[test]
+    fn first_word_casing_is_left_to_smart_insertion() {
+        assert_eq!(
+            convert_spoken_punctuation("and then comma we left", "en"),
+            "and then, we left"
+        );
+    }
+
+    #[test]
+    fn tables_are_language_specific() {
+        assert_eq!(
+            convert_spoken_punctuation("hola coma qué tal signo de interrogación", "es"),
+            "hola, qué tal?"
+        );
+        assert_eq!(
+            convert_spoken_punctuation("uno punto y coma dos", "es-ES"),
+            "uno; dos"
+        );
+        assert_eq!(
+            convert_spoken_punctuation("bonjour virgule ça va point d’interrogation", "fr"),
+            "bonjour, ça va?"
+        );
+        assert_eq!(
+            convert_spoken_punctuation("hallo komma wie geht's", "de"),
+            "hallo, wie geht's"
+        );
+        assert_eq!(
+            convert_spoken_punctuation("hello comma", "ja"),
+            "hello comma"
+        );
+    }
+
+    #[test]
+    fn auto_language_falls_back_to_app_language() {
+        assert_eq!(spoken_punctuation_language("auto", "de"), "de");
+        assert_eq!(spoken_punctuation_language("fr", "de"), "fr");
+        assert!(supports_spoken_punctuation("en-US"));
+        assert!(!supports_spoken_punctuation("zh-Hans"));
+    }
+}
@@ -1,5 +1,6 @@
 /// Returns the appropriate CPAL host for the current platform.
 /// On Linux, uses ALSA host. On other platforms, uses the default host.
+/// System audio bypasses CPAL; see `LoopbackCapture`.
 pub fn get_cpal_host() -> cpal::Host {
     #[cfg(target_os = "linux")]
     {
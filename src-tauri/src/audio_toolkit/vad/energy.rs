@@ -0,0 +1,112 @@
+use anyhow::Result;
+
+use super::{VadFrame, VoiceActivityDetector};
+
+/// Frames below this level are never speech.
+const MIN_SPEECH_DB: f32 = -60.0;
+/// Zero-crossing rate above which a frame only counts as speech when it is
+/// well above the noise floor (fricatives are noisy, hiss is too).
+const MAX_VOICED_ZCR: f32 = 0.25;
+/// Extra margin required for high zero-crossing frames.
+const UNVOICED_EXTRA_MARGIN_DB: f32 = 8.0;
+/// Noise floor tracking: fast when the level drops, slow while it rises.
+const FLOOR_FALL_RATE: f32 = 0.2;
+const FLOOR_RISE_RATE: f32 = 0.005;
+
+/// Lightweight detector based on frame energy relative to an adaptive noise
+/// floor, with zero-crossing rate to reject hiss. Needs no model files.
+pub struct EnergyVad {
+    margin_db: f32,
+    noise_floor_db: Option<f32>,
+}
+
+impl EnergyVad {
+    /// `sensitivity` in 0.0..=1.0; higher values detect quieter speech.
+    pub fn new(sensitivity: f32) -> Self {
+        let sensitivity = sensitivity.clamp(0.0, 1.0);
+        Self {
+            margin_db: 16.0 - 12.0 * sensitivity,
+            noise_floor_db: None,
+        }
+    }
+}
+
+fn energy_db(frame: &[f32]) -> f32 {
+    let mean_square = frame.iter().map(|s| s * s).sum::<f32>() / frame.len().max(1) as f32;
+    10.0 * (mean_square + 1e-10).log10()
+}
+
+fn zero_crossing_rate(frame: &[f32]) -> f32 {
+    let crossings = frame
+        .windows(2)
+        .filter(|pair| (pair[0] >= 0.0) != (pair[1] >= 0.0))
+        .count();
+    crossings as f32 / frame.len().max(1) as f32
+}
+
+impl VoiceActivityDetector for EnergyVad {
+    fn push_frame<'a>(&'a mut self, frame: &'a [f32]) -> Result<VadFrame<'a>> {
+        let energy = energy_db(frame);
+        let floor = *self.noise_floor_db.get_or_insert(energy);
+
+        let above_floor = energy - floor;
+        let is_speech = energy > MIN_SPEECH_DB
+            && above_floor > self.margin_db
+            && (zero_crossing_rate(frame) < MAX_VOICED_ZCR
+                || above_floor > self.margin_db + UNVOICED_EXTRA_MARGIN_DB);
+
+        let rate = if energy < floor {
+            FLOOR_FALL_RATE
+        } else {
+            FLOOR_RISE_RATE
+        };
+        self.noise_floor_db = Some(floor + rate * (energy - floor));
+
+        if is_speech {
+            Ok(VadFrame::Speech(frame))
+        } else {
+            Ok(VadFrame::Noise)
+        }
+    }
+
+    fn reset(&mut self) {
+        self.noise_floor_db = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio_toolkit::vad::test_signals::{noise, voiced, FRAME};
+
+    #[test]
+    fn voiced_frames_above_background_are_speech() {
+        let mut vad = EnergyVad::new(0.5);
+        let mut seed = 7;
+        for _ in 0..20 {
+            assert!(!vad.is_voice(&noise(0.003, FRAME, &mut seed)).unwrap());
+        }
+        let mut speech = voiced(0.1, FRAME, 0);
+        for (s, n) in speech.iter_mut().zip(noise(0.003, FRAME, &mut seed)) {
+            *s += n;
+        }
+        assert!(vad.is_voice(&speech).unwrap());
+    }
+
+    #[test]
+    fn steady_hiss_is_not_speech() {
+        let mut vad = EnergyVad::new(0.5);
+        let mut seed = 11;
+        let detected = (0..50)
+            .filter(|_| vad.is_voice(&noise(0.05, FRAME, &mut seed)).unwrap())
+            .count();
+        assert_eq!(detected, 0);
+    }
+
+    #[test]
+    fn digital_silence_is_not_speech() {
+        let mut vad = EnergyVad::new(1.0);
+        assert!(!vad.is_voice(&vec![0.0; FRAME]).unwrap());
+        assert!(!vad.is_voice(&voiced(0.0005, FRAME, 0)).unwrap());
+    }
+}
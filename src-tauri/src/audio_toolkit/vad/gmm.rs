@@ -0,0 +1,265 @@
+use anyhow::Result;
+use rustfft::{num_complex::Complex32, Fft, FftPlanner};
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use super::{VadFrame, VoiceActivityDetector};
+use crate::audio_toolkit::constants;
+
+/// Sub-bands used as features, as in the WebRTC VAD.
+const BANDS_HZ: [(f32, f32); 6] = [
+    (80.0, 250.0),
+    (250.0, 500.0),
+    (500.0, 1000.0),
+    (1000.0, 2000.0),
+    (2000.0, 3000.0),
+    (3000.0, 4000.0),
+];
+const FFT_LEN: usize = 512;
+
+/// Frames below this level are never speech.
+const MIN_SPEECH_DB: f32 = -65.0;
+/// Initial speech model offset above the first observed noise level.
+const INITIAL_SPEECH_OFFSET_DB: f32 = 20.0;
+const INITIAL_NOISE_STD_DB: f32 = 4.0;
+const INITIAL_SPEECH_STD_DB: f32 = 6.0;
+const MIN_STD_DB: f32 = 2.0;
+const MAX_STD_DB: f32 = 12.0;
+/// The speech mean is kept at least this far above the noise mean.
+const MIN_MODEL_SEPARATION_DB: f32 = 6.0;
+const NOISE_ADAPT_RATE: f32 = 0.05;
+/// Faster adaptation when a band drops below the noise mean.
+const NOISE_FALL_RATE: f32 = 0.2;
+const SPEECH_ADAPT_RATE: f32 = 0.02;
+/// Window for the per-band minimum that lifts the noise model when the
+/// background gets louder (~3 s of frames).
+const MINIMUM_WINDOW_FRAMES: usize = 100;
+
+#[derive(Clone, Copy)]
+struct Gaussian {
+    mean: f32,
+    std: f32,
+}
+
+impl Gaussian {
+    fn log_likelihood(&self, x: f32) -> f32 {
+        let z = (x - self.mean) / self.std;
+        -0.5 * z * z - self.std.ln()
+    }
+
+    fn adapt(&mut self, x: f32, rate: f32) {
+        self.mean += rate * (x - self.mean);
+        let variance = self.std * self.std;
+        let deviation = x - self.mean;
+        self.std = (variance + rate * (deviation * deviation - variance))
+            .sqrt()
+            .clamp(MIN_STD_DB, MAX_STD_DB);
+    }
+}
+
+#[derive(Clone, Copy)]
+struct BandModel {
+    noise: Gaussian,
+    speech: Gaussian,
+}
+
+/// WebRTC-style detector: per-band log energies are scored against adaptive
+/// Gaussian speech and noise models, and a frame is speech when the combined
+/// or any single-band log-likelihood ratio clears its threshold. Needs no
+/// model files.
+pub struct GmmVad {
+    fft: Arc<dyn Fft<f32>>,
+    spectrum: Vec<Complex32>,
+    band_bins: Vec<(usize, usize)>,
+    models: Option<[BandModel; BANDS_HZ.len()]>,
+    recent_energies: VecDeque<[f32; BANDS_HZ.len()]>,
+    global_threshold: f32,
+    local_threshold: f32,
+}
+
+impl GmmVad {
+    /// `sensitivity` in 0.0..=1.0; higher values detect quieter speech.
+    pub fn new(sensitivity: f32) -> Self {
+        let sensitivity = sensitivity.clamp(0.0, 1.0);
+        let mut planner = FftPlanner::<f32>::new();
+        let bin_hz = constants::WHISPER_SAMPLE_RATE as f32 / FFT_LEN as f32;
+        let band_bins = BANDS_HZ
+            .iter()
+            .map(|(low, high)| {
+                let start = (low / bin_hz).round() as usize;
+                let end = ((high / bin_hz).round() as usize).max(start + 1);
+                (start, end.min(FFT_LEN / 2))
+            })
+            .collect();
+
+        Self {
+            fft: planner.plan_fft_forward(FFT_LEN),
+            spectrum: vec![Complex32::new(0.0, 0.0); FFT_LEN],
+            band_bins,
+            models: None,
+            recent_energies: VecDeque::with_capacity(MINIMUM_WINDOW_FRAMES),
+            global_threshold: 1.5 - 2.0 * sensitivity,
+            local_threshold: 6.0 - 4.0 * sensitivity,
+        }
+    }
+
+    fn band_energies(&mut self, frame: &[f32]) -> [f32; BANDS_HZ.len()] {
+        let len = frame.len().min(FFT_LEN);
+        for (i, bin) in self.spectrum.iter_mut().enumerate() {
+            let sample = if i < len {
+                let window =
+                    0.5 * (1.0 - (2.0 * std::f32::consts::PI * i as f32 / len as f32).cos());
+                frame[i] * window
+            } else {
+                0.0
+            };
+            *bin = Complex32::new(sample, 0.0);
+        }
+        self.fft.process(&mut self.spectrum);
+
+        let scale = 1.0 / (len.max(1) as f32);
+        let mut energies = [0.0; BANDS_HZ.len()];
+        for (energy, &(start, end)) in energies.iter_mut().zip(&self.band_bins) {
+            let power = self.spectrum[start..end]
+                .iter()
+                .map(|bin| bin.norm_sqr())
+                .sum::<f32>()
+                * scale
+                / (end - start) as f32;
+            *energy = 10.0 * (power + 1e-10).log10();
+        }
+        energies
+    }
+}
+
+impl VoiceActivityDetector for GmmVad {
+    fn push_frame<'a>(&'a mut self, frame: &'a [f32]) -> Result<VadFrame<'a>> {
+        if frame.is_empty() {
+            return Ok(VadFrame::Noise);
+        }
+
+        let energies = self.band_energies(frame);
+        let models = self.models.get_or_insert_with(|| {
+            energies.map(|energy| BandModel {
+                noise: Gaussian {
+                    mean: energy,
+                    std: INITIAL_NOISE_STD_DB,
+                },
+                speech: Gaussian {
+                    mean: energy + INITIAL_SPEECH_OFFSET_DB,
+                    std: INITIAL_SPEECH_STD_DB,
+                },
+            })
+        });
+
+        let ratios: Vec<f32> = energies
+            .iter()
+            .zip(models.iter())
+            .map(|(&x, model)| model.speech.log_likelihood(x) - model.noise.log_likelihood(x))
+            .collect();
+        let mean_ratio = ratios.iter().sum::<f32>() / ratios.len() as f32;
+        let max_ratio = ratios.iter().copied().fold(f32::MIN, f32::max);
+
+        let frame_db =
+            10.0 * (frame.iter().map(|s| s * s).sum::<f32>() / frame.len() as f32 + 1e-10).log10();
+        let is_speech = frame_db > MIN_SPEECH_DB
+            && (mean_ratio > self.global_threshold || max_ratio > self.local_threshold);
+
+        if self.recent_energies.len() == MINIMUM_WINDOW_FRAMES {
+            self.recent_energies.pop_front();
+        }
+        self.recent_energies.push_back(energies);
+        let window_full = self.recent_energies.len() == MINIMUM_WINDOW_FRAMES;
+
+        for (band, (&x, model)) in energies.iter().zip(models.iter_mut()).enumerate() {
+            if x < model.noise.mean {
+                model.noise.adapt(x, NOISE_FALL_RATE);
+            } else if !is_speech {
+                model.noise.adapt(x, NOISE_ADAPT_RATE);
+            } else {
+                model.speech.adapt(x, SPEECH_ADAPT_RATE);
+            }
+            if window_full {
+                // Speech always has pauses, so a minimum that stays high for the
+                // whole window is background.
+                let minimum = self
+                    .recent_energies
+                    .iter()
+                    .map(|energies| energies[band])
+                    .fold(f32::MAX, f32::min);
+                model.noise.mean = model.noise.mean.max(minimum);
+            }
+            model.speech.mean = model
+                .speech
+                .mean
+                .max(model.noise.mean + MIN_MODEL_SEPARATION_DB);
+        }
+
+        if is_speech {
+            Ok(VadFrame::Speech(frame))
+        } else {
+            Ok(VadFrame::Noise)
+        }
+    }
+
+    fn reset(&mut self) {
+        self.models = None;
+        self.recent_energies.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio_toolkit::vad::test_signals::{noise, voiced, FRAME};
+
+    fn noisy_speech(amplitude: f32, offset: usize, seed: &mut u32) -> Vec<f32> {
+        voiced(amplitude, FRAME, offset)
+            .into_iter()
+            .zip(noise(0.005, FRAME, seed))
+            .map(|(s, n)| s + n)
+            .collect()
+    }
+
+    #[test]
+    fn speech_is_detected_over_steady_noise() {
+        let mut vad = GmmVad::new(0.5);
+        let mut seed = 3;
+        let false_alarms = (0..40)
+            .filter(|_| vad.is_voice(&noise(0.005, FRAME, &mut seed)).unwrap())
+            .count();
+        assert!(false_alarms <= 1, "{false_alarms} noise frames flagged");
+
+        let detected = (0..10)
+            .filter(|i| {
+                vad.is_voice(&noisy_speech(0.1, i * FRAME, &mut seed))
+                    .unwrap()
+            })
+            .count();
+        assert!(detected >= 9, "only {detected} speech frames detected");
+    }
+
+    #[test]
+    fn noise_model_adapts_to_louder_background() {
+        let mut vad = GmmVad::new(0.5);
+        let mut seed = 5;
+        for _ in 0..20 {
+            vad.is_voice(&noise(0.002, FRAME, &mut seed)).unwrap();
+        }
+        // A fan switching on is flagged at first, then learned as noise.
+        for _ in 0..300 {
+            vad.is_voice(&noise(0.02, FRAME, &mut seed)).unwrap();
+        }
+        let late_alarms = (0..30)
+            .filter(|_| vad.is_voice(&noise(0.02, FRAME, &mut seed)).unwrap())
+            .count();
+        assert!(late_alarms <= 1, "{late_alarms} late false alarms");
+    }
+
+    #[test]
+    fn quiet_frames_are_never_speech() {
+        let mut vad = GmmVad::new(1.0);
+        assert!(!vad.is_voice(&vec![0.0; FRAME]).unwrap());
+        assert!(!vad.is_voice(&voiced(0.0003, FRAME, 0)).unwrap());
+    }
+}
@@ -25,8 +25,42 @@ pub trait VoiceActivityDetector: Send + Sync {
     fn reset(&mut self) {}
 }
 
+mod energy;
+mod gmm;
 mod silero;
 mod smoothed;
 
+pub use energy::EnergyVad;
+pub use gmm::GmmVad;
 pub use silero::SileroVad;
-pub use smoothed::SmoothedVad;
+pub use smoothed::{validate_smoothing, SmoothedVad};
+
+#[cfg(test)]
+pub(crate) mod test_signals {
+    pub const FRAME: usize = 480;
+
+    /// Harmonic-rich voiced sound (150 Hz fundamental) with the given peak.
+    pub fn voiced(amplitude: f32, len: usize, offset: usize) -> Vec<f32> {
+        (0..len)
+            .map(|i| {
+                let t = (i + offset) as f32 / 16_000.0;
+                let sum: f32 = (1..=10)
+                    .map(|h| (2.0 * std::f32::consts::PI * 150.0 * h as f32 * t).sin() / h as f32)
+                    .sum();
+                amplitude * sum / 2.0
+            })
+            .collect()
+    }
+
+    /// Deterministic white noise in [-amplitude, amplitude].
+    pub fn noise(amplitude: f32, len: usize, seed: &mut u32) -> Vec<f32> {
+        (0..len)
+            .map(|_| {
+                *seed ^= *seed << 13;
+                *seed ^= *seed >> 17;
+                *seed ^= *seed << 5;
+                (*seed as f32 / u32::MAX as f32 * 2.0 - 1.0) * amplitude
+            })
+            .collect()
+    }
+}
@@ -2,6 +2,38 @@ use super::{VadFrame, VoiceActivityDetector};
 use anyhow::Result;
 use std::collections::VecDeque;
 
+/// Upper bounds for user-configurable smoothing, in 30 ms frames.
+pub const MAX_PREFILL_FRAMES: usize = 30;
+pub const MAX_HANGOVER_FRAMES: usize = 60;
+pub const MAX_ONSET_FRAMES: usize = 10;
+
+/// Checks smoothing parameters before they are stored or used.
+pub fn validate_smoothing(
+    prefill_frames: usize,
+    hangover_frames: usize,
+    onset_frames: usize,
+) -> Result<(), String> {
+    if prefill_frames > MAX_PREFILL_FRAMES {
+        return Err(format!(
+            "Prefill must be at most {} frames, got {}",
+            MAX_PREFILL_FRAMES, prefill_frames
+        ));
+    }
+    if hangover_frames > MAX_HANGOVER_FRAMES {
+        return Err(format!(
+            "Hangover must be at most {} frames, got {}",
+            MAX_HANGOVER_FRAMES, hangover_frames
+        ));
+    }
+    if !(1..=MAX_ONSET_FRAMES).contains(&onset_frames) {
+        return Err(format!(
+            "Onset must be between 1 and {} frames, got {}",
+            MAX_ONSET_FRAMES, onset_frames
+        ));
+    }
+    Ok(())
+}
+
 pub struct SmoothedVad {
     inner_vad: Box<dyn VoiceActivityDetector>,
     prefill_frames: usize,
@@ -103,3 +135,18 @@ impl VoiceActivityDetector for SmoothedVad {
         self.temp_out.clear();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn smoothing_bounds_are_enforced() {
+        assert!(validate_smoothing(15, 15, 2).is_ok());
+        assert!(validate_smoothing(0, 0, 1).is_ok());
+        assert!(validate_smoothing(MAX_PREFILL_FRAMES + 1, 15, 2).is_err());
+        assert!(validate_smoothing(15, MAX_HANGOVER_FRAMES + 1, 2).is_err());
+        assert!(validate_smoothing(15, 15, 0).is_err());
+        assert!(validate_smoothing(15, 15, MAX_ONSET_FRAMES + 1).is_err());
+    }
+}
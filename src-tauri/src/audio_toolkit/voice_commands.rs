@@ -0,0 +1,426 @@
+//! Spoken editing commands.
+//!
+//! Turns phrases such as "new paragraph", "scratch that" or "press enter" in a
+//! transcript into a sequence of [`EditOperation`]s. Parsing is pure; the
+//! operations are executed by `clipboard::paste_edit_operations`.
+//!
+//! Each language has its own grammar, keyed by the base language code used in
+//! `selected_language` (e.g. "en" for "en-US"). Commands are opt-in: callers
+//! only pass the languages the user enabled.
+
+use std::collections::HashSet;
+
+/// A key press produced by a spoken command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditKey {
+    Enter,
+    Tab,
+    Escape,
+    Backspace,
+    /// The platform undo shortcut (Cmd+Z / Ctrl+Z).
+    Undo,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EditOperation {
+    /// Text to insert; line breaks from "new line" commands are included.
+    Insert(String),
+    Key(EditKey),
+    /// Select the word before the cursor.
+    SelectLastWord,
+    /// Select the last sentence of the text inserted just before.
+    SelectLastSentence,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Command {
+    NewLine,
+    NewParagraph,
+    /// Drop the text dictated since the previous command; at the start of an
+    /// utterance, undo the previous insertion instead.
+    ScratchThat,
+    Press(EditKey),
+    AllCapsNextWord,
+    CapitalizeNextWord,
+    LowercaseNextWord,
+    SelectLastWord,
+    SelectLastSentence,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CaseModifier {
+    AllCaps,
+    Capitalize,
+    Lowercase,
+}
+
+const EN_COMMANDS: &[(&str, Command)] = &[
+    ("new line", Command::NewLine),
+    ("newline", Command::NewLine),
+    ("new paragraph", Command::NewParagraph),
+    ("scratch that", Command::ScratchThat),
+    ("delete that", Command::ScratchThat),
+    ("strike that", Command::ScratchThat),
+    ("undo that", Command::Press(EditKey::Undo)),
+    ("press enter", Command::Press(EditKey::Enter)),
+    ("press return", Command::Press(EditKey::Enter)),
+    ("press tab", Command::Press(EditKey::Tab)),
+    ("press escape", Command::Press(EditKey::Escape)),
+    ("press backspace", Command::Press(EditKey::Backspace)),
+    ("all caps next word", Command::AllCapsNextWord),
+    ("caps next word", Command::CapitalizeNextWord),
+    ("cap next word", Command::CapitalizeNextWord),
+    ("capitalize next word", Command::CapitalizeNextWord),
+    ("no caps next word", Command::LowercaseNextWord),
+    ("lowercase next word", Command::LowercaseNextWord),
+    ("select last word", Command::SelectLastWord),
+    ("select previous word", Command::SelectLastWord),
+    ("select last sentence", Command::SelectLastSentence),
+];
+
+const DE_COMMANDS: &[(&str, Command)] = &[
+    ("neue zeile", Command::NewLine),
+    ("neuer absatz", Command::NewParagraph),
+    ("lösch das", Command::ScratchThat),
+    ("lösche das", Command::ScratchThat),
+    ("streich das", Command::ScratchThat),
+    ("rückgängig machen", Command::Press(EditKey::Undo)),
+    ("enter drücken", Command::Press(EditKey::Enter)),
+    ("drücke enter", Command::Press(EditKey::Enter)),
+    ("tab drücken", Command::Press(EditKey::Tab)),
+    ("drücke tab", Command::Press(EditKey::Tab)),
+    ("escape drücken", Command::Press(EditKey::Escape)),
+    ("rücktaste drücken", Command::Press(EditKey::Backspace)),
+    ("nächstes wort in großbuchstaben", Command::AllCapsNextWord),
+    ("nächstes wort groß", Command::CapitalizeNextWord),
+    ("nächstes wort klein", Command::LowercaseNextWord),
+    ("letztes wort markieren", Command::SelectLastWord),
+    ("letzten satz markieren", Command::SelectLastSentence),
+];
+
+const ES_COMMANDS: &[(&str, Command)] = &[
+    ("nueva línea", Command::NewLine),
+    ("nuevo párrafo", Command::NewParagraph),
+    ("borra eso", Command::ScratchThat),
+    ("borrar eso", Command::ScratchThat),
+    ("deshacer eso", Command::Press(EditKey::Undo)),
+    ("pulsa intro", Command::Press(EditKey::Enter)),
+    ("pulsa enter", Command::Press(EditKey::Enter)),
+    ("pulsa tabulador", Command::Press(EditKey::Tab)),
+    ("pulsa escape", Command::Press(EditKey::Escape)),
+    ("pulsa retroceso", Command::Press(EditKey::Backspace)),
+    ("siguiente palabra en mayúsculas", Command::AllCapsNextWord),
+    (
+        "siguiente palabra con mayúscula",
+        Command::CapitalizeNextWord,
+    ),
+    (
+        "siguiente palabra en minúsculas",
+        Command::LowercaseNextWord,
+    ),
+    ("selecciona la última palabra", Command::SelectLastWord),
+    ("selecciona la última frase", Command::SelectLastSentence),
+];
+
+const FR_COMMANDS: &[(&str, Command)] = &[
+    ("nouvelle ligne", Command::NewLine),
+    ("à la ligne", Command::NewLine),
+    ("nouveau paragraphe", Command::NewParagraph),
+    ("efface ça", Command::ScratchThat),
+    ("supprime ça", Command::ScratchThat),
+    ("annule ça", Command::Press(EditKey::Undo)),
+    ("appuie sur entrée", Command::Press(EditKey::Enter)),
+    ("appuie sur tab", Command::Press(EditKey::Tab)),
+    ("appuie sur échap", Command::Press(EditKey::Escape)),
+    (
+        "appuie sur retour arrière",
+        Command::Press(EditKey::Backspace),
+    ),
+    ("mot suivant en majuscules", Command::AllCapsNextWord),
+    ("majuscule au mot suivant", Command::CapitalizeNextWord),
+    ("mot suivant en minuscules", Command::LowercaseNextWord),
+    ("sélectionne le dernier mot", Command::SelectLastWord),
+    (
+        "sélectionne la dernière phrase",
+        Command::SelectLastSentence,
+    ),
+];
+
+/// Base language codes that have a command grammar.
+pub const SUPPORTED_VOICE_COMMAND_LANGUAGES: &[&str] = &["en", "de", "es", "fr"];
+
+fn base_language(lang: &str) -> &str {
+    lang.split(&['-', '_'][..]).next().unwrap_or(lang)
+}
+
+fn commands_for_language(lang: &str) -> &'static [(&'static str, Command)] {
+    match base_language(lang) {
+        "en" => EN_COMMANDS,
+        "de" => DE_COMMANDS,
+        "es" => ES_COMMANDS,
+        "fr" => FR_COMMANDS,
+        _ => &[],
+    }
+}
+
+pub fn supports_voice_commands(lang: &str) -> bool {
+    SUPPORTED_VOICE_COMMAND_LANGUAGES.contains(&base_language(lang))
+}
+
+/// Languages whose grammar applies to a transcript in `selected_language`.
+/// With automatic language detection every enabled language applies.
+pub fn active_command_languages(selected_language: &str, enabled: &[String]) -> Vec<String> {
+    let mut seen = HashSet::new();
+    let mut languages: Vec<String> = enabled
+        .iter()
+        .map(|lang| base_language(lang.trim()).to_lowercase())
+        .filter(|lang| supports_voice_commands(lang) && seen.insert(lang.clone()))
+        .collect();
+
+    if selected_language == "auto" {
+        return languages;
+    }
+
+    let selected = base_language(selected_language).to_lowercase();
+    languages.retain(|lang| *lang == selected);
+    languages
+}
+
+fn normalize_word(word: &str) -> String {
+    word.trim_matches(|c: char| !c.is_alphanumeric())
+        .to_lowercase()
+}
+
+fn apply_case(word: &str, modifier: CaseModifier) -> String {
+    match modifier {
+        CaseModifier::AllCaps => word.to_uppercase(),
+        CaseModifier::Lowercase => word.to_lowercase(),
+        CaseModifier::Capitalize => {
+            let Some(index) = word.find(char::is_alphabetic) else {
+                return word.to_string();
+            };
+            let (prefix, rest) = word.split_at(index);
+            let mut chars = rest.chars();
+            match chars.next() {
+                Some(first) => format!("{}{}{}", prefix, first.to_uppercase(), chars.as_str()),
+                None => word.to_string(),
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+struct OperationBuilder {
+    operations: Vec<EditOperation>,
+    pending: String,
+    /// Start of the text dictated since the previous command within `pending`.
+    segment_start: usize,
+    case_modifier: Option<CaseModifier>,
+}
+
+impl OperationBuilder {
+    fn push_word(&mut self, word: &str) {
+        if !self.pending.is_empty() && !self.pending.ends_with('\n') {
+            self.pending.push(' ');
+        }
+        match self.case_modifier.take() {
+            Some(modifier) => self.pending.push_str(&apply_case(word, modifier)),
+            None => self.pending.push_str(word),
+        }
+    }
+
+    fn push_line_breaks(&mut self, breaks: &str) {
+        // Clause punctuation the recognizer put before the command is noise.
+        let trimmed_len = self.pending.trim_end_matches([' ', ',', ';']).len();
+        self.pending.truncate(trimmed_len);
+        self.pending.push_str(breaks);
+        self.segment_start = self.pending.len();
+    }
+
+    fn flush(&mut self) {
+        if !self.pending.is_empty() {
+            self.operations
+                .push(EditOperation::Insert(std::mem::take(&mut self.pending)));
+        }
+        self.segment_start = 0;
+    }
+
+    fn push_operation(&mut self, operation: EditOperation) {
+        self.flush();
+        self.operations.push(operation);
+    }
+
+    fn command(&mut self, command: Command) {
+        match command {
+            Command::NewLine => self.push_line_breaks("\n"),
+            Command::NewParagraph => self.push_line_breaks("\n\n"),
+            Command::ScratchThat => {
+                if self.pending.len() > self.segment_start {
+                    self.pending.truncate(self.segment_start);
+                } else if self.pending.is_empty() && self.operations.is_empty() {
+                    self.operations.push(EditOperation::Key(EditKey::Undo));
+                }
+            }
+            Command::Press(key) => self.push_operation(EditOperation::Key(key)),
+            Command::AllCapsNextWord => self.case_modifier = Some(CaseModifier::AllCaps),
+            Command::CapitalizeNextWord => self.case_modifier = Some(CaseModifier::Capitalize),
+            Command::LowercaseNextWord => self.case_modifier = Some(CaseModifier::Lowercase),
+            Command::SelectLastWord => self.push_operation(EditOperation::SelectLastWord),
+            Command::SelectLastSentence => self.push_operation(EditOperation::SelectLastSentence),
+        }
+    }
+
+    fn finish(mut self) -> Vec<EditOperation> {
+        self.flush();
+        self.operations
+    }
+}
+
+/// Parses spoken commands from `text` using the grammars of `languages`.
+/// Returns `None` when the transcript contains no command, so callers can keep
+/// the plain paste path untouched.
+pub fn parse_voice_commands(text: &str, languages: &[String]) -> Option<Vec<EditOperation>> {
+    let mut phrases: Vec<(Vec<String>, Command)> = languages
+        .iter()
+        .flat_map(|lang| commands_for_language(lang).iter())
+        .map(|(phrase, command)| {
+            (
+                phrase.split_whitespace().map(normalize_word).collect(),
+                *command,
+            )
+        })
+        .collect();
+    if phrases.is_empty() {
+        return None;
+    }
+    // Longest phrase first so "all caps next word" wins over "caps next word".
+    phrases.sort_by_key(|(phrase, _)| std::cmp::Reverse(phrase.len()));
+
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let normalized: Vec<String> = words.iter().map(|word| normalize_word(word)).collect();
+
+    let mut builder = OperationBuilder::default();
+    let mut found_command = false;
+    let mut i = 0;
+    while i < words.len() {
+        let matched = phrases.iter().find(|(phrase, _)| {
+            i + phrase.len() <= normalized.len()
+                && phrase
+                    .iter()
+                    .zip(&normalized[i..])
+                    .all(|(expected, actual)| expected == actual)
+        });
+
+        if let Some((phrase, command)) = matched {
+            builder.command(*command);
+            found_command = true;
+            i += phrase.len();
+        } else {
+            builder.push_word(words[i]);
+            i += 1;
+        }
+    }
+
+    found_command.then(|| builder.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn en() -> Vec<String> {
+        vec!["en".to_string()]
+    }
+
+    fn insert(text: &str) -> EditOperation {
+        EditOperation::Insert(text.to_string())
+    }
+
+    #[test]
+    fn plain_text_has_no_operations() {
+        assert_eq!(parse_voice_commands("Hello there, friend.", &en()), None);
+        assert_eq!(parse_voice_commands("new line", &[]), None);
+    }
+
+    #[test]
+    fn line_breaks_are_folded_into_inserted_text() {
+        assert_eq!(
+            parse_voice_commands(
+                "Dear team, new paragraph. Thanks for the update, new line. Bob",
+                &en()
+            ),
+            Some(vec![insert("Dear team\n\nThanks for the update\nBob")])
+        );
+    }
+
+    #[test]
+    fn scratch_that_drops_the_current_segment() {
+        assert_eq!(
+            parse_voice_commands(
+                "First line new line wrong words scratch that right words",
+                &en()
+            ),
+            Some(vec![insert("First line\nright words")])
+        );
+    }
+
+    #[test]
+    fn leading_scratch_that_undoes_previous_insertion() {
+        assert_eq!(
+            parse_voice_commands("Scratch that.", &en()),
+            Some(vec![EditOperation::Key(EditKey::Undo)])
+        );
+    }
+
+    #[test]
+    fn key_presses_split_inserted_text() {
+        assert_eq!(
+            parse_voice_commands("Sounds good. Press enter.", &en()),
+            Some(vec![
+                insert("Sounds good."),
+                EditOperation::Key(EditKey::Enter)
+            ])
+        );
+    }
+
+    #[test]
+    fn case_modifiers_apply_to_next_word_only() {
+        assert_eq!(
+            parse_voice_commands("use all caps next word json cap next word rust here", &en()),
+            Some(vec![insert("use JSON Rust here")])
+        );
+        assert_eq!(
+            parse_voice_commands("no caps next word IPHONE", &en()),
+            Some(vec![insert("iphone")])
+        );
+    }
+
+    #[test]
+    fn selection_commands_follow_inserted_text() {
+        assert_eq!(
+            parse_voice_commands("It works. Select last sentence.", &en()),
+            Some(vec![insert("It works."), EditOperation::SelectLastSentence])
+        );
+    }
+
+    #[test]
+    fn grammars_are_localized() {
+        let fr = vec!["fr".to_string()];
+        assert_eq!(
+            parse_voice_commands("Bonjour, nouvelle ligne. À bientôt", &fr),
+            Some(vec![insert("Bonjour\nÀ bientôt")])
+        );
+        assert_eq!(parse_voice_commands("Bonjour new line", &fr), None);
+    }
+
+    #[test]
+    fn active_languages_require_opt_in() {
+        let enabled = vec!["en".to_string(), "de".to_string(), "ja".to_string()];
+        assert_eq!(active_command_languages("en-US", &enabled), vec!["en"]);
+        assert!(active_command_languages("fr", &enabled).is_empty());
+        assert_eq!(active_command_languages("auto", &enabled), vec!["en", "de"]);
+
+        let twice = vec!["en".to_string(), "de".to_string(), "en-GB".to_string()];
+        assert_eq!(active_command_languages("auto", &twice), vec!["en", "de"]);
+    }
+}
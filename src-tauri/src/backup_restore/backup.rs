@@ -4,6 +4,10 @@
 //! manifest/checksums, and packages the final archive atomically.
 
 use super::*;
+use crate::managers::history::{
+    load_bias_terms, load_entry_tags, load_revisions, load_speaker_names, load_speaker_turns,
+    load_timings,
+};
 
 const ESTIMATED_ARCHIVE_METADATA_OVERHEAD_BYTES: u64 = 8 * 1024;
 const BACKUP_PROGRESS_TOTAL_UNITS: u64 = 10_000;
@@ -436,6 +440,14 @@ pub fn get_backup_estimate<R: tauri::Runtime>(
     })
 }
 
+fn is_precompressed_recording(relative: &str) -> bool {
+    relative.starts_with(&format!("{RECORDINGS_DIR}/"))
+        && Path::new(relative)
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("flac"))
+}
+
 fn estimate_total_backup_bytes(
     payload_bytes: u64,
     includes_recordings: bool,
@@ -642,6 +654,13 @@ pub(super) fn export_history_jsonl<R: tauri::Runtime>(
              ORDER BY id ASC",
         )
         .map_err(|error| format!("Failed to query history entries for backup: {error}"))?;
+    let has_timings = sqlite_table_exists(&conn, "transcription_timings")?;
+    let has_tags = sqlite_table_exists(&conn, "history_entry_tags")?
+        && sqlite_table_exists(&conn, "history_tags")?;
+    let has_revisions = sqlite_table_exists(&conn, "transcription_revisions")?;
+    let has_speakers = sqlite_table_exists(&conn, "history_speaker_turns")?
+        && sqlite_table_exists(&conn, "history_speaker_names")?;
+    let has_bias_terms = sqlite_table_exists(&conn, "history_bias_terms")?;
 
     let parent = output_path
         .parent()
@@ -671,6 +690,12 @@ pub(super) fn export_history_jsonl<R: tauri::Runtime>(
                 post_process_prompt: row.get(8)?,
                 duration_ms: row.get(9)?,
                 speech_duration_ms: row.get(10)?,
+                timings: Vec::new(),
+                tags: Vec::new(),
+                revisions: Vec::new(),
+                speaker_turns: Vec::new(),
+                speaker_names: Vec::new(),
+                bias_terms: Vec::new(),
             })
         })
         .map_err(|error| format!("Failed to iterate history entries for backup: {error}"))?;
@@ -686,6 +711,47 @@ pub(super) fn export_history_jsonl<R: tauri::Runtime>(
             )
         })?;
         row.file_name = safe_file_name.clone();
+        if has_timings {
+            row.timings = load_timings(&conn, row.id)
+                .map_err(|error| format!("Failed to read history timings for backup: {error}"))?;
+        }
+        if has_tags {
+            row.tags = load_entry_tags(&conn, row.id)
+                .map_err(|error| format!("Failed to read history tags for backup: {error}"))?
+                .into_iter()
+                .map(|tag| HistoryRowTagV1 {
+                    name: tag.name,
+                    kind: tag.kind,
+                })
+                .collect();
+        }
+        if has_revisions {
+            row.revisions = load_revisions(&conn, row.id)
+                .map_err(|error| format!("Failed to read history revisions for backup: {error}"))?
+                .into_iter()
+                .map(|revision| HistoryRowRevisionV1 {
+                    created_at: revision.created_at,
+                    model_id: revision.model_id,
+                    model_name: revision.model_name,
+                    transcription_text: revision.transcription_text,
+                    elapsed_ms: revision.elapsed_ms,
+                    bias_terms: revision.bias_terms,
+                })
+                .collect();
+        }
+        if has_speakers {
+            row.speaker_turns = load_speaker_turns(&conn, row.id).map_err(|error| {
+                format!("Failed to read history speaker turns for backup: {error}")
+            })?;
+            row.speaker_names = load_speaker_names(&conn, row.id).map_err(|error| {
+                format!("Failed to read history speaker names for backup: {error}")
+            })?;
+        }
+        if has_bias_terms {
+            row.bias_terms = load_bias_terms(&conn, row.id).map_err(|error| {
+                format!("Failed to read history bias terms for backup: {error}")
+            })?;
+        }
 
         if collect_recordings {
             referenced_recordings.insert(safe_file_name);
@@ -1066,7 +1132,13 @@ where
                 format!("Failed to open payload file '{relative}' for ZIP: {error}")
             })?;
 
-            zip.start_file(relative.clone(), options)
+            // Compressed recordings gain nothing from deflate.
+            let entry_options = if is_precompressed_recording(&relative) {
+                options.compression_method(CompressionMethod::Stored)
+            } else {
+                options
+            };
+            zip.start_file(relative.clone(), entry_options)
                 .map_err(|error| format!("Failed to start ZIP entry '{relative}': {error}"))?;
 
             loop {
@@ -5,6 +5,12 @@
 //! sibling modules.
 
 use crate::managers::audio::AudioRecordingManager;
+use crate::managers::history::{
+    HISTORY_BIAS_TERMS_SCHEMA, HISTORY_SPEAKERS_SCHEMA, HISTORY_TAGS_SCHEMA, HistorySpeaker,
+    HistorySpeakerTurn, HistoryTagKind, REVISION_BIAS_TERMS_SCHEMA,
+    TRANSCRIPTION_HISTORY_FTS_SCHEMA, TRANSCRIPTION_REVISIONS_SCHEMA,
+    TRANSCRIPTION_TIMINGS_SCHEMA, TranscriptTiming,
+};
 use crate::managers::transcription::TranscriptionManager;
 use crate::user_dictionary::{self, CustomWordEntry};
 use chrono::{DateTime, Duration, Local, Utc};
@@ -233,6 +239,37 @@ struct HistoryRowV1 {
     post_process_prompt: Option<String>,
     duration_ms: i64,
     speech_duration_ms: i64,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    timings: Vec<TranscriptTiming>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<HistoryRowTagV1>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    revisions: Vec<HistoryRowRevisionV1>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    speaker_turns: Vec<HistorySpeakerTurn>,
+    /// Only speakers the user renamed; the rest default to "Speaker N".
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    speaker_names: Vec<HistorySpeaker>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    bias_terms: Vec<String>,
+}
+
+/// Tags travel by name so restore can rebuild ids in the staged database.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+struct HistoryRowTagV1 {
+    name: String,
+    kind: HistoryTagKind,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+struct HistoryRowRevisionV1 {
+    created_at: i64,
+    model_id: String,
+    model_name: String,
+    transcription_text: String,
+    elapsed_ms: i64,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    bias_terms: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -4,7 +4,10 @@
 //! and restore runtime artifact cleanup.
 
 use super::*;
-use crate::managers::history::{CurrentStreakSnapshot, compute_standard_streak_snapshot, format_date_key};
+use crate::managers::history::{
+    CurrentStreakSnapshot, compute_standard_streak_snapshot, format_date_key, get_or_create_tag,
+    insert_bias_terms, insert_revision, insert_speaker_turns, insert_timings, set_speaker_name,
+};
 
 pub(super) const MANUAL_STATS_REPAIR_ENV_VAR: &str = "HANDY_MANUAL_STATS_REPAIR_20260303";
 pub(super) const MANUAL_STATS_REPAIR_BAD_WORDS: i64 = 46_208;
@@ -874,6 +877,23 @@ pub(super) fn initialize_history_db(path: &Path) -> Result<(), String> {
         ",
     )
     .map_err(|error| format!("Failed to initialize staged history schema: {error}"))?;
+    conn.execute_batch(TRANSCRIPTION_TIMINGS_SCHEMA)
+        .map_err(|error| format!("Failed to initialize staged history timings schema: {error}"))?;
+    conn.execute_batch(TRANSCRIPTION_HISTORY_FTS_SCHEMA)
+        .map_err(|error| format!("Failed to initialize staged history search index: {error}"))?;
+    conn.execute_batch(HISTORY_TAGS_SCHEMA)
+        .map_err(|error| format!("Failed to initialize staged history tags schema: {error}"))?;
+    conn.execute_batch(TRANSCRIPTION_REVISIONS_SCHEMA).map_err(|error| {
+        format!("Failed to initialize staged history revisions schema: {error}")
+    })?;
+    conn.execute_batch(HISTORY_SPEAKERS_SCHEMA)
+        .map_err(|error| format!("Failed to initialize staged history speakers schema: {error}"))?;
+    conn.execute_batch(HISTORY_BIAS_TERMS_SCHEMA).map_err(|error| {
+        format!("Failed to initialize staged history bias terms schema: {error}")
+    })?;
+    conn.execute_batch(REVISION_BIAS_TERMS_SCHEMA).map_err(|error| {
+        format!("Failed to initialize staged revision bias terms schema: {error}")
+    })?;
 
     Ok(())
 }
@@ -890,6 +910,36 @@ pub(super) fn import_history_jsonl<R: tauri::Runtime>(
         .transaction()
         .map_err(|error| format!("Failed to open staged history transaction: {error}"))?;
 
+    tx.execute_batch(TRANSCRIPTION_TIMINGS_SCHEMA)
+        .map_err(|error| format!("Failed to prepare staged history timings: {error}"))?;
+    tx.execute_batch(TRANSCRIPTION_HISTORY_FTS_SCHEMA)
+        .map_err(|error| format!("Failed to prepare staged history search index: {error}"))?;
+    tx.execute_batch(HISTORY_TAGS_SCHEMA)
+        .map_err(|error| format!("Failed to prepare staged history tags: {error}"))?;
+    tx.execute_batch(TRANSCRIPTION_REVISIONS_SCHEMA)
+        .map_err(|error| format!("Failed to prepare staged history revisions: {error}"))?;
+    tx.execute_batch(HISTORY_SPEAKERS_SCHEMA)
+        .map_err(|error| format!("Failed to prepare staged history speakers: {error}"))?;
+    tx.execute_batch(HISTORY_BIAS_TERMS_SCHEMA)
+        .map_err(|error| format!("Failed to prepare staged history bias terms: {error}"))?;
+    tx.execute_batch(REVISION_BIAS_TERMS_SCHEMA)
+        .map_err(|error| format!("Failed to prepare staged revision bias terms: {error}"))?;
+    tx.execute("DELETE FROM revision_bias_terms", [])
+        .map_err(|error| format!("Failed to clear staged revision bias terms: {error}"))?;
+    tx.execute("DELETE FROM history_bias_terms", [])
+        .map_err(|error| format!("Failed to clear staged history bias terms: {error}"))?;
+    tx.execute("DELETE FROM history_speaker_names", [])
+        .map_err(|error| format!("Failed to clear staged history speaker names: {error}"))?;
+    tx.execute("DELETE FROM history_speaker_turns", [])
+        .map_err(|error| format!("Failed to clear staged history speaker turns: {error}"))?;
+    tx.execute("DELETE FROM transcription_revisions", [])
+        .map_err(|error| format!("Failed to clear staged history revisions: {error}"))?;
+    tx.execute("DELETE FROM history_entry_tags", [])
+        .map_err(|error| format!("Failed to clear staged history tag links: {error}"))?;
+    tx.execute("DELETE FROM history_tags", [])
+        .map_err(|error| format!("Failed to clear staged history tags: {error}"))?;
+    tx.execute("DELETE FROM transcription_timings", [])
+        .map_err(|error| format!("Failed to clear staged history timings: {error}"))?;
     tx.execute("DELETE FROM transcription_history", [])
         .map_err(|error| format!("Failed to clear staged history entries: {error}"))?;
     tx.execute("DELETE FROM user_stats", [])
@@ -954,6 +1004,39 @@ pub(super) fn import_history_jsonl<R: tauri::Runtime>(
             ],
         )
         .map_err(|error| format!("Failed to insert staged history row: {error}"))?;
+        insert_timings(&tx, row.id, &row.timings)
+            .map_err(|error| format!("Failed to insert staged history timings: {error}"))?;
+        for tag in &row.tags {
+            let staged_tag = get_or_create_tag(&tx, &tag.name, tag.kind)
+                .map_err(|error| format!("Failed to insert staged history tag: {error}"))?;
+            tx.execute(
+                "INSERT OR IGNORE INTO history_entry_tags (history_id, tag_id) VALUES (?1, ?2)",
+                params![row.id, staged_tag.id],
+            )
+            .map_err(|error| format!("Failed to link staged history tag: {error}"))?;
+        }
+        for revision in &row.revisions {
+            insert_revision(
+                &tx,
+                row.id,
+                revision.created_at,
+                &revision.model_id,
+                &revision.model_name,
+                &revision.transcription_text,
+                revision.elapsed_ms,
+                &revision.bias_terms,
+            )
+            .map_err(|error| format!("Failed to insert staged history revision: {error}"))?;
+        }
+        insert_speaker_turns(&tx, row.id, &row.speaker_turns)
+            .map_err(|error| format!("Failed to insert staged history speaker turns: {error}"))?;
+        for speaker in &row.speaker_names {
+            set_speaker_name(&tx, row.id, speaker.speaker, &speaker.name).map_err(|error| {
+                format!("Failed to insert staged history speaker name: {error}")
+            })?;
+        }
+        insert_bias_terms(&tx, row.id, &row.bias_terms)
+            .map_err(|error| format!("Failed to insert staged history bias terms: {error}"))?;
 
         let stats_text = row
             .post_processed_text
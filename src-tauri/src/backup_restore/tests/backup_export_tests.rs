@@ -192,6 +192,41 @@
                 post_process_prompt: None,
                 duration_ms: 1_000,
                 speech_duration_ms: 0,
+                timings: vec![TranscriptTiming {
+                    start_ms: 0,
+                    end_ms: 900,
+                    text: "raw one".to_string(),
+                    granularity: TimingGranularity::Segment,
+                }],
+                tags: vec![HistoryRowTagV1 {
+                    name: "Meetings".to_string(),
+                    kind: HistoryTagKind::Notebook,
+                }],
+                revisions: vec![HistoryRowRevisionV1 {
+                    created_at: 1_700_100_050,
+                    model_id: "small".to_string(),
+                    model_name: "Whisper Small".to_string(),
+                    transcription_text: "raw won".to_string(),
+                    elapsed_ms: 310,
+                    bias_terms: vec!["Whisper".to_string()],
+                }],
+                speaker_turns: vec![
+                    HistorySpeakerTurn {
+                        start_ms: 0,
+                        end_ms: 400,
+                        speaker: 1,
+                    },
+                    HistorySpeakerTurn {
+                        start_ms: 400,
+                        end_ms: 900,
+                        speaker: 2,
+                    },
+                ],
+                speaker_names: vec![HistorySpeaker {
+                    speaker: 2,
+                    name: "Guest".to_string(),
+                }],
+                bias_terms: vec!["Acme Cloud".to_string(), "ChatGPT".to_string()],
             },
             HistoryRowV1 {
                 id: 2,
@@ -205,6 +240,12 @@
                 post_process_prompt: None,
                 duration_ms: 1_500,
                 speech_duration_ms: 600,
+                timings: Vec::new(),
+                tags: Vec::new(),
+                revisions: Vec::new(),
+                speaker_turns: Vec::new(),
+                speaker_names: Vec::new(),
+                bias_terms: Vec::new(),
             },
         ];
         let body = rows
@@ -227,6 +268,46 @@
         assert_eq!(persisted.total_words, 4);
         assert_eq!(persisted.total_duration_ms, 2_500);
         assert_eq!(persisted.total_speech_duration_ms, 1_600);
+
+        let conn = Connection::open(&staged_db).expect("open staged history db");
+        assert_eq!(
+            load_timings(&conn, 1).expect("read staged timings"),
+            rows[0].timings
+        );
+        assert!(load_timings(&conn, 2).expect("read staged timings").is_empty());
+        let staged_tags = load_entry_tags(&conn, 1).expect("read staged tags");
+        assert_eq!(staged_tags.len(), 1);
+        assert_eq!(staged_tags[0].name, "Meetings");
+        assert_eq!(staged_tags[0].kind, HistoryTagKind::Notebook);
+        let staged_revisions = load_revisions(&conn, 1).expect("read staged revisions");
+        assert_eq!(staged_revisions.len(), 1);
+        assert_eq!(staged_revisions[0].model_id, "small");
+        assert_eq!(staged_revisions[0].transcription_text, "raw won");
+        assert_eq!(staged_revisions[0].bias_terms, vec!["Whisper"]);
+        assert!(load_revisions(&conn, 2).expect("read staged revisions").is_empty());
+        assert_eq!(
+            load_speaker_turns(&conn, 1).expect("read staged speaker turns"),
+            rows[0].speaker_turns
+        );
+        assert_eq!(
+            load_speakers(&conn, 1).expect("read staged speakers"),
+            vec![
+                HistorySpeaker {
+                    speaker: 1,
+                    name: "Speaker 1".to_string(),
+                },
+                HistorySpeaker {
+                    speaker: 2,
+                    name: "Guest".to_string(),
+                },
+            ]
+        );
+        assert!(load_speaker_turns(&conn, 2).expect("read staged speaker turns").is_empty());
+        assert_eq!(
+            load_bias_terms(&conn, 1).expect("read staged bias terms"),
+            rows[0].bias_terms
+        );
+        assert!(load_bias_terms(&conn, 2).expect("read staged bias terms").is_empty());
     }
 
     #[test]
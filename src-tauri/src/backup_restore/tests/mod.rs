@@ -5,7 +5,10 @@ use super::backup::{
     export_history_jsonl, export_recordings_payload, map_stage_progress_units,
     package_progress_units, package_workspace_to_archive_with_cancel,
 };
-use crate::managers::history::format_date_key;
+use crate::managers::history::{
+    format_date_key, load_bias_terms, load_entry_tags, load_revisions, load_speaker_turns,
+    load_speakers, load_timings, TimingGranularity,
+};
 use rusqlite::{Connection, OptionalExtension, params};
 use serde_json::{json, Value};
 use std::io::{Read, Write};
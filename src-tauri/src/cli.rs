@@ -1,4 +1,5 @@
-use clap::Parser;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::path::PathBuf;
 
 #[derive(Parser, Debug, Clone, Default)]
 #[command(
@@ -29,4 +30,127 @@ pub struct CliArgs {
     /// Enable debug mode with verbose logging
     #[arg(long)]
     pub debug: bool,
+
+    /// Headless subcommand; when present the GUI is not started
+    #[command(subcommand)]
+    pub command: Option<CliCommand>,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum CliCommand {
+    /// Transcribe audio files offline without starting the GUI
+    Transcribe(TranscribeArgs),
+    /// Record a short quiet and spoken sample and report on the microphone
+    DiagnoseMic(DiagnoseMicArgs),
+    /// Write installed models to a signed bundle for offline machines
+    ExportModels(ExportModelsArgs),
+    /// Verify and install the models in a signed bundle
+    ImportModels(ImportModelsArgs),
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct TranscribeArgs {
+    /// Audio files to transcribe (WAV, FLAC, MP3, Ogg Vorbis)
+    #[arg(required = true)]
+    pub files: Vec<PathBuf>,
+
+    /// Model id to use (defaults to the model selected in the app)
+    #[arg(long)]
+    pub model: Option<String>,
+
+    /// Spoken language code, or "auto" (defaults to the app setting)
+    #[arg(long)]
+    pub language: Option<String>,
+
+    /// Translate the transcription to English (Whisper models only)
+    #[arg(long)]
+    pub translate: bool,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t = TranscribeOutputFormat::Text)]
+    pub format: TranscribeOutputFormat,
+
+    /// Print the raw engine output without dictionary hints, correction or filtering
+    #[arg(long)]
+    pub raw: bool,
+
+    /// Override the app data directory (models, settings and dictionary)
+    #[arg(long)]
+    pub data_dir: Option<PathBuf>,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct DiagnoseMicArgs {
+    /// Input device name (defaults to the microphone selected in the app)
+    #[arg(long)]
+    pub device: Option<String>,
+
+    /// Seconds of silence to record for the noise floor
+    #[arg(long, default_value_t = 3.0)]
+    pub silence_secs: f32,
+
+    /// Seconds of speech to record
+    #[arg(long, default_value_t = 5.0)]
+    pub speech_secs: f32,
+
+    /// Print the report as JSON
+    #[arg(long)]
+    pub json: bool,
+
+    /// Override the app data directory (settings)
+    #[arg(long)]
+    pub data_dir: Option<PathBuf>,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct ExportModelsArgs {
+    /// Model ids to export
+    #[arg(required_unless_present = "all", conflicts_with = "all")]
+    pub models: Vec<String>,
+
+    /// Export every downloaded model
+    #[arg(long)]
+    pub all: bool,
+
+    /// Bundle file to write
+    #[arg(long, short)]
+    pub output: PathBuf,
+
+    /// Ed25519 signing key (PKCS#8); created if missing. Defaults to the app's key
+    #[arg(long)]
+    pub signing_key: Option<PathBuf>,
+
+    /// Override the app data directory (models and signing key)
+    #[arg(long)]
+    pub data_dir: Option<PathBuf>,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct ImportModelsArgs {
+    /// Bundle file to import
+    pub bundle: PathBuf,
+
+    /// Hex public key to accept as a signer, in addition to the app's trusted keys
+    #[arg(long = "trusted-key")]
+    pub trusted_keys: Vec<String>,
+
+    /// Accept any signer when no trusted keys are configured
+    #[arg(long)]
+    pub allow_untrusted: bool,
+
+    /// Print the report as JSON
+    #[arg(long)]
+    pub json: bool,
+
+    /// Override the app data directory (models and settings)
+    #[arg(long)]
+    pub data_dir: Option<PathBuf>,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TranscribeOutputFormat {
+    /// Plain text, one block per file
+    Text,
+    /// A JSON array with one object per file
+    Json,
 }
@@ -1,7 +1,8 @@
+use crate::audio_toolkit::voice_commands::{self, EditKey, EditOperation};
 use crate::input::{self, EnigoState};
 #[cfg(target_os = "linux")]
 use crate::settings::TypingTool;
-use crate::settings::{get_settings, AutoSubmitKey, ClipboardHandling, PasteMethod};
+use crate::settings::{AppSettings, AutoSubmitKey, ClipboardHandling, PasteMethod};
 use enigo::{Direction, Enigo, Key, Keyboard};
 use tracing::{debug, info, warn};
 use std::process::Command;
@@ -797,6 +798,25 @@ pub fn paste_with_mode(
     text: String,
     app_handle: AppHandle,
     preparation_mode: PastePreparationMode,
+) -> Result<PasteResult, String> {
+    paste_with_options(text, app_handle, preparation_mode, true, None)
+}
+
+/// Settings to paste with: those of the recording session the text comes
+/// from, which may no longer be the latest, else the latest session's.
+fn paste_settings(app_handle: &AppHandle, session_id: Option<&str>) -> AppSettings {
+    match session_id {
+        Some(session_id) => crate::app_profiles::settings_for_session(app_handle, session_id),
+        None => crate::app_profiles::session_settings(app_handle),
+    }
+}
+
+fn paste_with_options(
+    text: String,
+    app_handle: AppHandle,
+    preparation_mode: PastePreparationMode,
+    allow_auto_submit: bool,
+    session_id: Option<&str>,
 ) -> Result<PasteResult, String> {
     if text.trim().is_empty() {
         info!("Skipping paste because text is empty after trimming");
@@ -806,7 +826,7 @@ pub fn paste_with_mode(
         });
     }
 
-    let settings = get_settings(&app_handle);
+    let settings = paste_settings(&app_handle, session_id);
 
     // Check if onboarding paste override is enabled
     // This works around WebView not receiving CGEvent-simulated Cmd+V keystrokes
@@ -944,7 +964,7 @@ pub fn paste_with_mode(
         }
     };
 
-    if should_send_auto_submit(settings.auto_submit, paste_method) {
+    if allow_auto_submit && should_send_auto_submit(settings.auto_submit, paste_method) {
         std::thread::sleep(Duration::from_millis(50));
         send_return_key(enigo, settings.auto_submit_key)?;
     }
@@ -974,6 +994,149 @@ pub fn paste_with_mode(
     })
 }
 
+/// Pastes the transcript of recording session `session_id` with that
+/// session's settings, executing spoken edit commands when they are enabled
+/// for its language.
+pub fn paste_transcript(
+    text: String,
+    app_handle: AppHandle,
+    session_id: &str,
+) -> Result<PasteResult, String> {
+    let settings = crate::app_profiles::settings_for_session(&app_handle, session_id);
+    let languages = voice_commands::active_command_languages(
+        &settings.selected_language,
+        &settings.voice_command_languages,
+    );
+    let operations = if languages.is_empty() {
+        None
+    } else {
+        voice_commands::parse_voice_commands(&text, &languages)
+    };
+
+    match operations {
+        Some(operations) => paste_edit_operations(operations, app_handle, session_id),
+        None => paste_with_options(
+            text,
+            app_handle,
+            PastePreparationMode::Adaptive,
+            true,
+            Some(session_id),
+        ),
+    }
+}
+
+/// Executes spoken edit operations in order. Text goes through the regular
+/// paste pipeline; auto-submit is only sent after a trailing insert.
+pub fn paste_edit_operations(
+    operations: Vec<EditOperation>,
+    app_handle: AppHandle,
+    session_id: &str,
+) -> Result<PasteResult, String> {
+    info!(
+        operations = operations.len(),
+        "Executing spoken edit commands"
+    );
+
+    let paste_method =
+        crate::app_profiles::settings_for_session(&app_handle, session_id).paste_method;
+    let mut pasted_text = String::new();
+    let mut did_paste = false;
+    let mut last_inserted = String::new();
+    let last_index = operations.len().saturating_sub(1);
+
+    for (index, operation) in operations.into_iter().enumerate() {
+        if index > 0 {
+            std::thread::sleep(Duration::from_millis(EDIT_OPERATION_DELAY_MS));
+        }
+
+        match operation {
+            EditOperation::Insert(text) if text.trim().is_empty() => {
+                // Bare line breaks would be skipped as empty text; press Enter instead.
+                if paste_method_performs_paste(paste_method) {
+                    let breaks = text.matches('\n').count();
+                    with_enigo(&app_handle, |enigo| {
+                        (0..breaks).try_for_each(|_| input::send_edit_key(enigo, EditKey::Enter))
+                    })?;
+                }
+                pasted_text.push_str(&text);
+                last_inserted.clear();
+            }
+            EditOperation::Insert(text) => {
+                let result = paste_with_options(
+                    text,
+                    app_handle.clone(),
+                    PastePreparationMode::Adaptive,
+                    index == last_index,
+                    Some(session_id),
+                )?;
+                did_paste |= result.did_paste;
+                pasted_text.push_str(&result.pasted_text);
+                last_inserted = result.pasted_text;
+            }
+            operation if !paste_method_performs_paste(paste_method) => {
+                debug!(operation = ?operation, "Skipping edit operation because pasting is disabled");
+            }
+            EditOperation::Key(key) => {
+                with_enigo(&app_handle, |enigo| input::send_edit_key(enigo, key))?;
+            }
+            EditOperation::SelectLastWord => {
+                with_enigo(&app_handle, input::select_previous_word)?;
+            }
+            EditOperation::SelectLastSentence => {
+                let count = last_sentence_char_count(&last_inserted);
+                if count == 0 {
+                    debug!("No inserted sentence to select");
+                } else {
+                    with_enigo(&app_handle, |enigo| {
+                        input::select_previous_chars(enigo, count)
+                    })?;
+                }
+            }
+        }
+    }
+
+    Ok(PasteResult {
+        pasted_text,
+        did_paste,
+    })
+}
+
+const EDIT_OPERATION_DELAY_MS: u64 = 50;
+
+fn with_enigo<T>(
+    app_handle: &AppHandle,
+    f: impl FnOnce(&mut Enigo) -> Result<T, String>,
+) -> Result<T, String> {
+    let enigo_state = app_handle
+        .try_state::<EnigoState>()
+        .ok_or("Enigo state not initialized")?;
+    if !enigo_state.is_available() {
+        enigo_state.try_init();
+    }
+
+    let mut guard = enigo_state
+        .0
+        .lock()
+        .map_err(|e| format!("Failed to lock Enigo: {}", e))?;
+    let enigo = guard.as_mut().ok_or(
+        "Accessibility permissions not granted. Please enable accessibility access in System Settings > Privacy & Security > Accessibility."
+    )?;
+    f(enigo)
+}
+
+/// Number of characters from the start of the last sentence in `text` to its
+/// end. Trailing whitespace is counted so the selection reaches the cursor.
+fn last_sentence_char_count(text: &str) -> usize {
+    let trimmed = text.trim_end();
+    let body = trimmed.trim_end_matches(['.', '!', '?', '…', '。', '！', '？']);
+    let start = body
+        .rfind(['.', '!', '?', '…', '。', '！', '？', '\n'])
+        .map(|index| index + body[index..].chars().next().map_or(1, char::len_utf8))
+        .unwrap_or(0);
+    let sentence_start = start + (trimmed[start..].len() - trimmed[start..].trim_start().len());
+    text[sentence_start..].chars().count()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1112,4 +1275,12 @@ mod tests {
                 .contains("after 2 attempts")
         );
     }
+
+    #[test]
+    fn last_sentence_char_count_stops_at_previous_sentence() {
+        assert_eq!(last_sentence_char_count("One. Two words. "), 11);
+        assert_eq!(last_sentence_char_count("Only one sentence"), 17);
+        assert_eq!(last_sentence_char_count("Line one\nLine two."), 9);
+        assert_eq!(last_sentence_char_count(""), 0);
+    }
 }
@@ -1,12 +1,23 @@
 use crate::audio_feedback;
-use crate::audio_toolkit::audio::{list_input_devices, list_output_devices};
+use crate::audio_toolkit::audio::{list_input_devices, list_loopback_sources, list_output_devices};
+use crate::audio_toolkit::vad::validate_smoothing;
 use crate::managers::audio::{AudioRecordingManager, MicrophoneMode};
-use crate::settings::{get_settings, write_settings};
+use crate::mic_diagnostics::{
+    MicrophoneDiagnostics, DEFAULT_SILENCE_DURATION, DEFAULT_SPEECH_DURATION,
+};
+use crate::settings::{
+    get_settings, write_settings, CaptureSource, MixedChannelLayout, VadBackend,
+    MAX_RECENT_AUDIO_SECS, MIN_RECENT_AUDIO_SECS,
+};
 use tracing::warn;
 use serde::{Deserialize, Serialize};
 use specta::Type;
 use std::sync::Arc;
-use tauri::{AppHandle, Manager};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Longest quiet or spoken sample the diagnostics will record.
+const MAX_DIAGNOSTIC_SAMPLE_MS: u32 = 30_000;
 
 #[derive(Serialize, Type)]
 pub struct CustomSounds {
@@ -67,6 +78,83 @@ pub fn get_microphone_mode(app: AppHandle) -> Result<bool, String> {
     Ok(settings.always_on_microphone)
 }
 
+fn apply_vad_settings(app: &AppHandle) -> Result<(), String> {
+    let rm = app.state::<Arc<AudioRecordingManager>>();
+    rm.apply_vad_settings()
+        .map_err(|e| format!("Failed to apply VAD settings: {}", e))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_vad_backend_setting(app: AppHandle, backend: VadBackend) -> Result<(), String> {
+    let mut settings = get_settings(&app);
+    settings.vad_backend = backend;
+    write_settings(&app, settings);
+
+    apply_vad_settings(&app)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_vad_sensitivity_setting(app: AppHandle, sensitivity: f32) -> Result<(), String> {
+    if !(0.0..=1.0).contains(&sensitivity) {
+        return Err(format!(
+            "VAD sensitivity must be between 0 and 1, got {}",
+            sensitivity
+        ));
+    }
+    let mut settings = get_settings(&app);
+    settings.vad_sensitivity = sensitivity;
+    write_settings(&app, settings);
+
+    apply_vad_settings(&app)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_vad_smoothing_setting(
+    app: AppHandle,
+    prefill_frames: usize,
+    hangover_frames: usize,
+    onset_frames: usize,
+) -> Result<(), String> {
+    validate_smoothing(prefill_frames, hangover_frames, onset_frames)?;
+    let mut settings = get_settings(&app);
+    settings.vad_prefill_frames = prefill_frames;
+    settings.vad_hangover_frames = hangover_frames;
+    settings.vad_onset_frames = onset_frames;
+    write_settings(&app, settings);
+
+    apply_vad_settings(&app)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_recent_audio_buffer_setting(
+    app: AppHandle,
+    enabled: bool,
+    secs: u32,
+) -> Result<(), String> {
+    if !(MIN_RECENT_AUDIO_SECS..=MAX_RECENT_AUDIO_SECS).contains(&secs) {
+        return Err(format!(
+            "Recent audio buffer must be between {} and {} seconds, got {}",
+            MIN_RECENT_AUDIO_SECS, MAX_RECENT_AUDIO_SECS, secs
+        ));
+    }
+    let mut settings = get_settings(&app);
+    let was_enabled = settings.recent_audio_buffer_enabled;
+    settings.recent_audio_buffer_enabled = enabled;
+    settings.recent_audio_buffer_secs = secs;
+    write_settings(&app, settings);
+
+    let rm = app.state::<Arc<AudioRecordingManager>>();
+    rm.apply_recent_audio_settings();
+    if was_enabled != enabled {
+        crate::shortcut::sync_recent_audio_shortcut(&app, enabled);
+    }
+    Ok(())
+}
+
 #[tauri::command]
 #[specta::specta]
 pub async fn get_available_microphones(app: AppHandle) -> Result<Vec<AudioDevice>, String> {
@@ -148,6 +236,63 @@ pub fn get_selected_microphone(app: AppHandle) -> Result<String, String> {
         .unwrap_or_else(|| "default".to_string()))
 }
 
+/// Monitor sources that can record what the machine is playing; "default"
+/// follows the default output.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_available_loopback_sources() -> Result<Vec<AudioDevice>, String> {
+    let sources = tauri::async_runtime::spawn_blocking(|| {
+        list_loopback_sources().map_err(|e| format!("Failed to list system audio sources: {}", e))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))??;
+
+    let mut result = vec![AudioDevice {
+        index: "default".to_string(),
+        name: "Default".to_string(),
+        is_default: true,
+        is_bluetooth: false,
+    }];
+    result.extend(sources.into_iter().map(|source| AudioDevice {
+        index: source.name,
+        name: source.description,
+        is_default: false,
+        is_bluetooth: false,
+    }));
+
+    Ok(result)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn set_capture_source(
+    app: AppHandle,
+    source: CaptureSource,
+    loopback_source: String,
+    layout: MixedChannelLayout,
+) -> Result<(), String> {
+    if source != CaptureSource::Microphone && !cfg!(target_os = "linux") {
+        return Err("System audio capture is only supported on Linux".to_string());
+    }
+    let rm = app.state::<Arc<AudioRecordingManager>>().inner().clone();
+
+    let mut settings = get_settings(&app);
+    settings.capture_source = source;
+    settings.loopback_source = (loopback_source != "default").then_some(loopback_source);
+    settings.mixed_channel_layout = layout;
+    write_settings(&app, settings);
+
+    // Reopen the stream on the new input without blocking the UI, as for a
+    // microphone change.
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = rm.update_selected_device() {
+            tracing::error!("Failed to switch capture source (background task): {}", e);
+        }
+    });
+
+    Ok(())
+}
+
 #[tauri::command]
 #[specta::specta]
 pub fn get_available_output_devices() -> Result<Vec<AudioDevice>, String> {
@@ -260,3 +405,32 @@ pub fn stop_mic_preview(app: AppHandle) {
         audio_manager.stop_microphone_stream();
     }
 }
+
+/// Record a quiet sample, then a spoken one, and report levels, noise, clipping
+/// and voice detection with recommendations. Emits `mic-diagnostics-phase` as
+/// each step starts so the UI can tell the user when to speak.
+#[tauri::command]
+#[specta::specta]
+pub async fn run_microphone_diagnostics(
+    app: AppHandle,
+    silence_ms: Option<u32>,
+    speech_ms: Option<u32>,
+) -> Result<MicrophoneDiagnostics, String> {
+    let sample_duration = |ms: Option<u32>, default: Duration| {
+        ms.map(|ms| Duration::from_millis(ms.clamp(1_000, MAX_DIAGNOSTIC_SAMPLE_MS) as u64))
+            .unwrap_or(default)
+    };
+    let silence_duration = sample_duration(silence_ms, DEFAULT_SILENCE_DURATION);
+    let speech_duration = sample_duration(speech_ms, DEFAULT_SPEECH_DURATION);
+
+    let rm = app.state::<Arc<AudioRecordingManager>>().inner().clone();
+    let phase_app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        rm.run_microphone_diagnostics(silence_duration, speech_duration, |phase| {
+            let _ = phase_app.emit("mic-diagnostics-phase", phase);
+        })
+        .map_err(|e| format!("Microphone diagnostics failed: {}", e))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
@@ -1,4 +1,14 @@
-use crate::managers::history::{HistoryEntry, HistoryManager, HistoryStats, HomeStats};
+use crate::audio_toolkit::load_audio_file_for_transcription;
+use crate::history_export::{self, ExportHistoryReport, ExportHistoryRequest};
+use crate::managers::history::{
+    HistoryEntry, HistoryManager, HistoryRevisionComparison, HistorySearchHit, HistorySpeaker,
+    HistoryStats, HistoryTag, HistoryTagKind, HistoryTagSummary, HomeStats,
+};
+use crate::managers::model::ModelManager;
+use crate::managers::transcription::{filter_timings, TranscriptionManager};
+use crate::settings::get_settings;
+use crate::user_dictionary;
+use std::path::Path;
 use std::sync::Arc;
 use tauri::{AppHandle, State};
 
@@ -12,13 +22,73 @@ pub async fn get_history_entries(
     search_query: Option<String>,
     starred_only: bool,
     time_period_start: Option<i64>,
+    tag_id: Option<i64>,
 ) -> Result<Vec<HistoryEntry>, String> {
     history_manager
-        .get_history_entries(limit, offset, search_query, starred_only, time_period_start)
+        .get_history_entries(
+            limit,
+            offset,
+            search_query,
+            starred_only,
+            time_period_start,
+            tag_id,
+        )
         .await
         .map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+#[specta::specta]
+pub async fn search_history_entries(
+    _app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    query: String,
+    limit: usize,
+    offset: usize,
+    starred_only: bool,
+    time_period_start: Option<i64>,
+) -> Result<Vec<HistorySearchHit>, String> {
+    history_manager
+        .search_history_entries(query, limit, offset, starred_only, time_period_start)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn export_history(
+    app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    request: ExportHistoryRequest,
+) -> Result<ExportHistoryReport, String> {
+    let mut entries = history_manager
+        .get_entries_for_export(&request.filter)
+        .map_err(|e| e.to_string())?;
+    // Entries recorded before timings were filtered at transcription still
+    // carry the engine's words; filter them like the text so removed filler
+    // words do not come back as cues.
+    let settings = get_settings(&app);
+    let dictionary = user_dictionary::get_dictionary_snapshot(&app);
+    for entry in &mut entries {
+        entry.timings = filter_timings(
+            std::mem::take(&mut entry.timings),
+            &settings,
+            dictionary.as_ref(),
+        );
+    }
+    history_export::write_export(
+        Path::new(&request.output_path),
+        &entries,
+        request.format,
+        request.text_source,
+    )?;
+
+    Ok(ExportHistoryReport {
+        output_path: request.output_path,
+        entry_count: entries.len() as u32,
+    })
+}
+
 #[tauri::command]
 #[specta::specta]
 pub async fn toggle_history_entry_saved(
@@ -32,6 +102,174 @@ pub async fn toggle_history_entry_saved(
         .map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+#[specta::specta]
+pub async fn list_history_tags(
+    _app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+) -> Result<Vec<HistoryTagSummary>, String> {
+    history_manager.list_tags().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn create_history_tag(
+    _app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    name: String,
+    kind: HistoryTagKind,
+) -> Result<HistoryTag, String> {
+    history_manager
+        .create_tag(name, kind)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn rename_history_tag(
+    _app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    id: i64,
+    name: String,
+) -> Result<HistoryTag, String> {
+    history_manager
+        .rename_tag(id, name)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn delete_history_tag(
+    _app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    id: i64,
+) -> Result<(), String> {
+    history_manager
+        .delete_tag(id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn tag_history_entry(
+    _app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    entry_id: i64,
+    tag_id: i64,
+) -> Result<(), String> {
+    history_manager
+        .tag_entry(entry_id, tag_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn untag_history_entry(
+    _app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    entry_id: i64,
+    tag_id: i64,
+) -> Result<(), String> {
+    history_manager
+        .untag_entry(entry_id, tag_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Re-run recognition on an entry's stored recording with `model_id` and keep
+/// the result as a revision of the entry. The selected model is not changed.
+#[tauri::command]
+#[specta::specta]
+pub async fn retranscribe_history_entry(
+    _app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    transcription_manager: State<'_, Arc<TranscriptionManager>>,
+    model_manager: State<'_, Arc<ModelManager>>,
+    id: i64,
+    model_id: String,
+) -> Result<HistoryRevisionComparison, String> {
+    let entry = history_manager
+        .get_entry_by_id(id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("History entry {} not found", id))?;
+    if !entry.audio_file_exists {
+        return Err(format!("Recording for history entry {} is missing", id));
+    }
+    let model_name = model_manager
+        .get_model_info(&model_id)
+        .map(|info| info.name)
+        .ok_or_else(|| format!("Model not found: {}", model_id))?;
+
+    let tm = transcription_manager.inner().clone();
+    let engine_model_id = model_id.clone();
+    let (output, elapsed) = tauri::async_runtime::spawn_blocking(move || {
+        let samples = load_audio_file_for_transcription(&entry.file_path)
+            .map_err(|e| format!("Failed to load recording: {}", e))?;
+        tm.transcribe_with_model(&engine_model_id, samples)
+            .map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))??;
+
+    history_manager
+        .add_revision(
+            id,
+            &model_id,
+            &model_name,
+            &output.text,
+            elapsed.as_millis() as i64,
+            &output.bias_terms,
+        )
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn list_history_revisions(
+    _app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    id: i64,
+) -> Result<Vec<HistoryRevisionComparison>, String> {
+    history_manager
+        .list_revisions(id)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn delete_history_revision(
+    _app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    revision_id: i64,
+) -> Result<(), String> {
+    history_manager
+        .delete_revision(revision_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Name a diarized speaker of an entry; an empty name restores "Speaker N".
+/// Returns the entry's speakers with their names.
+#[tauri::command]
+#[specta::specta]
+pub async fn rename_history_speaker(
+    _app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    id: i64,
+    speaker: i64,
+    name: String,
+) -> Result<Vec<HistorySpeaker>, String> {
+    history_manager
+        .rename_speaker(id, speaker, name)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 #[specta::specta]
 pub async fn get_audio_file_path(
@@ -76,6 +314,29 @@ pub async fn update_history_limit(
     Ok(())
 }
 
+/// Sets the format for new recordings and optionally converts existing ones
+/// in the background (progress arrives as `recording-migration-progress`).
+#[tauri::command]
+#[specta::specta]
+pub async fn update_recording_format(
+    app: AppHandle,
+    history_manager: State<'_, Arc<HistoryManager>>,
+    format: crate::settings::RecordingFormat,
+    migrate_existing: bool,
+) -> Result<(), String> {
+    let mut settings = crate::settings::get_settings(&app);
+    settings.recording_format = format;
+    crate::settings::write_settings(&app, settings);
+
+    if migrate_existing {
+        history_manager
+            .start_recording_migration(format)
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
 #[tauri::command]
 #[specta::specta]
 pub async fn update_recording_retention_period(
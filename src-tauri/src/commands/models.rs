@@ -1,7 +1,10 @@
 use crate::analytics::{self, BackendAnalyticsEvent};
 use crate::managers::model::{ModelInfo, ModelManager};
+use crate::managers::model_catalog::ModelCatalogStatus;
 use crate::managers::transcription::TranscriptionManager;
+use crate::model_bundle::{ModelBundleExportReport, ModelBundleImportReport};
 use crate::settings::{get_settings, write_settings};
+use std::path::Path;
 use std::sync::Arc;
 use tauri::{AppHandle, State};
 
@@ -173,3 +176,54 @@ pub async fn get_recommended_first_model(
     // Fallback to "small" usually
     Ok("small".to_string())
 }
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_model_catalog_status(
+    model_manager: State<'_, Arc<ModelManager>>,
+) -> Result<ModelCatalogStatus, String> {
+    Ok(model_manager.get_catalog_status())
+}
+
+/// Re-reads the model catalog override without restarting the app.
+#[tauri::command]
+#[specta::specta]
+pub async fn reload_model_catalog(
+    model_manager: State<'_, Arc<ModelManager>>,
+) -> Result<ModelCatalogStatus, String> {
+    model_manager.reload_catalog().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn export_model_bundle(
+    model_manager: State<'_, Arc<ModelManager>>,
+    model_ids: Vec<String>,
+    path: String,
+) -> Result<ModelBundleExportReport, String> {
+    let model_manager = model_manager.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        model_manager
+            .export_bundle(&model_ids, Path::new(&path))
+            .map_err(|e| format!("Failed to export models: {}", e))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn import_model_bundle(
+    model_manager: State<'_, Arc<ModelManager>>,
+    path: String,
+    allow_untrusted: bool,
+) -> Result<ModelBundleImportReport, String> {
+    let model_manager = model_manager.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        model_manager
+            .import_bundle(Path::new(&path), allow_untrusted)
+            .map_err(|e| format!("Failed to import models: {}", e))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
@@ -0,0 +1,224 @@
+//! Recognition biasing from the user dictionary.
+//!
+//! Dictionary correction fixes words after the engine has misheard them. Engines
+//! that accept hints can avoid the mistake instead: Whisper conditions its
+//! decoder on an initial prompt, so listing the user's terms there makes the
+//! right spelling more likely. Only "recognize this term" entries are used;
+//! replacement entries map spoken phrases to other text and aren't words the
+//! speaker says.
+
+use crate::user_dictionary::CustomWordEntry;
+use std::collections::HashSet;
+
+/// Approximate token budget for hint terms. Whisper keeps at most 224 prompt
+/// tokens; staying well below that leaves room for the decoder's own context
+/// and keeps a long dictionary from drowning out the audio.
+pub const MAX_BIAS_TOKENS: usize = 96;
+/// Longer entries are sentences or snippets rather than vocabulary.
+const MAX_BIAS_TERM_CHARS: usize = 48;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Script {
+    Latin,
+    Cyrillic,
+    Greek,
+    Arabic,
+    Hebrew,
+    Devanagari,
+    Thai,
+    /// Han, kana and hangul; Chinese, Japanese and Korean text mixes them.
+    Cjk,
+    Other,
+}
+
+fn script_of(c: char) -> Script {
+    match c as u32 {
+        0x0041..=0x024F | 0x1E00..=0x1EFF => Script::Latin,
+        0x0370..=0x03FF | 0x1F00..=0x1FFF => Script::Greek,
+        0x0400..=0x052F => Script::Cyrillic,
+        0x0590..=0x05FF => Script::Hebrew,
+        0x0600..=0x06FF | 0x0750..=0x077F => Script::Arabic,
+        0x0900..=0x097F => Script::Devanagari,
+        0x0E00..=0x0E7F => Script::Thai,
+        0x1100..=0x11FF
+        | 0x3040..=0x30FF
+        | 0x3130..=0x318F
+        | 0x3400..=0x4DBF
+        | 0x4E00..=0x9FFF
+        | 0xAC00..=0xD7AF
+        | 0xF900..=0xFAFF => Script::Cjk,
+        _ => Script::Other,
+    }
+}
+
+/// Script a language is written in, or `None` when unknown or auto-detected.
+fn language_script(language: &str) -> Option<Script> {
+    let base = language.split(['-', '_']).next().unwrap_or(language);
+    let script = match base.to_ascii_lowercase().as_str() {
+        "zh" | "yue" | "ja" | "ko" => Script::Cjk,
+        "ru" | "uk" | "be" | "bg" | "sr" | "mk" | "kk" | "mn" | "tg" | "ba" | "tt" => {
+            Script::Cyrillic
+        }
+        "el" => Script::Greek,
+        "ar" | "fa" | "ur" | "ps" | "sd" => Script::Arabic,
+        "he" | "yi" => Script::Hebrew,
+        "hi" | "mr" | "ne" | "sa" => Script::Devanagari,
+        "th" => Script::Thai,
+        "" | "auto" => return None,
+        _ if base.chars().all(|c| c.is_ascii_alphabetic()) => Script::Latin,
+        _ => return None,
+    };
+    Some(script)
+}
+
+/// Whether `term` can occur in speech in a language written in `script`.
+/// Latin-only terms (product names, acronyms) are kept for every language.
+fn fits_script(term: &str, script: Option<Script>) -> bool {
+    let Some(script) = script else {
+        return true;
+    };
+    term.chars()
+        .filter(|c| c.is_alphabetic())
+        .all(|c| matches!(script_of(c), Script::Latin) || script_of(c) == script)
+}
+
+/// Rough token cost of a term plus its separator: Whisper's BPE spends about
+/// one token per four ASCII characters and about one per other character.
+fn estimated_tokens(term: &str) -> usize {
+    let ascii = term.chars().filter(char::is_ascii).count();
+    let other = term.chars().count() - ascii;
+    ascii.div_ceil(4) + other + 1
+}
+
+/// Terms the engine is least likely to spell right on its own: names with
+/// inner capitals, acronyms, digits and multi-word phrases.
+fn is_distinctive(term: &str) -> bool {
+    term.chars().skip(1).any(|c| c.is_uppercase())
+        || term.chars().any(|c| c.is_ascii_digit())
+        || term.split_whitespace().nth(1).is_some()
+}
+
+/// Picks the dictionary terms to hint for `language`, most distinctive first
+/// and otherwise in dictionary order, within [`MAX_BIAS_TOKENS`].
+pub fn select_bias_terms(entries: &[CustomWordEntry], language: &str) -> Vec<String> {
+    let script = language_script(language);
+    let mut seen = HashSet::new();
+    let mut candidates: Vec<&str> = entries
+        .iter()
+        .filter(|entry| !entry.is_replacement)
+        .map(|entry| {
+            let replacement = entry.replacement.trim();
+            if replacement.is_empty() {
+                entry.input.trim()
+            } else {
+                replacement
+            }
+        })
+        .filter(|term| {
+            !term.is_empty()
+                && term.chars().count() <= MAX_BIAS_TERM_CHARS
+                && !term.chars().any(char::is_control)
+                && fits_script(term, script)
+        })
+        .filter(|term| seen.insert(term.to_lowercase()))
+        .collect();
+    candidates.sort_by_key(|term| !is_distinctive(term));
+
+    let mut budget = MAX_BIAS_TOKENS;
+    let mut terms = Vec::new();
+    for term in candidates {
+        let cost = estimated_tokens(term);
+        if cost <= budget {
+            budget -= cost;
+            terms.push(term.to_string());
+        }
+    }
+    terms
+}
+
+/// Whisper initial prompt listing `terms`. The list carries no framing words,
+/// which would nudge Whisper towards the framing's language.
+pub fn whisper_initial_prompt(terms: &[String]) -> Option<String> {
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn term(word: &str) -> CustomWordEntry {
+        CustomWordEntry {
+            input: word.to_string(),
+            aliases: Vec::new(),
+            replacement: word.to_string(),
+            is_replacement: false,
+            fuzzy_enabled: None,
+        }
+    }
+
+    fn replacement(input: &str, replacement: &str) -> CustomWordEntry {
+        CustomWordEntry {
+            input: input.to_string(),
+            aliases: Vec::new(),
+            replacement: replacement.to_string(),
+            is_replacement: true,
+            fuzzy_enabled: Some(false),
+        }
+    }
+
+    #[test]
+    fn distinctive_terms_come_first_and_replacements_are_skipped() {
+        let entries = vec![
+            term("kubernetes"),
+            replacement("btw", "by the way"),
+            term("ChatGPT"),
+            term("Kubernetes"),
+            term("Acme Cloud"),
+            term("   "),
+        ];
+        assert_eq!(
+            select_bias_terms(&entries, "en"),
+            vec!["ChatGPT", "Acme Cloud", "kubernetes"]
+        );
+    }
+
+    #[test]
+    fn terms_follow_the_language_script() {
+        let entries = vec![term("Москва"), term("東京"), term("Anthropic")];
+        assert_eq!(select_bias_terms(&entries, "de"), vec!["Anthropic"]);
+        assert_eq!(
+            select_bias_terms(&entries, "ru"),
+            vec!["Москва", "Anthropic"]
+        );
+        assert_eq!(
+            select_bias_terms(&entries, "zh-Hans"),
+            vec!["東京", "Anthropic"]
+        );
+        assert_eq!(select_bias_terms(&entries, "auto").len(), 3);
+    }
+
+    #[test]
+    fn selection_stays_within_the_token_budget() {
+        let entries: Vec<CustomWordEntry> = (0..200)
+            .map(|i| term(&format!("Projectname{}", i)))
+            .collect();
+        let terms = select_bias_terms(&entries, "en");
+        let cost: usize = terms.iter().map(|term| estimated_tokens(term)).sum();
+        assert!(!terms.is_empty() && terms.len() < entries.len());
+        assert!(cost <= MAX_BIAS_TOKENS);
+        assert_eq!(terms[0], "Projectname0");
+    }
+
+    #[test]
+    fn prompt_is_a_plain_list() {
+        assert_eq!(whisper_initial_prompt(&[]), None);
+        assert_eq!(
+            whisper_initial_prompt(&["ChatGPT".to_string(), "Acme Cloud".to_string()]),
+            Some("ChatGPT, Acme Cloud".to_string())
+        );
+    }
+}
@@ -0,0 +1,681 @@
+//! Headless entry points that run without starting Tauri.
+//!
+//! These reuse the same model catalog, engine loading and text pipeline as the
+//! GUI but resolve everything from disk, so they work on machines without a
+//! display (CI, SSH sessions, batch jobs).
+
+use crate::audio_toolkit::{list_input_devices, load_audio_file_for_transcription, CpalDeviceInfo};
+use crate::cli::{
+    DiagnoseMicArgs, ExportModelsArgs, ImportModelsArgs, TranscribeArgs, TranscribeOutputFormat,
+};
+use crate::managers::audio::create_vad;
+use crate::managers::model::{ModelInfo, ModelManager};
+use crate::managers::model_catalog;
+use crate::managers::transcription::{apply_text_pipeline, EngineInferenceOptions, LoadedEngine};
+use crate::mic_diagnostics::{self, DiagnosticIssue, DiagnosticPhase, MicrophoneDiagnostics};
+use crate::model_bundle::{self, ModelBundleExportReport, ModelBundleImportReport};
+use crate::settings::{get_default_settings, AppSettings, SETTINGS_STORE_PATH};
+use crate::user_dictionary::{load_entries_from_path, USER_DICTIONARY_FILE_NAME};
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Bundle identifier from `tauri.conf.json`; Tauri derives the app data dir from it.
+const APP_IDENTIFIER: &str = "com.pais.codictate";
+
+/// Silero model as bundled under the app's resources.
+const VAD_MODEL_RESOURCE: &str = "resources/models/silero_vad_v4.onnx";
+/// Longest quiet or spoken sample `diagnose-mic` will record.
+const MAX_DIAGNOSTIC_SAMPLE_SECS: f32 = 30.0;
+
+/// Exit code when every file was transcribed.
+pub const EXIT_OK: i32 = 0;
+/// Exit code when setup succeeded but at least one file failed.
+pub const EXIT_PARTIAL_FAILURE: i32 = 1;
+/// Exit code when no file could be processed (bad model, missing data dir, ...).
+pub const EXIT_SETUP_FAILURE: i32 = 2;
+
+#[derive(Debug, Serialize)]
+struct FileTranscription {
+    file: String,
+    model: String,
+    text: Option<String>,
+    filler_words_removed: usize,
+    duration_ms: u64,
+    error: Option<String>,
+}
+
+/// Run `codictate transcribe` and return the process exit code.
+pub fn run_transcribe(args: TranscribeArgs) -> i32 {
+    let (model_info, mut engine, settings, dictionary) = match prepare(&args) {
+        Ok(prepared) => prepared,
+        Err(err) => {
+            eprintln!("error: {err}");
+            return EXIT_SETUP_FAILURE;
+        }
+    };
+
+    let mut options = EngineInferenceOptions::from_settings(&settings);
+    if !args.raw {
+        options = options.with_dictionary_bias(&engine, &settings, &dictionary);
+    }
+    let mut results = Vec::with_capacity(args.files.len());
+
+    for file in &args.files {
+        let started = Instant::now();
+        let outcome = load_audio_file_for_transcription(file)
+            .and_then(|samples| engine.transcribe_samples(samples, &options));
+
+        let result = match outcome {
+            Ok(raw) => {
+                let (text, filler_words_removed) = if args.raw {
+                    (raw.text.trim().to_string(), 0)
+                } else {
+                    apply_text_pipeline(&raw.text, &settings, &dictionary)
+                };
+                FileTranscription {
+                    file: file.display().to_string(),
+                    model: model_info.id.clone(),
+                    text: Some(text),
+                    filler_words_removed,
+                    duration_ms: started.elapsed().as_millis() as u64,
+                    error: None,
+                }
+            }
+            Err(err) => FileTranscription {
+                file: file.display().to_string(),
+                model: model_info.id.clone(),
+                text: None,
+                filler_words_removed: 0,
+                duration_ms: started.elapsed().as_millis() as u64,
+                error: Some(err.to_string()),
+            },
+        };
+
+        if args.format == TranscribeOutputFormat::Text {
+            print_text_result(&result, args.files.len() > 1);
+        }
+        results.push(result);
+    }
+
+    engine.unload();
+
+    if args.format == TranscribeOutputFormat::Json {
+        match serde_json::to_string_pretty(&results) {
+            Ok(json) => println!("{json}"),
+            Err(err) => {
+                eprintln!("error: failed to serialize results: {err}");
+                return EXIT_SETUP_FAILURE;
+            }
+        }
+    }
+
+    if results.iter().any(|result| result.error.is_some()) {
+        EXIT_PARTIAL_FAILURE
+    } else {
+        EXIT_OK
+    }
+}
+
+/// Run `codictate diagnose-mic` and return the process exit code.
+pub fn run_diagnose_mic(args: DiagnoseMicArgs) -> i32 {
+    match diagnose_mic(&args) {
+        Ok(report) => {
+            if args.json {
+                match serde_json::to_string_pretty(&report) {
+                    Ok(json) => println!("{json}"),
+                    Err(err) => {
+                        eprintln!("error: failed to serialize report: {err}");
+                        return EXIT_SETUP_FAILURE;
+                    }
+                }
+            } else {
+                print!("{}", format_diagnostics(&report));
+            }
+            EXIT_OK
+        }
+        Err(err) => {
+            eprintln!("error: {err}");
+            EXIT_SETUP_FAILURE
+        }
+    }
+}
+
+fn diagnose_mic(args: &DiagnoseMicArgs) -> Result<MicrophoneDiagnostics> {
+    let silence_duration = diagnostic_duration("--silence-secs", args.silence_secs)?;
+    let speech_duration = diagnostic_duration("--speech-secs", args.speech_secs)?;
+
+    let data_dir = match &args.data_dir {
+        Some(dir) => dir.clone(),
+        None => default_app_data_dir()?,
+    };
+    let settings = load_settings_from_dir(&data_dir);
+
+    let devices =
+        list_input_devices().map_err(|e| anyhow!("Failed to list audio devices: {}", e))?;
+    let device = select_input_device(
+        devices,
+        args.device.as_deref(),
+        settings.selected_microphone.as_deref(),
+    )?;
+
+    let vad_path = bundled_vad_path().unwrap_or_else(|| PathBuf::from(VAD_MODEL_RESOURCE));
+    let mut vad = create_vad(&vad_path.to_string_lossy(), &settings);
+
+    mic_diagnostics::run(
+        &device.device,
+        &device.name,
+        vad.as_mut(),
+        &settings,
+        silence_duration,
+        speech_duration,
+        |phase| match phase {
+            DiagnosticPhase::Silence => eprintln!(
+                "Stay quiet for {:.0} seconds...",
+                silence_duration.as_secs_f32()
+            ),
+            DiagnosticPhase::Speech => eprintln!(
+                "Now speak normally for {:.0} seconds...",
+                speech_duration.as_secs_f32()
+            ),
+            DiagnosticPhase::Analyzing => eprintln!("Analyzing..."),
+        },
+    )
+}
+
+/// Run `codictate export-models` and return the process exit code.
+pub fn run_export_models(args: ExportModelsArgs) -> i32 {
+    match export_models(&args) {
+        Ok(report) => {
+            println!(
+                "Exported {} model(s), {:.1} MB, to {}",
+                report.models.len(),
+                report.total_bytes as f64 / (1024.0 * 1024.0),
+                report.path
+            );
+            println!("Signer key: {}", report.signer);
+            EXIT_OK
+        }
+        Err(err) => {
+            eprintln!("error: {err}");
+            EXIT_SETUP_FAILURE
+        }
+    }
+}
+
+fn export_models(args: &ExportModelsArgs) -> Result<ModelBundleExportReport> {
+    let data_dir = match &args.data_dir {
+        Some(dir) => dir.clone(),
+        None => default_app_data_dir()?,
+    };
+    let models_dir = data_dir.join("models");
+    let catalog = load_models_from_dir(&data_dir)?;
+
+    let mut models = if args.all {
+        catalog
+            .values()
+            .filter(|model| ModelManager::resolve_model_path(&models_dir, model).is_ok())
+            .cloned()
+            .collect::<Vec<_>>()
+    } else {
+        args.models
+            .iter()
+            .map(|id| {
+                let model = catalog
+                    .get(id)
+                    .ok_or_else(|| anyhow!("Unknown model '{}'", id))?;
+                ModelManager::resolve_model_path(&models_dir, model)
+                    .map_err(|_| anyhow!("Model '{}' is not downloaded", id))?;
+                Ok(model.clone())
+            })
+            .collect::<Result<Vec<_>>>()?
+    };
+    models.sort_by(|a, b| a.id.cmp(&b.id));
+    models.dedup_by(|a, b| a.id == b.id);
+
+    let key_path = args
+        .signing_key
+        .clone()
+        .unwrap_or_else(|| data_dir.join(model_bundle::SIGNING_KEY_FILE_NAME));
+    let signing_key = model_bundle::load_or_create_signing_key(&key_path)?;
+    model_bundle::export_bundle(&models_dir, &models, &signing_key, &args.output)
+}
+
+/// Run `codictate import-models` and return the process exit code.
+pub fn run_import_models(args: ImportModelsArgs) -> i32 {
+    let report = match import_models(&args) {
+        Ok(report) => report,
+        Err(err) => {
+            eprintln!("error: {err}");
+            return EXIT_SETUP_FAILURE;
+        }
+    };
+
+    if args.json {
+        match serde_json::to_string_pretty(&report) {
+            Ok(json) => println!("{json}"),
+            Err(err) => {
+                eprintln!("error: failed to serialize report: {err}");
+                return EXIT_SETUP_FAILURE;
+            }
+        }
+    } else {
+        println!("Signer key: {}", report.signer);
+        for id in &report.imported {
+            println!("imported  {id}");
+        }
+        for id in &report.skipped {
+            println!("skipped   {id} (already installed)");
+        }
+        for failure in &report.failed {
+            eprintln!("error: {}: {}", failure.model_id, failure.message);
+        }
+    }
+
+    if report.failed.is_empty() {
+        EXIT_OK
+    } else {
+        EXIT_PARTIAL_FAILURE
+    }
+}
+
+fn import_models(args: &ImportModelsArgs) -> Result<ModelBundleImportReport> {
+    let data_dir = match &args.data_dir {
+        Some(dir) => dir.clone(),
+        None => default_app_data_dir()?,
+    };
+
+    let mut trusted_keys = load_settings_from_dir(&data_dir).trusted_model_bundle_keys;
+    for key in &args.trusted_keys {
+        if !model_bundle::is_valid_public_key(key) {
+            return Err(anyhow!("Invalid --trusted-key '{}'", key));
+        }
+        trusted_keys.push(key.trim().to_ascii_lowercase());
+    }
+
+    let catalog = load_models_from_dir(&data_dir)?;
+    model_bundle::import_bundle(
+        &args.bundle,
+        &data_dir.join("models"),
+        &catalog,
+        &trusted_keys,
+        args.allow_untrusted,
+    )
+}
+
+fn diagnostic_duration(flag: &str, secs: f32) -> Result<Duration> {
+    if !(1.0..=MAX_DIAGNOSTIC_SAMPLE_SECS).contains(&secs) {
+        return Err(anyhow!(
+            "{} must be between 1 and {} seconds",
+            flag,
+            MAX_DIAGNOSTIC_SAMPLE_SECS
+        ));
+    }
+    Ok(Duration::from_secs_f32(secs))
+}
+
+/// Pick the requested device, else the app's selected microphone, else the
+/// system default. Only an explicitly requested device must exist.
+fn select_input_device(
+    devices: Vec<CpalDeviceInfo>,
+    requested: Option<&str>,
+    selected_in_settings: Option<&str>,
+) -> Result<CpalDeviceInfo> {
+    let names = devices
+        .iter()
+        .map(|device| device.name.clone())
+        .collect::<Vec<_>>();
+    let find = |name: &str| devices.iter().find(|device| device.name == name).cloned();
+
+    if let Some(name) = requested {
+        return find(name).ok_or_else(|| {
+            anyhow!(
+                "Unknown input device '{}'; available: {}",
+                name,
+                names.join(", ")
+            )
+        });
+    }
+    if let Some(device) = selected_in_settings
+        .filter(|name| !name.eq_ignore_ascii_case("default"))
+        .and_then(find)
+    {
+        return Ok(device);
+    }
+    devices
+        .iter()
+        .find(|device| device.is_default)
+        .or_else(|| devices.first())
+        .cloned()
+        .ok_or_else(|| anyhow!("No input devices found"))
+}
+
+/// Locate the bundled Silero model next to the executable (Windows, Linux
+/// packages) or in the macOS app bundle's Resources.
+fn bundled_vad_path() -> Option<PathBuf> {
+    let exe_dir = std::env::current_exe().ok()?.parent()?.to_path_buf();
+    [
+        exe_dir.join(VAD_MODEL_RESOURCE),
+        exe_dir.join("../Resources").join(VAD_MODEL_RESOURCE),
+        exe_dir.join("../lib/Codictate").join(VAD_MODEL_RESOURCE),
+    ]
+    .into_iter()
+    .find(|path| path.exists())
+}
+
+fn format_diagnostics(report: &MicrophoneDiagnostics) -> String {
+    let mut out = format!(
+        "Device:        {} ({} Hz, {} ch, {})\n\
+         Noise floor:   {:.1} dBFS\n\
+         Speech level:  {:.1} dBFS (peak {:.1} dBFS)\n\
+         SNR:           {:.1} dB\n\
+         Clipping:      {:.2}%\n\
+         DC offset:     {:.2}%\n\
+         Voice detect:  {:.0}% of speech, {:.0}% of silence\n\n",
+        report.device_name,
+        report.sample_rate,
+        report.channels,
+        report.sample_format,
+        report.noise_floor_dbfs,
+        report.speech_level_dbfs,
+        report.speech_peak_dbfs,
+        report.snr_db,
+        report.clipping_ratio * 100.0,
+        report.dc_offset * 100.0,
+        report.vad_speech_ratio * 100.0,
+        report.vad_noise_ratio * 100.0,
+    );
+    if report.issues.is_empty() {
+        out.push_str("No problems found.\n");
+    } else {
+        out.push_str("Recommendations:\n");
+        for issue in &report.issues {
+            out.push_str(&format!("  - {}\n", issue_message(issue, report)));
+        }
+    }
+    out
+}
+
+fn issue_message(issue: &DiagnosticIssue, report: &MicrophoneDiagnostics) -> String {
+    match issue {
+        DiagnosticIssue::NoSignal => format!(
+            "'{}' delivered almost no sound. Check that it is the microphone you \
+             speak into, that it is not muted in the system sound settings and \
+             that Codictate has microphone permission.",
+            report.device_name
+        ),
+        DiagnosticIssue::LowSampleRate => format!(
+            "The device only records at {} Hz, below the 16 kHz recognition needs. \
+             Bluetooth headsets do this while their microphone is active; prefer a \
+             built-in or wired microphone.",
+            report.sample_rate
+        ),
+        DiagnosticIssue::Clipping => format!(
+            "{:.1}% of the speech sample is clipped. Lower the input volume in the \
+             system sound settings or move further from the microphone.",
+            report.clipping_ratio * 100.0
+        ),
+        DiagnosticIssue::LowLevel => format!(
+            "Speech peaks at {:.0} dBFS, which is quiet. Raise the input volume in \
+             the system sound settings or speak closer to the microphone.",
+            report.speech_level_dbfs
+        ),
+        DiagnosticIssue::NoisyEnvironment { noise_suppression } => format!(
+            "Background noise sits at {:.0} dBFS. {}",
+            report.noise_floor_dbfs,
+            if *noise_suppression {
+                "Move away from fans or other noise sources."
+            } else {
+                "Turn on noise suppression or move away from fans or other noise sources."
+            }
+        ),
+        DiagnosticIssue::LowSnr => format!(
+            "Speech is only {:.0} dB above the background noise. Speak closer to the \
+             microphone or reduce the noise.",
+            report.snr_db
+        ),
+        DiagnosticIssue::DcOffset { high_pass_filter } => format!(
+            "The signal is offset by {:.1}% of full scale. {}",
+            report.dc_offset * 100.0,
+            if *high_pass_filter {
+                "The high-pass filter removes it from recordings, but the device or its \
+                 driver may be faulty."
+            } else {
+                "Turn on the high-pass filter to remove it."
+            }
+        ),
+        DiagnosticIssue::VadMissesSpeech => format!(
+            "Voice detection kept only {:.0}% of the speech sample. Increase the \
+             voice detection sensitivity.",
+            report.vad_speech_ratio * 100.0
+        ),
+        DiagnosticIssue::VadTriggersOnNoise => format!(
+            "Voice detection treated {:.0}% of the quiet sample as speech. Decrease \
+             the voice detection sensitivity.",
+            report.vad_noise_ratio * 100.0
+        ),
+    }
+}
+
+fn print_text_result(result: &FileTranscription, with_header: bool) {
+    match (&result.text, &result.error) {
+        (Some(text), _) => {
+            if with_header {
+                println!("==> {} <==", result.file);
+            }
+            println!("{text}");
+        }
+        (None, Some(err)) => eprintln!("error: {}: {}", result.file, err),
+        (None, None) => {}
+    }
+}
+
+fn prepare(
+    args: &TranscribeArgs,
+) -> Result<(
+    ModelInfo,
+    LoadedEngine,
+    AppSettings,
+    Vec<crate::user_dictionary::CustomWordEntry>,
+)> {
+    let data_dir = match &args.data_dir {
+        Some(dir) => dir.clone(),
+        None => default_app_data_dir()?,
+    };
+
+    let mut settings = load_settings_from_dir(&data_dir);
+    if let Some(language) = &args.language {
+        settings.selected_language = language.clone();
+    }
+    if args.translate {
+        settings.translate_to_english = true;
+    }
+
+    let (dictionary, _) = load_entries_from_path(&data_dir.join(USER_DICTIONARY_FILE_NAME));
+
+    let models_dir = data_dir.join("models");
+    let catalog = load_models_from_dir(&data_dir)?;
+
+    let model_info = select_model(
+        &catalog,
+        &models_dir,
+        args.model.as_deref(),
+        &settings.selected_model,
+    )?;
+    if settings.translate_to_english && !model_info.supports_translation {
+        return Err(anyhow!(
+            "Model '{}' does not support translation to English",
+            model_info.id
+        ));
+    }
+
+    let model_path = ModelManager::engine_model_path(&models_dir, &model_info)?;
+    let engine = LoadedEngine::load(&model_info.engine_type, &model_info.id, &model_path)?;
+
+    Ok((model_info, engine, settings, dictionary))
+}
+
+/// Mirror of Tauri's `app_data_dir()` for builds that never create an `AppHandle`.
+fn default_app_data_dir() -> Result<PathBuf> {
+    dirs::data_dir()
+        .map(|dir| dir.join(APP_IDENTIFIER))
+        .ok_or_else(|| anyhow!("Could not resolve the app data directory; pass --data-dir"))
+}
+
+/// The model catalog plus custom models found in the data dir's models folder.
+fn load_models_from_dir(data_dir: &Path) -> Result<HashMap<String, ModelInfo>> {
+    let mut catalog = model_catalog::load_catalog(data_dir).models;
+    ModelManager::discover_custom_models(&data_dir.join("models"), &mut catalog)?;
+    Ok(catalog)
+}
+
+/// Read persisted settings from the store file, falling back to defaults when
+/// the file is missing or unreadable.
+fn load_settings_from_dir(data_dir: &Path) -> AppSettings {
+    fs::read_to_string(data_dir.join(SETTINGS_STORE_PATH))
+        .ok()
+        .and_then(|raw| serde_json::from_str::<serde_json::Value>(&raw).ok())
+        .and_then(|mut store| store.get_mut("settings").map(serde_json::Value::take))
+        .and_then(|value| serde_json::from_value::<AppSettings>(value).ok())
+        .unwrap_or_else(get_default_settings)
+}
+
+/// Pick the requested model, else the app's selected model, else the first
+/// downloaded model by accuracy.
+fn select_model(
+    catalog: &HashMap<String, ModelInfo>,
+    models_dir: &Path,
+    requested: Option<&str>,
+    selected_in_settings: &str,
+) -> Result<ModelInfo> {
+    if let Some(model_id) = requested {
+        return catalog
+            .get(model_id)
+            .cloned()
+            .ok_or_else(|| anyhow!("Unknown model '{}'", model_id));
+    }
+
+    if let Some(model) = catalog.get(selected_in_settings) {
+        if ModelManager::engine_model_path(models_dir, model).is_ok() {
+            return Ok(model.clone());
+        }
+    }
+
+    let mut downloaded: Vec<&ModelInfo> = catalog
+        .values()
+        .filter(|model| ModelManager::engine_model_path(models_dir, model).is_ok())
+        .collect();
+    downloaded.sort_by(|a, b| {
+        b.accuracy_score
+            .partial_cmp(&a.accuracy_score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.id.cmp(&b.id))
+    });
+
+    downloaded
+        .first()
+        .map(|model| (*model).clone())
+        .ok_or_else(|| {
+            anyhow!(
+                "No downloaded models found in {}; download one in the app or pass --model",
+                models_dir.display()
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn load_settings_falls_back_to_defaults_without_store() {
+        let temp_dir = TempDir::new().unwrap();
+        let settings = load_settings_from_dir(temp_dir.path());
+        assert_eq!(
+            settings.selected_language,
+            get_default_settings().selected_language
+        );
+    }
+
+    #[test]
+    fn load_settings_reads_store_settings_key() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut settings = serde_json::to_value(get_default_settings()).unwrap();
+        settings["selected_language"] = serde_json::json!("de");
+        fs::write(
+            temp_dir.path().join(SETTINGS_STORE_PATH),
+            serde_json::json!({ "settings": settings }).to_string(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            load_settings_from_dir(temp_dir.path()).selected_language,
+            "de"
+        );
+    }
+
+    #[test]
+    fn select_model_prefers_downloaded_settings_model() {
+        let temp_dir = TempDir::new().unwrap();
+        let catalog = ModelManager::builtin_models();
+        let (model_id, model) = catalog
+            .iter()
+            .find(|(_, model)| !model.is_directory)
+            .unwrap();
+        fs::write(temp_dir.path().join(&model.filename), b"stub").unwrap();
+
+        let selected = select_model(&catalog, temp_dir.path(), None, model_id).unwrap();
+        assert_eq!(&selected.id, model_id);
+    }
+
+    #[test]
+    fn diagnostic_durations_are_bounded() {
+        assert_eq!(
+            diagnostic_duration("--speech-secs", 2.5).unwrap(),
+            Duration::from_millis(2500)
+        );
+        assert!(diagnostic_duration("--speech-secs", 0.5).is_err());
+        assert!(diagnostic_duration("--speech-secs", f32::NAN).is_err());
+    }
+
+    #[test]
+    fn diagnostics_text_lists_recommendations() {
+        let report = MicrophoneDiagnostics {
+            device_name: "USB Mic".to_string(),
+            sample_rate: 48_000,
+            channels: 2,
+            sample_format: "f32".to_string(),
+            noise_floor_dbfs: -48.0,
+            speech_level_dbfs: -20.0,
+            speech_peak_dbfs: -6.0,
+            snr_db: 28.0,
+            clipping_ratio: 0.0,
+            dc_offset: 0.0,
+            vad_speech_ratio: 0.8,
+            vad_noise_ratio: 0.0,
+            issues: vec![DiagnosticIssue::NoisyEnvironment {
+                noise_suppression: true,
+            }],
+        };
+
+        let text = format_diagnostics(&report);
+        assert!(text.starts_with("Device:        USB Mic (48000 Hz, 2 ch, f32)\n"));
+        assert!(text.contains("\nNoise floor:   -48.0 dBFS\n"));
+        assert!(text.ends_with(
+            "Recommendations:\n  - Background noise sits at -48 dBFS. \
+             Move away from fans or other noise sources.\n"
+        ));
+    }
+
+    #[test]
+    fn select_model_errors_without_downloads() {
+        let temp_dir = TempDir::new().unwrap();
+        let catalog = ModelManager::builtin_models();
+        assert!(select_model(&catalog, temp_dir.path(), None, "").is_err());
+        assert!(select_model(&catalog, temp_dir.path(), Some("missing-model"), "").is_err());
+    }
+}
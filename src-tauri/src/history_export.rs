@@ -0,0 +1,693 @@
+//! Plain-file export of history entries (SRT, WebVTT, Markdown, CSV).
+//!
+//! Unlike `.codictatebackup` archives these files are meant for people who do
+//! not run the app, so they only carry the chosen text, timing, speakers and
+//! metadata.
+
+use crate::managers::history::{
+    HistoryEntry, HistorySpeakerTurn, TimingGranularity, TranscriptTiming,
+};
+use chrono::{DateTime, Local, Utc};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+/// Subtitle cues built from word timings stop growing past these bounds.
+const MAX_CUE_CHARS: usize = 42;
+const MAX_CUE_DURATION_MS: i64 = 6_000;
+const MAX_CUE_WORD_GAP_MS: i64 = 1_000;
+/// Entries without a recorded duration still get a readable cue.
+const MIN_ENTRY_CUE_MS: i64 = 1_000;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryExportFormat {
+    Srt,
+    Webvtt,
+    Markdown,
+    Csv,
+}
+
+/// Which text variant of each entry ends up in the export.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryExportTextSource {
+    /// Engine output after dictionary and filler filtering.
+    Raw,
+    /// LLM-refined text, falling back to raw when the entry was not refined.
+    PostProcessed,
+    /// Text that was pasted, falling back to post-processed, then raw.
+    Inserted,
+}
+
+/// Entry selection for an export: explicit ids win over the list filters.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+pub struct HistoryExportFilter {
+    #[serde(default)]
+    pub entry_ids: Option<Vec<i64>>,
+    #[serde(default)]
+    pub search_query: Option<String>,
+    #[serde(default)]
+    pub starred_only: bool,
+    #[serde(default)]
+    pub time_period_start: Option<i64>,
+    #[serde(default)]
+    pub tag_id: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ExportHistoryRequest {
+    pub output_path: String,
+    pub format: HistoryExportFormat,
+    pub text_source: HistoryExportTextSource,
+    #[serde(default)]
+    pub filter: HistoryExportFilter,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ExportHistoryReport {
+    pub output_path: String,
+    pub entry_count: u32,
+}
+
+fn entry_text(entry: &HistoryEntry, source: HistoryExportTextSource) -> &str {
+    match source {
+        HistoryExportTextSource::Raw => &entry.transcription_text,
+        HistoryExportTextSource::PostProcessed => entry
+            .post_processed_text
+            .as_deref()
+            .unwrap_or(&entry.transcription_text),
+        HistoryExportTextSource::Inserted => &entry.effective_text,
+    }
+}
+
+/// Render `entries` (oldest first) in the requested format.
+pub fn render(
+    entries: &[HistoryEntry],
+    format: HistoryExportFormat,
+    text_source: HistoryExportTextSource,
+) -> String {
+    match format {
+        HistoryExportFormat::Srt => render_subtitles(entries, text_source, SubtitleKind::Srt),
+        HistoryExportFormat::Webvtt => render_subtitles(entries, text_source, SubtitleKind::WebVtt),
+        HistoryExportFormat::Markdown => render_markdown(entries, text_source),
+        HistoryExportFormat::Csv => render_csv(entries, text_source),
+    }
+}
+
+/// Render and write the export via a temporary sibling file so an interrupted
+/// export never leaves a truncated file at `output_path`.
+pub fn write_export(
+    output_path: &Path,
+    entries: &[HistoryEntry],
+    format: HistoryExportFormat,
+    text_source: HistoryExportTextSource,
+) -> Result<(), String> {
+    let contents = render(entries, format, text_source);
+    let file_name = output_path
+        .file_name()
+        .ok_or_else(|| "Invalid export output path".to_string())?;
+    let temp_path = output_path.with_file_name(format!(".{}.tmp", file_name.to_string_lossy()));
+
+    let mut file = fs::File::create(&temp_path)
+        .map_err(|error| format!("Failed to create history export file: {error}"))?;
+    file.write_all(contents.as_bytes())
+        .and_then(|_| file.sync_all())
+        .map_err(|error| {
+            let _ = fs::remove_file(&temp_path);
+            format!("Failed to write history export file: {error}")
+        })?;
+    fs::rename(&temp_path, output_path).map_err(|error| {
+        let _ = fs::remove_file(&temp_path);
+        format!("Failed to finalize history export file: {error}")
+    })
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum SubtitleKind {
+    Srt,
+    WebVtt,
+}
+
+struct Cue {
+    start_ms: i64,
+    end_ms: i64,
+    text: String,
+    /// Diarized speaker number, when the entry has speaker turns.
+    speaker: Option<i64>,
+}
+
+/// Cues from the entry's engine timings. Timings describe the raw transcript,
+/// so there are none when the exported text is anything else.
+fn timed_cues(entry: &HistoryEntry, text: &str) -> Option<Vec<Cue>> {
+    (!entry.timings.is_empty() && text == entry.transcription_text.trim())
+        .then(|| cues_from_timings(&entry.timings, &entry.speaker_turns))
+}
+
+fn speaker_name(entry: &HistoryEntry, speaker: i64) -> String {
+    entry
+        .speakers
+        .iter()
+        .find(|candidate| candidate.speaker == speaker)
+        .map(|candidate| candidate.name.clone())
+        .unwrap_or_else(|| format!("Speaker {speaker}"))
+}
+
+/// The speaker whose turns overlap `start_ms..end_ms` the most.
+fn speaker_at(turns: &[HistorySpeakerTurn], start_ms: i64, end_ms: i64) -> Option<i64> {
+    let mut overlaps: Vec<(i64, i64)> = Vec::new();
+    for turn in turns {
+        let overlap = end_ms.min(turn.end_ms) - start_ms.max(turn.start_ms);
+        // Zero-length timings still belong to the turn they fall in.
+        if overlap > 0 || (overlap == 0 && start_ms == end_ms) {
+            match overlaps
+                .iter_mut()
+                .find(|(speaker, _)| *speaker == turn.speaker)
+            {
+                Some((_, total)) => *total += overlap,
+                None => overlaps.push((turn.speaker, overlap)),
+            }
+        }
+    }
+    overlaps
+        .into_iter()
+        .max_by_key(|&(_, total)| total)
+        .map(|(speaker, _)| speaker)
+}
+
+/// Entries are laid out back to back on one timeline, each offset by the
+/// recording durations before it. Engine timings describe the raw transcript,
+/// so they are only used when the exported text is that transcript; diarized
+/// cues then name their speaker.
+fn render_subtitles(
+    entries: &[HistoryEntry],
+    text_source: HistoryExportTextSource,
+    kind: SubtitleKind,
+) -> String {
+    let mut out = String::new();
+    if kind == SubtitleKind::WebVtt {
+        out.push_str("WEBVTT\n\n");
+    }
+
+    let mut offset_ms = 0_i64;
+    let mut index = 1_usize;
+    for entry in entries {
+        let text = entry_text(entry, text_source).trim();
+        let entry_span_ms = entry.duration_ms.max(MIN_ENTRY_CUE_MS);
+        let cues = if let Some(cues) = timed_cues(entry, text) {
+            cues
+        } else if text.is_empty() {
+            Vec::new()
+        } else {
+            vec![Cue {
+                start_ms: 0,
+                end_ms: entry_span_ms,
+                text: text.to_string(),
+                speaker: None,
+            }]
+        };
+
+        let mut entry_end_ms = entry_span_ms;
+        for cue in cues {
+            entry_end_ms = entry_end_ms.max(cue.end_ms);
+            let start = format_cue_timestamp(offset_ms + cue.start_ms, kind);
+            let end = format_cue_timestamp(offset_ms + cue.end_ms, kind);
+            if kind == SubtitleKind::Srt {
+                out.push_str(&format!("{index}\n"));
+            }
+            let body = cue_text(&cue.text, kind);
+            let text = match (cue.speaker, kind) {
+                (Some(speaker), SubtitleKind::Srt) => {
+                    format!(
+                        "{}: {}",
+                        cue_text(&speaker_name(entry, speaker), kind),
+                        body
+                    )
+                }
+                (Some(speaker), SubtitleKind::WebVtt) => format!(
+                    "<v {}>{}",
+                    cue_text(&speaker_name(entry, speaker), kind).replace('\n', " "),
+                    body
+                ),
+                (None, _) => body,
+            };
+            out.push_str(&format!("{start} --> {end}\n{text}\n\n"));
+            index += 1;
+        }
+        offset_ms += entry_end_ms;
+    }
+
+    out
+}
+
+/// Cue text that cannot end the cue or be read as markup: blank lines (e.g.
+/// from a spoken "new paragraph") would end the cue early and `-->` would
+/// start a new one, and WebVTT reads `&`, `<` and `>` as markup.
+fn cue_text(text: &str, kind: SubtitleKind) -> String {
+    let text = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+        .replace("-->", "->");
+    match kind {
+        SubtitleKind::Srt => text,
+        SubtitleKind::WebVtt => text
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;"),
+    }
+}
+
+fn cues_from_timings(timings: &[TranscriptTiming], turns: &[HistorySpeakerTurn]) -> Vec<Cue> {
+    let mut cues: Vec<Cue> = Vec::new();
+    let mut current: Option<Cue> = None;
+
+    // Long-form chunk rows span up to a minute, so they only become cues when
+    // the engine reported no finer timings.
+    let chunks_as_cues = timings
+        .iter()
+        .all(|timing| timing.granularity == TimingGranularity::Chunk);
+
+    for timing in timings {
+        if timing.granularity == TimingGranularity::Chunk && !chunks_as_cues {
+            continue;
+        }
+        let speaker = speaker_at(turns, timing.start_ms, timing.end_ms);
+        if timing.granularity != TimingGranularity::Word {
+            cues.extend(current.take());
+            cues.push(Cue {
+                start_ms: timing.start_ms,
+                end_ms: timing.end_ms,
+                text: timing.text.clone(),
+                speaker,
+            });
+            continue;
+        }
+
+        if let Some(cue) = current.as_mut() {
+            let fits = cue.text.len() + 1 + timing.text.len() <= MAX_CUE_CHARS
+                && timing.end_ms - cue.start_ms <= MAX_CUE_DURATION_MS
+                && timing.start_ms - cue.end_ms <= MAX_CUE_WORD_GAP_MS
+                && speaker == cue.speaker;
+            if fits {
+                cue.text.push(' ');
+                cue.text.push_str(&timing.text);
+                cue.end_ms = timing.end_ms;
+                continue;
+            }
+            cues.extend(current.take());
+        }
+        current = Some(Cue {
+            start_ms: timing.start_ms,
+            end_ms: timing.end_ms,
+            text: timing.text.clone(),
+            speaker,
+        });
+    }
+
+    cues.extend(current);
+    cues
+}
+
+fn format_cue_timestamp(ms: i64, kind: SubtitleKind) -> String {
+    let ms = ms.max(0);
+    let hours = ms / 3_600_000;
+    let minutes = (ms / 60_000) % 60;
+    let seconds = (ms / 1_000) % 60;
+    let millis = ms % 1_000;
+    let separator = match kind {
+        SubtitleKind::Srt => ',',
+        SubtitleKind::WebVtt => '.',
+    };
+    format!("{hours:02}:{minutes:02}:{seconds:02}{separator}{millis:03}")
+}
+
+fn format_local_timestamp(timestamp: i64) -> String {
+    DateTime::<Utc>::from_timestamp(timestamp, 0)
+        .map(|dt| {
+            dt.with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+        })
+        .unwrap_or_default()
+}
+
+/// The exported text as one paragraph per speaker turn, when the entry is
+/// diarized and its timings match the text.
+fn speaker_paragraphs(entry: &HistoryEntry, text: &str) -> Option<String> {
+    if entry.speaker_turns.is_empty() {
+        return None;
+    }
+    let mut paragraphs: Vec<(Option<i64>, String)> = Vec::new();
+    for cue in timed_cues(entry, text)? {
+        match paragraphs.last_mut() {
+            Some((speaker, paragraph)) if *speaker == cue.speaker => {
+                paragraph.push(' ');
+                paragraph.push_str(cue.text.trim());
+            }
+            _ => paragraphs.push((cue.speaker, cue.text.trim().to_string())),
+        }
+    }
+    Some(
+        paragraphs
+            .into_iter()
+            .map(|(speaker, paragraph)| match speaker {
+                Some(speaker) => format!("**{}:** {}", speaker_name(entry, speaker), paragraph),
+                None => paragraph,
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n"),
+    )
+}
+
+fn render_markdown(entries: &[HistoryEntry], text_source: HistoryExportTextSource) -> String {
+    let mut out = String::from("# Codictate history\n");
+    for entry in entries {
+        let starred = if entry.saved { " ★" } else { "" };
+        let text = entry_text(entry, text_source).trim();
+        out.push_str(&format!(
+            "\n## {}{}\n\n_{} · {:.1} s_\n\n{}\n",
+            entry.title.trim(),
+            starred,
+            format_local_timestamp(entry.timestamp),
+            entry.duration_ms.max(0) as f64 / 1000.0,
+            speaker_paragraphs(entry, text).unwrap_or_else(|| text.to_string())
+        ));
+    }
+    out
+}
+
+fn render_csv(entries: &[HistoryEntry], text_source: HistoryExportTextSource) -> String {
+    let mut out = String::from("id,timestamp,date,title,starred,duration_ms,text,speakers\r\n");
+    for entry in entries {
+        let fields = [
+            entry.id.to_string(),
+            entry.timestamp.to_string(),
+            format_local_timestamp(entry.timestamp),
+            entry.title.clone(),
+            entry.saved.to_string(),
+            entry.duration_ms.to_string(),
+            entry_text(entry, text_source).to_string(),
+            entry
+                .speakers
+                .iter()
+                .map(|speaker| speaker.name.as_str())
+                .collect::<Vec<_>>()
+                .join("; "),
+        ];
+        let row = fields
+            .iter()
+            .map(|field| csv_field(field))
+            .collect::<Vec<_>>()
+            .join(",");
+        out.push_str(&row);
+        out.push_str("\r\n");
+    }
+    out
+}
+
+/// RFC 4180 quoting; a leading formula character is prefixed with `'` so
+/// spreadsheet apps do not evaluate dictated text.
+fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@']) {
+        format!("'{value}")
+    } else {
+        value.to_string()
+    };
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::managers::history::HistorySpeaker;
+
+    fn entry(id: i64, raw: &str, duration_ms: i64, timings: Vec<TranscriptTiming>) -> HistoryEntry {
+        HistoryEntry {
+            id,
+            file_name: format!("codictate-{id}.wav"),
+            timestamp: 1_700_000_000 + id,
+            saved: false,
+            title: format!("Recording {id}"),
+            transcription_text: raw.to_string(),
+            post_processed_text: None,
+            inserted_text: None,
+            effective_text: raw.to_string(),
+            raw_text: raw.to_string(),
+            post_process_prompt: None,
+            duration_ms,
+            file_path: String::new(),
+            audio_file_exists: false,
+            timings,
+            tags: Vec::new(),
+            speaker_turns: Vec::new(),
+            speakers: Vec::new(),
+            bias_terms: Vec::new(),
+        }
+    }
+
+    fn word(start_ms: i64, end_ms: i64, text: &str) -> TranscriptTiming {
+        TranscriptTiming {
+            start_ms,
+            end_ms,
+            text: text.to_string(),
+            granularity: TimingGranularity::Word,
+        }
+    }
+
+    #[test]
+    fn srt_groups_words_and_offsets_following_entries() {
+        let entries = vec![
+            entry(
+                1,
+                "hello world again",
+                2_000,
+                vec![
+                    word(0, 400, "hello"),
+                    word(450, 900, "world"),
+                    word(2_500, 2_900, "again"),
+                ],
+            ),
+            entry(2, "second", 1_500, Vec::new()),
+        ];
+
+        let srt = render(
+            &entries,
+            HistoryExportFormat::Srt,
+            HistoryExportTextSource::Raw,
+        );
+        assert_eq!(
+            srt,
+            "1\n00:00:00,000 --> 00:00:00,900\nhello world\n\n\
+             2\n00:00:02,500 --> 00:00:02,900\nagain\n\n\
+             3\n00:00:02,900 --> 00:00:04,400\nsecond\n\n"
+        );
+    }
+
+    #[test]
+    fn long_form_chunk_rows_only_become_cues_without_finer_timings() {
+        let chunk = |start_ms, end_ms, text: &str| TranscriptTiming {
+            start_ms,
+            end_ms,
+            text: text.to_string(),
+            granularity: TimingGranularity::Chunk,
+        };
+        let with_words = vec![word(0, 400, "hello"), chunk(0, 30_000, "hello")];
+        assert_eq!(cues_from_timings(&with_words, &[]).len(), 1);
+        assert_eq!(cues_from_timings(&with_words, &[])[0].end_ms, 400);
+
+        let chunks_only = vec![chunk(0, 30_000, "first"), chunk(30_000, 45_000, "second")];
+        let cues = cues_from_timings(&chunks_only, &[]);
+        assert_eq!(cues.len(), 2);
+        assert_eq!(cues[1].text, "second");
+    }
+
+    #[test]
+    fn diarized_entries_name_speakers_in_cues_and_markdown() {
+        let mut interview = entry(
+            1,
+            "how are you fine thanks",
+            3_000,
+            vec![
+                word(0, 300, "how"),
+                word(300, 500, "are"),
+                word(500, 800, "you"),
+                word(1_200, 1_600, "fine"),
+                word(1_600, 2_000, "thanks"),
+            ],
+        );
+        interview.speaker_turns = vec![
+            HistorySpeakerTurn {
+                start_ms: 0,
+                end_ms: 900,
+                speaker: 1,
+            },
+            HistorySpeakerTurn {
+                start_ms: 1_100,
+                end_ms: 2_100,
+                speaker: 2,
+            },
+        ];
+        interview.speakers = vec![
+            HistorySpeaker {
+                speaker: 1,
+                name: "Host".to_string(),
+            },
+            HistorySpeaker {
+                speaker: 2,
+                name: "Speaker 2".to_string(),
+            },
+        ];
+        let entries = [interview];
+
+        let vtt = render(
+            &entries,
+            HistoryExportFormat::Webvtt,
+            HistoryExportTextSource::Raw,
+        );
+        assert_eq!(
+            vtt,
+            "WEBVTT\n\n00:00:00.000 --> 00:00:00.800\n<v Host>how are you\n\n\
+             00:00:01.200 --> 00:00:02.000\n<v Speaker 2>fine thanks\n\n"
+        );
+
+        let markdown = render(
+            &entries,
+            HistoryExportFormat::Markdown,
+            HistoryExportTextSource::Raw,
+        );
+        assert!(markdown.contains("**Host:** how are you\n\n**Speaker 2:** fine thanks\n"));
+
+        let csv = render(
+            &entries,
+            HistoryExportFormat::Csv,
+            HistoryExportTextSource::Raw,
+        );
+        assert!(csv.ends_with(",Host; Speaker 2\r\n"), "{csv}");
+    }
+
+    #[test]
+    fn webvtt_ignores_timings_when_text_was_refined() {
+        let mut refined = entry(
+            1,
+            "um hello",
+            1_200,
+            vec![word(0, 300, "um"), word(400, 900, "hello")],
+        );
+        refined.post_processed_text = Some("Hello.".to_string());
+
+        let vtt = render(
+            &[refined],
+            HistoryExportFormat::Webvtt,
+            HistoryExportTextSource::PostProcessed,
+        );
+        assert_eq!(vtt, "WEBVTT\n\n00:00:00.000 --> 00:00:01.200\nHello.\n\n");
+    }
+
+    #[test]
+    fn multi_paragraph_cues_keep_all_their_text() {
+        let entries = vec![entry(
+            1,
+            "First point.\n\nSecond point.\n \nThird.",
+            2_000,
+            Vec::new(),
+        )];
+
+        let srt = render(
+            &entries,
+            HistoryExportFormat::Srt,
+            HistoryExportTextSource::Raw,
+        );
+        assert_eq!(
+            srt,
+            "1\n00:00:00,000 --> 00:00:02,000\nFirst point.\nSecond point.\nThird.\n\n"
+        );
+    }
+
+    #[test]
+    fn cue_arrows_and_webvtt_markup_are_neutralized() {
+        let mut item = entry(1, "a --> b & <c>", 1_000, Vec::new());
+        item.speaker_turns = vec![HistorySpeakerTurn {
+            start_ms: 0,
+            end_ms: 1_000,
+            speaker: 1,
+        }];
+        item.speakers = vec![HistorySpeaker {
+            speaker: 1,
+            name: "Q&A <host>".to_string(),
+        }];
+
+        let vtt = render(
+            &[item.clone()],
+            HistoryExportFormat::Webvtt,
+            HistoryExportTextSource::Raw,
+        );
+        assert_eq!(
+            vtt,
+            "WEBVTT\n\n00:00:00.000 --> 00:00:01.000\na -> b &amp; &lt;c&gt;\n\n"
+        );
+
+        item.timings = vec![word(0, 500, "a"), word(500, 1_000, "-->")];
+        item.transcription_text = "a -->".to_string();
+        item.raw_text = item.transcription_text.clone();
+        let vtt = render(
+            &[item.clone()],
+            HistoryExportFormat::Webvtt,
+            HistoryExportTextSource::Raw,
+        );
+        assert_eq!(
+            vtt,
+            "WEBVTT\n\n00:00:00.000 --> 00:00:01.000\n<v Q&amp;A &lt;host&gt;>a ->\n\n"
+        );
+
+        let srt = render(
+            &[item],
+            HistoryExportFormat::Srt,
+            HistoryExportTextSource::Raw,
+        );
+        assert_eq!(
+            srt,
+            "1\n00:00:00,000 --> 00:00:01,000\nQ&A <host>: a ->\n\n"
+        );
+    }
+
+    #[test]
+    fn inserted_source_falls_back_through_effective_text() {
+        let mut item = entry(1, "raw", 0, Vec::new());
+        item.post_processed_text = Some("processed".to_string());
+        item.effective_text = "processed".to_string();
+
+        assert_eq!(
+            entry_text(&item, HistoryExportTextSource::Inserted),
+            "processed"
+        );
+        assert_eq!(entry_text(&item, HistoryExportTextSource::Raw), "raw");
+    }
+
+    #[test]
+    fn csv_quotes_and_neutralizes_formulas() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a, \"b\""), "\"a, \"\"b\"\"\"");
+        assert_eq!(csv_field("=SUM(A1)"), "'=SUM(A1)");
+        assert_eq!(csv_field("line\nbreak"), "\"line\nbreak\"");
+    }
+
+    #[test]
+    fn cue_timestamps_use_format_specific_separator() {
+        assert_eq!(
+            format_cue_timestamp(3_723_004, SubtitleKind::Srt),
+            "01:02:03,004"
+        );
+        assert_eq!(
+            format_cue_timestamp(3_723_004, SubtitleKind::WebVtt),
+            "01:02:03.004"
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use crate::audio_toolkit::voice_commands::EditKey;
 use enigo::{Enigo, Key, Keyboard, Mouse, Settings};
 use std::sync::Mutex;
 use tauri::{AppHandle, Manager};
@@ -219,3 +220,60 @@ pub fn paste_text_direct(enigo: &mut Enigo, text: &str) -> Result<(), String> {
 
     Ok(())
 }
+
+/// Sends the key for a spoken edit command.
+pub fn send_edit_key(enigo: &mut Enigo, key: EditKey) -> Result<(), String> {
+    let key_code = match key {
+        EditKey::Enter => Key::Return,
+        EditKey::Tab => Key::Tab,
+        EditKey::Escape => Key::Escape,
+        EditKey::Backspace => Key::Backspace,
+        EditKey::Undo => return send_undo_cmd_z(enigo),
+    };
+
+    enigo
+        .key(key_code, enigo::Direction::Click)
+        .map_err(|e| format!("Failed to click {:?} key: {}", key, e))?;
+
+    Ok(())
+}
+
+/// Selects the word before the cursor with Option+Shift+Left (macOS) or
+/// Ctrl+Shift+Left (Windows/Linux).
+pub fn select_previous_word(enigo: &mut Enigo) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    let modifier_key = Key::Alt;
+    #[cfg(not(target_os = "macos"))]
+    let modifier_key = Key::Control;
+
+    enigo
+        .key(modifier_key, enigo::Direction::Press)
+        .map_err(|e| format!("Failed to press modifier key: {}", e))?;
+    let result = select_previous_chars(enigo, 1);
+    enigo
+        .key(modifier_key, enigo::Direction::Release)
+        .map_err(|e| format!("Failed to release modifier key: {}", e))?;
+
+    result
+}
+
+/// Extends the selection `count` characters to the left with Shift+Left.
+pub fn select_previous_chars(enigo: &mut Enigo, count: usize) -> Result<(), String> {
+    enigo
+        .key(Key::Shift, enigo::Direction::Press)
+        .map_err(|e| format!("Failed to press Shift key: {}", e))?;
+
+    let mut result = Ok(());
+    for _ in 0..count {
+        if let Err(e) = enigo.key(Key::LeftArrow, enigo::Direction::Click) {
+            result = Err(format!("Failed to click Left key: {}", e));
+            break;
+        }
+    }
+
+    enigo
+        .key(Key::Shift, enigo::Direction::Release)
+        .map_err(|e| format!("Failed to release Shift key: {}", e))?;
+
+    result
+}
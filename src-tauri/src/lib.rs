@@ -3,6 +3,7 @@ mod accessibility;
 mod actions;
 mod analytics;
 mod growth;
+mod app_profiles;
 #[cfg(all(target_os = "macos", target_arch = "aarch64"))]
 mod apple_intelligence;
 mod audio_device_info;
@@ -12,15 +13,21 @@ mod backup_restore;
 pub mod cli;
 mod clipboard;
 mod commands;
+mod dictionary_bias;
 mod dictionary_normalization;
 #[cfg(target_os = "macos")]
 mod fn_key_monitor;
+pub mod headless;
 mod helpers;
+mod history_export;
 mod i18n;
 mod input;
 mod llm_client;
 mod managers;
 mod menu;
+mod mic_diagnostics;
+mod model_bundle;
+mod modes;
 mod notification;
 mod overlay;
 mod permissions;
@@ -37,6 +44,7 @@ mod undo;
 mod user_dictionary;
 mod user_profile;
 mod utils;
+mod word_diff;
 
 pub use cli::CliArgs;
 use once_cell::sync::Lazy;
@@ -801,7 +809,7 @@ fn initialize_core_logic(app_handle: &AppHandle) {
     let initial_theme = tray::get_current_theme(app_handle);
 
     // Choose the appropriate initial icon based on theme
-    let initial_icon_path = tray::get_icon_path(initial_theme, tray::TrayIconState::Idle);
+    let initial_icon_path = tray::get_icon_path(initial_theme, tray::idle_state(app_handle));
 
     let tray = TrayIconBuilder::new()
         .icon(
@@ -849,7 +857,9 @@ fn initialize_core_logic(app_handle: &AppHandle) {
             "quit" => {
                 app.exit(0);
             }
-            _ => {}
+            id => {
+                modes::handle_tray_menu_event(app, id);
+            }
         })
         .build(app_handle)
         .unwrap();
@@ -907,6 +917,10 @@ fn initialize_core_logic(app_handle: &AppHandle) {
     let app_for_overlay_fully_ready = app_handle.clone();
     app_handle.listen("overlay-fully-ready", move |_event| {
         overlay::mark_overlay_ready(&app_for_overlay_fully_ready);
+        overlay::emit_recent_audio_buffer(
+            &app_for_overlay_fully_ready,
+            settings::get_settings(&app_for_overlay_fully_ready).recent_audio_secs(),
+        );
         tracing::debug!("Received overlay-fully-ready event from frontend");
     });
 
@@ -955,6 +969,7 @@ pub fn run(cli_args: CliArgs) {
         shortcut::change_overlay_position_setting,
         shortcut::change_debug_mode_setting,
         shortcut::change_word_correction_threshold_setting,
+        shortcut::change_dictionary_biasing_enabled_setting,
         shortcut::change_paste_method_setting,
         shortcut::get_available_typing_tools,
         shortcut::change_typing_tool_setting,
@@ -971,14 +986,35 @@ pub fn run(cli_args: CliArgs) {
         shortcut::add_post_process_prompt,
         shortcut::update_post_process_prompt,
         shortcut::delete_post_process_prompt,
+        shortcut::add_app_profile,
+        shortcut::update_app_profile,
+        shortcut::delete_app_profile,
+        shortcut::get_frontmost_app_identifiers,
+        shortcut::add_mode,
+        shortcut::update_mode,
+        shortcut::delete_mode,
+        shortcut::set_active_mode,
+        shortcut::change_mode_binding,
         shortcut::set_post_process_selected_prompt,
         shortcut::suspend_binding,
         shortcut::resume_binding,
         shortcut::change_mute_while_recording_setting,
+        shortcut::change_audio_high_pass_filter_setting,
+        shortcut::change_audio_noise_suppression_setting,
+        shortcut::change_audio_auto_gain_setting,
+        shortcut::change_auto_stop_on_silence_setting,
+        shortcut::change_auto_stop_timing_setting,
+        shortcut::change_long_form_recording_setting,
+        shortcut::change_speaker_diarization_setting,
+        shortcut::change_trusted_model_bundle_keys_setting,
         shortcut::change_append_trailing_space_setting,
         shortcut::change_paste_last_use_smart_insertion_setting,
         shortcut::change_filler_word_filter_setting,
+        shortcut::change_spoken_punctuation_setting,
+        shortcut::get_voice_command_languages,
+        shortcut::change_voice_command_languages_setting,
         shortcut::change_hallucination_filter_setting,
+        shortcut::change_streaming_partials_setting,
         shortcut::change_app_language_setting,
         shortcut::change_update_checks_setting,
         shortcut::change_share_usage_analytics_setting,
@@ -1031,11 +1067,21 @@ pub fn run(cli_args: CliArgs) {
         commands::models::has_any_models_available,
         commands::models::has_any_models_or_downloads,
         commands::models::get_recommended_first_model,
+        commands::models::get_model_catalog_status,
+        commands::models::reload_model_catalog,
+        commands::models::export_model_bundle,
+        commands::models::import_model_bundle,
         commands::audio::update_microphone_mode,
         commands::audio::get_microphone_mode,
+        commands::audio::change_vad_backend_setting,
+        commands::audio::change_vad_sensitivity_setting,
+        commands::audio::change_vad_smoothing_setting,
+        commands::audio::change_recent_audio_buffer_setting,
         commands::audio::get_available_microphones,
         commands::audio::set_selected_microphone,
         commands::audio::get_selected_microphone,
+        commands::audio::get_available_loopback_sources,
+        commands::audio::set_capture_source,
         commands::audio::get_available_output_devices,
         commands::audio::set_selected_output_device,
         commands::audio::get_selected_output_device,
@@ -1046,16 +1092,30 @@ pub fn run(cli_args: CliArgs) {
         commands::audio::is_recording,
         commands::audio::start_mic_preview,
         commands::audio::stop_mic_preview,
+        commands::audio::run_microphone_diagnostics,
         commands::transcription::set_model_unload_timeout,
         commands::transcription::get_model_load_status,
         commands::transcription::warm_up_transcription_model,
         commands::transcription::unload_model_manually,
         commands::history::get_history_entries,
+        commands::history::search_history_entries,
+        commands::history::export_history,
         commands::history::toggle_history_entry_saved,
+        commands::history::list_history_tags,
+        commands::history::create_history_tag,
+        commands::history::rename_history_tag,
+        commands::history::delete_history_tag,
+        commands::history::tag_history_entry,
+        commands::history::untag_history_entry,
+        commands::history::retranscribe_history_entry,
+        commands::history::list_history_revisions,
+        commands::history::delete_history_revision,
+        commands::history::rename_history_speaker,
         commands::history::get_audio_file_path,
         commands::history::delete_history_entry,
         commands::history::update_history_limit,
         commands::history::update_recording_retention_period,
+        commands::history::update_recording_format,
         commands::history::get_home_stats,
         commands::history::clear_all_history,
         commands::history::get_history_storage_usage,
@@ -1105,6 +1165,7 @@ pub fn run(cli_args: CliArgs) {
         shortcut::change_overlay_position_setting,
         shortcut::change_debug_mode_setting,
         shortcut::change_word_correction_threshold_setting,
+        shortcut::change_dictionary_biasing_enabled_setting,
         shortcut::change_paste_method_setting,
         shortcut::change_clipboard_handling_setting,
         shortcut::change_post_process_enabled_setting,
@@ -1116,14 +1177,35 @@ pub fn run(cli_args: CliArgs) {
         shortcut::add_post_process_prompt,
         shortcut::update_post_process_prompt,
         shortcut::delete_post_process_prompt,
+        shortcut::add_app_profile,
+        shortcut::update_app_profile,
+        shortcut::delete_app_profile,
+        shortcut::get_frontmost_app_identifiers,
+        shortcut::add_mode,
+        shortcut::update_mode,
+        shortcut::delete_mode,
+        shortcut::set_active_mode,
+        shortcut::change_mode_binding,
         shortcut::set_post_process_selected_prompt,
         shortcut::suspend_binding,
         shortcut::resume_binding,
         shortcut::change_mute_while_recording_setting,
+        shortcut::change_audio_high_pass_filter_setting,
+        shortcut::change_audio_noise_suppression_setting,
+        shortcut::change_audio_auto_gain_setting,
+        shortcut::change_auto_stop_on_silence_setting,
+        shortcut::change_auto_stop_timing_setting,
+        shortcut::change_long_form_recording_setting,
+        shortcut::change_speaker_diarization_setting,
+        shortcut::change_trusted_model_bundle_keys_setting,
         shortcut::change_append_trailing_space_setting,
         shortcut::change_paste_last_use_smart_insertion_setting,
         shortcut::change_filler_word_filter_setting,
+        shortcut::change_spoken_punctuation_setting,
+        shortcut::get_voice_command_languages,
+        shortcut::change_voice_command_languages_setting,
         shortcut::change_hallucination_filter_setting,
+        shortcut::change_streaming_partials_setting,
         shortcut::change_app_language_setting,
         shortcut::change_update_checks_setting,
         shortcut::change_share_usage_analytics_setting,
@@ -1177,11 +1259,21 @@ pub fn run(cli_args: CliArgs) {
         commands::models::has_any_models_available,
         commands::models::has_any_models_or_downloads,
         commands::models::get_recommended_first_model,
+        commands::models::get_model_catalog_status,
+        commands::models::reload_model_catalog,
+        commands::models::export_model_bundle,
+        commands::models::import_model_bundle,
         commands::audio::update_microphone_mode,
         commands::audio::get_microphone_mode,
+        commands::audio::change_vad_backend_setting,
+        commands::audio::change_vad_sensitivity_setting,
+        commands::audio::change_vad_smoothing_setting,
+        commands::audio::change_recent_audio_buffer_setting,
         commands::audio::get_available_microphones,
         commands::audio::set_selected_microphone,
         commands::audio::get_selected_microphone,
+        commands::audio::get_available_loopback_sources,
+        commands::audio::set_capture_source,
         commands::audio::get_available_output_devices,
         commands::audio::set_selected_output_device,
         commands::audio::get_selected_output_device,
@@ -1192,16 +1284,30 @@ pub fn run(cli_args: CliArgs) {
         commands::audio::is_recording,
         commands::audio::start_mic_preview,
         commands::audio::stop_mic_preview,
+        commands::audio::run_microphone_diagnostics,
         commands::transcription::set_model_unload_timeout,
         commands::transcription::get_model_load_status,
         commands::transcription::warm_up_transcription_model,
         commands::transcription::unload_model_manually,
         commands::history::get_history_entries,
+        commands::history::search_history_entries,
+        commands::history::export_history,
         commands::history::toggle_history_entry_saved,
+        commands::history::list_history_tags,
+        commands::history::create_history_tag,
+        commands::history::rename_history_tag,
+        commands::history::delete_history_tag,
+        commands::history::tag_history_entry,
+        commands::history::untag_history_entry,
+        commands::history::retranscribe_history_entry,
+        commands::history::list_history_revisions,
+        commands::history::delete_history_revision,
+        commands::history::rename_history_speaker,
         commands::history::get_audio_file_path,
         commands::history::delete_history_entry,
         commands::history::update_history_limit,
         commands::history::update_recording_retention_period,
+        commands::history::update_recording_format,
         commands::history::get_home_stats,
         commands::history::clear_all_history,
         commands::history::get_history_storage_usage,
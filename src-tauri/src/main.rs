@@ -2,11 +2,29 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 use clap::Parser;
+use codictate_app_lib::cli::CliCommand;
 use codictate_app_lib::CliArgs;
 
 fn main() {
     let cli_args = CliArgs::parse();
 
+    // Headless subcommands never start Tauri, so they run before any GUI setup.
+    match cli_args.command.clone() {
+        Some(CliCommand::Transcribe(args)) => {
+            std::process::exit(codictate_app_lib::headless::run_transcribe(args));
+        }
+        Some(CliCommand::DiagnoseMic(args)) => {
+            std::process::exit(codictate_app_lib::headless::run_diagnose_mic(args));
+        }
+        Some(CliCommand::ExportModels(args)) => {
+            std::process::exit(codictate_app_lib::headless::run_export_models(args));
+        }
+        Some(CliCommand::ImportModels(args)) => {
+            std::process::exit(codictate_app_lib::headless::run_import_models(args));
+        }
+        None => {}
+    }
+
     #[cfg(target_os = "linux")]
     {
         // DMABUF renderer causes crashes on various GPU/display server configurations
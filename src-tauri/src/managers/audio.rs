@@ -1,12 +1,17 @@
 use crate::audio_toolkit::audio::{
-    list_input_devices, AudioRecorder, RecorderStartError, RecorderStartWait, RecorderStopWait,
+    list_input_devices, AudioProcessingConfig, AudioRecorder, CaptureInput, CapturedAudio,
+    LongFormChunker, RecorderStartError, RecorderStartWait, RecorderStopWait, SourceChannels,
+    TrailingSilenceDetector, DEFAULT_MONITOR_SOURCE,
 };
-use crate::audio_toolkit::vad::SmoothedVad;
-use crate::audio_toolkit::SileroVad;
+use crate::audio_toolkit::vad::{EnergyVad, GmmVad, SmoothedVad};
+use crate::audio_toolkit::{SileroVad, VoiceActivityDetector};
 use crate::helpers::clamshell;
+use crate::managers::transcription::TranscriptionManager;
+use crate::mic_diagnostics::{self, DiagnosticPhase, MicrophoneDiagnostics};
 use crate::overlay;
-use crate::settings::{get_settings, AppSettings};
+use crate::settings::{get_settings, AppSettings, CaptureSource, MixedChannelLayout, VadBackend};
 use crate::utils;
+use crate::TranscriptionCoordinator;
 use anyhow::Result;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{mpsc, Arc, Condvar, Mutex};
@@ -114,6 +119,12 @@ pub struct StoppedRecording {
     pub samples_for_transcription: Vec<f32>,
     pub speech_duration_ms: i64,
     pub recording_duration_ms: i64,
+    /// Microphone and system audio kept apart by mixed capture, for telling
+    /// the local speaker from the others.
+    pub source_channels: Option<SourceChannels>,
+    /// The recording before the VAD dropped its pauses, kept for history so
+    /// playback and timings include them.
+    pub captured: Option<CapturedAudio>,
 }
 
 fn build_stopped_recording(
@@ -140,6 +151,8 @@ fn build_stopped_recording(
         samples_for_transcription,
         speech_duration_ms,
         recording_duration_ms,
+        source_channels: None,
+        captured: None,
     }
 }
 
@@ -892,23 +905,122 @@ fn finalize_prepare_cancellation(
 
 /* ──────────────────────────────────────────────────────────────── */
 
+fn audio_processing_config(settings: &AppSettings) -> AudioProcessingConfig {
+    AudioProcessingConfig {
+        high_pass_filter: settings.audio_high_pass_filter,
+        noise_suppression: settings.audio_noise_suppression,
+        auto_gain: settings.audio_auto_gain,
+    }
+}
+
+fn capture_input(settings: &AppSettings) -> CaptureInput {
+    let source = settings
+        .loopback_source
+        .clone()
+        .unwrap_or_else(|| DEFAULT_MONITOR_SOURCE.to_string());
+    match settings.capture_source {
+        CaptureSource::Microphone => CaptureInput::Microphone,
+        CaptureSource::SystemAudio => CaptureInput::Loopback { source },
+        CaptureSource::Mixed => CaptureInput::Mixed {
+            source,
+            keep_separate: settings.mixed_channel_layout == MixedChannelLayout::Separate,
+        },
+    }
+}
+
+/// Builds the configured detector wrapped in `SmoothedVad`. Falls back to the
+/// GMM detector when the Silero model cannot be loaded, so silence trimming
+/// keeps working on machines where ONNX is unavailable.
+pub(crate) fn create_vad(vad_path: &str, settings: &AppSettings) -> Box<dyn VoiceActivityDetector> {
+    let sensitivity = settings.vad_sensitivity.clamp(0.0, 1.0);
+    let inner: Box<dyn VoiceActivityDetector> = match settings.vad_backend {
+        // Sensitivity 0.5 maps to the historical Silero threshold of 0.3.
+        VadBackend::Silero => match SileroVad::new(vad_path, 0.55 - 0.5 * sensitivity) {
+            Ok(silero) => Box::new(silero),
+            Err(e) => {
+                warn!("Failed to create SileroVad, falling back to GMM VAD: {}", e);
+                Box::new(GmmVad::new(sensitivity))
+            }
+        },
+        VadBackend::Gmm => Box::new(GmmVad::new(sensitivity)),
+        VadBackend::Energy => Box::new(EnergyVad::new(sensitivity)),
+    };
+
+    let (prefill, hangover, onset) = match crate::audio_toolkit::vad::validate_smoothing(
+        settings.vad_prefill_frames,
+        settings.vad_hangover_frames,
+        settings.vad_onset_frames,
+    ) {
+        Ok(()) => (
+            settings.vad_prefill_frames,
+            settings.vad_hangover_frames,
+            settings.vad_onset_frames,
+        ),
+        Err(e) => {
+            warn!("Invalid VAD smoothing settings, using defaults: {}", e);
+            (15, 15, 2)
+        }
+    };
+    Box::new(SmoothedVad::new(inner, prefill, hangover, onset))
+}
+
+pub(crate) fn resolve_vad_path(
+    app_handle: &tauri::AppHandle,
+) -> Result<std::path::PathBuf, anyhow::Error> {
+    app_handle
+        .path()
+        .resolve(
+            "resources/models/silero_vad_v4.onnx",
+            tauri::path::BaseDirectory::Resource,
+        )
+        .map_err(|e| anyhow::anyhow!("Failed to resolve VAD path: {}", e))
+}
+
 fn create_audio_recorder(
     vad_path: &str,
     app_handle: &tauri::AppHandle,
 ) -> Result<AudioRecorder, anyhow::Error> {
-    let silero = SileroVad::new(vad_path, 0.3)
-        .map_err(|e| anyhow::anyhow!("Failed to create SileroVad: {}", e))?;
-    let smoothed_vad = SmoothedVad::new(Box::new(silero), 15, 15, 2);
+    let settings = get_settings(app_handle);
 
-    // Recorder with VAD plus a spectrum-level callback that forwards updates to the frontend.
+    // Recorder with VAD plus spectrum-level, speech-segment and chunk callbacks that
+    // forward updates to the frontend, the partial-transcript and long-form workers.
     let recorder = AudioRecorder::new()
         .map_err(|e| anyhow::anyhow!("Failed to create AudioRecorder: {}", e))?
-        .with_vad(Box::new(smoothed_vad))
+        .with_vad(create_vad(vad_path, &settings))
+        .with_processing(audio_processing_config(&settings))
+        .with_recent_audio(settings.recent_audio_secs())
         .with_level_callback({
             let app_handle = app_handle.clone();
             move |levels| {
                 utils::emit_levels(&app_handle, &levels);
             }
+        })
+        .with_activity_callback({
+            let app_handle = app_handle.clone();
+            move |frame_was_speech| {
+                if let Some(rm) = app_handle.try_state::<Arc<AudioRecordingManager>>() {
+                    rm.observe_vad_activity(frame_was_speech);
+                }
+            }
+        })
+        .with_segment_callback({
+            let app_handle = app_handle.clone();
+            move |segment| {
+                // Forward to the partial-transcript worker; a no-op unless a streaming
+                // session is active for the current recording.
+                if let Some(tm) = app_handle.try_state::<Arc<TranscriptionManager>>() {
+                    tm.submit_partial_segment(segment);
+                }
+            }
+        })
+        .with_chunk_callback({
+            let app_handle = app_handle.clone();
+            move |chunk| {
+                // Only cut while a long-form session is running; see `set_long_form_chunking`.
+                if let Some(tm) = app_handle.try_state::<Arc<TranscriptionManager>>() {
+                    tm.submit_long_form_chunk(chunk);
+                }
+            }
         });
 
     Ok(recorder)
@@ -916,6 +1028,12 @@ fn create_audio_recorder(
 
 /* ──────────────────────────────────────────────────────────────── */
 
+/// Trailing-silence watch for the current hands-free recording.
+struct ArmedSilenceStop {
+    binding_id: String,
+    detector: TrailingSilenceDetector,
+}
+
 #[derive(Clone)]
 pub struct AudioRecordingManager {
     state: Arc<Mutex<RecordingState>>,
@@ -935,6 +1053,8 @@ pub struct AudioRecordingManager {
     recording_start_time: Arc<Mutex<Option<Instant>>>,
     /// Channel to stop the time tracking timer
     timer_stop_tx: Arc<Mutex<Option<mpsc::Sender<()>>>>,
+    /// Armed while a recording runs with hands-free auto-stop enabled.
+    silence_stop: Arc<Mutex<Option<ArmedSilenceStop>>>,
     
     /// The name of the device currently opened in the stream (for logging)
     current_device_name: Arc<Mutex<Option<String>>>,
@@ -982,6 +1102,7 @@ impl AudioRecordingManager {
             has_recorded_before: Arc::new(Mutex::new(false)),
             recording_start_time: Arc::new(Mutex::new(None)),
             timer_stop_tx: Arc::new(Mutex::new(None)),
+            silence_stop: Arc::new(Mutex::new(None)),
             current_device_name: Arc::new(Mutex::new(None)),
             active_stream_state: Arc::new(Mutex::new(None)),
             device_cache: Arc::new(Mutex::new(None)),
@@ -1877,6 +1998,89 @@ impl AudioRecordingManager {
 
     /* ---------- microphone life-cycle -------------------------------------- */
 
+    /// Rebuilds the voice activity detector from current settings for an
+    /// already created recorder; a recorder created later picks the settings
+    /// up itself.
+    pub fn apply_vad_settings(&self) -> Result<(), anyhow::Error> {
+        let vad_path = resolve_vad_path(&self.app_handle)?;
+        let vad = create_vad(
+            vad_path.to_str().unwrap(),
+            &get_settings(&self.app_handle),
+        );
+        if let Some(recorder) = self.recorder.lock().unwrap().as_mut() {
+            recorder.set_vad(vad);
+        }
+        Ok(())
+    }
+
+    /// Applies the recent-audio buffer settings to the recorder and refreshes
+    /// the tray and overlay indicators that show the microphone is kept open.
+    pub fn apply_recent_audio_settings(&self) {
+        let secs = get_settings(&self.app_handle).recent_audio_secs();
+        if let Some(recorder) = self.recorder.lock().unwrap().as_ref() {
+            recorder.set_recent_audio(secs);
+        }
+        info!(
+            secs,
+            event_code = "recent_audio_buffer_applied",
+            "Recent audio buffer {}",
+            if secs > 0 { "enabled" } else { "disabled" }
+        );
+
+        utils::emit_recent_audio_buffer(&self.app_handle, secs);
+        if matches!(*self.state.lock().unwrap(), RecordingState::Idle) {
+            crate::tray::change_tray_icon(&self.app_handle, crate::tray::TrayIconState::Idle);
+        }
+    }
+
+    /// Takes the speech held by the recent-audio buffer, to transcribe what
+    /// was said before any shortcut was pressed. `None` while recording, when
+    /// the buffer is off or when it holds no speech.
+    pub fn take_recent_audio(&self) -> Option<StoppedRecording> {
+        if !matches!(*self.state.lock().unwrap(), RecordingState::Idle) {
+            debug!("Recent audio not taken: a recording is in progress");
+            return None;
+        }
+        let buffered_secs = get_settings(&self.app_handle).recent_audio_secs();
+        if buffered_secs == 0 || !*self.is_open.lock().unwrap() {
+            debug!("Recent audio not taken: buffer is off");
+            return None;
+        }
+
+        let take_wait = match self
+            .recorder
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(AudioRecorder::begin_take_recent_audio)
+        {
+            Some(Ok(wait)) => wait,
+            Some(Err(err)) => {
+                warn!("Failed to request recent audio from recorder: {err}");
+                return None;
+            }
+            None => return None,
+        };
+        let samples = match take_wait.wait() {
+            Ok(samples) => samples,
+            Err(err) => {
+                warn!("Recent audio request failed: {err}");
+                return None;
+            }
+        };
+
+        info!(
+            buffered_secs,
+            speech_ms = (samples.len() * 1000 / WHISPER_SAMPLE_RATE) as u64,
+            event_code = "recent_audio_taken",
+            "Recent audio taken from buffer"
+        );
+        if samples.is_empty() {
+            return None;
+        }
+        Some(build_stopped_recording(samples, 0))
+    }
+
     /// Applies mute if mute_while_recording is enabled and stream is open
     pub fn apply_mute(&self) {
         let settings = get_settings(&self.app_handle);
@@ -2173,14 +2377,7 @@ impl AudioRecordingManager {
             matches!(context, StreamOpenContext::UserTriggered) && !used_cached_devices,
         );
 
-        let vad_path = self
-            .app_handle
-            .path()
-            .resolve(
-                "resources/models/silero_vad_v4.onnx",
-                tauri::path::BaseDirectory::Resource,
-            )
-            .map_err(|e| anyhow::anyhow!("Failed to resolve VAD path: {}", e))?;
+        let vad_path = resolve_vad_path(&self.app_handle)?;
 
         enum StreamOpenAttempt {
             Opened(u64),
@@ -2217,6 +2414,7 @@ impl AudioRecordingManager {
                         }
 
                         if let Some(rec) = recorder_opt.as_mut() {
+                            rec.set_capture_input(capture_input(&settings));
                             rec.open(device_to_open.clone()).map_err(|e| e.to_string())
                         } else {
                             Err("Audio recorder unavailable after initialization".to_string())
@@ -2489,6 +2687,7 @@ impl AudioRecordingManager {
         }
 
         *self.mode.lock().unwrap() = new_mode;
+        self.apply_recent_audio_settings();
         Ok(())
     }
 
@@ -2776,11 +2975,14 @@ impl AudioRecordingManager {
             "Dispatching recorder start command"
         );
         let start_dispatch_started = Instant::now();
+        // Settings may have changed since the stream was opened (always-on mic).
+        let processing_config = audio_processing_config(&get_settings(&self.app_handle));
         let start_wait: Option<Result<RecorderStartWait, RecorderStartError>> = {
             let recorder_guard = self.recorder.lock().unwrap();
-            recorder_guard
-                .as_ref()
-                .map(|rec| rec.begin_start_blocking())
+            recorder_guard.as_ref().map(|rec| {
+                rec.set_processing(processing_config);
+                rec.begin_start_blocking()
+            })
         };
         let Some(start_wait) = start_wait else {
             error!(
@@ -2924,14 +3126,25 @@ impl AudioRecordingManager {
                     );
                 }
 
+                let long_form = self
+                    .app_handle
+                    .try_state::<Arc<TranscriptionManager>>()
+                    .is_some_and(|tm| tm.begin_long_form_session(session_id));
+                self.set_long_form_chunking(long_form);
+
                 // Start recording timer
                 let start_time = Instant::now();
                 *self.recording_start_time.lock().unwrap() = Some(start_time);
-                self.start_recording_timer(binding_id.to_string());
+                self.start_recording_timer(binding_id.to_string(), long_form);
+                self.arm_silence_stop(binding_id);
 
                 // Mark that we've successfully recorded (for first-trigger detection)
                 self.mark_recording_started();
 
+                if let Some(tm) = self.app_handle.try_state::<Arc<TranscriptionManager>>() {
+                    tm.begin_partial_session(session_id);
+                }
+
                 info!(
                     session = session_id,
                     binding = binding_id,
@@ -3018,8 +3231,9 @@ impl AudioRecordingManager {
         }
     }
     
-    /// Start a timer thread that emits recording time updates every second
-    fn start_recording_timer(&self, binding_id: String) {
+    /// Start a timer thread that emits recording time updates every second.
+    /// Long-form recordings have no limit, so they are never auto-stopped.
+    fn start_recording_timer(&self, binding_id: String, long_form: bool) {
         use crate::actions::ACTION_MAP;
         use crate::i18n;
         
@@ -3027,7 +3241,11 @@ impl AudioRecordingManager {
         *self.timer_stop_tx.lock().unwrap() = Some(stop_tx);
         
         let app_handle = self.app_handle.clone();
-        let max_secs = utils::get_recording_limit_seconds();
+        let max_secs = if long_form {
+            0
+        } else {
+            utils::get_recording_limit_seconds()
+        };
         let is_recording = self.is_recording.clone();
         let recording_start_time = self.recording_start_time.clone();
         let timer_stop_tx = self.timer_stop_tx.clone();
@@ -3066,7 +3284,7 @@ impl AudioRecordingManager {
                     
                     // Check for 30s warning - use native notification
                     let remaining = max_secs.saturating_sub(elapsed);
-                    if remaining <= 30 && remaining > 0 && !warned_at_30s {
+                    if max_secs > 0 && remaining <= 30 && remaining > 0 && !warned_at_30s {
                         // Use centralized notification module
                         crate::notification::show_info_with_text(&app_handle, &warning_message);
                         
@@ -3075,7 +3293,7 @@ impl AudioRecordingManager {
                     }
                     
                     // Check for auto-stop at limit
-                    if elapsed >= max_secs {
+                    if max_secs > 0 && elapsed >= max_secs {
                         info!("Recording limit reached ({}s), auto-stopping", max_secs);
                         
                         // Clean up timer state before triggering stop to avoid double-call
@@ -3113,6 +3331,54 @@ impl AudioRecordingManager {
         });
     }
     
+    /// Enable or disable chunk cuts for the current recording. Must match
+    /// whether a long-form session is running, or cut audio would be lost.
+    fn set_long_form_chunking(&self, enabled: bool) {
+        if let Some(rec) = self.recorder.lock().unwrap().as_ref() {
+            rec.set_chunking(enabled.then(LongFormChunker::default));
+        }
+    }
+
+    fn arm_silence_stop(&self, binding_id: &str) {
+        let settings = get_settings(&self.app_handle);
+        let armed = settings.auto_stop_on_silence.then(|| ArmedSilenceStop {
+            binding_id: binding_id.to_string(),
+            detector: TrailingSilenceDetector::new(
+                settings.auto_stop_silence_ms,
+                settings.auto_stop_min_speech_ms,
+            ),
+        });
+        *self.silence_stop.lock().unwrap() = armed;
+    }
+
+    fn disarm_silence_stop(&self) {
+        *self.silence_stop.lock().unwrap() = None;
+    }
+
+    /// Called by the recorder for every recorded frame. Once hands-free
+    /// trailing silence is detected, the stop is routed through the
+    /// coordinator so the usual transcribe/paste flow runs.
+    pub fn observe_vad_activity(&self, frame_was_speech: bool) {
+        let binding_id = {
+            let mut armed = self.silence_stop.lock().unwrap();
+            let fired = armed
+                .as_mut()
+                .is_some_and(|watch| watch.detector.observe(frame_was_speech));
+            if fired {
+                armed.take().map(|watch| watch.binding_id)
+            } else {
+                None
+            }
+        };
+
+        if let Some(binding_id) = binding_id {
+            info!("Trailing silence detected, auto-stopping '{}'", binding_id);
+            if let Some(coordinator) = self.app_handle.try_state::<TranscriptionCoordinator>() {
+                coordinator.request_auto_stop(&binding_id);
+            }
+        }
+    }
+
     /// Stop the recording timer if running
     fn stop_recording_timer(&self) {
         if let Some(tx) = self.timer_stop_tx.lock().unwrap().take() {
@@ -3168,6 +3434,7 @@ impl AudioRecordingManager {
                 
                 // Stop the recording timer
                 self.stop_recording_timer();
+                self.disarm_silence_stop();
 
                 let samples = match begin_recorder_stop_wait(&self.recorder) {
                     Ok(Some(stop_wait)) => {
@@ -3188,6 +3455,25 @@ impl AudioRecordingManager {
                         Vec::new()
                     }
                 };
+                let source_channels = self
+                    .recorder
+                    .lock()
+                    .unwrap()
+                    .as_ref()
+                    .and_then(AudioRecorder::take_source_channels);
+                let captured = self
+                    .recorder
+                    .lock()
+                    .unwrap()
+                    .as_ref()
+                    .and_then(AudioRecorder::take_captured_audio);
+
+                self.end_partial_session();
+                self.set_long_form_chunking(false);
+                let long_form_speech_ms = self
+                    .app_handle
+                    .try_state::<Arc<TranscriptionManager>>()
+                    .and_then(|tm| tm.long_form_speech_ms());
 
                 *self.is_recording.lock().unwrap() = false;
 
@@ -3201,11 +3487,21 @@ impl AudioRecordingManager {
                 
                 // Check for 0 samples - this likely means the audio stream died (e.g. device disconnected)
                 // User will see no audio movement in the visualizer and can switch manually
-                if speech_sample_count == 0 {
+                if speech_sample_count == 0 && long_form_speech_ms.is_none() {
                     warn!("Recording yielded 0 samples - device may have stopped working. User should check audio visualizer and switch microphone if needed.");
                 }
 
-                Some(build_stopped_recording(samples, recording_duration_ms))
+                let mut stopped = build_stopped_recording(samples, recording_duration_ms);
+                stopped.source_channels = source_channels;
+                stopped.captured = captured;
+                // Long-form chunks handed off while recording count as speech too.
+                if let Some(spooled_ms) = long_form_speech_ms {
+                    stopped.speech_duration_ms += spooled_ms;
+                    stopped.recording_duration_ms = stopped
+                        .recording_duration_ms
+                        .max(stopped.speech_duration_ms);
+                }
+                Some(stopped)
             }
             _ => {
                 // Idle or other binding active
@@ -3217,6 +3513,45 @@ impl AudioRecordingManager {
     pub fn is_recording(&self) -> bool {
         *self.is_recording.lock().unwrap()
     }
+
+    /// Record a quiet and a spoken sample from the microphone recordings would
+    /// use and report on them. Opens its own stream next to any open one.
+    pub fn run_microphone_diagnostics(
+        &self,
+        silence_duration: Duration,
+        speech_duration: Duration,
+        on_phase: impl FnMut(DiagnosticPhase),
+    ) -> Result<MicrophoneDiagnostics, anyhow::Error> {
+        if self.is_recording() {
+            return Err(anyhow::anyhow!(
+                "Stop recording before running microphone diagnostics"
+            ));
+        }
+
+        let settings = get_settings(&self.app_handle);
+        let devices = list_input_devices()
+            .map_err(|e| anyhow::anyhow!("Failed to list audio devices: {}", e))?;
+        let (device, device_name) = self
+            .get_effective_device_from_list(&settings, devices)
+            .ok_or_else(|| anyhow::anyhow!("No microphone is available"))?;
+        let vad_path = resolve_vad_path(&self.app_handle)?;
+        let mut vad = create_vad(&vad_path.to_string_lossy(), &settings);
+
+        info!(
+            device = %device_name,
+            event_code = "mic_diagnostics_start",
+            "Running microphone diagnostics"
+        );
+        mic_diagnostics::run(
+            &device,
+            &device_name,
+            vad.as_mut(),
+            &settings,
+            silence_duration,
+            speech_duration,
+            on_phase,
+        )
+    }
     
     pub fn get_active_binding_id(&self) -> Option<String> {
         match &*self.state.lock().unwrap() {
@@ -3237,8 +3572,12 @@ impl AudioRecordingManager {
         }
     }
 
-
-
+    /// Stop streaming partials once the recorder has flushed its last segment.
+    fn end_partial_session(&self) {
+        if let Some(tm) = self.app_handle.try_state::<Arc<TranscriptionManager>>() {
+            tm.end_partial_session();
+        }
+    }
 
     /// Cancel any ongoing recording without returning audio samples
     pub fn cancel_recording(&self) {
@@ -3256,6 +3595,7 @@ impl AudioRecordingManager {
                 
                 // Stop the recording timer
                 self.stop_recording_timer();
+                self.disarm_silence_stop();
 
                 match begin_recorder_stop_wait(&self.recorder) {
                     Ok(Some(stop_wait)) => {
@@ -3267,6 +3607,12 @@ impl AudioRecordingManager {
                     Err(err) => warn!("Failed to dispatch recorder stop during cancellation: {err}"),
                 }
 
+                self.end_partial_session();
+                self.set_long_form_chunking(false);
+                if let Some(tm) = self.app_handle.try_state::<Arc<TranscriptionManager>>() {
+                    tm.cancel_long_form_session();
+                }
+
                 *self.is_recording.lock().unwrap() = false;
 
                 // In on-demand mode turn the mic off again
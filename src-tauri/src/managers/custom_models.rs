@@ -0,0 +1,473 @@
+//! User-provided model directories for every engine type.
+//!
+//! A custom model is a directory in the models folder holding the engine's
+//! files plus a `codictate-model.json` sidecar naming the engine, e.g.
+//! `{"engine_type": "Parakeet", "name": "Parakeet (medical)", "languages": ["en"]}`.
+//! Moonshine models also name their size with `"variant": "tiny"` or `"base"`.
+//! Layouts are checked against what each engine loads before the model is
+//! offered; a directory that fails the check is still listed, with the reason in
+//! `ModelInfo::load_error`, so users can see why their model isn't usable.
+
+use super::model::{custom_model_display_name, EngineType, ModelInfo};
+use anyhow::{anyhow, bail, Context, Result};
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+/// Sidecar that marks a directory in the models folder as a custom model.
+pub const CUSTOM_MODEL_METADATA_FILE_NAME: &str = "codictate-model.json";
+
+/// Staging directories written next to models while an extraction or bundle
+/// import is in progress.
+const STAGING_SUFFIXES: &[&str] = &[".extracting", ".importing"];
+
+const PARAKEET_FILES: &[&str] = &[
+    "encoder-model.int8.onnx",
+    "decoder_joint-model.int8.onnx",
+    "nemo128.onnx",
+    "vocab.txt",
+];
+const MOONSHINE_FILES: &[&str] = &[
+    "encoder_model.onnx",
+    "decoder_model_merged.onnx",
+    "tokenizer.json",
+];
+const SENSE_VOICE_FILES: &[&str] = &["model.int8.onnx", "tokens.txt"];
+
+/// Size of a Moonshine model, which decides the decoder layout it loads with.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum MoonshineVariant {
+    Tiny,
+    #[default]
+    Base,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CustomModelMetadata {
+    engine_type: EngineType,
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    languages: Vec<String>,
+    #[serde(default)]
+    supports_translation: bool,
+    /// Moonshine only; `base` when omitted.
+    #[serde(default)]
+    variant: Option<MoonshineVariant>,
+}
+
+fn read_metadata(model_dir: &Path) -> Result<CustomModelMetadata> {
+    let path = model_dir.join(CUSTOM_MODEL_METADATA_FILE_NAME);
+    let raw = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", CUSTOM_MODEL_METADATA_FILE_NAME))?;
+    let metadata: CustomModelMetadata = serde_json::from_str(&raw)
+        .map_err(|e| anyhow!("Invalid {}: {}", CUSTOM_MODEL_METADATA_FILE_NAME, e))?;
+
+    if metadata
+        .name
+        .as_deref()
+        .is_some_and(|name| name.trim().is_empty())
+    {
+        bail!("Invalid {}: name is empty", CUSTOM_MODEL_METADATA_FILE_NAME);
+    }
+    if metadata.languages.iter().any(|code| code.trim().is_empty()) {
+        bail!(
+            "Invalid {}: languages contains an empty code",
+            CUSTOM_MODEL_METADATA_FILE_NAME
+        );
+    }
+    if metadata.supports_translation && !matches!(metadata.engine_type, EngineType::Whisper) {
+        bail!(
+            "Invalid {}: only Whisper models support translation",
+            CUSTOM_MODEL_METADATA_FILE_NAME
+        );
+    }
+    if metadata.variant.is_some() && !matches!(metadata.engine_type, EngineType::Moonshine) {
+        bail!(
+            "Invalid {}: only Moonshine models have a variant",
+            CUSTOM_MODEL_METADATA_FILE_NAME
+        );
+    }
+    Ok(metadata)
+}
+
+fn file_names(model_dir: &Path) -> Result<Vec<String>> {
+    let mut names = Vec::new();
+    for entry in fs::read_dir(model_dir)? {
+        let entry = entry?;
+        if entry.file_type()?.is_file() {
+            names.push(entry.file_name().to_string_lossy().to_string());
+        }
+    }
+    names.sort();
+    Ok(names)
+}
+
+/// The single `.<extension>` file of a one-file engine.
+fn single_model_file(model_dir: &Path, extension: &str) -> Result<PathBuf> {
+    let suffix = format!(".{}", extension);
+    let candidates: Vec<String> = file_names(model_dir)?
+        .into_iter()
+        .filter(|name| name.ends_with(&suffix) && !name.starts_with('.'))
+        .collect();
+    match candidates.as_slice() {
+        [name] => Ok(model_dir.join(name)),
+        [] => bail!("No {} model file found", suffix),
+        _ => bail!(
+            "Found several {} files ({}); keep only one",
+            suffix,
+            candidates.join(", ")
+        ),
+    }
+}
+
+fn require_files(model_dir: &Path, required: &[&str]) -> Result<PathBuf> {
+    let present: HashSet<String> = file_names(model_dir)?.into_iter().collect();
+    let missing: Vec<&str> = required
+        .iter()
+        .copied()
+        .filter(|name| !present.contains(*name))
+        .collect();
+    if !missing.is_empty() {
+        bail!("Missing model files: {}", missing.join(", "));
+    }
+    Ok(model_dir.to_path_buf())
+}
+
+/// Checks `model_dir` against what `engine_type` loads and returns the path to
+/// hand to the engine: the model file for single-file engines, else the
+/// directory itself.
+fn engine_entry(model_dir: &Path, engine_type: &EngineType) -> Result<PathBuf> {
+    match engine_type {
+        EngineType::Whisper => single_model_file(model_dir, "bin"),
+        EngineType::GigaAM => single_model_file(model_dir, "onnx"),
+        EngineType::Parakeet => require_files(model_dir, PARAKEET_FILES),
+        EngineType::Moonshine => require_files(model_dir, MOONSHINE_FILES),
+        EngineType::SenseVoice => require_files(model_dir, SENSE_VOICE_FILES),
+        EngineType::MoonshineStreaming => {
+            let has_model = file_names(model_dir)?
+                .iter()
+                .any(|name| name.ends_with(".onnx") || name.ends_with(".ort"));
+            if !has_model {
+                bail!("No .onnx or .ort model files found");
+            }
+            Ok(model_dir.to_path_buf())
+        }
+    }
+}
+
+/// Re-validates a custom model directory and returns the path its engine loads.
+pub(crate) fn engine_model_path(model_dir: &Path) -> Result<PathBuf> {
+    let metadata = read_metadata(model_dir)?;
+    engine_entry(model_dir, &metadata.engine_type)
+}
+
+/// The variant to load the Moonshine model in `model_dir` with: the one its
+/// sidecar names, else `Base` (as for catalog models, which have no sidecar).
+pub(crate) fn moonshine_variant(model_dir: &Path) -> Result<MoonshineVariant> {
+    if !model_dir.join(CUSTOM_MODEL_METADATA_FILE_NAME).is_file() {
+        return Ok(MoonshineVariant::Base);
+    }
+    Ok(read_metadata(model_dir)?.variant.unwrap_or_default())
+}
+
+fn directory_size_mb(model_dir: &Path) -> u64 {
+    let mut total = 0;
+    let mut pending = vec![model_dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            match entry.metadata() {
+                Ok(meta) if meta.is_dir() => pending.push(entry.path()),
+                Ok(meta) => total += meta.len(),
+                Err(_) => {}
+            }
+        }
+    }
+    total / (1024 * 1024)
+}
+
+fn custom_model_info(model_id: &str, model_dir: &Path) -> ModelInfo {
+    let (metadata, load_error) = match read_metadata(model_dir) {
+        Ok(metadata) => {
+            let load_error = engine_entry(model_dir, &metadata.engine_type)
+                .err()
+                .map(|e| e.to_string());
+            (Some(metadata), load_error)
+        }
+        Err(e) => (None, Some(e.to_string())),
+    };
+
+    let engine_type = metadata
+        .as_ref()
+        .map(|metadata| metadata.engine_type.clone())
+        .unwrap_or(EngineType::Whisper);
+    let name = metadata
+        .as_ref()
+        .and_then(|metadata| metadata.name.as_deref())
+        .map(|name| name.trim().to_string())
+        .unwrap_or_else(|| custom_model_display_name(model_id));
+
+    ModelInfo {
+        id: model_id.to_string(),
+        name,
+        description: metadata
+            .as_ref()
+            .and_then(|metadata| metadata.description.clone())
+            .unwrap_or_else(|| "Not officially supported".to_string()),
+        filename: model_id.to_string(),
+        url: None, // Custom models have no download URL
+        mirrors: vec![],
+        sha256: None,
+        size_mb: directory_size_mb(model_dir),
+        is_downloaded: true, // Already present on disk
+        is_downloading: false,
+        partial_size: 0,
+        is_directory: true,
+        engine_type,
+        accuracy_score: 0.0, // Sentinel: UI hides score bars when both are 0
+        speed_score: 0.0,
+        supports_translation: metadata
+            .as_ref()
+            .is_some_and(|metadata| metadata.supports_translation),
+        is_recommended: false,
+        supported_languages: metadata
+            .map(|metadata| metadata.languages)
+            .unwrap_or_default(),
+        is_custom: true,
+        load_error,
+    }
+}
+
+/// Discover custom model directories (those with a metadata sidecar) in the
+/// models directory. Directories of catalog models and staging directories are
+/// skipped; a directory whose layout doesn't validate is listed with a
+/// `load_error`.
+pub(crate) fn discover_custom_model_dirs(
+    models_dir: &Path,
+    available_models: &mut HashMap<String, ModelInfo>,
+) -> Result<()> {
+    if !models_dir.exists() {
+        return Ok(());
+    }
+
+    let catalog_dirs: HashSet<String> = available_models
+        .values()
+        .filter(|m| m.is_directory)
+        .map(|m| m.filename.clone())
+        .collect();
+
+    for entry in fs::read_dir(models_dir)? {
+        let entry = match entry {
+            Ok(e) => e,
+            Err(e) => {
+                warn!("Failed to read directory entry: {}", e);
+                continue;
+            }
+        };
+
+        let path = entry.path();
+        if !path.is_dir() || !path.join(CUSTOM_MODEL_METADATA_FILE_NAME).is_file() {
+            continue;
+        }
+
+        let dirname = match path.file_name().and_then(|s| s.to_str()) {
+            Some(name) => name.to_string(),
+            None => continue,
+        };
+        if dirname.starts_with('.')
+            || STAGING_SUFFIXES
+                .iter()
+                .any(|suffix| dirname.ends_with(suffix))
+            || catalog_dirs.contains(&dirname)
+        {
+            continue;
+        }
+
+        if available_models.contains_key(&dirname) {
+            warn!(
+                "Skipping custom model directory {}: a model with that id already exists",
+                dirname
+            );
+            continue;
+        }
+
+        let model = custom_model_info(&dirname, &path);
+        match &model.load_error {
+            Some(error) => warn!("Custom model {} is not usable: {}", dirname, error),
+            None => info!(
+                "Discovered custom {:?} model: {} ({} MB)",
+                model.engine_type, dirname, model.size_mb
+            ),
+        }
+        available_models.insert(dirname, model);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write_model(models_dir: &Path, dirname: &str, metadata: &str, files: &[&str]) -> PathBuf {
+        let dir = models_dir.join(dirname);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join(CUSTOM_MODEL_METADATA_FILE_NAME), metadata).unwrap();
+        for file in files {
+            fs::write(dir.join(file), b"weights").unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn discovers_valid_directories_for_each_engine() {
+        let temp_dir = TempDir::new().unwrap();
+        let models_dir = temp_dir.path();
+        write_model(
+            models_dir,
+            "parakeet-medical",
+            r#"{"engine_type": "Parakeet", "name": "Parakeet Medical", "languages": ["en"]}"#,
+            PARAKEET_FILES,
+        );
+        write_model(
+            models_dir,
+            "sense-voice-ft",
+            r#"{"engine_type": "SenseVoice", "languages": ["zh", "en"]}"#,
+            SENSE_VOICE_FILES,
+        );
+        write_model(
+            models_dir,
+            "whisper-ft",
+            r#"{"engine_type": "Whisper", "supports_translation": true}"#,
+            &["ggml-model.bin", "README.md"],
+        );
+
+        let mut models = HashMap::new();
+        discover_custom_model_dirs(models_dir, &mut models).unwrap();
+        assert_eq!(models.len(), 3);
+
+        let parakeet = &models["parakeet-medical"];
+        assert!(parakeet.is_custom && parakeet.is_directory);
+        assert!(matches!(parakeet.engine_type, EngineType::Parakeet));
+        assert_eq!(parakeet.name, "Parakeet Medical");
+        assert_eq!(parakeet.supported_languages, vec!["en"]);
+        assert_eq!(parakeet.load_error, None);
+
+        assert_eq!(models["sense-voice-ft"].name, "Sense Voice Ft");
+        assert!(models["whisper-ft"].supports_translation);
+        assert_eq!(
+            engine_model_path(&models_dir.join("whisper-ft")).unwrap(),
+            models_dir.join("whisper-ft/ggml-model.bin")
+        );
+        assert_eq!(
+            engine_model_path(&models_dir.join("parakeet-medical")).unwrap(),
+            models_dir.join("parakeet-medical")
+        );
+    }
+
+    #[test]
+    fn invalid_directories_are_listed_with_load_error() {
+        let temp_dir = TempDir::new().unwrap();
+        let models_dir = temp_dir.path();
+        write_model(
+            models_dir,
+            "moonshine-partial",
+            r#"{"engine_type": "Moonshine"}"#,
+            &["encoder_model.onnx"],
+        );
+        write_model(models_dir, "broken-json", "{ not json", &[]);
+        write_model(
+            models_dir,
+            "gigaam-two",
+            r#"{"engine_type": "GigaAM"}"#,
+            &["a.onnx", "b.onnx"],
+        );
+        write_model(
+            models_dir,
+            "parakeet-translate",
+            r#"{"engine_type": "Parakeet", "supports_translation": true}"#,
+            PARAKEET_FILES,
+        );
+        write_model(
+            models_dir,
+            "moonshine-huge",
+            r#"{"engine_type": "Moonshine", "variant": "huge"}"#,
+            MOONSHINE_FILES,
+        );
+        write_model(
+            models_dir,
+            "parakeet-tiny",
+            r#"{"engine_type": "Parakeet", "variant": "tiny"}"#,
+            PARAKEET_FILES,
+        );
+
+        let mut models = HashMap::new();
+        discover_custom_model_dirs(models_dir, &mut models).unwrap();
+        assert_eq!(models.len(), 6);
+
+        let error = |id: &str| models[id].load_error.clone().unwrap();
+        assert_eq!(
+            error("moonshine-partial"),
+            "Missing model files: decoder_model_merged.onnx, tokenizer.json"
+        );
+        assert!(error("broken-json").starts_with("Invalid codictate-model.json"));
+        assert!(error("gigaam-two").contains("keep only one"));
+        assert!(error("parakeet-translate").contains("only Whisper"));
+        assert!(error("moonshine-huge").contains("unknown variant"));
+        assert!(error("parakeet-tiny").contains("only Moonshine"));
+        assert!(engine_model_path(&models_dir.join("moonshine-partial")).is_err());
+    }
+
+    #[test]
+    fn moonshine_variant_comes_from_the_sidecar() {
+        let temp_dir = TempDir::new().unwrap();
+        let models_dir = temp_dir.path();
+        let tiny = write_model(
+            models_dir,
+            "moonshine-tiny-ft",
+            r#"{"engine_type": "Moonshine", "variant": "tiny"}"#,
+            MOONSHINE_FILES,
+        );
+        let unnamed = write_model(
+            models_dir,
+            "moonshine-ft",
+            r#"{"engine_type": "Moonshine"}"#,
+            MOONSHINE_FILES,
+        );
+        let catalog = models_dir.join("moonshine-base");
+        fs::create_dir_all(&catalog).unwrap();
+
+        assert_eq!(moonshine_variant(&tiny).unwrap(), MoonshineVariant::Tiny);
+        assert_eq!(moonshine_variant(&unnamed).unwrap(), MoonshineVariant::Base);
+        assert_eq!(moonshine_variant(&catalog).unwrap(), MoonshineVariant::Base);
+    }
+
+    #[test]
+    fn skips_catalog_staging_and_unmarked_directories() {
+        let temp_dir = TempDir::new().unwrap();
+        let models_dir = temp_dir.path();
+        let metadata = r#"{"engine_type": "SenseVoice"}"#;
+        write_model(models_dir, "sense-voice-int8", metadata, SENSE_VOICE_FILES);
+        write_model(models_dir, "custom.importing", metadata, SENSE_VOICE_FILES);
+        write_model(models_dir, ".hidden", metadata, SENSE_VOICE_FILES);
+        fs::create_dir_all(models_dir.join("no-sidecar")).unwrap();
+
+        let mut catalog_model = custom_model_info("sense-voice-int8", Path::new("/nonexistent"));
+        catalog_model.is_custom = false;
+        let mut models = HashMap::from([("sense-voice-int8".to_string(), catalog_model)]);
+        discover_custom_model_dirs(models_dir, &mut models).unwrap();
+
+        assert_eq!(models.len(), 1);
+        assert!(!models["sense-voice-int8"].is_custom);
+    }
+}
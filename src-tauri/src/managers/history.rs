@@ -7,9 +7,19 @@ use serde::{Deserialize, Serialize};
 use specta::Type;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use tauri::{AppHandle, Emitter, Manager};
 
-use crate::audio_toolkit::save_wav_file;
+use crate::audio_toolkit::{
+    concat_recording_files, convert_recording_file, save_flac_file, save_wav_file,
+};
+use crate::history_export::HistoryExportFilter;
+use crate::managers::long_form::SpooledRecording;
+use crate::settings::RecordingFormat;
+use crate::word_diff::{diff_words, WordDiffSpan};
+
+const RECORDING_MIGRATION_EVENT: &str = "recording-migration-progress";
 
 /// Database migrations for transcription history.
 /// Each migration is applied in order. The library tracks which migrations
@@ -65,8 +75,313 @@ static MIGRATIONS: &[M] = &[
     M::up("ALTER TABLE user_stats ADD COLUMN restored_streak_days INTEGER DEFAULT 0;"),
     M::up("ALTER TABLE user_stats ADD COLUMN restored_streak_counted_through_date TEXT;"),
     M::up("ALTER TABLE user_stats ADD COLUMN restored_streak_restore_date TEXT;"),
+    // Migration 14: Engine segment/word timings per history entry
+    M::up(TRANSCRIPTION_TIMINGS_SCHEMA),
+    // Migration 15: FTS5 index over effective and raw text, kept in sync by triggers
+    M::up(TRANSCRIPTION_HISTORY_FTS_SCHEMA),
+    // Migration 16: User-defined tags and notebooks for history entries
+    M::up(HISTORY_TAGS_SCHEMA),
+    // Migration 17: Alternate transcripts produced by re-running other models
+    M::up(TRANSCRIPTION_REVISIONS_SCHEMA),
+    // Migration 18: Diarized speaker turns and user-given speaker names
+    M::up(HISTORY_SPEAKERS_SCHEMA),
+    // Migration 19: Dictionary terms hinted to the engine per entry
+    M::up(HISTORY_BIAS_TERMS_SCHEMA),
+    // Migration 20: Dictionary terms hinted to the engine per revision
+    M::up(REVISION_BIAS_TERMS_SCHEMA),
 ];
 
+/// Child table of `transcription_history` holding engine timings.
+///
+/// Rows are removed by trigger rather than `ON DELETE CASCADE` because connections
+/// are opened without `PRAGMA foreign_keys`. Shared with the restore path, so every
+/// statement must stay idempotent.
+pub(crate) const TRANSCRIPTION_TIMINGS_SCHEMA: &str =
+    "CREATE TABLE IF NOT EXISTS transcription_timings (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        history_id INTEGER NOT NULL,
+        position INTEGER NOT NULL,
+        granularity TEXT NOT NULL,
+        start_ms INTEGER NOT NULL,
+        end_ms INTEGER NOT NULL,
+        text TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS idx_transcription_timings_history
+        ON transcription_timings (history_id, position);
+    CREATE TRIGGER IF NOT EXISTS transcription_timings_delete_with_history
+        AFTER DELETE ON transcription_history
+    BEGIN
+        DELETE FROM transcription_timings WHERE history_id = OLD.id;
+    END;";
+
+/// Full-text index of `transcription_history`, keyed by history id.
+///
+/// Indexes the effective text (inserted, else post-processed, else raw) and the
+/// raw transcript with Porter stemming. Like the timings schema this is shared
+/// with legacy reconciliation and restore, so it must stay idempotent; the final
+/// statement backfills rows that predate the triggers.
+pub(crate) const TRANSCRIPTION_HISTORY_FTS_SCHEMA: &str =
+    "CREATE VIRTUAL TABLE IF NOT EXISTS transcription_history_fts USING fts5(
+        effective_text,
+        raw_text,
+        tokenize = 'porter unicode61 remove_diacritics 2'
+    );
+    CREATE TRIGGER IF NOT EXISTS transcription_history_fts_insert
+        AFTER INSERT ON transcription_history
+    BEGIN
+        INSERT INTO transcription_history_fts (rowid, effective_text, raw_text)
+        VALUES (
+            NEW.id,
+            COALESCE(NEW.inserted_text, NEW.post_processed_text, NEW.transcription_text),
+            NEW.transcription_text
+        );
+    END;
+    CREATE TRIGGER IF NOT EXISTS transcription_history_fts_update
+        AFTER UPDATE OF transcription_text, post_processed_text, inserted_text
+        ON transcription_history
+    BEGIN
+        DELETE FROM transcription_history_fts WHERE rowid = OLD.id;
+        INSERT INTO transcription_history_fts (rowid, effective_text, raw_text)
+        VALUES (
+            NEW.id,
+            COALESCE(NEW.inserted_text, NEW.post_processed_text, NEW.transcription_text),
+            NEW.transcription_text
+        );
+    END;
+    CREATE TRIGGER IF NOT EXISTS transcription_history_fts_delete
+        AFTER DELETE ON transcription_history
+    BEGIN
+        DELETE FROM transcription_history_fts WHERE rowid = OLD.id;
+    END;
+    INSERT INTO transcription_history_fts (rowid, effective_text, raw_text)
+    SELECT
+        id,
+        COALESCE(inserted_text, post_processed_text, transcription_text),
+        transcription_text
+    FROM transcription_history
+    WHERE id NOT IN (SELECT rowid FROM transcription_history_fts);";
+
+/// Tags and notebooks plus their many-to-many link to history entries.
+///
+/// Links are removed by trigger when either side is deleted. Idempotent for the
+/// same reasons as [`TRANSCRIPTION_TIMINGS_SCHEMA`].
+pub(crate) const HISTORY_TAGS_SCHEMA: &str = "CREATE TABLE IF NOT EXISTS history_tags (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        name TEXT NOT NULL COLLATE NOCASE,
+        kind TEXT NOT NULL DEFAULT 'tag',
+        created_at INTEGER NOT NULL,
+        UNIQUE (kind, name)
+    );
+    CREATE TABLE IF NOT EXISTS history_entry_tags (
+        history_id INTEGER NOT NULL,
+        tag_id INTEGER NOT NULL,
+        PRIMARY KEY (history_id, tag_id)
+    );
+    CREATE INDEX IF NOT EXISTS idx_history_entry_tags_tag
+        ON history_entry_tags (tag_id);
+    CREATE TRIGGER IF NOT EXISTS history_entry_tags_delete_with_history
+        AFTER DELETE ON transcription_history
+    BEGIN
+        DELETE FROM history_entry_tags WHERE history_id = OLD.id;
+    END;
+    CREATE TRIGGER IF NOT EXISTS history_entry_tags_delete_with_tag
+        AFTER DELETE ON history_tags
+    BEGIN
+        DELETE FROM history_entry_tags WHERE tag_id = OLD.id;
+    END;";
+
+/// Alternate transcripts of an entry's recording made with another model.
+///
+/// Idempotent and trigger-cleaned for the same reasons as
+/// [`TRANSCRIPTION_TIMINGS_SCHEMA`].
+pub(crate) const TRANSCRIPTION_REVISIONS_SCHEMA: &str =
+    "CREATE TABLE IF NOT EXISTS transcription_revisions (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        history_id INTEGER NOT NULL,
+        created_at INTEGER NOT NULL,
+        model_id TEXT NOT NULL,
+        model_name TEXT NOT NULL,
+        transcription_text TEXT NOT NULL,
+        elapsed_ms INTEGER NOT NULL DEFAULT 0
+    );
+    CREATE INDEX IF NOT EXISTS idx_transcription_revisions_history
+        ON transcription_revisions (history_id, created_at);
+    CREATE TRIGGER IF NOT EXISTS transcription_revisions_delete_with_history
+        AFTER DELETE ON transcription_history
+    BEGIN
+        DELETE FROM transcription_revisions WHERE history_id = OLD.id;
+    END;";
+
+/// Diarized speaker turns of an entry, plus the names users gave its speakers.
+/// Speakers without a row in `history_speaker_names` show as "Speaker N".
+///
+/// Idempotent and trigger-cleaned for the same reasons as
+/// [`TRANSCRIPTION_TIMINGS_SCHEMA`].
+pub(crate) const HISTORY_SPEAKERS_SCHEMA: &str =
+    "CREATE TABLE IF NOT EXISTS history_speaker_turns (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        history_id INTEGER NOT NULL,
+        position INTEGER NOT NULL,
+        speaker INTEGER NOT NULL,
+        start_ms INTEGER NOT NULL,
+        end_ms INTEGER NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS idx_history_speaker_turns_history
+        ON history_speaker_turns (history_id, position);
+    CREATE TABLE IF NOT EXISTS history_speaker_names (
+        history_id INTEGER NOT NULL,
+        speaker INTEGER NOT NULL,
+        name TEXT NOT NULL,
+        PRIMARY KEY (history_id, speaker)
+    );
+    CREATE TRIGGER IF NOT EXISTS history_speakers_delete_with_history
+        AFTER DELETE ON transcription_history
+    BEGIN
+        DELETE FROM history_speaker_turns WHERE history_id = OLD.id;
+        DELETE FROM history_speaker_names WHERE history_id = OLD.id;
+    END;";
+
+/// Dictionary terms an entry was recognized with (e.g. Whisper's initial
+/// prompt), in the order they were hinted.
+///
+/// Idempotent and trigger-cleaned for the same reasons as
+/// [`TRANSCRIPTION_TIMINGS_SCHEMA`].
+pub(crate) const HISTORY_BIAS_TERMS_SCHEMA: &str = "CREATE TABLE IF NOT EXISTS history_bias_terms (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        history_id INTEGER NOT NULL,
+        position INTEGER NOT NULL,
+        term TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS idx_history_bias_terms_history
+        ON history_bias_terms (history_id, position);
+    CREATE TRIGGER IF NOT EXISTS history_bias_terms_delete_with_history
+        AFTER DELETE ON transcription_history
+    BEGIN
+        DELETE FROM history_bias_terms WHERE history_id = OLD.id;
+    END;";
+
+/// Dictionary terms a revision was recognized with, like
+/// [`HISTORY_BIAS_TERMS_SCHEMA`] for entries. Rows go with their revision,
+/// including revisions removed along with their entry.
+pub(crate) const REVISION_BIAS_TERMS_SCHEMA: &str =
+    "CREATE TABLE IF NOT EXISTS revision_bias_terms (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        revision_id INTEGER NOT NULL,
+        position INTEGER NOT NULL,
+        term TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS idx_revision_bias_terms_revision
+        ON revision_bias_terms (revision_id, position);
+    CREATE TRIGGER IF NOT EXISTS revision_bias_terms_delete_with_revision
+        AFTER DELETE ON transcription_revisions
+    BEGIN
+        DELETE FROM revision_bias_terms WHERE revision_id = OLD.id;
+    END;";
+
+/// Private-use sentinels handed to FTS5 `snippet()`; replaced with `<mark>` after
+/// the snippet text has been HTML-escaped.
+const SNIPPET_MATCH_START: char = '\u{E000}';
+const SNIPPET_MATCH_END: char = '\u{E001}';
+
+const MAX_TAG_NAME_CHARS: usize = 64;
+const MAX_SPEAKER_NAME_CHARS: usize = 64;
+
+/// Whether a timing row covers an engine segment (phrase), a single word, or
+/// one background-transcribed chunk of a long-form recording.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, Type, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TimingGranularity {
+    Segment,
+    Word,
+    Chunk,
+}
+
+impl TimingGranularity {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::Segment => "segment",
+            Self::Word => "word",
+            Self::Chunk => "chunk",
+        }
+    }
+
+    pub(crate) fn parse(value: &str) -> Self {
+        match value {
+            "word" => Self::Word,
+            "chunk" => Self::Chunk,
+            _ => Self::Segment,
+        }
+    }
+}
+
+/// Engine-reported timing for a span of the raw transcript, in milliseconds from
+/// the start of the saved recording.
+#[derive(Clone, Debug, Serialize, Deserialize, Type, PartialEq, Eq)]
+pub struct TranscriptTiming {
+    pub start_ms: i64,
+    pub end_ms: i64,
+    pub text: String,
+    pub granularity: TimingGranularity,
+}
+
+/// Stretch of the saved recording attributed to one diarized speaker, on the
+/// same clock as [`TranscriptTiming`].
+#[derive(Clone, Debug, Serialize, Deserialize, Type, PartialEq, Eq)]
+pub struct HistorySpeakerTurn {
+    pub start_ms: i64,
+    pub end_ms: i64,
+    /// 1-based speaker number, in order of first appearance.
+    pub speaker: i64,
+}
+
+/// A diarized speaker of an entry and the name it is shown under.
+#[derive(Clone, Debug, Serialize, Deserialize, Type, PartialEq, Eq)]
+pub struct HistorySpeaker {
+    pub speaker: i64,
+    /// The user-given name, or "Speaker N" until renamed.
+    pub name: String,
+}
+
+/// A free-form tag or a named notebook (collection). Entries can carry any
+/// number of either; both protect the entry from automatic retention cleanup.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, Type, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryTagKind {
+    Tag,
+    Notebook,
+}
+
+impl HistoryTagKind {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::Tag => "tag",
+            Self::Notebook => "notebook",
+        }
+    }
+
+    pub(crate) fn parse(value: &str) -> Self {
+        match value {
+            "notebook" => Self::Notebook,
+            _ => Self::Tag,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type, PartialEq, Eq)]
+pub struct HistoryTag {
+    pub id: i64,
+    pub name: String,
+    pub kind: HistoryTagKind,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct HistoryTagSummary {
+    pub id: i64,
+    pub name: String,
+    pub kind: HistoryTagKind,
+    pub entry_count: i64,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, Type)]
 pub struct HistoryEntry {
     pub id: i64,
@@ -83,6 +398,55 @@ pub struct HistoryEntry {
     pub duration_ms: i64,
     pub file_path: String,
     pub audio_file_exists: bool,
+    /// Segment or word timings in playback order; empty for engines without
+    /// timestamps. Long-form recordings append one chunk row per transcribed chunk.
+    #[serde(default)]
+    pub timings: Vec<TranscriptTiming>,
+    #[serde(default)]
+    pub tags: Vec<HistoryTag>,
+    /// Who spoke when, in playback order; empty unless speaker diarization was
+    /// on when the entry was recorded.
+    #[serde(default)]
+    pub speaker_turns: Vec<HistorySpeakerTurn>,
+    /// Every speaker in `speaker_turns`, by number.
+    #[serde(default)]
+    pub speakers: Vec<HistorySpeaker>,
+    /// Dictionary terms hinted to the engine for this entry; empty when
+    /// biasing was off or the engine takes no hints.
+    #[serde(default)]
+    pub bias_terms: Vec<String>,
+}
+
+/// Transcript of an entry's recording produced by re-running a different model.
+#[derive(Clone, Debug, Serialize, Deserialize, Type, PartialEq, Eq)]
+pub struct HistoryRevision {
+    pub id: i64,
+    pub history_id: i64,
+    pub created_at: i64,
+    pub model_id: String,
+    pub model_name: String,
+    /// Engine output after the text pipeline, comparable to the entry's raw text.
+    pub transcription_text: String,
+    /// Wall-clock time spent in the engine, excluding model load.
+    pub elapsed_ms: i64,
+    /// Dictionary terms hinted to the model, as for [`HistoryEntry::bias_terms`].
+    #[serde(default)]
+    pub bias_terms: Vec<String>,
+}
+
+/// A revision next to the word diff from the entry's transcript to it.
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct HistoryRevisionComparison {
+    pub revision: HistoryRevision,
+    pub diff: Vec<WordDiffSpan>,
+}
+
+/// Audio stored alongside a new history entry.
+pub enum RecordingAudio {
+    Samples(Vec<f32>),
+    /// Chunks of a long-form recording, joined on disk without loading the
+    /// whole recording into memory.
+    Spooled(SpooledRecording),
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Type)]
@@ -91,6 +455,16 @@ pub struct HistoryStats {
     pub total_entries: i64,
 }
 
+/// Ranked full-text search result.
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct HistorySearchHit {
+    pub entry: HistoryEntry,
+    /// HTML-escaped excerpt around the best match with hits wrapped in `<mark>`.
+    pub snippet_html: String,
+    /// Relevance score; higher is better. Only comparable within one query.
+    pub score: f64,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, Type)]
 pub struct HomeStats {
     pub total_words: i64,
@@ -152,6 +526,19 @@ pub struct HistoryManager {
     app_handle: AppHandle,
     recordings_dir: PathBuf,
     db_path: PathBuf,
+    recording_migration_running: AtomicBool,
+}
+
+/// Progress of converting stored recordings to another format.
+#[derive(Clone, Debug, Default, Serialize, Type)]
+pub struct RecordingMigrationProgress {
+    pub format: Option<RecordingFormat>,
+    pub total: u64,
+    pub processed: u64,
+    pub converted: u64,
+    pub skipped: u64,
+    pub failed: u64,
+    pub done: bool,
 }
 
 const DURATION_SEMANTICS_VERSION_V1: i64 = 1;
@@ -181,6 +568,149 @@ fn compute_effective_text(
         .to_string()
 }
 
+/// Translate user search input into an FTS5 MATCH expression.
+///
+/// `"quoted text"` becomes a phrase query and a trailing `*` a prefix query; every
+/// other whitespace-separated term is quoted so FTS5 operators and column filters
+/// in user input are matched literally. Terms are implicitly ANDed. With
+/// `prefix_last_term`, the final bare term also matches as a prefix, for
+/// search-as-you-type. Returns `None` when the input has no searchable text.
+pub(crate) fn build_fts_match_expression(input: &str, prefix_last_term: bool) -> Option<String> {
+    struct Token {
+        text: String,
+        phrase: bool,
+        prefix: bool,
+    }
+
+    fn push_token(tokens: &mut Vec<Token>, raw: &str, phrase: bool) {
+        let prefix = !phrase && raw.ends_with('*');
+        let text = raw.trim_end_matches('*').replace('"', "");
+        if text.chars().any(char::is_alphanumeric) {
+            tokens.push(Token {
+                text,
+                phrase,
+                prefix,
+            });
+        }
+    }
+
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut in_phrase = false;
+    for ch in input.chars() {
+        match ch {
+            '"' => {
+                push_token(&mut tokens, current.trim(), in_phrase);
+                current.clear();
+                in_phrase = !in_phrase;
+            }
+            ch if ch.is_whitespace() && !in_phrase => {
+                push_token(&mut tokens, &current, false);
+                current.clear();
+            }
+            ch => current.push(ch),
+        }
+    }
+    push_token(&mut tokens, current.trim(), in_phrase);
+
+    if prefix_last_term {
+        if let Some(last) = tokens.last_mut().filter(|token| !token.phrase) {
+            last.prefix = true;
+        }
+    }
+
+    let expression = tokens
+        .iter()
+        .map(|token| {
+            if token.prefix {
+                format!("\"{}\"*", token.text)
+            } else {
+                format!("\"{}\"", token.text)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    (!expression.is_empty()).then_some(expression)
+}
+
+/// How a history list narrows entries by their text.
+enum HistoryTextMatch {
+    /// FTS5 MATCH expression (see [`build_fts_match_expression`]) or literal
+    /// substring. Word matches come first by rank, then substring-only ones.
+    WordsOrSubstring { words: String, substring: String },
+    /// Literal substring of the effective or raw text.
+    Substring(String),
+}
+
+/// Whether `text` has Han, kana or Hangul characters. `unicode61` keeps a run
+/// of them as one token, so words inside it cannot be found through FTS.
+fn contains_cjk(text: &str) -> bool {
+    text.chars().any(|ch| {
+        matches!(ch,
+            '\u{1100}'..='\u{11FF}'
+            | '\u{3040}'..='\u{30FF}'
+            | '\u{3130}'..='\u{318F}'
+            | '\u{3400}'..='\u{4DBF}'
+            | '\u{4E00}'..='\u{9FFF}'
+            | '\u{AC00}'..='\u{D7AF}'
+            | '\u{F900}'..='\u{FAFF}'
+            | '\u{20000}'..='\u{2FA1F}')
+    })
+}
+
+/// A snippet around the first case-insensitive occurrence of `needle` in
+/// `text`, marked like FTS5 `snippet()` output.
+fn substring_snippet(text: &str, needle: &str) -> Option<String> {
+    const CONTEXT_CHARS: usize = 40;
+
+    let chars: Vec<char> = text.chars().collect();
+    let needle: Vec<char> = needle.chars().collect();
+    if needle.is_empty() || needle.len() > chars.len() {
+        return None;
+    }
+    let start = chars.windows(needle.len()).position(|window| {
+        window
+            .iter()
+            .zip(&needle)
+            .all(|(a, b)| a.to_lowercase().eq(b.to_lowercase()))
+    })?;
+    let end = start + needle.len();
+    let from = start.saturating_sub(CONTEXT_CHARS);
+    let to = (end + CONTEXT_CHARS).min(chars.len());
+
+    let mut snippet = String::new();
+    if from > 0 {
+        snippet.push('…');
+    }
+    snippet.extend(&chars[from..start]);
+    snippet.push(SNIPPET_MATCH_START);
+    snippet.extend(&chars[start..end]);
+    snippet.push(SNIPPET_MATCH_END);
+    snippet.extend(&chars[end..to]);
+    if to < chars.len() {
+        snippet.push('…');
+    }
+    Some(snippet)
+}
+
+/// HTML-escape an FTS5 snippet and turn the match sentinels into `<mark>` tags.
+fn render_snippet_html(snippet: &str) -> String {
+    let mut html = String::with_capacity(snippet.len() + 16);
+    for ch in snippet.chars() {
+        match ch {
+            SNIPPET_MATCH_START => html.push_str("<mark>"),
+            SNIPPET_MATCH_END => html.push_str("</mark>"),
+            '&' => html.push_str("&amp;"),
+            '<' => html.push_str("&lt;"),
+            '>' => html.push_str("&gt;"),
+            '"' => html.push_str("&quot;"),
+            '\'' => html.push_str("&#39;"),
+            ch => html.push(ch),
+        }
+    }
+    html
+}
+
 fn compute_duration_metrics(
     total_words: i64,
     total_duration_ms: i64,
@@ -378,12 +908,31 @@ fn normalize_runtime_durations(recording_duration_ms: i64, speech_duration_ms: i
     (normalized_recording, normalized_speech)
 }
 
+fn recording_format_of(file_name: &str) -> Option<RecordingFormat> {
+    let extension = Path::new(file_name).extension()?.to_str()?;
+    RecordingFormat::ALL
+        .into_iter()
+        .find(|format| format.extension().eq_ignore_ascii_case(extension))
+}
+
+fn resolve_recording_path(recordings_dir: &Path, file_name: &str) -> PathBuf {
+    let path = recordings_dir.join(file_name);
+    if path.exists() || recording_format_of(file_name).is_none() {
+        return path;
+    }
+    RecordingFormat::ALL
+        .into_iter()
+        .map(|format| path.with_extension(format.extension()))
+        .find(|candidate| candidate.exists())
+        .unwrap_or(path)
+}
+
 fn map_history_entry(
     row: &rusqlite::Row<'_>,
     recordings_dir: &PathBuf,
 ) -> rusqlite::Result<HistoryEntry> {
     let file_name: String = row.get("file_name")?;
-    let file_path = recordings_dir.join(&file_name);
+    let file_path = resolve_recording_path(recordings_dir, &file_name);
     let transcription_text: String = row.get("transcription_text")?;
     let post_processed_text: Option<String> = row.get("post_processed_text")?;
     let inserted_text: Option<String> = row.get("inserted_text")?;
@@ -408,9 +957,336 @@ fn map_history_entry(
         duration_ms: row.get("duration_ms")?,
         file_path: file_path.to_string_lossy().to_string(),
         audio_file_exists: file_path.exists(),
+        timings: Vec::new(),
+        tags: Vec::new(),
+        speaker_turns: Vec::new(),
+        speakers: Vec::new(),
+        bias_terms: Vec::new(),
+    })
+}
+
+pub(crate) fn insert_timings(
+    conn: &Connection,
+    history_id: i64,
+    timings: &[TranscriptTiming],
+) -> rusqlite::Result<()> {
+    if timings.is_empty() {
+        return Ok(());
+    }
+
+    let mut stmt = conn.prepare_cached(
+        "INSERT INTO transcription_timings (history_id, position, granularity, start_ms, end_ms, text)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+    )?;
+    for (position, timing) in timings.iter().enumerate() {
+        stmt.execute(params![
+            history_id,
+            position as i64,
+            timing.granularity.as_str(),
+            timing.start_ms,
+            timing.end_ms,
+            timing.text,
+        ])?;
+    }
+    Ok(())
+}
+
+pub(crate) fn load_timings(
+    conn: &Connection,
+    history_id: i64,
+) -> rusqlite::Result<Vec<TranscriptTiming>> {
+    let mut stmt = conn.prepare_cached(
+        "SELECT granularity, start_ms, end_ms, text FROM transcription_timings
+         WHERE history_id = ?1
+         ORDER BY position ASC",
+    )?;
+    let rows = stmt.query_map(params![history_id], |row| {
+        Ok(TranscriptTiming {
+            granularity: TimingGranularity::parse(&row.get::<_, String>(0)?),
+            start_ms: row.get(1)?,
+            end_ms: row.get(2)?,
+            text: row.get(3)?,
+        })
+    })?;
+    rows.collect()
+}
+
+pub(crate) fn load_entry_tags(
+    conn: &Connection,
+    history_id: i64,
+) -> rusqlite::Result<Vec<HistoryTag>> {
+    let mut stmt = conn.prepare_cached(
+        "SELECT t.id, t.name, t.kind FROM history_entry_tags et
+         JOIN history_tags t ON t.id = et.tag_id
+         WHERE et.history_id = ?1
+         ORDER BY t.kind ASC, t.name ASC",
+    )?;
+    let rows = stmt.query_map(params![history_id], |row| {
+        Ok(HistoryTag {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            kind: HistoryTagKind::parse(&row.get::<_, String>(2)?),
+        })
+    })?;
+    rows.collect()
+}
+
+pub(crate) fn insert_revision(
+    conn: &Connection,
+    history_id: i64,
+    created_at: i64,
+    model_id: &str,
+    model_name: &str,
+    transcription_text: &str,
+    elapsed_ms: i64,
+    bias_terms: &[String],
+) -> rusqlite::Result<HistoryRevision> {
+    conn.execute(
+        "INSERT INTO transcription_revisions (history_id, created_at, model_id, model_name, transcription_text, elapsed_ms)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+            history_id,
+            created_at,
+            model_id,
+            model_name,
+            transcription_text,
+            elapsed_ms,
+        ],
+    )?;
+    let id = conn.last_insert_rowid();
+    if !bias_terms.is_empty() {
+        let mut stmt = conn.prepare_cached(
+            "INSERT INTO revision_bias_terms (revision_id, position, term) VALUES (?1, ?2, ?3)",
+        )?;
+        for (position, term) in bias_terms.iter().enumerate() {
+            stmt.execute(params![id, position as i64, term])?;
+        }
+    }
+    Ok(HistoryRevision {
+        id,
+        history_id,
+        created_at,
+        model_id: model_id.to_string(),
+        model_name: model_name.to_string(),
+        transcription_text: transcription_text.to_string(),
+        elapsed_ms,
+        bias_terms: bias_terms.to_vec(),
     })
 }
 
+pub(crate) fn load_revisions(
+    conn: &Connection,
+    history_id: i64,
+) -> rusqlite::Result<Vec<HistoryRevision>> {
+    let mut stmt = conn.prepare_cached(
+        "SELECT id, created_at, model_id, model_name, transcription_text, elapsed_ms
+         FROM transcription_revisions
+         WHERE history_id = ?1
+         ORDER BY created_at ASC, id ASC",
+    )?;
+    let rows = stmt.query_map(params![history_id], |row| {
+        Ok(HistoryRevision {
+            id: row.get(0)?,
+            history_id,
+            created_at: row.get(1)?,
+            model_id: row.get(2)?,
+            model_name: row.get(3)?,
+            transcription_text: row.get(4)?,
+            elapsed_ms: row.get(5)?,
+            bias_terms: Vec::new(),
+        })
+    })?;
+    let mut revisions = rows.collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let mut terms_stmt = conn.prepare_cached(
+        "SELECT term FROM revision_bias_terms WHERE revision_id = ?1 ORDER BY position ASC",
+    )?;
+    for revision in &mut revisions {
+        revision.bias_terms = terms_stmt
+            .query_map(params![revision.id], |row| row.get(0))?
+            .collect::<rusqlite::Result<_>>()?;
+    }
+    Ok(revisions)
+}
+
+pub(crate) fn insert_speaker_turns(
+    conn: &Connection,
+    history_id: i64,
+    turns: &[HistorySpeakerTurn],
+) -> rusqlite::Result<()> {
+    if turns.is_empty() {
+        return Ok(());
+    }
+
+    let mut stmt = conn.prepare_cached(
+        "INSERT INTO history_speaker_turns (history_id, position, speaker, start_ms, end_ms)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+    )?;
+    for (position, turn) in turns.iter().enumerate() {
+        stmt.execute(params![
+            history_id,
+            position as i64,
+            turn.speaker,
+            turn.start_ms,
+            turn.end_ms,
+        ])?;
+    }
+    Ok(())
+}
+
+pub(crate) fn load_speaker_turns(
+    conn: &Connection,
+    history_id: i64,
+) -> rusqlite::Result<Vec<HistorySpeakerTurn>> {
+    let mut stmt = conn.prepare_cached(
+        "SELECT speaker, start_ms, end_ms FROM history_speaker_turns
+         WHERE history_id = ?1
+         ORDER BY position ASC",
+    )?;
+    let rows = stmt.query_map(params![history_id], |row| {
+        Ok(HistorySpeakerTurn {
+            speaker: row.get(0)?,
+            start_ms: row.get(1)?,
+            end_ms: row.get(2)?,
+        })
+    })?;
+    rows.collect()
+}
+
+pub(crate) fn insert_bias_terms(
+    conn: &Connection,
+    history_id: i64,
+    terms: &[String],
+) -> rusqlite::Result<()> {
+    if terms.is_empty() {
+        return Ok(());
+    }
+
+    let mut stmt = conn.prepare_cached(
+        "INSERT INTO history_bias_terms (history_id, position, term) VALUES (?1, ?2, ?3)",
+    )?;
+    for (position, term) in terms.iter().enumerate() {
+        stmt.execute(params![history_id, position as i64, term])?;
+    }
+    Ok(())
+}
+
+pub(crate) fn load_bias_terms(conn: &Connection, history_id: i64) -> rusqlite::Result<Vec<String>> {
+    let mut stmt = conn.prepare_cached(
+        "SELECT term FROM history_bias_terms WHERE history_id = ?1 ORDER BY position ASC",
+    )?;
+    let rows = stmt.query_map(params![history_id], |row| row.get(0))?;
+    rows.collect()
+}
+
+pub(crate) fn default_speaker_name(speaker: i64) -> String {
+    format!("Speaker {}", speaker)
+}
+
+/// Speakers that appear in an entry's turns, with user-given names applied.
+pub(crate) fn load_speakers(
+    conn: &Connection,
+    history_id: i64,
+) -> rusqlite::Result<Vec<HistorySpeaker>> {
+    let mut stmt = conn.prepare_cached(
+        "SELECT t.speaker, n.name FROM
+         (SELECT DISTINCT speaker FROM history_speaker_turns WHERE history_id = ?1) t
+         LEFT JOIN history_speaker_names n
+             ON n.history_id = ?1 AND n.speaker = t.speaker
+         ORDER BY t.speaker ASC",
+    )?;
+    let rows = stmt.query_map(params![history_id], |row| {
+        let speaker: i64 = row.get(0)?;
+        let name: Option<String> = row.get(1)?;
+        Ok(HistorySpeaker {
+            speaker,
+            name: name.unwrap_or_else(|| default_speaker_name(speaker)),
+        })
+    })?;
+    rows.collect()
+}
+
+/// Names users gave an entry's speakers; unnamed speakers are left out.
+pub(crate) fn load_speaker_names(
+    conn: &Connection,
+    history_id: i64,
+) -> rusqlite::Result<Vec<HistorySpeaker>> {
+    let mut stmt = conn.prepare_cached(
+        "SELECT speaker, name FROM history_speaker_names
+         WHERE history_id = ?1
+         ORDER BY speaker ASC",
+    )?;
+    let rows = stmt.query_map(params![history_id], |row| {
+        Ok(HistorySpeaker {
+            speaker: row.get(0)?,
+            name: row.get(1)?,
+        })
+    })?;
+    rows.collect()
+}
+
+pub(crate) fn set_speaker_name(
+    conn: &Connection,
+    history_id: i64,
+    speaker: i64,
+    name: &str,
+) -> rusqlite::Result<()> {
+    conn.execute(
+        "INSERT INTO history_speaker_names (history_id, speaker, name) VALUES (?1, ?2, ?3)
+         ON CONFLICT (history_id, speaker) DO UPDATE SET name = excluded.name",
+        params![history_id, speaker, name],
+    )?;
+    Ok(())
+}
+
+/// Find a tag by kind and (case-insensitive) name, creating it when missing.
+pub(crate) fn get_or_create_tag(
+    conn: &Connection,
+    name: &str,
+    kind: HistoryTagKind,
+) -> rusqlite::Result<HistoryTag> {
+    conn.execute(
+        "INSERT OR IGNORE INTO history_tags (name, kind, created_at) VALUES (?1, ?2, ?3)",
+        params![name, kind.as_str(), Utc::now().timestamp()],
+    )?;
+    conn.query_row(
+        "SELECT id, name FROM history_tags WHERE kind = ?1 AND name = ?2",
+        params![kind.as_str(), name],
+        |row| {
+            Ok(HistoryTag {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                kind,
+            })
+        },
+    )
+}
+
+fn attach_entry_details(conn: &Connection, entries: &mut [HistoryEntry]) -> Result<()> {
+    for entry in entries.iter_mut() {
+        entry.timings = load_timings(conn, entry.id)?;
+        entry.tags = load_entry_tags(conn, entry.id)?;
+        entry.speaker_turns = load_speaker_turns(conn, entry.id)?;
+        entry.speakers = load_speakers(conn, entry.id)?;
+        entry.bias_terms = load_bias_terms(conn, entry.id)?;
+    }
+    Ok(())
+}
+
+fn normalize_tag_name(name: &str) -> Result<String> {
+    let name = name.split_whitespace().collect::<Vec<_>>().join(" ");
+    if name.is_empty() {
+        return Err(anyhow::anyhow!("Tag name cannot be empty"));
+    }
+    if name.chars().count() > MAX_TAG_NAME_CHARS {
+        return Err(anyhow::anyhow!(
+            "Tag name cannot be longer than {} characters",
+            MAX_TAG_NAME_CHARS
+        ));
+    }
+    Ok(name)
+}
+
 impl HistoryManager {
     pub fn new(app_handle: &AppHandle) -> Result<Self> {
         // Create recordings directory in app data dir
@@ -428,6 +1304,7 @@ impl HistoryManager {
             app_handle: app_handle.clone(),
             recordings_dir,
             db_path,
+            recording_migration_running: AtomicBool::new(false),
         };
 
         // Initialize database and run migrations synchronously
@@ -667,7 +1544,62 @@ impl HistoryManager {
             info!("Reconciled legacy schema: created missing table 'user_stats_migration_backup'");
         }
 
+        if !Self::table_exists(conn, "transcription_timings")? {
+            conn.execute_batch(TRANSCRIPTION_TIMINGS_SCHEMA)?;
+            schema_changed = true;
+            info!("Reconciled legacy schema: created missing table 'transcription_timings'");
+        }
+
+        if !Self::table_exists(conn, "transcription_history_fts")? {
+            conn.execute_batch(TRANSCRIPTION_HISTORY_FTS_SCHEMA)?;
+            schema_changed = true;
+            info!("Reconciled legacy schema: created missing table 'transcription_history_fts'");
+        }
+
+        if !Self::table_exists(conn, "history_tags")?
+            || !Self::table_exists(conn, "history_entry_tags")?
+        {
+            conn.execute_batch(HISTORY_TAGS_SCHEMA)?;
+            schema_changed = true;
+            info!("Reconciled legacy schema: created missing history tag tables");
+        }
+
+        if !Self::table_exists(conn, "transcription_revisions")? {
+            conn.execute_batch(TRANSCRIPTION_REVISIONS_SCHEMA)?;
+            schema_changed = true;
+            info!("Reconciled legacy schema: created missing table 'transcription_revisions'");
+        }
+
+        if !Self::table_exists(conn, "history_speaker_turns")?
+            || !Self::table_exists(conn, "history_speaker_names")?
+        {
+            conn.execute_batch(HISTORY_SPEAKERS_SCHEMA)?;
+            schema_changed = true;
+            info!("Reconciled legacy schema: created missing history speaker tables");
+        }
+
+        if !Self::table_exists(conn, "history_bias_terms")? {
+            conn.execute_batch(HISTORY_BIAS_TERMS_SCHEMA)?;
+            schema_changed = true;
+            info!("Reconciled legacy schema: created missing table 'history_bias_terms'");
+        }
+
+        if !Self::table_exists(conn, "revision_bias_terms")? {
+            conn.execute_batch(REVISION_BIAS_TERMS_SCHEMA)?;
+            schema_changed = true;
+            info!("Reconciled legacy schema: created missing table 'revision_bias_terms'");
+        }
+
         let history_complete = Self::table_exists(conn, "transcription_history")?
+            && Self::table_exists(conn, "transcription_timings")?
+            && Self::table_exists(conn, "transcription_history_fts")?
+            && Self::table_exists(conn, "history_tags")?
+            && Self::table_exists(conn, "history_entry_tags")?
+            && Self::table_exists(conn, "transcription_revisions")?
+            && Self::table_exists(conn, "history_speaker_turns")?
+            && Self::table_exists(conn, "history_speaker_names")?
+            && Self::table_exists(conn, "history_bias_terms")?
+            && Self::table_exists(conn, "revision_bias_terms")?
             && Self::column_exists(conn, "transcription_history", "post_processed_text")?
             && Self::column_exists(conn, "transcription_history", "post_process_prompt")?
             && Self::column_exists(conn, "transcription_history", "duration_ms")?
@@ -1091,25 +2023,39 @@ impl HistoryManager {
         })
     }
 
-    /// Save a transcription to history (both database and WAV file)
+    /// Save a transcription to history (both database and audio file).
+    /// Speaker turns are added later with `set_speaker_turns`, so saving does
+    /// not wait for diarization.
     pub fn save_transcription(
         &self,
-        audio_samples: Vec<f32>,
+        audio: &RecordingAudio,
         transcription_text: String,
         post_processed_text: Option<String>,
         post_process_prompt: Option<String>,
         recording_duration_ms: i64,
         speech_duration_ms: i64,
         filler_words_removed: i64,
+        timings: Vec<TranscriptTiming>,
+        bias_terms: Vec<String>,
     ) -> Result<SavedTranscription> {
         self.with_write_permit(|| {
             let timestamp = Utc::now().timestamp();
-            let file_name = format!("codictate-{}.wav", timestamp);
+            let format = crate::settings::get_settings(&self.app_handle).recording_format;
+            let file_name = format!("codictate-{}.{}", timestamp, format.extension());
             let title = self.format_timestamp_title(timestamp);
 
-            // Save WAV file
             let file_path = self.recordings_dir.join(&file_name);
-            save_wav_file(file_path, &audio_samples)?;
+            match (audio, format) {
+                (RecordingAudio::Samples(samples), RecordingFormat::Wav) => {
+                    save_wav_file(file_path, samples)?
+                }
+                (RecordingAudio::Samples(samples), RecordingFormat::Flac) => {
+                    save_flac_file(file_path, samples)?
+                }
+                (RecordingAudio::Spooled(recording), _) => {
+                    concat_recording_files(recording.parts(), file_path)?
+                }
+            }
 
             // Save to database
             let contribution = self.save_to_database(
@@ -1122,6 +2068,8 @@ impl HistoryManager {
                 recording_duration_ms,
                 speech_duration_ms,
                 filler_words_removed,
+                &timings,
+                &bias_terms,
             )?;
 
             // Clean up old entries
@@ -1150,6 +2098,8 @@ impl HistoryManager {
         recording_duration_ms: i64,
         speech_duration_ms: i64,
         filler_words_removed: i64,
+        timings: &[TranscriptTiming],
+        bias_terms: &[String],
     ) -> Result<SavedTranscription> {
         let mut conn = self.get_connection()?;
         let tx = conn.transaction()?;
@@ -1184,6 +2134,8 @@ impl HistoryManager {
             ],
         )?;
         let entry_id = tx.last_insert_rowid();
+        insert_timings(&tx, entry_id, timings)?;
+        insert_bias_terms(&tx, entry_id, bias_terms)?;
 
         // 2. Update user_stats
         // Calculate word count
@@ -1380,13 +2332,13 @@ impl HistoryManager {
                 params![id],
             )?;
 
-            // Delete WAV file
-            let file_path = self.recordings_dir.join(file_name);
+            // Delete audio file
+            let file_path = resolve_recording_path(&self.recordings_dir, file_name);
             if file_path.exists() {
                 if let Err(e) = fs::remove_file(&file_path) {
-                    error!("Failed to delete WAV file {}: {}", file_name, e);
+                    error!("Failed to delete audio file {}: {}", file_name, e);
                 } else {
-                    debug!("Deleted old WAV file: {}", file_name);
+                    debug!("Deleted old audio file: {}", file_name);
                     deleted_count += 1;
                 }
             }
@@ -1398,9 +2350,11 @@ impl HistoryManager {
     fn cleanup_by_count(&self, limit: usize) -> Result<()> {
         let conn = self.get_connection()?;
 
-        // Get all entries that are not saved, ordered by timestamp desc
+        // Get all entries that are neither saved nor tagged, ordered by timestamp desc
         let mut stmt = conn.prepare(
-            "SELECT id, file_name FROM transcription_history WHERE saved = 0 ORDER BY timestamp DESC"
+            "SELECT id, file_name FROM transcription_history
+             WHERE saved = 0 AND id NOT IN (SELECT history_id FROM history_entry_tags)
+             ORDER BY timestamp DESC",
         )?;
 
         let rows = stmt.query_map([], |row| {
@@ -1439,9 +2393,11 @@ impl HistoryManager {
             _ => unreachable!("Should not reach here"),
         };
 
-        // Get all unsaved entries older than the cutoff timestamp
+        // Get all unsaved, untagged entries older than the cutoff timestamp
         let mut stmt = conn.prepare(
-            "SELECT id, file_name FROM transcription_history WHERE saved = 0 AND timestamp < ?1",
+            "SELECT id, file_name FROM transcription_history
+             WHERE saved = 0 AND timestamp < ?1
+               AND id NOT IN (SELECT history_id FROM history_entry_tags)",
         )?;
 
         let rows = stmt.query_map(params![cutoff_timestamp], |row| {
@@ -1474,7 +2430,7 @@ impl HistoryManager {
             |row| row.get(0),
         ).unwrap_or(0);
 
-        // Calculate size of all WAV files in the recordings directory
+        // Calculate size of all recordings, whatever their format
         let mut total_size_bytes: u64 = 0;
         if self.recordings_dir.exists() {
             match fs::read_dir(&self.recordings_dir) {
@@ -1511,7 +2467,9 @@ impl HistoryManager {
         
         // Get entries older than cutoff
         let mut stmt = conn.prepare(
-            "SELECT id, file_name, timestamp, saved FROM transcription_history WHERE timestamp < ?1 AND saved = 0",
+            "SELECT id, file_name, timestamp, saved FROM transcription_history
+             WHERE timestamp < ?1 AND saved = 0
+               AND id NOT IN (SELECT history_id FROM history_entry_tags)",
         )?;
 
         let rows = stmt.query_map(params![cutoff_timestamp], |row| {
@@ -1549,27 +2507,78 @@ impl HistoryManager {
         search_query: Option<String>,
         starred_only: bool,
         time_period_start: Option<i64>,
+        tag_id: Option<i64>,
     ) -> Result<Vec<HistoryEntry>> {
         let conn = self.get_connection()?;
         Self::get_history_entries_with_conn(
             &conn,
             &self.recordings_dir,
-            limit,
+            Some(limit),
             offset,
             search_query,
             starred_only,
             time_period_start,
+            tag_id,
         )
     }
 
+    /// List entries, newest first. Search input is matched as whole words
+    /// through the FTS index; when that finds nothing, or the input is CJK
+    /// (which `unicode61` does not split into words), it is matched as a
+    /// substring instead.
     fn get_history_entries_with_conn(
         conn: &Connection,
         recordings_dir: &PathBuf,
-        limit: usize,
+        limit: Option<usize>,
         offset: usize,
         search_query: Option<String>,
         starred_only: bool,
         time_period_start: Option<i64>,
+        tag_id: Option<i64>,
+    ) -> Result<Vec<HistoryEntry>> {
+        let list = |limit: Option<usize>, offset: usize, text_match: Option<&HistoryTextMatch>| {
+            Self::list_history_entries_with_conn(
+                conn,
+                recordings_dir,
+                limit,
+                offset,
+                text_match,
+                starred_only,
+                time_period_start,
+                tag_id,
+            )
+        };
+
+        let Some(query_str) = search_query
+            .as_deref()
+            .map(str::trim)
+            .filter(|query_str| !query_str.is_empty())
+        else {
+            return list(limit, offset, None);
+        };
+
+        // Words inside a token (or unsegmented CJK text) are invisible to FTS,
+        // so substring matches are always included after the ranked ones.
+        let substring = query_str.to_string();
+        let text_match = match (!contains_cjk(query_str))
+            .then(|| build_fts_match_expression(query_str, true))
+            .flatten()
+        {
+            Some(words) => HistoryTextMatch::WordsOrSubstring { words, substring },
+            None => HistoryTextMatch::Substring(substring),
+        };
+        list(limit, offset, Some(&text_match))
+    }
+
+    fn list_history_entries_with_conn(
+        conn: &Connection,
+        recordings_dir: &PathBuf,
+        limit: Option<usize>,
+        offset: usize,
+        text_match: Option<&HistoryTextMatch>,
+        starred_only: bool,
+        time_period_start: Option<i64>,
+        tag_id: Option<i64>,
     ) -> Result<Vec<HistoryEntry>> {
         let mut query = String::from(
             "SELECT id, file_name, timestamp, saved, title, transcription_text, post_processed_text, inserted_text, post_process_prompt, duration_ms 
@@ -1579,28 +2588,49 @@ impl HistoryManager {
         let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
         let mut param_index = 1;
         let mut has_where = false;
+        // Parameter index of the FTS expression, reused to order by its rank.
+        let mut words_param = None;
 
-        if let Some(query_str) = search_query {
-            if !query_str.trim().is_empty() {
-                // Escape LIKE wildcards so literal '%' and '_' in user input
-                // don't act as SQL pattern characters.
-                let escaped = query_str.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_");
-                let like_query = format!("%{}%", escaped);
+        let substring = match text_match {
+            Some(HistoryTextMatch::WordsOrSubstring { words, substring }) => {
                 query.push_str(
-                    " WHERE (COALESCE(inserted_text, post_processed_text, transcription_text) LIKE ?",
+                    " WHERE (id IN (SELECT rowid FROM transcription_history_fts WHERE transcription_history_fts MATCH ?",
                 );
                 query.push_str(&param_index.to_string());
-                query.push_str(" ESCAPE '\\'");
-                params.push(Box::new(like_query.clone()));
+                query.push_str(") OR");
+                params.push(Box::new(words.clone()));
+                words_param = Some(param_index);
                 param_index += 1;
-                query.push_str(" OR transcription_text LIKE ?");
-                query.push_str(&param_index.to_string());
-                query.push_str(" ESCAPE '\\'");
-                params.push(Box::new(like_query));
-                param_index += 1;
-                query.push(')');
-                has_where = true;
+                Some(substring)
+            }
+            Some(HistoryTextMatch::Substring(substring)) => {
+                query.push_str(" WHERE (");
+                Some(substring)
             }
+            None => None,
+        };
+        if let Some(query_str) = substring {
+            // Escape LIKE wildcards so literal '%' and '_' in user input
+            // don't act as SQL pattern characters.
+            let escaped = query_str
+                .replace('\\', "\\\\")
+                .replace('%', "\\%")
+                .replace('_', "\\_");
+            let like_query = format!("%{}%", escaped);
+            query.push_str(
+                " COALESCE(inserted_text, post_processed_text, transcription_text) LIKE ?",
+            );
+            query.push_str(&param_index.to_string());
+            query.push_str(" ESCAPE '\\'");
+            params.push(Box::new(like_query.clone()));
+            param_index += 1;
+            query.push_str(" OR transcription_text LIKE ?");
+            query.push_str(&param_index.to_string());
+            query.push_str(" ESCAPE '\\'");
+            params.push(Box::new(like_query));
+            param_index += 1;
+            query.push(')');
+            has_where = true;
         }
 
         if starred_only {
@@ -1613,6 +2643,21 @@ impl HistoryManager {
             query.push_str(" saved = 1");
         }
 
+        if let Some(tag_id) = tag_id {
+            if has_where {
+                query.push_str(" AND");
+            } else {
+                query.push_str(" WHERE");
+                has_where = true;
+            }
+            query.push_str(&format!(
+                " id IN (SELECT history_id FROM history_entry_tags WHERE tag_id = ?{})",
+                param_index
+            ));
+            params.push(Box::new(tag_id));
+            param_index += 1;
+        }
+
         if let Some(start_ts) = time_period_start {
             if has_where {
                 query.push_str(" AND");
@@ -1626,9 +2671,18 @@ impl HistoryManager {
             param_index += 1;
         }
 
-        query.push_str(" ORDER BY timestamp DESC LIMIT ?");
+        query.push_str(" ORDER BY");
+        if let Some(words_param) = words_param {
+            // Substring-only matches have no rank and sort after every word match.
+            query.push_str(&format!(
+                " (SELECT bm25(transcription_history_fts, 2.0, 1.0) FROM transcription_history_fts
+                   WHERE transcription_history_fts MATCH ?{words_param} AND rowid = transcription_history.id) ASC NULLS LAST,"
+            ));
+        }
+        // SQLite needs a LIMIT before OFFSET; a negative limit means none.
+        query.push_str(" timestamp DESC LIMIT ?");
         query.push_str(&param_index.to_string());
-        params.push(Box::new(limit as i64));
+        params.push(Box::new(limit.map_or(-1, |limit| limit as i64)));
         param_index += 1;
 
         query.push_str(" OFFSET ?");
@@ -1648,28 +2702,211 @@ impl HistoryManager {
         for row in rows {
             entries.push(row?);
         }
+        attach_entry_details(conn, &mut entries)?;
 
         Ok(entries)
     }
 
-    pub fn get_latest_entry(&self) -> Result<Option<HistoryEntry>> {
+    /// Ranked full-text search with highlighted snippets. Supports `"quoted phrases"`
+    /// and `prefix*` terms; see [`build_fts_match_expression`].
+    pub async fn search_history_entries(
+        &self,
+        query: String,
+        limit: usize,
+        offset: usize,
+        starred_only: bool,
+        time_period_start: Option<i64>,
+    ) -> Result<Vec<HistorySearchHit>> {
         let conn = self.get_connection()?;
-        Self::get_latest_entry_with_conn(&conn, &self.recordings_dir)
+        Self::search_history_entries_with_conn(
+            &conn,
+            &self.recordings_dir,
+            &query,
+            limit,
+            offset,
+            starred_only,
+            time_period_start,
+        )
     }
 
-    fn get_latest_entry_with_conn(conn: &Connection, recordings_dir: &PathBuf) -> Result<Option<HistoryEntry>> {
-        let mut stmt = conn.prepare(
-            "SELECT id, file_name, timestamp, saved, title, transcription_text, post_processed_text, inserted_text, post_process_prompt, duration_ms
-             FROM transcription_history
-             ORDER BY timestamp DESC
-             LIMIT 1",
-        )?;
-
-        let entry = stmt
-            .query_row([], |row| {
-                map_history_entry(row, recordings_dir)
-            })
+    /// Falls back to substring matching, newest first, like
+    /// [`Self::get_history_entries_with_conn`].
+    fn search_history_entries_with_conn(
+        conn: &Connection,
+        recordings_dir: &PathBuf,
+        query: &str,
+        limit: usize,
+        offset: usize,
+        starred_only: bool,
+        time_period_start: Option<i64>,
+    ) -> Result<Vec<HistorySearchHit>> {
+        let query = query.trim();
+        let rank = |match_expr: &str, limit: usize, offset: usize| {
+            Self::rank_history_entries_with_conn(
+                conn,
+                recordings_dir,
+                match_expr,
+                limit,
+                offset,
+                starred_only,
+                time_period_start,
+            )
+        };
+        let words = (!contains_cjk(query))
+            .then(|| build_fts_match_expression(query, false))
+            .flatten();
+        if let Some(match_expr) = &words {
+            let hits = rank(match_expr, limit, offset)?;
+            if !hits.is_empty() || (offset > 0 && !rank(match_expr, 1, 0)?.is_empty()) {
+                return Ok(hits);
+            }
+        }
+        if query.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let substring = HistoryTextMatch::Substring(query.to_string());
+        let entries = Self::list_history_entries_with_conn(
+            conn,
+            recordings_dir,
+            Some(limit),
+            offset,
+            Some(&substring),
+            starred_only,
+            time_period_start,
+            None,
+        )?;
+        Ok(entries
+            .into_iter()
+            .map(|entry| {
+                let snippet = substring_snippet(&entry.effective_text, query)
+                    .or_else(|| substring_snippet(&entry.transcription_text, query))
+                    .unwrap_or_else(|| entry.effective_text.clone());
+                HistorySearchHit {
+                    snippet_html: render_snippet_html(&snippet),
+                    score: 0.0,
+                    entry,
+                }
+            })
+            .collect())
+    }
+
+    fn rank_history_entries_with_conn(
+        conn: &Connection,
+        recordings_dir: &PathBuf,
+        match_expr: &str,
+        limit: usize,
+        offset: usize,
+        starred_only: bool,
+        time_period_start: Option<i64>,
+    ) -> Result<Vec<HistorySearchHit>> {
+        // bm25 weights favour the effective text over the raw transcript.
+        let mut sql = format!(
+            "SELECT h.id AS id, h.file_name AS file_name, h.timestamp AS timestamp, h.saved AS saved,
+                    h.title AS title, h.transcription_text AS transcription_text,
+                    h.post_processed_text AS post_processed_text, h.inserted_text AS inserted_text,
+                    h.post_process_prompt AS post_process_prompt, h.duration_ms AS duration_ms,
+                    snippet(transcription_history_fts, -1, '{SNIPPET_MATCH_START}', '{SNIPPET_MATCH_END}', '…', 16) AS snippet,
+                    bm25(transcription_history_fts, 2.0, 1.0) AS rank
+             FROM transcription_history_fts
+             JOIN transcription_history h ON h.id = transcription_history_fts.rowid
+             WHERE transcription_history_fts MATCH ?1"
+        );
+        let mut params: Vec<Box<dyn rusqlite::ToSql>> = vec![Box::new(match_expr.to_string())];
+
+        if starred_only {
+            sql.push_str(" AND h.saved = 1");
+        }
+        if let Some(start_ts) = time_period_start {
+            params.push(Box::new(start_ts));
+            sql.push_str(&format!(" AND h.timestamp >= ?{}", params.len()));
+        }
+        params.push(Box::new(limit as i64));
+        sql.push_str(&format!(
+            " ORDER BY rank ASC, h.timestamp DESC LIMIT ?{}",
+            params.len()
+        ));
+        params.push(Box::new(offset as i64));
+        sql.push_str(&format!(" OFFSET ?{}", params.len()));
+
+        let mut stmt = conn.prepare(&sql)?;
+        let params_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+        let rows = stmt.query_map(rusqlite::params_from_iter(params_refs), |row| {
+            let snippet: String = row.get("snippet")?;
+            let rank: f64 = row.get("rank")?;
+            Ok(HistorySearchHit {
+                entry: map_history_entry(row, recordings_dir)?,
+                snippet_html: render_snippet_html(&snippet),
+                score: -rank,
+            })
+        })?;
+
+        let mut hits = Vec::new();
+        for row in rows {
+            let mut hit = row?;
+            attach_entry_details(conn, std::slice::from_mut(&mut hit.entry))?;
+            hits.push(hit);
+        }
+        Ok(hits)
+    }
+
+    /// Entries for a plain-file export, oldest first. Explicit ids take precedence
+    /// over the list filters; ids that no longer exist are skipped.
+    pub fn get_entries_for_export(
+        &self,
+        filter: &HistoryExportFilter,
+    ) -> Result<Vec<HistoryEntry>> {
+        let conn = self.get_connection()?;
+        let mut entries = match &filter.entry_ids {
+            Some(ids) => {
+                let mut found = Vec::with_capacity(ids.len());
+                for id in ids {
+                    if let Some(entry) =
+                        Self::get_entry_by_id_with_conn(&conn, &self.recordings_dir, *id)?
+                    {
+                        found.push(entry);
+                    }
+                }
+                found
+            }
+            None => Self::get_history_entries_with_conn(
+                &conn,
+                &self.recordings_dir,
+                None,
+                0,
+                filter.search_query.clone(),
+                filter.starred_only,
+                filter.time_period_start,
+                filter.tag_id,
+            )?,
+        };
+
+        entries.sort_by_key(|entry| (entry.timestamp, entry.id));
+        entries.dedup_by_key(|entry| entry.id);
+        Ok(entries)
+    }
+
+    pub fn get_latest_entry(&self) -> Result<Option<HistoryEntry>> {
+        let conn = self.get_connection()?;
+        Self::get_latest_entry_with_conn(&conn, &self.recordings_dir)
+    }
+
+    fn get_latest_entry_with_conn(conn: &Connection, recordings_dir: &PathBuf) -> Result<Option<HistoryEntry>> {
+        let mut stmt = conn.prepare(
+            "SELECT id, file_name, timestamp, saved, title, transcription_text, post_processed_text, inserted_text, post_process_prompt, duration_ms
+             FROM transcription_history
+             ORDER BY timestamp DESC
+             LIMIT 1",
+        )?;
+
+        let mut entry = stmt
+            .query_row([], |row| {
+                map_history_entry(row, recordings_dir)
+            })
             .optional()?;
+        if let Some(entry) = entry.as_mut() {
+            attach_entry_details(conn, std::slice::from_mut(entry))?;
+        }
 
         Ok(entry)
     }
@@ -1701,8 +2938,466 @@ impl HistoryManager {
         })
     }
 
+    pub fn list_tags(&self) -> Result<Vec<HistoryTagSummary>> {
+        let conn = self.get_connection()?;
+        Self::list_tags_with_conn(&conn)
+    }
+
+    fn list_tags_with_conn(conn: &Connection) -> Result<Vec<HistoryTagSummary>> {
+        let mut stmt = conn.prepare(
+            "SELECT t.id, t.name, t.kind, COUNT(et.history_id)
+             FROM history_tags t
+             LEFT JOIN history_entry_tags et ON et.tag_id = t.id
+             GROUP BY t.id
+             ORDER BY t.kind ASC, t.name ASC",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(HistoryTagSummary {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                kind: HistoryTagKind::parse(&row.get::<_, String>(2)?),
+                entry_count: row.get(3)?,
+            })
+        })?;
+
+        let mut tags = Vec::new();
+        for row in rows {
+            tags.push(row?);
+        }
+        Ok(tags)
+    }
+
+    /// Create a tag or notebook; an existing one with the same name is returned as-is.
+    pub async fn create_tag(&self, name: String, kind: HistoryTagKind) -> Result<HistoryTag> {
+        self.with_write_permit(|| {
+            let conn = self.get_connection()?;
+            let tag = get_or_create_tag(&conn, &normalize_tag_name(&name)?, kind)?;
+            debug!("Created history {} {}", kind.as_str(), tag.id);
+            self.emit_history_updated();
+            Ok(tag)
+        })
+    }
+
+    pub async fn rename_tag(&self, id: i64, name: String) -> Result<HistoryTag> {
+        self.with_write_permit(|| {
+            let conn = self.get_connection()?;
+            let tag = Self::rename_tag_with_conn(&conn, id, &normalize_tag_name(&name)?)?;
+            self.emit_history_updated();
+            Ok(tag)
+        })
+    }
+
+    fn rename_tag_with_conn(conn: &Connection, id: i64, name: &str) -> Result<HistoryTag> {
+        let kind: String = conn
+            .query_row(
+                "SELECT kind FROM history_tags WHERE id = ?1",
+                params![id],
+                |row| row.get(0),
+            )
+            .optional()?
+            .ok_or_else(|| anyhow::anyhow!("Tag {} not found", id))?;
+
+        let conflict: Option<i64> = conn
+            .query_row(
+                "SELECT id FROM history_tags WHERE kind = ?1 AND name = ?2 AND id != ?3",
+                params![kind, name, id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        if conflict.is_some() {
+            return Err(anyhow::anyhow!(
+                "A {} named '{}' already exists",
+                kind,
+                name
+            ));
+        }
+
+        conn.execute(
+            "UPDATE history_tags SET name = ?1 WHERE id = ?2",
+            params![name, id],
+        )?;
+        Ok(HistoryTag {
+            id,
+            name: name.to_string(),
+            kind: HistoryTagKind::parse(&kind),
+        })
+    }
+
+    /// Delete a tag or notebook. Its entries are kept, only the links are removed.
+    pub async fn delete_tag(&self, id: i64) -> Result<()> {
+        self.with_write_permit(|| {
+            let conn = self.get_connection()?;
+            conn.execute("DELETE FROM history_tags WHERE id = ?1", params![id])?;
+            debug!("Deleted history tag {}", id);
+            self.emit_history_updated();
+            Ok(())
+        })
+    }
+
+    pub async fn tag_entry(&self, entry_id: i64, tag_id: i64) -> Result<()> {
+        self.with_write_permit(|| {
+            let conn = self.get_connection()?;
+            Self::tag_entry_with_conn(&conn, entry_id, tag_id)?;
+            self.emit_history_updated();
+            Ok(())
+        })
+    }
+
+    fn tag_entry_with_conn(conn: &Connection, entry_id: i64, tag_id: i64) -> Result<()> {
+        let inserted = conn.execute(
+            "INSERT OR IGNORE INTO history_entry_tags (history_id, tag_id)
+             SELECT h.id, t.id FROM transcription_history h, history_tags t
+             WHERE h.id = ?1 AND t.id = ?2",
+            params![entry_id, tag_id],
+        )?;
+        if inserted == 0 {
+            let linked: bool = conn.query_row(
+                "SELECT EXISTS(SELECT 1 FROM history_entry_tags WHERE history_id = ?1 AND tag_id = ?2)",
+                params![entry_id, tag_id],
+                |row| row.get(0),
+            )?;
+            if !linked {
+                return Err(anyhow::anyhow!(
+                    "History entry {} or tag {} not found",
+                    entry_id,
+                    tag_id
+                ));
+            }
+        }
+        debug!("Tagged history entry {} with tag {}", entry_id, tag_id);
+        Ok(())
+    }
+
+    pub async fn untag_entry(&self, entry_id: i64, tag_id: i64) -> Result<()> {
+        self.with_write_permit(|| {
+            let conn = self.get_connection()?;
+            conn.execute(
+                "DELETE FROM history_entry_tags WHERE history_id = ?1 AND tag_id = ?2",
+                params![entry_id, tag_id],
+            )?;
+            debug!("Removed tag {} from history entry {}", tag_id, entry_id);
+            self.emit_history_updated();
+            Ok(())
+        })
+    }
+
+    pub fn get_entry_by_id(&self, id: i64) -> Result<Option<HistoryEntry>> {
+        let conn = self.get_connection()?;
+        Self::get_entry_by_id_with_conn(&conn, &self.recordings_dir, id)
+    }
+
+    /// Store a transcript produced by re-running `model_id` on an entry's
+    /// recording, with the dictionary terms it was biased with, and compare
+    /// it with the entry's transcript.
+    pub async fn add_revision(
+        &self,
+        history_id: i64,
+        model_id: &str,
+        model_name: &str,
+        transcription_text: &str,
+        elapsed_ms: i64,
+        bias_terms: &[String],
+    ) -> Result<HistoryRevisionComparison> {
+        self.with_write_permit(|| {
+            let conn = self.get_connection()?;
+            let original = Self::entry_transcript_with_conn(&conn, history_id)?;
+            let revision = insert_revision(
+                &conn,
+                history_id,
+                Utc::now().timestamp(),
+                model_id,
+                model_name,
+                transcription_text,
+                elapsed_ms,
+                bias_terms,
+            )?;
+            debug!(
+                "Stored revision {} of history entry {} from model {}",
+                revision.id, history_id, model_id
+            );
+            self.emit_history_updated();
+            Ok(HistoryRevisionComparison {
+                diff: diff_words(&original, &revision.transcription_text),
+                revision,
+            })
+        })
+    }
+
+    /// Revisions of an entry, oldest first, each diffed against the entry's transcript.
+    pub fn list_revisions(&self, history_id: i64) -> Result<Vec<HistoryRevisionComparison>> {
+        let conn = self.get_connection()?;
+        Self::list_revisions_with_conn(&conn, history_id)
+    }
+
+    fn list_revisions_with_conn(
+        conn: &Connection,
+        history_id: i64,
+    ) -> Result<Vec<HistoryRevisionComparison>> {
+        let original = Self::entry_transcript_with_conn(conn, history_id)?;
+        Ok(load_revisions(conn, history_id)?
+            .into_iter()
+            .map(|revision| HistoryRevisionComparison {
+                diff: diff_words(&original, &revision.transcription_text),
+                revision,
+            })
+            .collect())
+    }
+
+    fn entry_transcript_with_conn(conn: &Connection, history_id: i64) -> Result<String> {
+        conn.query_row(
+            "SELECT transcription_text FROM transcription_history WHERE id = ?1",
+            params![history_id],
+            |row| row.get(0),
+        )
+        .optional()?
+        .ok_or_else(|| anyhow::anyhow!("History entry {} not found", history_id))
+    }
+
+    pub async fn delete_revision(&self, id: i64) -> Result<()> {
+        self.with_write_permit(|| {
+            let conn = self.get_connection()?;
+            conn.execute(
+                "DELETE FROM transcription_revisions WHERE id = ?1",
+                params![id],
+            )?;
+            debug!("Deleted history revision {}", id);
+            self.emit_history_updated();
+            Ok(())
+        })
+    }
+
+    /// Replace the speaker turns of a saved entry, e.g. once diarization of
+    /// its recording has finished.
+    pub fn set_speaker_turns(&self, entry_id: i64, turns: &[HistorySpeakerTurn]) -> Result<()> {
+        self.with_write_permit(|| {
+            let mut conn = self.get_connection()?;
+            Self::set_speaker_turns_with_conn(&mut conn, entry_id, turns)?;
+            self.emit_history_updated();
+            Ok(())
+        })
+    }
+
+    fn set_speaker_turns_with_conn(
+        conn: &mut Connection,
+        entry_id: i64,
+        turns: &[HistorySpeakerTurn],
+    ) -> Result<()> {
+        let tx = conn.transaction()?;
+        let exists: bool = tx.query_row(
+            "SELECT EXISTS(SELECT 1 FROM transcription_history WHERE id = ?1)",
+            params![entry_id],
+            |row| row.get(0),
+        )?;
+        if !exists {
+            return Err(anyhow::anyhow!("History entry {} not found", entry_id));
+        }
+        tx.execute(
+            "DELETE FROM history_speaker_turns WHERE history_id = ?1",
+            params![entry_id],
+        )?;
+        insert_speaker_turns(&tx, entry_id, turns)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Name a diarized speaker of an entry. A blank name restores the default
+    /// "Speaker N" label.
+    pub async fn rename_speaker(
+        &self,
+        entry_id: i64,
+        speaker: i64,
+        name: String,
+    ) -> Result<Vec<HistorySpeaker>> {
+        self.with_write_permit(|| {
+            let conn = self.get_connection()?;
+            let speakers = Self::rename_speaker_with_conn(&conn, entry_id, speaker, &name)?;
+            self.emit_history_updated();
+            Ok(speakers)
+        })
+    }
+
+    fn rename_speaker_with_conn(
+        conn: &Connection,
+        entry_id: i64,
+        speaker: i64,
+        name: &str,
+    ) -> Result<Vec<HistorySpeaker>> {
+        let known: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM history_speaker_turns WHERE history_id = ?1 AND speaker = ?2)",
+            params![entry_id, speaker],
+            |row| row.get(0),
+        )?;
+        if !known {
+            return Err(anyhow::anyhow!(
+                "History entry {} has no speaker {}",
+                entry_id,
+                speaker
+            ));
+        }
+
+        let name = name.split_whitespace().collect::<Vec<_>>().join(" ");
+        if name.chars().count() > MAX_SPEAKER_NAME_CHARS {
+            return Err(anyhow::anyhow!(
+                "Speaker name cannot be longer than {} characters",
+                MAX_SPEAKER_NAME_CHARS
+            ));
+        }
+        if name.is_empty() || name == default_speaker_name(speaker) {
+            conn.execute(
+                "DELETE FROM history_speaker_names WHERE history_id = ?1 AND speaker = ?2",
+                params![entry_id, speaker],
+            )?;
+        } else {
+            set_speaker_name(conn, entry_id, speaker, &name)?;
+        }
+        debug!("Renamed speaker {} of history entry {}", speaker, entry_id);
+        Ok(load_speakers(conn, entry_id)?)
+    }
+
+    fn emit_history_updated(&self) {
+        if let Err(e) = self.app_handle.emit("history-updated", ()) {
+            error!("Failed to emit history-updated event: {}", e);
+        }
+    }
+
+    /// Resolves a recording, following it to its new extension when a format
+    /// migration renamed it after the caller read `file_name`.
     pub fn get_audio_file_path(&self, file_name: &str) -> PathBuf {
-        self.recordings_dir.join(file_name)
+        resolve_recording_path(&self.recordings_dir, file_name)
+    }
+
+    /// Converts every stored recording to `format` on a background thread,
+    /// emitting `recording-migration-progress` after each file.
+    pub fn start_recording_migration(self: &Arc<Self>, format: RecordingFormat) -> Result<()> {
+        if self.recording_migration_running.swap(true, Ordering::SeqCst) {
+            return Err(anyhow::anyhow!("A recording migration is already running"));
+        }
+
+        let manager = Arc::clone(self);
+        std::thread::spawn(move || {
+            if let Err(e) = manager.run_recording_migration(format) {
+                error!("Recording migration failed: {}", e);
+            }
+            manager
+                .recording_migration_running
+                .store(false, Ordering::SeqCst);
+        });
+        Ok(())
+    }
+
+    fn run_recording_migration(&self, format: RecordingFormat) -> Result<()> {
+        let file_names: Vec<String> = {
+            let conn = self.get_connection()?;
+            let mut stmt = conn.prepare(
+                "SELECT DISTINCT file_name FROM transcription_history
+                 WHERE file_name IS NOT NULL AND TRIM(file_name) != ''",
+            )?;
+            let file_names = stmt
+                .query_map([], |row| row.get::<_, String>(0))?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            file_names
+                .into_iter()
+                .filter(|file_name| recording_format_of(file_name) != Some(format))
+                .collect()
+        };
+
+        let mut progress = RecordingMigrationProgress {
+            format: Some(format),
+            total: file_names.len() as u64,
+            ..Default::default()
+        };
+        info!(
+            "Migrating {} recordings to {}",
+            progress.total,
+            format.extension()
+        );
+        self.emit_recording_migration_progress(&progress);
+
+        for file_name in file_names {
+            // The user switched formats again; the next migration takes over.
+            if crate::settings::get_settings(&self.app_handle).recording_format != format {
+                info!("Recording format changed, stopping migration");
+                break;
+            }
+
+            let result = self.with_write_permit(|| {
+                let conn = self.get_connection()?;
+                Self::migrate_recording_with_conn(&conn, &self.recordings_dir, &file_name, format)
+            });
+            match result {
+                Ok(true) => progress.converted += 1,
+                Ok(false) => progress.skipped += 1,
+                Err(e) => {
+                    error!("Failed to migrate recording {}: {}", file_name, e);
+                    progress.failed += 1;
+                }
+            }
+            progress.processed += 1;
+            self.emit_recording_migration_progress(&progress);
+        }
+
+        progress.done = true;
+        self.emit_recording_migration_progress(&progress);
+        if let Err(e) = self.app_handle.emit("history-updated", ()) {
+            error!("Failed to emit history-updated event: {}", e);
+        }
+        info!(
+            "Recording migration finished: {} converted, {} skipped, {} failed",
+            progress.converted, progress.skipped, progress.failed
+        );
+        Ok(())
+    }
+
+    fn emit_recording_migration_progress(&self, progress: &RecordingMigrationProgress) {
+        if let Err(e) = self.app_handle.emit(RECORDING_MIGRATION_EVENT, progress) {
+            error!("Failed to emit recording migration progress: {}", e);
+        }
+    }
+
+    /// Converts one recording and repoints every row that references it.
+    /// Returns `false` when the source file no longer exists.
+    fn migrate_recording_with_conn(
+        conn: &Connection,
+        recordings_dir: &Path,
+        file_name: &str,
+        format: RecordingFormat,
+    ) -> Result<bool> {
+        let source = recordings_dir.join(file_name);
+        if !source.is_file() {
+            return Ok(false);
+        }
+
+        let stem = Path::new(file_name)
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .ok_or_else(|| anyhow::anyhow!("Invalid recording file name {}", file_name))?;
+        let target_name = format!("{}.{}", stem, format.extension());
+        let target = recordings_dir.join(&target_name);
+        if target.exists() {
+            return Err(anyhow::anyhow!("{} already exists", target_name));
+        }
+
+        // Convert next to the target so the final rename is atomic.
+        let partial = recordings_dir.join(format!(".{}.partial.{}", stem, format.extension()));
+        if let Err(e) = convert_recording_file(&source, &partial) {
+            let _ = fs::remove_file(&partial);
+            return Err(e);
+        }
+        fs::rename(&partial, &target)?;
+
+        if let Err(e) = conn.execute(
+            "UPDATE transcription_history SET file_name = ?1 WHERE file_name = ?2",
+            params![target_name, file_name],
+        ) {
+            let _ = fs::remove_file(&target);
+            return Err(e.into());
+        }
+
+        if let Err(e) = fs::remove_file(&source) {
+            error!("Failed to remove migrated recording {}: {}", file_name, e);
+        }
+        debug!("Migrated recording {} to {}", file_name, target_name);
+        Ok(true)
     }
 
     pub async fn delete_entry(&self, id: i64) -> Result<()> {
@@ -1740,11 +3435,12 @@ impl HistoryManager {
             let entries = Self::get_history_entries_with_conn(
                 &conn,
                 &self.recordings_dir,
-                usize::MAX,
+                None,
                 0,
                 None,
                 false,
                 None,
+                None,
             )?;
             let total = entries.len();
 
@@ -1781,9 +3477,12 @@ impl HistoryManager {
              FROM transcription_history WHERE id = ?1",
         )?;
 
-        let entry = stmt
+        let mut entry = stmt
             .query_row([id], |row| map_history_entry(row, recordings_dir))
             .optional()?;
+        if let Some(entry) = entry.as_mut() {
+            attach_entry_details(conn, std::slice::from_mut(entry))?;
+        }
 
         Ok(entry)
     }
@@ -1947,6 +3646,20 @@ mod tests {
             ) VALUES (1, 0, 0, 0, 0, '[]', 0, 0, 0);",
         )
         .expect("create transcription_history table");
+        conn.execute_batch(TRANSCRIPTION_TIMINGS_SCHEMA)
+            .expect("create transcription_timings table");
+        conn.execute_batch(TRANSCRIPTION_HISTORY_FTS_SCHEMA)
+            .expect("create transcription_history_fts table");
+        conn.execute_batch(HISTORY_TAGS_SCHEMA)
+            .expect("create history tag tables");
+        conn.execute_batch(TRANSCRIPTION_REVISIONS_SCHEMA)
+            .expect("create transcription_revisions table");
+        conn.execute_batch(HISTORY_SPEAKERS_SCHEMA)
+            .expect("create history speaker tables");
+        conn.execute_batch(HISTORY_BIAS_TERMS_SCHEMA)
+            .expect("create history_bias_terms table");
+        conn.execute_batch(REVISION_BIAS_TERMS_SCHEMA)
+            .expect("create revision_bias_terms table");
         conn
     }
 
@@ -2169,11 +3882,12 @@ mod tests {
         let entries = HistoryManager::get_history_entries_with_conn(
             &conn,
             &recordings_dir,
-            10,
+            Some(10),
             0,
             None,
             false,
             None,
+            None,
         )
         .expect("fetch history entries");
 
@@ -2186,6 +3900,104 @@ mod tests {
         fs::remove_dir_all(recordings_dir).expect("cleanup recordings dir");
     }
 
+    #[test]
+    fn migrate_recording_converts_file_and_repoints_rows() {
+        let conn = setup_conn();
+        insert_entry(&conn, 100, "first", None, None);
+        let recordings_dir = make_temp_backup_dir("history-recording-migration");
+        let samples: Vec<f32> = (0..8000).map(|i| (i as f32 * 0.03).sin() * 0.3).collect();
+        save_wav_file(recordings_dir.join("codictate-100.wav"), &samples).expect("seed wav");
+
+        let converted = HistoryManager::migrate_recording_with_conn(
+            &conn,
+            &recordings_dir,
+            "codictate-100.wav",
+            RecordingFormat::Flac,
+        )
+        .expect("migrate recording");
+
+        assert!(converted);
+        assert!(!recordings_dir.join("codictate-100.wav").exists());
+        assert!(recordings_dir.join("codictate-100.flac").exists());
+        let file_name: String = conn
+            .query_row("SELECT file_name FROM transcription_history", [], |row| {
+                row.get(0)
+            })
+            .expect("read file name");
+        assert_eq!(file_name, "codictate-100.flac");
+        // Stale references from before the migration still resolve.
+        assert_eq!(
+            resolve_recording_path(&recordings_dir, "codictate-100.wav"),
+            recordings_dir.join("codictate-100.flac")
+        );
+
+        let missing = HistoryManager::migrate_recording_with_conn(
+            &conn,
+            &recordings_dir,
+            "codictate-404.wav",
+            RecordingFormat::Flac,
+        )
+        .expect("skip missing recording");
+        assert!(!missing);
+
+        fs::remove_dir_all(recordings_dir).expect("cleanup recordings dir");
+    }
+
+    #[test]
+    fn timings_round_trip_in_order_with_history_entry() {
+        let conn = setup_conn();
+        insert_entry(&conn, 100, "hello world", None, None);
+        let timings = vec![
+            TranscriptTiming {
+                start_ms: 0,
+                end_ms: 420,
+                text: "hello".to_string(),
+                granularity: TimingGranularity::Word,
+            },
+            TranscriptTiming {
+                start_ms: 480,
+                end_ms: 910,
+                text: "world".to_string(),
+                granularity: TimingGranularity::Word,
+            },
+        ];
+        insert_timings(&conn, 1, &timings).expect("insert timings");
+
+        let dummy_path = std::path::PathBuf::from("/tmp");
+        let entry = HistoryManager::get_entry_by_id_with_conn(&conn, &dummy_path, 1)
+            .expect("fetch entry")
+            .expect("entry exists");
+
+        assert_eq!(entry.timings, timings);
+    }
+
+    #[test]
+    fn deleting_history_entry_removes_its_timings() {
+        let conn = setup_conn();
+        insert_entry(&conn, 100, "first", None, None);
+        insert_entry(&conn, 200, "second", None, None);
+        let timing = TranscriptTiming {
+            start_ms: 0,
+            end_ms: 1000,
+            text: "segment".to_string(),
+            granularity: TimingGranularity::Segment,
+        };
+        insert_timings(&conn, 1, std::slice::from_ref(&timing)).expect("insert timings");
+        insert_timings(&conn, 2, std::slice::from_ref(&timing)).expect("insert timings");
+
+        conn.execute("DELETE FROM transcription_history WHERE id = 1", [])
+            .expect("delete entry");
+
+        let remaining: Vec<i64> = conn
+            .prepare("SELECT history_id FROM transcription_timings")
+            .expect("prepare")
+            .query_map([], |row| row.get(0))
+            .expect("query")
+            .collect::<rusqlite::Result<_>>()
+            .expect("collect");
+        assert_eq!(remaining, vec![2]);
+    }
+
     #[test]
     fn effective_text_uses_inserted_text_first() {
         assert_eq!(
@@ -2635,6 +4447,461 @@ mod tests {
         assert_eq!(ids.len(), 1);
     }
 
+    fn fts_search_ids(conn: &Connection, query: &str) -> Vec<i64> {
+        let dummy_path = std::path::PathBuf::from("/tmp");
+        HistoryManager::search_history_entries_with_conn(
+            conn,
+            &dummy_path,
+            query,
+            50,
+            0,
+            false,
+            None,
+        )
+        .expect("run fts search")
+        .into_iter()
+        .map(|hit| hit.entry.id)
+        .collect()
+    }
+
+    #[test]
+    fn fts_match_expression_quotes_terms_phrases_and_prefixes() {
+        assert_eq!(
+            build_fts_match_expression(r#"meeting "next tuesday" proj*"#, false).as_deref(),
+            Some(r#""meeting" "next tuesday" "proj"*"#)
+        );
+        assert_eq!(
+            build_fts_match_expression("title:foo OR bar", true).as_deref(),
+            Some(r#""title:foo" "OR" "bar"*"#)
+        );
+        assert_eq!(
+            build_fts_match_expression(r#"unterminated "phrase here"#, true).as_deref(),
+            Some(r#""unterminated" "phrase here""#)
+        );
+        assert_eq!(build_fts_match_expression("  ?! * ", true), None);
+    }
+
+    #[test]
+    fn fts_search_supports_stemming_phrases_and_prefixes() {
+        let conn = setup_conn();
+        insert_entry(
+            &conn,
+            100,
+            "schedule the meeting for next tuesday",
+            None,
+            None,
+        );
+        insert_entry(&conn, 200, "tuesday is next to monday", None, None);
+        insert_entry(&conn, 300, "the project kickoff", None, None);
+
+        assert_eq!(fts_search_ids(&conn, "meetings"), vec![1]);
+        assert_eq!(fts_search_ids(&conn, r#""next tuesday""#), vec![1]);
+        assert_eq!(fts_search_ids(&conn, "proj*"), vec![3]);
+        assert!(fts_search_ids(&conn, "budget").is_empty());
+    }
+
+    fn list_search_ids(conn: &Connection, query: &str) -> Vec<i64> {
+        let dummy_path = std::path::PathBuf::from("/tmp");
+        HistoryManager::get_history_entries_with_conn(
+            conn,
+            &dummy_path,
+            Some(50),
+            0,
+            Some(query.to_string()),
+            false,
+            None,
+            None,
+        )
+        .expect("fetch history entries")
+        .into_iter()
+        .map(|entry| entry.id)
+        .collect()
+    }
+
+    #[test]
+    fn search_falls_back_to_substrings_when_no_word_matches() {
+        let conn = setup_conn();
+        insert_entry(&conn, 100, "the project kickoff", None, None);
+        insert_entry(&conn, 200, "weekly summary", None, None);
+
+        // "ject" only occurs inside a word, which FTS tokens cannot match.
+        assert_eq!(fts_search_ids(&conn, "ject"), vec![1]);
+        assert_eq!(list_search_ids(&conn, "ject"), vec![1]);
+        // Whole-word matches still come from the index.
+        assert_eq!(fts_search_ids(&conn, "proj*"), vec![1]);
+
+        let dummy_path = std::path::PathBuf::from("/tmp");
+        let hits = HistoryManager::search_history_entries_with_conn(
+            &conn,
+            &dummy_path,
+            "ject",
+            10,
+            0,
+            false,
+            None,
+        )
+        .expect("run substring search");
+        assert_eq!(hits[0].snippet_html, "the pro<mark>ject</mark> kickoff");
+    }
+
+    #[test]
+    fn history_list_search_returns_word_matches_then_substring_matches() {
+        let conn = setup_conn();
+        insert_entry(&conn, 100, "the project kickoff", None, None);
+        insert_entry(&conn, 200, "weekly summary", None, None);
+        insert_entry(&conn, 300, "subproject notes", None, None);
+
+        // The newer "subproject" only matches as a substring, so it follows the word match.
+        assert_eq!(list_search_ids(&conn, "project"), vec![1, 3]);
+
+        let dummy_path = std::path::PathBuf::from("/tmp");
+        let second_page = HistoryManager::get_history_entries_with_conn(
+            &conn,
+            &dummy_path,
+            Some(1),
+            1,
+            Some("project".to_string()),
+            false,
+            None,
+            None,
+        )
+        .expect("fetch history entries");
+        assert_eq!(second_page.len(), 1);
+        assert_eq!(second_page[0].id, 3);
+    }
+
+    #[test]
+    fn cjk_search_matches_inside_unsegmented_text() {
+        let conn = setup_conn();
+        insert_entry(&conn, 100, "今天的会议很长", None, None);
+        insert_entry(&conn, 200, "明天见", None, None);
+
+        assert_eq!(fts_search_ids(&conn, "会议"), vec![1]);
+        assert_eq!(list_search_ids(&conn, "会议"), vec![1]);
+        assert_eq!(list_search_ids(&conn, "天"), vec![2, 1]);
+    }
+
+    #[test]
+    fn fts_search_ranks_effective_text_and_escapes_snippets() {
+        let conn = setup_conn();
+        insert_entry(&conn, 100, "raw budget", Some("unrelated words"), None);
+        insert_entry(&conn, 200, "draft", Some("<b>budget</b> review"), None);
+
+        let dummy_path = std::path::PathBuf::from("/tmp");
+        let hits = HistoryManager::search_history_entries_with_conn(
+            &conn,
+            &dummy_path,
+            "budget",
+            10,
+            0,
+            false,
+            None,
+        )
+        .expect("run fts search");
+
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].entry.id, 2);
+        assert!(hits[0].score >= hits[1].score);
+        assert_eq!(
+            hits[0].snippet_html,
+            "&lt;b&gt;<mark>budget</mark>&lt;/b&gt; review"
+        );
+    }
+
+    #[test]
+    fn fts_index_follows_updates_and_deletes() {
+        let conn = setup_conn();
+        insert_entry(&conn, 100, "original words", None, None);
+
+        HistoryManager::update_inserted_text_by_id_with_conn(&conn, 1, "replacement".to_string())
+            .expect("update inserted text");
+        assert_eq!(fts_search_ids(&conn, "replacement"), vec![1]);
+        // Raw text stays searchable after the effective text changes.
+        assert_eq!(fts_search_ids(&conn, "original"), vec![1]);
+
+        conn.execute("DELETE FROM transcription_history WHERE id = 1", [])
+            .expect("delete entry");
+        assert!(fts_search_ids(&conn, "original").is_empty());
+    }
+
+    #[test]
+    fn fts_schema_backfills_existing_rows() {
+        let conn = setup_conn();
+        conn.execute_batch(
+            "DROP TABLE transcription_history_fts;
+             DROP TRIGGER IF EXISTS transcription_history_fts_insert;",
+        )
+        .expect("drop fts index");
+        insert_entry(&conn, 100, "legacy dictation", None, None);
+
+        conn.execute_batch(TRANSCRIPTION_HISTORY_FTS_SCHEMA)
+            .expect("recreate fts index");
+        conn.execute_batch(TRANSCRIPTION_HISTORY_FTS_SCHEMA)
+            .expect("fts schema is idempotent");
+        assert_eq!(fts_search_ids(&conn, "legacy"), vec![1]);
+    }
+
+    #[test]
+    fn tag_filter_limits_entries_and_entries_carry_tags() {
+        let conn = setup_conn();
+        insert_entry(&conn, 100, "groceries", None, None);
+        insert_entry(&conn, 200, "standup notes", None, None);
+        let tag = get_or_create_tag(&conn, "Work", HistoryTagKind::Notebook).expect("create tag");
+        HistoryManager::tag_entry_with_conn(&conn, 2, tag.id).expect("tag entry");
+        // Tagging twice is a no-op rather than an error.
+        HistoryManager::tag_entry_with_conn(&conn, 2, tag.id).expect("tag entry again");
+
+        let dummy_path = std::path::PathBuf::from("/tmp");
+        let entries = HistoryManager::get_history_entries_with_conn(
+            &conn,
+            &dummy_path,
+            Some(10),
+            0,
+            None,
+            false,
+            None,
+            Some(tag.id),
+        )
+        .expect("fetch tagged entries");
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].id, 2);
+        assert_eq!(entries[0].tags, vec![tag.clone()]);
+
+        let summaries = HistoryManager::list_tags_with_conn(&conn).expect("list tags");
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0].entry_count, 1);
+    }
+
+    #[test]
+    fn tag_names_are_unique_per_kind_case_insensitively() {
+        let conn = setup_conn();
+        let work = get_or_create_tag(&conn, "Work", HistoryTagKind::Tag).expect("create tag");
+        let again = get_or_create_tag(&conn, "work", HistoryTagKind::Tag).expect("reuse tag");
+        let notebook =
+            get_or_create_tag(&conn, "Work", HistoryTagKind::Notebook).expect("create notebook");
+        let other = get_or_create_tag(&conn, "Home", HistoryTagKind::Tag).expect("create tag");
+
+        assert_eq!(work.id, again.id);
+        assert_ne!(work.id, notebook.id);
+        assert!(HistoryManager::rename_tag_with_conn(&conn, other.id, "WORK").is_err());
+        assert_eq!(
+            HistoryManager::rename_tag_with_conn(&conn, other.id, "House")
+                .expect("rename tag")
+                .name,
+            "House"
+        );
+        assert!(normalize_tag_name("   ").is_err());
+        assert_eq!(normalize_tag_name("  two   words ").unwrap(), "two words");
+    }
+
+    #[test]
+    fn tag_links_are_removed_with_entries_and_tags() {
+        let conn = setup_conn();
+        insert_entry(&conn, 100, "first", None, None);
+        insert_entry(&conn, 200, "second", None, None);
+        let tag = get_or_create_tag(&conn, "Keep", HistoryTagKind::Tag).expect("create tag");
+        HistoryManager::tag_entry_with_conn(&conn, 1, tag.id).expect("tag entry");
+        HistoryManager::tag_entry_with_conn(&conn, 2, tag.id).expect("tag entry");
+        assert!(HistoryManager::tag_entry_with_conn(&conn, 99, tag.id).is_err());
+
+        conn.execute("DELETE FROM transcription_history WHERE id = 1", [])
+            .expect("delete entry");
+        assert_eq!(
+            HistoryManager::list_tags_with_conn(&conn).expect("list tags")[0].entry_count,
+            1
+        );
+
+        conn.execute("DELETE FROM history_tags WHERE id = ?1", params![tag.id])
+            .expect("delete tag");
+        let links: i64 = conn
+            .query_row("SELECT COUNT(*) FROM history_entry_tags", [], |row| {
+                row.get(0)
+            })
+            .expect("count links");
+        assert_eq!(links, 0);
+    }
+
+    #[test]
+    fn revisions_are_diffed_against_the_entry_and_removed_with_it() {
+        let conn = setup_conn();
+        insert_entry(&conn, 100, "ship the build on friday", None, None);
+        insert_revision(
+            &conn,
+            1,
+            150,
+            "parakeet-tdt-0.6b-v3",
+            "Parakeet V3",
+            "ship the build on Friday",
+            420,
+            &["Friday".to_string(), "build".to_string()],
+        )
+        .expect("insert revision");
+
+        let comparisons =
+            HistoryManager::list_revisions_with_conn(&conn, 1).expect("list revisions");
+        assert_eq!(comparisons.len(), 1);
+        assert_eq!(comparisons[0].revision.model_id, "parakeet-tdt-0.6b-v3");
+        assert_eq!(comparisons[0].revision.bias_terms, vec!["Friday", "build"]);
+        assert_eq!(
+            comparisons[0].diff,
+            diff_words("ship the build on friday", "ship the build on Friday")
+        );
+        assert!(HistoryManager::list_revisions_with_conn(&conn, 99).is_err());
+
+        conn.execute("DELETE FROM transcription_history WHERE id = 1", [])
+            .expect("delete entry");
+        assert!(load_revisions(&conn, 1).expect("load revisions").is_empty());
+        let orphaned_terms: i64 = conn
+            .query_row("SELECT COUNT(*) FROM revision_bias_terms", [], |row| {
+                row.get(0)
+            })
+            .expect("count revision bias terms");
+        assert_eq!(orphaned_terms, 0);
+    }
+
+    #[test]
+    fn speakers_are_named_by_default_renamed_and_removed_with_the_entry() {
+        let conn = setup_conn();
+        insert_entry(&conn, 100, "so what brings you here", None, None);
+        let turns = vec![
+            HistorySpeakerTurn {
+                start_ms: 0,
+                end_ms: 1_500,
+                speaker: 1,
+            },
+            HistorySpeakerTurn {
+                start_ms: 1_500,
+                end_ms: 4_000,
+                speaker: 2,
+            },
+            HistorySpeakerTurn {
+                start_ms: 4_000,
+                end_ms: 5_000,
+                speaker: 1,
+            },
+        ];
+        insert_speaker_turns(&conn, 1, &turns).expect("insert speaker turns");
+        assert_eq!(load_speaker_turns(&conn, 1).expect("load turns"), turns);
+        assert_eq!(
+            load_speakers(&conn, 1).expect("load speakers"),
+            vec![
+                HistorySpeaker {
+                    speaker: 1,
+                    name: "Speaker 1".to_string(),
+                },
+                HistorySpeaker {
+                    speaker: 2,
+                    name: "Speaker 2".to_string(),
+                },
+            ]
+        );
+
+        let speakers = HistoryManager::rename_speaker_with_conn(&conn, 1, 2, "  Dr.   Chen ")
+            .expect("rename speaker");
+        assert_eq!(speakers[1].name, "Dr. Chen");
+        assert!(HistoryManager::rename_speaker_with_conn(&conn, 1, 3, "Nobody").is_err());
+
+        let speakers =
+            HistoryManager::rename_speaker_with_conn(&conn, 1, 2, "").expect("reset speaker");
+        assert_eq!(speakers[1].name, "Speaker 2");
+        assert!(load_speaker_names(&conn, 1).expect("load names").is_empty());
+
+        set_speaker_name(&conn, 1, 1, "Host").expect("name speaker");
+        conn.execute("DELETE FROM transcription_history WHERE id = 1", [])
+            .expect("delete entry");
+        assert!(load_speaker_turns(&conn, 1).expect("load turns").is_empty());
+        assert!(load_speaker_names(&conn, 1).expect("load names").is_empty());
+    }
+
+    #[test]
+    fn speaker_turns_are_replaced_on_saved_entries_only() {
+        let mut conn = setup_conn();
+        insert_entry(&conn, 100, "so what brings you here", None, None);
+        let turn = |start_ms, end_ms, speaker| HistorySpeakerTurn {
+            start_ms,
+            end_ms,
+            speaker,
+        };
+        insert_speaker_turns(&conn, 1, &[turn(0, 900, 1)]).expect("insert speaker turns");
+
+        let turns = vec![turn(0, 1_500, 1), turn(1_500, 4_000, 2)];
+        HistoryManager::set_speaker_turns_with_conn(&mut conn, 1, &turns)
+            .expect("set speaker turns");
+        assert_eq!(load_speaker_turns(&conn, 1).expect("load turns"), turns);
+
+        assert!(HistoryManager::set_speaker_turns_with_conn(&mut conn, 2, &turns).is_err());
+        assert!(load_speaker_turns(&conn, 2).expect("load turns").is_empty());
+    }
+
+    #[test]
+    fn bias_terms_keep_their_order_and_are_removed_with_the_entry() {
+        let conn = setup_conn();
+        insert_entry(&conn, 100, "deploy acme cloud with kubernetes", None, None);
+        let terms = vec!["Acme Cloud".to_string(), "Kubernetes".to_string()];
+        insert_bias_terms(&conn, 1, &terms).expect("insert bias terms");
+        assert_eq!(load_bias_terms(&conn, 1).expect("load bias terms"), terms);
+        assert!(load_bias_terms(&conn, 2)
+            .expect("load bias terms")
+            .is_empty());
+
+        conn.execute("DELETE FROM transcription_history WHERE id = 1", [])
+            .expect("delete entry");
+        assert!(load_bias_terms(&conn, 1)
+            .expect("load bias terms")
+            .is_empty());
+    }
+
+    #[test]
+    fn history_list_search_uses_fts_prefix_for_last_term() {
+        let conn = setup_conn();
+        insert_entry(&conn, 100, "quarterly report", None, None);
+        insert_entry(&conn, 200, "weekly summary", None, None);
+
+        let dummy_path = std::path::PathBuf::from("/tmp");
+        let entries = HistoryManager::get_history_entries_with_conn(
+            &conn,
+            &dummy_path,
+            Some(10),
+            0,
+            Some("quarterly rep".to_string()),
+            false,
+            None,
+            None,
+        )
+        .expect("fetch history entries");
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].transcription_text, "quarterly report");
+    }
+
+    #[test]
+    fn history_list_without_limit_returns_every_entry_after_offset() {
+        let conn = setup_conn();
+        insert_entry(&conn, 100, "first", None, None);
+        insert_entry(&conn, 200, "second", None, None);
+        insert_entry(&conn, 300, "third", None, None);
+
+        let dummy_path = std::path::PathBuf::from("/tmp");
+        let entries = HistoryManager::get_history_entries_with_conn(
+            &conn,
+            &dummy_path,
+            None,
+            1,
+            None,
+            false,
+            None,
+            None,
+        )
+        .expect("fetch history entries");
+
+        let texts: Vec<&str> = entries
+            .iter()
+            .map(|entry| entry.transcription_text.as_str())
+            .collect();
+        assert_eq!(texts, vec!["second", "first"]);
+    }
+
     fn read_stats(conn: &Connection) -> (i64, i64, i64, i64, String, i64) {
         conn.query_row(
             "SELECT total_words, total_duration_ms, total_speech_duration_ms, total_transcriptions, transcription_dates, total_filler_words_removed FROM user_stats WHERE id = 1",
@@ -0,0 +1,357 @@
+//! Long-form recordings: chunks cut by the recorder are spooled to disk and
+//! transcribed in the background while the recording continues, then stitched
+//! into a single transcript when it stops.
+
+use crate::audio_toolkit::audio::{
+    load_audio_file_for_transcription, save_wav_file, stitch_chunk_text, CapturedAudio,
+    RecordedChunk,
+};
+use crate::audio_toolkit::constants::WHISPER_SAMPLE_RATE;
+use crate::managers::history::{TimingGranularity, TranscriptTiming};
+use crate::managers::transcription::{
+    map_timings_to_capture, TranscriptionManager, TranscriptionOutput,
+};
+use anyhow::Result;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread::{self, JoinHandle};
+use tauri::{AppHandle, Manager};
+use tracing::{debug, error, info, warn};
+
+const SPOOL_DIR_NAME: &str = "long-form";
+
+fn samples_to_ms(samples: usize) -> i64 {
+    (samples as u64 * 1000 / WHISPER_SAMPLE_RATE as u64) as i64
+}
+
+/// Chunk files of a finished long-form recording. The spool directory is
+/// removed when this is dropped, i.e. once the history entry has been written.
+pub struct SpooledRecording {
+    dir: PathBuf,
+    parts: Vec<(PathBuf, usize)>,
+}
+
+impl SpooledRecording {
+    /// Chunk files in order, each with the number of leading samples it
+    /// shares with the previous chunk.
+    pub fn parts(&self) -> &[(PathBuf, usize)] {
+        &self.parts
+    }
+}
+
+impl Drop for SpooledRecording {
+    fn drop(&mut self) {
+        remove_spool_dir(&self.dir);
+    }
+}
+
+/// Write `samples` to `path`, returning it unless there was nothing to write
+/// or writing failed.
+fn spool_samples(path: PathBuf, samples: &[f32]) -> Option<PathBuf> {
+    if samples.is_empty() {
+        return None;
+    }
+    match save_wav_file(&path, samples) {
+        Ok(()) => Some(path),
+        Err(e) => {
+            error!("Failed to spool long-form audio {:?}: {}", path, e);
+            None
+        }
+    }
+}
+
+fn remove_spool_dir(dir: &Path) {
+    if let Err(e) = fs::remove_dir_all(dir) {
+        if e.kind() != std::io::ErrorKind::NotFound {
+            warn!("Failed to remove long-form spool {:?}: {}", dir, e);
+        }
+    }
+}
+
+struct SpooledChunk {
+    /// Speech the engine hears; `None` when the VAD kept nothing.
+    speech: Option<PathBuf>,
+    len: usize,
+    /// Leading samples shared with the previous chunk.
+    overlap: usize,
+    /// The same stretch of the recording with its pauses, if it was captured.
+    capture: Option<PathBuf>,
+}
+
+struct ChunkTranscript {
+    chunk: SpooledChunk,
+    output: Result<TranscriptionOutput, String>,
+}
+
+/// Transcript and recording assembled from every chunk of a long-form session.
+pub struct FinishedLongForm {
+    pub output: TranscriptionOutput,
+    pub recording: SpooledRecording,
+}
+
+/// Spooling and background transcription for one long-form recording.
+///
+/// Chunks pass through two threads: the spooler writes each one to disk as
+/// soon as the recorder cuts it, and the transcriber reads them back one at a
+/// time, so memory stays bounded even when transcription falls behind.
+pub(crate) struct LongFormSession {
+    session_id: String,
+    dir: PathBuf,
+    chunk_tx: mpsc::Sender<(RecordedChunk, usize)>,
+    spooler: JoinHandle<()>,
+    transcriber: JoinHandle<Vec<ChunkTranscript>>,
+    cancelled: Arc<AtomicBool>,
+    next_overlap: usize,
+    speech_samples: usize,
+}
+
+impl LongFormSession {
+    pub(crate) fn begin(
+        app_handle: &AppHandle,
+        transcription_manager: TranscriptionManager,
+        session_id: &str,
+    ) -> Result<Self> {
+        let spool_root = app_handle.path().app_data_dir()?.join(SPOOL_DIR_NAME);
+        // Only one recording runs at a time, so anything left here is from a
+        // session that never finished (e.g. the app was killed).
+        remove_spool_dir(&spool_root);
+        let dir = spool_root.join(session_id);
+        fs::create_dir_all(&dir)?;
+
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let (chunk_tx, chunk_rx) = mpsc::channel::<(RecordedChunk, usize)>();
+        let (spooled_tx, spooled_rx) = mpsc::channel::<SpooledChunk>();
+
+        let spooler = thread::spawn({
+            let dir = dir.clone();
+            let cancelled = Arc::clone(&cancelled);
+            move || {
+                for (index, (chunk, overlap)) in chunk_rx.into_iter().enumerate() {
+                    if cancelled.load(Ordering::Relaxed) {
+                        return;
+                    }
+                    let speech = dir.join(format!("chunk-{index:04}.wav"));
+                    let capture = dir.join(format!("capture-{index:04}.wav"));
+                    let spooled = SpooledChunk {
+                        speech: spool_samples(speech, &chunk.samples),
+                        len: chunk.samples.len(),
+                        overlap,
+                        capture: spool_samples(capture, &chunk.capture),
+                    };
+                    if spooled_tx.send(spooled).is_err() {
+                        return;
+                    }
+                }
+            }
+        });
+
+        let transcriber = thread::spawn({
+            let cancelled = Arc::clone(&cancelled);
+            let session_id = session_id.to_string();
+            move || {
+                let mut transcripts = Vec::new();
+                for chunk in spooled_rx {
+                    if cancelled.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    let output = match &chunk.speech {
+                        Some(path) => load_audio_file_for_transcription(path)
+                            .and_then(|samples| transcription_manager.transcribe(samples))
+                            .map_err(|e| e.to_string()),
+                        None => Ok(TranscriptionOutput::default()),
+                    };
+                    match &output {
+                        Ok(output) => debug!(
+                            session = %session_id,
+                            chunk = transcripts.len(),
+                            chars = output.text.len(),
+                            "Long-form chunk transcribed"
+                        ),
+                        Err(e) => warn!(
+                            session = %session_id,
+                            chunk = transcripts.len(),
+                            error = %e,
+                            event_code = "long_form_chunk_failed",
+                            "Long-form chunk transcription failed"
+                        ),
+                    }
+                    transcripts.push(ChunkTranscript { chunk, output });
+                }
+                transcripts
+            }
+        });
+
+        info!(
+            session = session_id,
+            event_code = "long_form_session_started",
+            "Long-form recording started"
+        );
+        Ok(Self {
+            session_id: session_id.to_string(),
+            dir,
+            chunk_tx,
+            spooler,
+            transcriber,
+            cancelled,
+            next_overlap: 0,
+            speech_samples: 0,
+        })
+    }
+
+    pub(crate) fn submit(&mut self, chunk: RecordedChunk) {
+        let overlap = std::mem::replace(&mut self.next_overlap, chunk.carried_over);
+        self.speech_samples += chunk.samples.len().saturating_sub(overlap);
+        let _ = self.chunk_tx.send((chunk, overlap));
+    }
+
+    /// Speech spooled so far, excluding audio repeated by chunk overlaps.
+    pub(crate) fn speech_ms(&self) -> i64 {
+        samples_to_ms(self.speech_samples)
+    }
+
+    /// Queue the audio recorded after the last chunk, wait for every chunk to
+    /// be transcribed and stitch the results. With the recorder's `captured`
+    /// audio the recording keeps its pauses and timings are moved onto it.
+    pub(crate) fn finish(
+        mut self,
+        tail: Vec<f32>,
+        captured: Option<CapturedAudio>,
+    ) -> Result<FinishedLongForm> {
+        let (tail_capture, speech_map) = match captured {
+            Some(captured) => (captured.samples, Some(captured.speech_map)),
+            None => (Vec::new(), None),
+        };
+        if !tail.is_empty() || !tail_capture.is_empty() {
+            self.submit(RecordedChunk {
+                samples: tail,
+                carried_over: 0,
+                capture: tail_capture,
+            });
+        }
+        let Self {
+            session_id,
+            dir,
+            chunk_tx,
+            spooler,
+            transcriber,
+            speech_samples,
+            ..
+        } = self;
+        drop(chunk_tx);
+        let _ = spooler.join();
+        let transcripts = transcriber
+            .join()
+            .map_err(|_| anyhow::anyhow!("Long-form transcription worker panicked"))?;
+
+        let chunk_count = transcripts.len();
+        let mut text = String::new();
+        let mut filler_words_removed = 0;
+        let mut timings = Vec::new();
+        let mut bias_terms: Vec<String> = Vec::new();
+        let mut chunk_timings = Vec::with_capacity(chunk_count);
+        let mut speech_parts = Vec::with_capacity(chunk_count);
+        let mut capture_parts = Some(Vec::with_capacity(chunk_count));
+        let mut last_error = None;
+        let mut offset = 0usize;
+        let mut covered_ms = 0i64;
+        for ChunkTranscript { chunk, output } in transcripts {
+            let start = offset.saturating_sub(chunk.overlap);
+            let start_ms = samples_to_ms(start);
+            offset = start + chunk.len;
+            let end_ms = samples_to_ms(offset);
+
+            let chunk_text = match output {
+                Ok(output) => {
+                    stitch_chunk_text(&mut text, &output.text, chunk.overlap > 0);
+                    filler_words_removed += output.filler_words_removed;
+                    // Chunks usually share one dictionary; keep each hint once.
+                    for term in output.bias_terms {
+                        if !bias_terms.contains(&term) {
+                            bias_terms.push(term);
+                        }
+                    }
+                    // Engine timings inside the overlap duplicate the previous chunk's.
+                    timings.extend(
+                        output
+                            .timings
+                            .into_iter()
+                            .map(|timing| TranscriptTiming {
+                                start_ms: timing.start_ms + start_ms,
+                                end_ms: timing.end_ms + start_ms,
+                                ..timing
+                            })
+                            .filter(|timing| timing.start_ms >= covered_ms),
+                    );
+                    output.text
+                }
+                Err(e) => {
+                    last_error = Some(e);
+                    String::new()
+                }
+            };
+            if chunk.len > 0 {
+                chunk_timings.push(TranscriptTiming {
+                    start_ms,
+                    end_ms,
+                    text: chunk_text,
+                    granularity: TimingGranularity::Chunk,
+                });
+            }
+            covered_ms = end_ms;
+            if let Some(path) = chunk.speech {
+                speech_parts.push((path, chunk.overlap));
+            }
+            match (capture_parts.as_mut(), chunk.capture) {
+                (Some(parts), Some(path)) => parts.push((path, 0)),
+                _ => capture_parts = None,
+            }
+        }
+        timings.extend(chunk_timings);
+
+        // Keep the pauses when every chunk has them; timings follow the audio.
+        let parts = match (capture_parts, speech_map) {
+            (Some(parts), Some(speech_map)) if !parts.is_empty() => {
+                map_timings_to_capture(&mut timings, &speech_map);
+                parts
+            }
+            _ => speech_parts,
+        };
+        let recording = SpooledRecording { dir, parts };
+        if let Some(e) = last_error.filter(|_| text.is_empty()) {
+            return Err(anyhow::anyhow!("Long-form transcription failed: {}", e));
+        }
+
+        info!(
+            session = %session_id,
+            chunks = chunk_count,
+            speech_ms = samples_to_ms(speech_samples),
+            event_code = "long_form_session_finished",
+            "Long-form recording transcribed"
+        );
+        Ok(FinishedLongForm {
+            output: TranscriptionOutput {
+                text,
+                filler_words_removed,
+                timings,
+                bias_terms,
+            },
+            recording,
+        })
+    }
+
+    /// Drop the session without transcribing what is left. The transcriber
+    /// stops after its current chunk; spooled audio is deleted.
+    pub(crate) fn cancel(self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+        drop(self.chunk_tx);
+        let _ = self.spooler.join();
+        remove_spool_dir(&self.dir);
+        debug!(
+            session = %self.session_id,
+            event_code = "long_form_session_cancelled",
+            "Long-form recording discarded"
+        );
+    }
+}
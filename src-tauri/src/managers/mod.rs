@@ -1,7 +1,11 @@
 pub mod audio;
 pub mod correction;
+pub mod custom_models;
 pub mod history;
+pub mod long_form;
 #[cfg(all(target_os = "macos", target_arch = "aarch64"))]
 pub mod mlx;
 pub mod model;
+pub mod model_catalog;
+pub mod model_download;
 pub mod transcription;
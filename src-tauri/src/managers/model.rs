@@ -1,25 +1,22 @@
+use crate::managers::custom_models;
+use crate::managers::model_catalog::{self, ModelCatalogStatus};
+use crate::managers::model_download::{self, ModelTransfer, TransferOutcome};
+use crate::model_bundle::{self, ModelBundleExportReport, ModelBundleImportReport};
 use crate::settings::{get_settings, write_settings};
 use anyhow::Result;
 use flate2::read::GzDecoder;
-use futures_util::StreamExt;
 use tracing::{debug, info, warn};
 use serde::{Deserialize, Serialize};
 use specta::Type;
 use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::fs::File;
-use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use tar::Archive;
 use tauri::{AppHandle, Emitter, Manager};
-use tokio::time::timeout;
-
-const DOWNLOAD_REQUEST_TIMEOUT: Duration = Duration::from_secs(20);
-const DOWNLOAD_STALL_TIMEOUT: Duration = Duration::from_secs(30);
-const DOWNLOAD_POLL_INTERVAL: Duration = Duration::from_secs(1);
 
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub enum EngineType {
@@ -38,6 +35,8 @@ pub struct ModelInfo {
     pub description: String,
     pub filename: String,
     pub url: Option<String>,
+    pub mirrors: Vec<String>, // Fallback download URLs, tried in order after `url`
+    pub sha256: Option<String>, // Expected SHA-256 of the download, when the catalog pins one
     pub size_mb: u64,
     pub is_downloaded: bool,
     pub is_downloading: bool,
@@ -50,6 +49,7 @@ pub struct ModelInfo {
     pub is_recommended: bool,       // Whether this is the recommended model for new users
     pub supported_languages: Vec<String>, // Languages this model can transcribe
     pub is_custom: bool,            // Whether this is a user-provided custom model
+    pub load_error: Option<String>, // Why the model can't be used: invalid custom layout or last failed load
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
@@ -68,384 +68,56 @@ pub struct DownloadFailureEvent {
     pub resumable: bool,
 }
 
+/// Display name for a custom model id: `-` and `_` become spaces and each
+/// word is capitalized.
+pub(crate) fn custom_model_display_name(model_id: &str) -> String {
+    model_id
+        .replace(['-', '_'], " ")
+        .split_whitespace()
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                None => String::new(),
+                Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
 pub struct ModelManager {
     app_handle: AppHandle,
+    app_data_dir: PathBuf,
     models_dir: PathBuf,
     available_models: Mutex<HashMap<String, ModelInfo>>,
+    catalog_status: Mutex<ModelCatalogStatus>,
     cancel_flags: Arc<Mutex<HashMap<String, Arc<AtomicBool>>>>,
     extracting_models: Arc<Mutex<HashSet<String>>>,
 }
 
 impl ModelManager {
     pub fn new(app_handle: &AppHandle) -> Result<Self> {
-        // Create models directory in app data
-        let models_dir = app_handle
+        let app_data_dir = app_handle
             .path()
             .app_data_dir()
-            .map_err(|e| anyhow::anyhow!("Failed to get app data dir: {}", e))?
-            .join("models");
+            .map_err(|e| anyhow::anyhow!("Failed to get app data dir: {}", e))?;
+
+        // Create models directory in app data
+        let models_dir = app_data_dir.join("models");
 
         if !models_dir.exists() {
             fs::create_dir_all(&models_dir)?;
         }
 
-        let mut available_models = HashMap::new();
-
-        // Whisper supported languages (99 languages from tokenizer)
-        // Including zh-Hans and zh-Hant variants to match frontend language codes
-        let whisper_languages: Vec<String> = vec![
-            "en", "zh", "zh-Hans", "zh-Hant", "de", "es", "ru", "ko", "fr", "ja", "pt", "tr", "pl",
-            "ca", "nl", "ar", "sv", "it", "id", "hi", "fi", "vi", "he", "uk", "el", "ms", "cs",
-            "ro", "da", "hu", "ta", "no", "th", "ur", "hr", "bg", "lt", "la", "mi", "ml", "cy",
-            "sk", "te", "fa", "lv", "bn", "sr", "az", "sl", "kn", "et", "mk", "br", "eu", "is",
-            "hy", "ne", "mn", "bs", "kk", "sq", "sw", "gl", "mr", "pa", "si", "km", "sn", "yo",
-            "so", "af", "oc", "ka", "be", "tg", "sd", "gu", "am", "yi", "lo", "uz", "fo", "ht",
-            "ps", "tk", "nn", "mt", "sa", "lb", "my", "bo", "tl", "mg", "as", "tt", "haw", "ln",
-            "ha", "ba", "jw", "su", "yue",
-        ]
-        .into_iter()
-        .map(String::from)
-        .collect();
-
-        // TODO this should be read from a JSON file or something..
-        available_models.insert(
-            "small".to_string(),
-            ModelInfo {
-                id: "small".to_string(),
-                name: "Whisper Small".to_string(),
-                description: "Fast and fairly accurate.".to_string(),
-                filename: "ggml-small.bin".to_string(),
-                url: Some("https://blob.handy.computer/ggml-small.bin".to_string()),
-                size_mb: 487,
-                is_downloaded: false,
-                is_downloading: false,
-                partial_size: 0,
-                is_directory: false,
-                engine_type: EngineType::Whisper,
-                accuracy_score: 0.60,
-                speed_score: 0.85,
-                supports_translation: true,
-                is_recommended: false,
-                supported_languages: whisper_languages.clone(),
-                is_custom: false,
-            },
-        );
-
-        // Add downloadable models
-        available_models.insert(
-            "medium".to_string(),
-            ModelInfo {
-                id: "medium".to_string(),
-                name: "Whisper Medium".to_string(),
-                description: "Good accuracy, medium speed".to_string(),
-                filename: "whisper-medium-q4_1.bin".to_string(),
-                url: Some("https://blob.handy.computer/whisper-medium-q4_1.bin".to_string()),
-                size_mb: 492, // Approximate size
-                is_downloaded: false,
-                is_downloading: false,
-                partial_size: 0,
-                is_directory: false,
-                engine_type: EngineType::Whisper,
-                accuracy_score: 0.75,
-                speed_score: 0.60,
-                supports_translation: true,
-                is_recommended: false,
-                supported_languages: whisper_languages.clone(),
-                is_custom: false,
-            },
-        );
-
-        available_models.insert(
-            "turbo".to_string(),
-            ModelInfo {
-                id: "turbo".to_string(),
-                name: "Whisper Turbo".to_string(),
-                description: "Balanced accuracy and speed.".to_string(),
-                filename: "ggml-large-v3-turbo.bin".to_string(),
-                url: Some("https://blob.handy.computer/ggml-large-v3-turbo.bin".to_string()),
-                size_mb: 1600, // Approximate size
-                is_downloaded: false,
-                is_downloading: false,
-                partial_size: 0,
-                is_directory: false,
-                engine_type: EngineType::Whisper,
-                accuracy_score: 0.80,
-                speed_score: 0.75,
-                supports_translation: false, // Turbo doesn't support translation
-                is_recommended: false,
-                supported_languages: whisper_languages.clone(),
-                is_custom: false,
-            },
-        );
-
-        available_models.insert(
-            "large".to_string(),
-            ModelInfo {
-                id: "large".to_string(),
-                name: "Whisper Large".to_string(),
-                description: "Good accuracy, but slow.".to_string(),
-                filename: "ggml-large-v3-q5_0.bin".to_string(),
-                url: Some("https://blob.handy.computer/ggml-large-v3-q5_0.bin".to_string()),
-                size_mb: 1100, // Approximate size
-                is_downloaded: false,
-                is_downloading: false,
-                partial_size: 0,
-                is_directory: false,
-                engine_type: EngineType::Whisper,
-                accuracy_score: 0.85,
-                speed_score: 0.30,
-                supports_translation: true,
-                is_recommended: false,
-                supported_languages: whisper_languages.clone(),
-                is_custom: false,
-            },
-        );
-
-        available_models.insert(
-            "breeze-asr".to_string(),
-            ModelInfo {
-                id: "breeze-asr".to_string(),
-                name: "Breeze ASR".to_string(),
-                description: "Optimized for Taiwanese Mandarin. Code-switching support."
-                    .to_string(),
-                filename: "breeze-asr-q5_k.bin".to_string(),
-                url: Some("https://blob.handy.computer/breeze-asr-q5_k.bin".to_string()),
-                size_mb: 1080,
-                is_downloaded: false,
-                is_downloading: false,
-                partial_size: 0,
-                is_directory: false,
-                engine_type: EngineType::Whisper,
-                accuracy_score: 0.85,
-                speed_score: 0.35,
-                supports_translation: false,
-                is_recommended: false,
-                supported_languages: whisper_languages,
-                is_custom: false,
-            },
-        );
-
-        // Add NVIDIA Parakeet models (directory-based)
-        available_models.insert(
-            "parakeet-tdt-0.6b-v2".to_string(),
-            ModelInfo {
-                id: "parakeet-tdt-0.6b-v2".to_string(),
-                name: "Parakeet V2".to_string(),
-                description: "English only. The best model for English speakers.".to_string(),
-                filename: "parakeet-tdt-0.6b-v2-int8".to_string(), // Directory name
-                url: Some("https://blob.handy.computer/parakeet-v2-int8.tar.gz".to_string()),
-                size_mb: 473, // Approximate size for int8 quantized model
-                is_downloaded: false,
-                is_downloading: false,
-                partial_size: 0,
-                is_directory: true,
-                engine_type: EngineType::Parakeet,
-                accuracy_score: 0.85,
-                speed_score: 0.85,
-                supports_translation: false,
-                is_recommended: false,
-                supported_languages: vec!["en".to_string()],
-                is_custom: false,
-            },
-        );
-
-        // Parakeet V3 supported languages (25 EU languages + Russian/Ukrainian):
-        // bg, hr, cs, da, nl, en, et, fi, fr, de, el, hu, it, lv, lt, mt, pl, pt, ro, sk, sl, es, sv, ru, uk
-        let parakeet_v3_languages: Vec<String> = vec![
-            "bg", "hr", "cs", "da", "nl", "en", "et", "fi", "fr", "de", "el", "hu", "it", "lv",
-            "lt", "mt", "pl", "pt", "ro", "sk", "sl", "es", "sv", "ru", "uk",
-        ]
-        .into_iter()
-        .map(String::from)
-        .collect();
-
-        available_models.insert(
-            "parakeet-tdt-0.6b-v3".to_string(),
-            ModelInfo {
-                id: "parakeet-tdt-0.6b-v3".to_string(),
-                name: "Parakeet V3".to_string(),
-                description: "Fast and accurate. Supports 25 European languages.".to_string(),
-                filename: "parakeet-tdt-0.6b-v3-int8".to_string(), // Directory name
-                url: Some("https://blob.handy.computer/parakeet-v3-int8.tar.gz".to_string()),
-                size_mb: 478, // Approximate size for int8 quantized model
-                is_downloaded: false,
-                is_downloading: false,
-                partial_size: 0,
-                is_directory: true,
-                engine_type: EngineType::Parakeet,
-                accuracy_score: 0.80,
-                speed_score: 0.85,
-                supports_translation: false,
-                is_recommended: true,
-                supported_languages: parakeet_v3_languages,
-                is_custom: false,
-            },
-        );
-
-        available_models.insert(
-            "moonshine-base".to_string(),
-            ModelInfo {
-                id: "moonshine-base".to_string(),
-                name: "Moonshine Base".to_string(),
-                description: "Very fast, English only. Handles accents well.".to_string(),
-                filename: "moonshine-base".to_string(),
-                url: Some("https://blob.handy.computer/moonshine-base.tar.gz".to_string()),
-                size_mb: 58,
-                is_downloaded: false,
-                is_downloading: false,
-                partial_size: 0,
-                is_directory: true,
-                engine_type: EngineType::Moonshine,
-                accuracy_score: 0.70,
-                speed_score: 0.90,
-                supports_translation: false,
-                is_recommended: false,
-                supported_languages: vec!["en".to_string()],
-                is_custom: false,
-            },
-        );
-
-        available_models.insert(
-            "moonshine-tiny-streaming-en".to_string(),
-            ModelInfo {
-                id: "moonshine-tiny-streaming-en".to_string(),
-                name: "Moonshine V2 Tiny".to_string(),
-                description: "Ultra-fast, English only".to_string(),
-                filename: "moonshine-tiny-streaming-en".to_string(),
-                url: Some(
-                    "https://blob.handy.computer/moonshine-tiny-streaming-en.tar.gz".to_string(),
-                ),
-                size_mb: 31,
-                is_downloaded: false,
-                is_downloading: false,
-                partial_size: 0,
-                is_directory: true,
-                engine_type: EngineType::MoonshineStreaming,
-                accuracy_score: 0.55,
-                speed_score: 0.95,
-                supports_translation: false,
-                is_recommended: false,
-                supported_languages: vec!["en".to_string()],
-                is_custom: false,
-            },
-        );
-
-        available_models.insert(
-            "moonshine-small-streaming-en".to_string(),
-            ModelInfo {
-                id: "moonshine-small-streaming-en".to_string(),
-                name: "Moonshine V2 Small".to_string(),
-                description: "Fast, English only. Good balance of speed and accuracy.".to_string(),
-                filename: "moonshine-small-streaming-en".to_string(),
-                url: Some(
-                    "https://blob.handy.computer/moonshine-small-streaming-en.tar.gz".to_string(),
-                ),
-                size_mb: 100,
-                is_downloaded: false,
-                is_downloading: false,
-                partial_size: 0,
-                is_directory: true,
-                engine_type: EngineType::MoonshineStreaming,
-                accuracy_score: 0.65,
-                speed_score: 0.90,
-                supports_translation: false,
-                is_recommended: false,
-                supported_languages: vec!["en".to_string()],
-                is_custom: false,
-            },
-        );
-
-        available_models.insert(
-            "moonshine-medium-streaming-en".to_string(),
-            ModelInfo {
-                id: "moonshine-medium-streaming-en".to_string(),
-                name: "Moonshine V2 Medium".to_string(),
-                description: "English only. High quality.".to_string(),
-                filename: "moonshine-medium-streaming-en".to_string(),
-                url: Some(
-                    "https://blob.handy.computer/moonshine-medium-streaming-en.tar.gz".to_string(),
-                ),
-                size_mb: 192,
-                is_downloaded: false,
-                is_downloading: false,
-                partial_size: 0,
-                is_directory: true,
-                engine_type: EngineType::MoonshineStreaming,
-                accuracy_score: 0.75,
-                speed_score: 0.80,
-                supports_translation: false,
-                is_recommended: false,
-                supported_languages: vec!["en".to_string()],
-                is_custom: false,
-            },
-        );
-
-        // SenseVoice supported languages
-        let sense_voice_languages: Vec<String> =
-            vec!["zh", "zh-Hans", "zh-Hant", "en", "yue", "ja", "ko"]
-                .into_iter()
-                .map(String::from)
-                .collect();
-
-        available_models.insert(
-            "sense-voice-int8".to_string(),
-            ModelInfo {
-                id: "sense-voice-int8".to_string(),
-                name: "SenseVoice".to_string(),
-                description: "Very fast. Chinese, English, Japanese, Korean, Cantonese."
-                    .to_string(),
-                filename: "sense-voice-int8".to_string(),
-                url: Some("https://blob.handy.computer/sense-voice-int8.tar.gz".to_string()),
-                size_mb: 160,
-                is_downloaded: false,
-                is_downloading: false,
-                partial_size: 0,
-                is_directory: true,
-                engine_type: EngineType::SenseVoice,
-                accuracy_score: 0.65,
-                speed_score: 0.95,
-                supports_translation: false,
-                is_recommended: false,
-                supported_languages: sense_voice_languages,
-                is_custom: false,
-            },
-        );
-
-        // GigaAM v3 supported languages
-        let gigaam_languages: Vec<String> = vec!["ru"].into_iter().map(String::from).collect();
-
-        available_models.insert(
-            "gigaam-v3-e2e-ctc".to_string(),
-            ModelInfo {
-                id: "gigaam-v3-e2e-ctc".to_string(),
-                name: "GigaAM v3".to_string(),
-                description: "Russian speech recognition. Fast and accurate.".to_string(),
-                filename: "giga-am-v3.int8.onnx".to_string(),
-                url: Some("https://blob.handy.computer/giga-am-v3.int8.onnx".to_string()),
-                size_mb: 225,
-                is_downloaded: false,
-                is_downloading: false,
-                partial_size: 0,
-                is_directory: false,
-                engine_type: EngineType::GigaAM,
-                accuracy_score: 0.85,
-                speed_score: 0.75,
-                supports_translation: false,
-                is_recommended: false,
-                supported_languages: gigaam_languages,
-                is_custom: false,
-            },
-        );
-
-        // Auto-discover custom Whisper models (.bin files) in the models directory
-        if let Err(e) = Self::discover_custom_whisper_models(&models_dir, &mut available_models) {
-            warn!("Failed to discover custom models: {}", e);
-        }
+        let (available_models, catalog_status) =
+            Self::load_available_models(&app_data_dir, &models_dir);
 
         let manager = Self {
             app_handle: app_handle.clone(),
+            app_data_dir,
             models_dir,
             available_models: Mutex::new(available_models),
+            catalog_status: Mutex::new(catalog_status),
             cancel_flags: Arc::new(Mutex::new(HashMap::new())),
             extracting_models: Arc::new(Mutex::new(HashSet::new())),
         };
@@ -462,6 +134,108 @@ impl ModelManager {
         Ok(manager)
     }
 
+    /// Bundled model catalog, before overrides, download status and custom discovery.
+    pub(crate) fn builtin_models() -> HashMap<String, ModelInfo> {
+        model_catalog::bundled_models()
+    }
+
+    /// The catalog (bundled manifest plus any override) merged with custom
+    /// models discovered in `models_dir`.
+    fn load_available_models(
+        app_data_dir: &Path,
+        models_dir: &Path,
+    ) -> (HashMap<String, ModelInfo>, ModelCatalogStatus) {
+        let catalog = model_catalog::load_catalog(app_data_dir);
+        let mut available_models = catalog.models;
+
+        if let Err(e) = Self::discover_custom_models(models_dir, &mut available_models) {
+            warn!("Failed to discover custom models: {}", e);
+        }
+
+        (available_models, catalog.status)
+    }
+
+    pub fn get_catalog_status(&self) -> ModelCatalogStatus {
+        self.catalog_status.lock().unwrap().clone()
+    }
+
+    /// Re-reads the catalog override and rediscovers custom models, keeping the
+    /// state of downloads that are still running.
+    pub fn reload_catalog(&self) -> Result<ModelCatalogStatus> {
+        let (reloaded, status) = Self::load_available_models(&self.app_data_dir, &self.models_dir);
+
+        let downloading: Vec<String> = {
+            let mut models = self.available_models.lock().unwrap();
+            let downloading = models
+                .values()
+                .filter(|model| model.is_downloading)
+                .map(|model| model.id.clone())
+                .collect();
+            *models = reloaded;
+            downloading
+        };
+        *self.catalog_status.lock().unwrap() = status.clone();
+
+        self.update_download_status()?;
+        {
+            let mut models = self.available_models.lock().unwrap();
+            for id in downloading {
+                if let Some(model) = models.get_mut(&id) {
+                    model.is_downloading = true;
+                }
+            }
+        }
+
+        let _ = self.app_handle.emit("model-catalog-updated", &status);
+        Ok(status)
+    }
+
+    /// Writes the given downloaded models to a signed offline bundle at `output`.
+    pub fn export_bundle(
+        &self,
+        model_ids: &[String],
+        output: &Path,
+    ) -> Result<ModelBundleExportReport> {
+        let models = model_ids
+            .iter()
+            .map(|id| match self.get_model_info(id) {
+                Some(model) if model.is_downloaded => Ok(model),
+                Some(_) => Err(anyhow::anyhow!("Model not downloaded: {}", id)),
+                None => Err(anyhow::anyhow!("Model not found: {}", id)),
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let signing_key = model_bundle::load_or_create_signing_key(
+            &self.app_data_dir.join(model_bundle::SIGNING_KEY_FILE_NAME),
+        )?;
+        model_bundle::export_bundle(&self.models_dir, &models, &signing_key, output)
+    }
+
+    /// Verifies and installs the models in an offline bundle, then refreshes
+    /// the model list. `allow_untrusted` accepts any signer while no trusted
+    /// keys are configured.
+    pub fn import_bundle(
+        &self,
+        bundle_path: &Path,
+        allow_untrusted: bool,
+    ) -> Result<ModelBundleImportReport> {
+        let catalog = self.available_models.lock().unwrap().clone();
+        let trusted_keys = get_settings(&self.app_handle).trusted_model_bundle_keys;
+        let report = model_bundle::import_bundle(
+            bundle_path,
+            &self.models_dir,
+            &catalog,
+            &trusted_keys,
+            allow_untrusted,
+        )?;
+
+        if !report.imported.is_empty() {
+            self.reload_catalog()?;
+            self.auto_select_model_if_needed()?;
+        }
+        Ok(report)
+    }
+
     pub fn get_available_models(&self) -> Vec<ModelInfo> {
         let models = self.available_models.lock().unwrap();
         models.values().cloned().collect()
@@ -575,7 +349,10 @@ impl ModelManager {
         if settings.selected_model.is_empty() {
             // Find the first available (downloaded) model
             let models = self.available_models.lock().unwrap();
-            if let Some(available_model) = models.values().find(|model| model.is_downloaded) {
+            if let Some(available_model) = models
+                .values()
+                .find(|model| model.is_downloaded && model.load_error.is_none())
+            {
                 info!(
                     "Auto-selecting model: {} ({})",
                     available_model.id, available_model.name
@@ -593,9 +370,19 @@ impl ModelManager {
         Ok(())
     }
 
+    /// Discover custom models in the models directory: bare Whisper `.bin` files
+    /// and model directories carrying a metadata sidecar, for any engine.
+    pub(crate) fn discover_custom_models(
+        models_dir: &Path,
+        available_models: &mut HashMap<String, ModelInfo>,
+    ) -> Result<()> {
+        Self::discover_custom_whisper_models(models_dir, available_models)?;
+        custom_models::discover_custom_model_dirs(models_dir, available_models)
+    }
+
     /// Discover custom Whisper models (.bin files) in the models directory.
     /// Skips files that match predefined model filenames.
-    fn discover_custom_whisper_models(
+    pub(crate) fn discover_custom_whisper_models(
         models_dir: &Path,
         available_models: &mut HashMap<String, ModelInfo>,
     ) -> Result<()> {
@@ -657,19 +444,7 @@ impl ModelManager {
                 continue;
             }
 
-            // Generate display name: replace - and _ with space, capitalize words
-            let display_name = model_id
-                .replace(['-', '_'], " ")
-                .split_whitespace()
-                .map(|word| {
-                    let mut chars = word.chars();
-                    match chars.next() {
-                        None => String::new(),
-                        Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
-                    }
-                })
-                .collect::<Vec<_>>()
-                .join(" ");
+            let display_name = custom_model_display_name(&model_id);
 
             // Get file size in MB
             let size_mb = match path.metadata() {
@@ -693,6 +468,8 @@ impl ModelManager {
                     description: "Not officially supported".to_string(),
                     filename,
                     url: None, // Custom models have no download URL
+                    mirrors: vec![],
+                    sha256: None,
                     size_mb,
                     is_downloaded: true, // Already present on disk
                     is_downloading: false,
@@ -705,6 +482,7 @@ impl ModelManager {
                     is_recommended: false,
                     supported_languages: vec![],
                     is_custom: true,
+                    load_error: None,
                 },
             );
         }
@@ -723,7 +501,11 @@ impl ModelManager {
 
         let url = model_info
             .url
+            .clone()
             .ok_or_else(|| anyhow::anyhow!("No download URL for model"))?;
+        let sources: Vec<String> = std::iter::once(url)
+            .chain(model_info.mirrors.iter().cloned())
+            .collect();
         let model_path = self.models_dir.join(&model_info.filename);
         let partial_path = self
             .models_dir
@@ -732,22 +514,16 @@ impl ModelManager {
         // Don't download if complete version already exists
         if model_path.exists() {
             // Clean up any partial file that might exist
-            if partial_path.exists() {
-                let _ = fs::remove_file(&partial_path);
-            }
+            model_download::remove_partial(&partial_path);
             self.update_download_status()?;
             return Ok(());
         }
 
-        // Check if we have a partial download to resume
-        let mut resume_from = if partial_path.exists() {
-            let size = partial_path.metadata()?.len();
-            info!("Resuming download of model {} from byte {}", model_id, size);
-            size
+        if partial_path.exists() {
+            info!("Resuming download of model {}", model_id);
         } else {
-            info!("Starting fresh download of model {} from {}", model_id, url);
-            0
-        };
+            info!("Starting fresh download of model {}", model_id);
+        }
 
         // Mark as downloading
         {
@@ -789,117 +565,44 @@ impl ModelManager {
             let _ = self.app_handle.emit("model-download-failed", &payload);
         };
 
-        // Create HTTP client with range request for resuming
         let client = reqwest::Client::new();
-        let mut request = client.get(&url);
-
-        if resume_from > 0 {
-            request = request.header("Range", format!("bytes={}-", resume_from));
-        }
-
-        let mut response = match timeout(DOWNLOAD_REQUEST_TIMEOUT, request.send()).await {
-            Ok(Ok(response)) => response,
-            Ok(Err(error)) => {
-                let error_msg = format!("Failed to start download: {}", error);
-                mark_download_failed("network_error", error_msg.clone());
-                return Err(anyhow::anyhow!(error_msg));
-            }
-            Err(_) => {
-                let error_msg =
-                    format!("Download request timed out after {:?}", DOWNLOAD_REQUEST_TIMEOUT);
-                mark_download_failed("network_timeout", error_msg.clone());
-                return Err(anyhow::anyhow!(error_msg));
-            }
-        };
-
-        // If we tried to resume but server returned 200 (not 206 Partial Content),
-        // the server doesn't support range requests. Delete partial file and restart
-        // fresh to avoid file corruption (appending full file to partial).
-        if resume_from > 0 && response.status() == reqwest::StatusCode::OK {
-            warn!(
-                "Server doesn't support range requests for model {}, restarting download",
-                model_id
-            );
-            drop(response);
-            let _ = fs::remove_file(&partial_path);
-
-            // Reset resume_from since we're starting fresh
-            resume_from = 0;
-
-            // Restart download without range header
-            response = match timeout(DOWNLOAD_REQUEST_TIMEOUT, client.get(&url).send()).await {
-                Ok(Ok(response)) => response,
-                Ok(Err(error)) => {
-                    let error_msg = format!("Failed to restart download: {}", error);
-                    mark_download_failed("network_error", error_msg.clone());
-                    return Err(anyhow::anyhow!(error_msg));
-                }
-                Err(_) => {
-                    let error_msg = format!(
-                        "Download restart request timed out after {:?}",
-                        DOWNLOAD_REQUEST_TIMEOUT
-                    );
-                    mark_download_failed("network_timeout", error_msg.clone());
-                    return Err(anyhow::anyhow!(error_msg));
-                }
-            };
-        }
-
-        // Check for success or partial content status
-        if !response.status().is_success()
-            && response.status() != reqwest::StatusCode::PARTIAL_CONTENT
-        {
-            let error_msg = format!("Failed to download model: HTTP {}", response.status());
-            mark_download_failed("http_error", error_msg.clone());
-            return Err(anyhow::anyhow!(error_msg));
-        }
-
-        let total_size = if resume_from > 0 {
-            // For resumed downloads, add the resume point to content length
-            resume_from + response.content_length().unwrap_or(0)
-        } else {
-            response.content_length().unwrap_or(0)
-        };
-
-        let mut downloaded = resume_from;
-        let mut stream = response.bytes_stream();
-
-        // Open file for appending if resuming, or create new if starting fresh
-        let mut file = if resume_from > 0 {
-            std::fs::OpenOptions::new()
-                .create(true)
-                .append(true)
-                .open(&partial_path)?
-        } else {
-            std::fs::File::create(&partial_path)?
-        };
-
-        // Emit initial progress
-        let initial_progress = DownloadProgress {
-            model_id: model_id.to_string(),
-            downloaded,
-            total: total_size,
-            percentage: if total_size > 0 {
-                (downloaded as f64 / total_size as f64) * 100.0
-            } else {
-                0.0
-            },
+        let quarantine_dir = self.models_dir.join(model_download::QUARANTINE_DIR_NAME);
+        let transfer = ModelTransfer {
+            client: &client,
+            sources,
+            partial_path: &partial_path,
+            expected_sha256: model_info.sha256.as_deref(),
+            quarantine_dir: &quarantine_dir,
+            cancel_flag: &cancel_flag,
         };
-        let _ = self
-            .app_handle
-            .emit("model-download-progress", &initial_progress);
 
         // Throttle progress events to max 10/sec (100ms intervals)
-        let mut last_emit = Instant::now();
+        let mut last_emit: Option<Instant> = None;
         let throttle_duration = Duration::from_millis(100);
-        let mut last_chunk_at = Instant::now();
-
-        // Download with progress
-        loop {
-            // Check if download was cancelled
-            if cancel_flag.load(Ordering::Relaxed) {
-                // Close the file before returning
-                drop(file);
+
+        let outcome = transfer
+            .run(|downloaded, total| {
+                if last_emit.is_some_and(|at| at.elapsed() < throttle_duration) {
+                    return;
+                }
+                let progress = DownloadProgress {
+                    model_id: model_id.to_string(),
+                    downloaded,
+                    total,
+                    percentage: if total > 0 {
+                        (downloaded as f64 / total as f64) * 100.0
+                    } else {
+                        0.0
+                    },
+                };
+                let _ = self.app_handle.emit("model-download-progress", &progress);
+                last_emit = Some(Instant::now());
+            })
+            .await;
+
+        let size = match outcome {
+            Ok(TransferOutcome::Completed { size }) => size,
+            Ok(TransferOutcome::Cancelled) => {
                 info!("Download cancelled for: {}", model_id);
 
                 // Update state to mark as not downloading
@@ -919,94 +622,23 @@ impl ModelManager {
                 // Keep partial file for resume functionality
                 return Ok(());
             }
-
-            let next_chunk = match timeout(DOWNLOAD_POLL_INTERVAL, stream.next()).await {
-                Ok(next_chunk) => next_chunk,
-                Err(_) => {
-                    if last_chunk_at.elapsed() >= DOWNLOAD_STALL_TIMEOUT {
-                        let error_msg = format!(
-                            "Download stalled: no data received for {:?}",
-                            DOWNLOAD_STALL_TIMEOUT
-                        );
-                        mark_download_failed("stalled", error_msg.clone());
-                        return Err(anyhow::anyhow!(error_msg));
-                    }
-                    continue;
-                }
-            };
-
-            let Some(chunk) = next_chunk else {
-                break;
-            };
-
-            let chunk = match chunk {
-                Ok(chunk) => chunk,
-                Err(error) => {
-                    let error_msg = format!("Failed while downloading model: {}", error);
-                    mark_download_failed("network_error", error_msg.clone());
-                    return Err(anyhow::anyhow!(error_msg));
-                }
-            };
-
-            if let Err(error) = file.write_all(&chunk) {
-                let error_msg = format!("Failed to write model chunk: {}", error);
-                mark_download_failed("io_error", error_msg.clone());
-                return Err(anyhow::anyhow!(error_msg));
-            }
-
-            last_chunk_at = Instant::now();
-            downloaded += chunk.len() as u64;
-
-            let percentage = if total_size > 0 {
-                (downloaded as f64 / total_size as f64) * 100.0
-            } else {
-                0.0
-            };
-
-            // Emit progress event (throttled to avoid UI freeze)
-            if last_emit.elapsed() >= throttle_duration {
-                let progress = DownloadProgress {
-                    model_id: model_id.to_string(),
-                    downloaded,
-                    total: total_size,
-                    percentage,
-                };
-                let _ = self.app_handle.emit("model-download-progress", &progress);
-                last_emit = Instant::now();
+            Err(failure) => {
+                mark_download_failed(failure.reason, failure.message.clone());
+                return Err(anyhow::anyhow!(failure.message));
             }
-        }
+        };
 
         // Emit final progress to ensure 100% is shown
         let final_progress = DownloadProgress {
             model_id: model_id.to_string(),
-            downloaded,
-            total: total_size,
-            percentage: if total_size > 0 {
-                (downloaded as f64 / total_size as f64) * 100.0
-            } else {
-                100.0
-            },
+            downloaded: size,
+            total: size,
+            percentage: 100.0,
         };
         let _ = self
             .app_handle
             .emit("model-download-progress", &final_progress);
 
-        file.flush()?;
-        drop(file); // Ensure file is closed before moving
-
-        // Verify downloaded file size matches expected size
-        if total_size > 0 {
-            let actual_size = partial_path.metadata()?.len();
-            if actual_size != total_size {
-                let error_msg = format!(
-                    "Download incomplete: expected {} bytes, got {} bytes",
-                    total_size, actual_size
-                );
-                mark_download_failed("network_error", error_msg.clone());
-                return Err(anyhow::anyhow!(error_msg));
-            }
-        }
-
         // Handle directory-based models (extract tar.gz) vs file-based models
         if model_info.is_directory {
             // Track that this model is being extracted
@@ -1116,10 +748,11 @@ impl ModelManager {
             let _ = self.app_handle.emit("model-extraction-completed", model_id);
 
             // Remove the downloaded tar.gz file
-            let _ = fs::remove_file(&partial_path);
+            model_download::remove_partial(&partial_path);
         } else {
             // Move partial file to final location for file-based models
             fs::rename(&partial_path, &model_path)?;
+            model_download::remove_partial(&partial_path);
         }
 
         // Update download status
@@ -1206,6 +839,7 @@ impl ModelManager {
             info!("Partial file deleted successfully");
             deleted_something = true;
         }
+        let _ = fs::remove_file(model_download::partial_meta_path(&partial_path));
 
         if !deleted_something {
             return Err(anyhow::anyhow!("No model files found to delete"));
@@ -1246,10 +880,36 @@ impl ModelManager {
             ));
         }
 
-        let model_path = self.models_dir.join(&model_info.filename);
-        let partial_path = self
-            .models_dir
-            .join(format!("{}.partial", &model_info.filename));
+        Self::engine_model_path(&self.models_dir, &model_info)
+    }
+
+    /// The path to hand to the model's engine. Custom model directories are
+    /// re-validated and resolve to their model file for single-file engines.
+    pub(crate) fn engine_model_path(models_dir: &Path, model_info: &ModelInfo) -> Result<PathBuf> {
+        let model_path = Self::resolve_model_path(models_dir, model_info)?;
+        if model_info.is_custom && model_info.is_directory {
+            return custom_models::engine_model_path(&model_path).map_err(|e| {
+                anyhow::anyhow!("Custom model {} is not usable: {}", model_info.id, e)
+            });
+        }
+        Ok(model_path)
+    }
+
+    /// Records why a model failed to load (or clears it after a successful load)
+    /// so the model list can show it.
+    pub fn set_load_error(&self, model_id: &str, error: Option<String>) {
+        let mut models = self.available_models.lock().unwrap();
+        if let Some(model) = models.get_mut(model_id) {
+            model.load_error = error;
+        }
+    }
+
+    /// Resolve the on-disk path for a downloaded model inside `models_dir`,
+    /// rejecting incomplete downloads.
+    pub(crate) fn resolve_model_path(models_dir: &Path, model_info: &ModelInfo) -> Result<PathBuf> {
+        let model_id = &model_info.id;
+        let model_path = models_dir.join(&model_info.filename);
+        let partial_path = models_dir.join(format!("{}.partial", &model_info.filename));
 
         if model_info.is_directory {
             // For directory-based models, ensure the directory exists and is complete
@@ -1341,6 +1001,8 @@ mod tests {
                 description: "Test".to_string(),
                 filename: "ggml-small.bin".to_string(),
                 url: Some("https://example.com".to_string()),
+                mirrors: vec![],
+                sha256: None,
                 size_mb: 100,
                 is_downloaded: false,
                 is_downloading: false,
@@ -1353,6 +1015,7 @@ mod tests {
                 is_recommended: false,
                 supported_languages: vec!["en".to_string()],
                 is_custom: false,
+                load_error: None,
             },
         );
 
@@ -0,0 +1,396 @@
+//! Versioned model catalog.
+//!
+//! The transcription models offered for download are described by a JSON
+//! manifest bundled with the app (`resources/model_catalog.json`). An optional
+//! override manifest in the app data directory can add models, replace bundled
+//! entries (e.g. to pin a checksum or point at a mirror) and hide bundled models,
+//! so a fleet can change its catalog without an app release.
+
+use super::model::{EngineType, ModelInfo};
+use anyhow::{anyhow, bail, Result};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use tracing::{info, warn};
+
+/// Manifest schema this build understands.
+pub const CATALOG_SCHEMA_VERSION: u32 = 1;
+/// Override manifest read from the app data directory.
+pub const CATALOG_OVERRIDE_FILE_NAME: &str = "model_catalog.json";
+
+const BUNDLED_CATALOG: &str = include_str!("../../resources/model_catalog.json");
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct CatalogManifest {
+    pub schema_version: u32,
+    pub catalog_version: String,
+    /// Named language lists that models can reference instead of repeating them.
+    #[serde(default)]
+    pub language_sets: HashMap<String, Vec<String>>,
+    #[serde(default)]
+    pub models: Vec<CatalogModel>,
+    /// Ids of models from earlier manifests to drop from the catalog.
+    #[serde(default)]
+    pub removed_models: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct CatalogModel {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub filename: String,
+    #[serde(default)]
+    pub url: Option<String>,
+    /// Alternative download locations, tried in order after `url`.
+    #[serde(default)]
+    pub mirrors: Vec<String>,
+    /// Hex SHA-256 of the downloaded file (the archive for directory models).
+    #[serde(default)]
+    pub sha256: Option<String>,
+    pub size_mb: u64,
+    #[serde(default)]
+    pub is_directory: bool,
+    pub engine_type: EngineType,
+    pub accuracy_score: f32,
+    pub speed_score: f32,
+    #[serde(default)]
+    pub supports_translation: bool,
+    #[serde(default)]
+    pub is_recommended: bool,
+    pub languages: CatalogLanguages,
+}
+
+/// A model's languages: the name of a language set, or an explicit list.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum CatalogLanguages {
+    Set(String),
+    List(Vec<String>),
+}
+
+/// Where the active catalog came from.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ModelCatalogStatus {
+    pub schema_version: u32,
+    pub bundled_version: String,
+    pub override_path: String,
+    /// Version of the override manifest, when one was applied.
+    pub override_version: Option<String>,
+    /// Why the override manifest was ignored, when it exists but is invalid.
+    pub override_error: Option<String>,
+    pub model_count: usize,
+}
+
+pub(crate) struct ModelCatalog {
+    pub models: HashMap<String, ModelInfo>,
+    pub status: ModelCatalogStatus,
+}
+
+/// Parses and validates a manifest.
+pub(crate) fn parse_manifest(json: &str) -> Result<CatalogManifest> {
+    let manifest: CatalogManifest =
+        serde_json::from_str(json).map_err(|e| anyhow!("Invalid model catalog: {}", e))?;
+    validate_manifest(&manifest)?;
+    Ok(manifest)
+}
+
+fn validate_manifest(manifest: &CatalogManifest) -> Result<()> {
+    if manifest.schema_version != CATALOG_SCHEMA_VERSION {
+        bail!(
+            "Unsupported model catalog schema_version {} (expected {})",
+            manifest.schema_version,
+            CATALOG_SCHEMA_VERSION
+        );
+    }
+    if manifest.catalog_version.trim().is_empty() {
+        bail!("Model catalog is missing catalog_version");
+    }
+
+    for (name, languages) in &manifest.language_sets {
+        if languages.iter().any(|code| code.trim().is_empty()) {
+            bail!("Language set '{}' contains an empty language code", name);
+        }
+    }
+
+    let mut ids = HashSet::new();
+    for model in &manifest.models {
+        validate_model(model).map_err(|e| anyhow!("Model '{}': {}", model.id, e))?;
+        if !ids.insert(model.id.as_str()) {
+            bail!("Model '{}' is listed more than once", model.id);
+        }
+    }
+    Ok(())
+}
+
+fn validate_model(model: &CatalogModel) -> Result<()> {
+    let id_ok = !model.id.is_empty()
+        && model
+            .id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if !id_ok {
+        bail!("id may only contain letters, digits, '-', '_' and '.'");
+    }
+    if model.name.trim().is_empty() {
+        bail!("name is empty");
+    }
+    // The filename is joined onto the models directory, so it must stay inside it.
+    if model.filename.is_empty()
+        || model.filename.starts_with('.')
+        || model.filename.contains(['/', '\\'])
+    {
+        bail!("filename must be a plain file or directory name");
+    }
+
+    for url in model.url.iter().chain(&model.mirrors) {
+        if !(url.starts_with("https://") || url.starts_with("http://")) {
+            bail!("download URL '{}' is not http(s)", url);
+        }
+    }
+    if model.url.is_none() && !model.mirrors.is_empty() {
+        bail!("mirrors require a primary url");
+    }
+    if let Some(sha256) = &model.sha256 {
+        if sha256.len() != 64 || !sha256.chars().all(|c| c.is_ascii_hexdigit()) {
+            bail!("sha256 must be 64 hex characters");
+        }
+    }
+
+    for (field, score) in [
+        ("accuracy_score", model.accuracy_score),
+        ("speed_score", model.speed_score),
+    ] {
+        if !(0.0..=1.0).contains(&score) {
+            bail!("{} must be between 0 and 1", field);
+        }
+    }
+    Ok(())
+}
+
+/// Applies `manifests` in order: later manifests add language sets and models,
+/// replace models with the same id, and remove models listed in `removed_models`.
+pub(crate) fn resolve_manifests(
+    manifests: &[&CatalogManifest],
+) -> Result<HashMap<String, ModelInfo>> {
+    let mut language_sets: HashMap<String, Vec<String>> = HashMap::new();
+    let mut models = HashMap::new();
+
+    for manifest in manifests {
+        language_sets.extend(manifest.language_sets.clone());
+        for id in &manifest.removed_models {
+            models.remove(id);
+        }
+        for model in &manifest.models {
+            let supported_languages = match &model.languages {
+                CatalogLanguages::List(languages) => languages.clone(),
+                CatalogLanguages::Set(name) => {
+                    language_sets.get(name).cloned().ok_or_else(|| {
+                        anyhow!("Model '{}': unknown language set '{}'", model.id, name)
+                    })?
+                }
+            };
+            models.insert(model.id.clone(), model_info(model, supported_languages));
+        }
+    }
+    Ok(models)
+}
+
+fn model_info(model: &CatalogModel, supported_languages: Vec<String>) -> ModelInfo {
+    ModelInfo {
+        id: model.id.clone(),
+        name: model.name.clone(),
+        description: model.description.clone(),
+        filename: model.filename.clone(),
+        url: model.url.clone(),
+        mirrors: model.mirrors.clone(),
+        sha256: model.sha256.as_ref().map(|sha| sha.to_ascii_lowercase()),
+        size_mb: model.size_mb,
+        is_downloaded: false,
+        is_downloading: false,
+        partial_size: 0,
+        is_directory: model.is_directory,
+        engine_type: model.engine_type.clone(),
+        accuracy_score: model.accuracy_score,
+        speed_score: model.speed_score,
+        supports_translation: model.supports_translation,
+        is_recommended: model.is_recommended,
+        supported_languages,
+        is_custom: false,
+        load_error: None,
+    }
+}
+
+fn bundled_manifest() -> CatalogManifest {
+    parse_manifest(BUNDLED_CATALOG).expect("bundled model catalog is valid")
+}
+
+/// Models from the bundled manifest alone.
+pub(crate) fn bundled_models() -> HashMap<String, ModelInfo> {
+    resolve_manifests(&[&bundled_manifest()]).expect("bundled model catalog resolves")
+}
+
+/// The bundled manifest merged with the override in `app_data_dir`, if any.
+/// An invalid override is logged and ignored so the app keeps its bundled models.
+pub(crate) fn load_catalog(app_data_dir: &Path) -> ModelCatalog {
+    let bundled = bundled_manifest();
+    let override_path = app_data_dir.join(CATALOG_OVERRIDE_FILE_NAME);
+    let mut status = ModelCatalogStatus {
+        schema_version: CATALOG_SCHEMA_VERSION,
+        bundled_version: bundled.catalog_version.clone(),
+        override_path: override_path.to_string_lossy().to_string(),
+        override_version: None,
+        override_error: None,
+        model_count: 0,
+    };
+
+    let overlay = match fs::read_to_string(&override_path) {
+        Ok(json) => Some(parse_manifest(&json).and_then(|overlay| {
+            resolve_manifests(&[&bundled, &overlay]).map(|models| (overlay, models))
+        })),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => Some(Err(anyhow!(
+            "Failed to read {}: {}",
+            override_path.display(),
+            e
+        ))),
+    };
+
+    let models = match overlay {
+        Some(Ok((overlay, models))) => {
+            info!(
+                "Applied model catalog override {} from {}",
+                overlay.catalog_version,
+                override_path.display()
+            );
+            status.override_version = Some(overlay.catalog_version);
+            models
+        }
+        Some(Err(e)) => {
+            warn!("Ignoring model catalog override: {}", e);
+            status.override_error = Some(e.to_string());
+            resolve_manifests(&[&bundled]).expect("bundled model catalog resolves")
+        }
+        None => resolve_manifests(&[&bundled]).expect("bundled model catalog resolves"),
+    };
+
+    status.model_count = models.len();
+    ModelCatalog { models, status }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const OVERRIDE: &str = r#"{
+        "schema_version": 1,
+        "catalog_version": "fleet-3",
+        "language_sets": { "nordic": ["da", "no", "sv"] },
+        "removed_models": ["large"],
+        "models": [
+            {
+                "id": "small",
+                "name": "Whisper Small (pinned)",
+                "filename": "ggml-small.bin",
+                "url": "https://models.example.com/ggml-small.bin",
+                "mirrors": ["https://blob.handy.computer/ggml-small.bin"],
+                "sha256": "1BE3A9B2063867B937E64E2EC7483364A79917E157FA98C5D94B5C1FFFEA987B",
+                "size_mb": 487,
+                "engine_type": "Whisper",
+                "accuracy_score": 0.6,
+                "speed_score": 0.85,
+                "languages": "whisper"
+            },
+            {
+                "id": "nordic-asr",
+                "name": "Nordic ASR",
+                "filename": "nordic-asr.bin",
+                "url": "https://models.example.com/nordic-asr.bin",
+                "size_mb": 900,
+                "engine_type": "Whisper",
+                "accuracy_score": 0.8,
+                "speed_score": 0.4,
+                "languages": "nordic"
+            }
+        ]
+    }"#;
+
+    #[test]
+    fn bundled_catalog_is_valid() {
+        let models = bundled_models();
+        assert_eq!(models.len(), 13);
+        assert_eq!(models.values().filter(|m| m.is_recommended).count(), 1);
+        assert!(models["small"]
+            .supported_languages
+            .contains(&"yue".to_string()));
+        assert_eq!(models["gigaam-v3-e2e-ctc"].supported_languages, vec!["ru"]);
+    }
+
+    #[test]
+    #[ignore = "checksums are pinned with `bun run models:pin-checksums`, which needs network access"]
+    fn bundled_models_pin_their_checksums() {
+        for (id, model) in bundled_models() {
+            if model.url.is_some() {
+                assert!(model.sha256.is_some(), "{id} has no sha256");
+            }
+        }
+    }
+
+    #[test]
+    fn override_replaces_adds_and_removes_models() {
+        let bundled = bundled_manifest();
+        let overlay = parse_manifest(OVERRIDE).unwrap();
+        let models = resolve_manifests(&[&bundled, &overlay]).unwrap();
+
+        assert!(!models.contains_key("large"));
+        let small = &models["small"];
+        assert_eq!(small.name, "Whisper Small (pinned)");
+        assert_eq!(small.mirrors.len(), 1);
+        assert!(small.sha256.as_deref().unwrap().starts_with("1be3a9"));
+        assert_eq!(
+            models["nordic-asr"].supported_languages,
+            vec!["da", "no", "sv"]
+        );
+        assert_eq!(models.len(), 13);
+    }
+
+    #[test]
+    fn invalid_manifests_are_rejected() {
+        let with = |from: &str, to: &str| OVERRIDE.replacen(from, to, 1);
+        assert!(parse_manifest(&with("\"schema_version\": 1", "\"schema_version\": 2")).is_err());
+        assert!(parse_manifest(&with("ggml-small.bin\",", "../ggml-small.bin\",")).is_err());
+        assert!(parse_manifest(&with("\"sha256\": \"1BE3", "\"sha256\": \"zz")).is_err());
+        assert!(parse_manifest(&with("\"speed_score\": 0.4", "\"speed_score\": 4")).is_err());
+        assert!(parse_manifest(&with("\"nordic-asr\"", "\"small\"")).is_err());
+        assert!(parse_manifest(&with("\"size_mb\": 900", "\"size\": 900")).is_err());
+
+        let overlay = parse_manifest(&with("\"languages\": \"nordic\"", "\"languages\": \"x\""));
+        assert!(resolve_manifests(&[&bundled_manifest(), &overlay.unwrap()]).is_err());
+    }
+
+    #[test]
+    fn load_catalog_falls_back_to_bundled_on_invalid_override() {
+        let temp_dir = TempDir::new().unwrap();
+        let catalog = load_catalog(temp_dir.path());
+        assert!(catalog.status.override_version.is_none());
+        assert!(catalog.status.override_error.is_none());
+
+        let path = temp_dir.path().join(CATALOG_OVERRIDE_FILE_NAME);
+        fs::write(&path, OVERRIDE).unwrap();
+        let catalog = load_catalog(temp_dir.path());
+        assert_eq!(catalog.status.override_version.as_deref(), Some("fleet-3"));
+        assert!(catalog.models.contains_key("nordic-asr"));
+
+        fs::write(&path, "{ not json").unwrap();
+        let catalog = load_catalog(temp_dir.path());
+        assert!(catalog.status.override_error.is_some());
+        assert_eq!(catalog.models.len(), 13);
+        assert_eq!(catalog.status.model_count, 13);
+    }
+}
@@ -0,0 +1,706 @@
+//! HTTP transfer for catalog models.
+//!
+//! A download tries the model's `url` and then each mirror in order. Bytes land
+//! in `<filename>.partial`; a `.partial.json` sidecar records which source and
+//! server validators the partial file came from, so a download interrupted by a
+//! crash or restart resumes with an HTTP Range request instead of appending
+//! bytes from a different file. When the catalog pins a SHA-256, the finished
+//! file is verified before the caller extracts or activates it, and a file that
+//! fails verification is moved to a quarantine directory.
+
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::time::timeout;
+use tracing::{info, warn};
+
+const DOWNLOAD_REQUEST_TIMEOUT: Duration = Duration::from_secs(20);
+const DOWNLOAD_STALL_TIMEOUT: Duration = Duration::from_secs(30);
+const DOWNLOAD_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Directory inside the models directory that holds downloads which failed
+/// checksum verification.
+pub(crate) const QUARANTINE_DIR_NAME: &str = "quarantine";
+
+/// What a `.partial` file is a prefix of.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct PartialMeta {
+    url: String,
+    #[serde(default)]
+    etag: Option<String>,
+    #[serde(default)]
+    last_modified: Option<String>,
+    #[serde(default)]
+    total_size: u64,
+    #[serde(default)]
+    sha256: Option<String>,
+}
+
+/// Why a transfer failed; `reason` matches `DownloadFailureEvent::reason`.
+#[derive(Debug, Clone)]
+pub(crate) struct DownloadFailure {
+    pub reason: &'static str,
+    pub message: String,
+}
+
+impl DownloadFailure {
+    fn new(reason: &'static str, message: String) -> Self {
+        Self { reason, message }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TransferOutcome {
+    /// The partial file is complete (and verified, when a checksum is pinned).
+    Completed { size: u64 },
+    /// The cancel flag was set; the partial file is kept for resuming.
+    Cancelled,
+}
+
+pub(crate) struct ModelTransfer<'a> {
+    pub client: &'a reqwest::Client,
+    /// Primary URL first, then mirrors.
+    pub sources: Vec<String>,
+    pub partial_path: &'a Path,
+    pub expected_sha256: Option<&'a str>,
+    pub quarantine_dir: &'a Path,
+    pub cancel_flag: &'a AtomicBool,
+}
+
+/// Sidecar metadata path for a `.partial` file.
+pub(crate) fn partial_meta_path(partial_path: &Path) -> PathBuf {
+    let mut name = partial_path.as_os_str().to_os_string();
+    name.push(".json");
+    PathBuf::from(name)
+}
+
+/// Removes a partial download and its sidecar.
+pub(crate) fn remove_partial(partial_path: &Path) {
+    let _ = fs::remove_file(partial_path);
+    let _ = fs::remove_file(partial_meta_path(partial_path));
+}
+
+fn read_partial_meta(partial_path: &Path) -> Option<PartialMeta> {
+    let json = fs::read_to_string(partial_meta_path(partial_path)).ok()?;
+    serde_json::from_str(&json).ok()
+}
+
+fn write_partial_meta(partial_path: &Path, meta: &PartialMeta) -> std::io::Result<()> {
+    let json = serde_json::to_string(meta).map_err(std::io::Error::other)?;
+    fs::write(partial_meta_path(partial_path), json)
+}
+
+pub(crate) fn sha256_file(path: &Path) -> std::io::Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0_u8; 1024 * 1024];
+
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Moves a corrupted download into `quarantine_dir`, replacing any earlier
+/// quarantined copy of the same file so repeated failures don't fill the disk.
+fn quarantine_file(path: &Path, quarantine_dir: &Path) -> std::io::Result<PathBuf> {
+    fs::create_dir_all(quarantine_dir)?;
+    let file_name = path
+        .file_name()
+        .map(|name| {
+            name.to_string_lossy()
+                .trim_end_matches(".partial")
+                .to_string()
+        })
+        .unwrap_or_default();
+
+    for entry in fs::read_dir(quarantine_dir)?.flatten() {
+        if entry
+            .file_name()
+            .to_string_lossy()
+            .starts_with(&format!("{}.", file_name))
+        {
+            let _ = fs::remove_file(entry.path());
+        }
+    }
+
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0);
+    let target = quarantine_dir.join(format!("{}.{}.corrupt", file_name, stamp));
+    fs::rename(path, &target)?;
+    Ok(target)
+}
+
+/// Total size from a `Content-Range: bytes start-end/total` header, checking
+/// that the range starts where the partial file ends.
+fn content_range_total(value: &str, expected_start: u64) -> Option<u64> {
+    let range = value.trim().strip_prefix("bytes ")?;
+    let (span, total) = range.split_once('/')?;
+    let (start, _) = span.split_once('-')?;
+    if start.trim().parse::<u64>().ok()? != expected_start {
+        return None;
+    }
+    total.trim().parse().ok()
+}
+
+fn header_string(
+    response: &reqwest::Response,
+    name: reqwest::header::HeaderName,
+) -> Option<String> {
+    response
+        .headers()
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+}
+
+impl ModelTransfer<'_> {
+    /// Downloads into `partial_path` from the first source that delivers a
+    /// complete, verified file. `on_progress` receives `(downloaded, total)`.
+    pub async fn run(
+        &self,
+        mut on_progress: impl FnMut(u64, u64),
+    ) -> Result<TransferOutcome, DownloadFailure> {
+        let mut last_failure = None;
+
+        for url in &self.sources {
+            let outcome = match self.fetch(url, &mut on_progress).await {
+                Ok(TransferOutcome::Completed { size }) => self
+                    .verify()
+                    .await
+                    .map(|()| TransferOutcome::Completed { size }),
+                other => other,
+            };
+
+            match outcome {
+                Ok(outcome) => return Ok(outcome),
+                Err(failure) => {
+                    warn!("Model download from {} failed: {}", url, failure.message);
+                    last_failure = Some(failure);
+                }
+            }
+        }
+
+        Err(last_failure.unwrap_or_else(|| {
+            DownloadFailure::new("network_error", "No download URL for model".to_string())
+        }))
+    }
+
+    /// Bytes already in the partial file that may be resumed from `url`. Parts
+    /// from another source are only reused when a checksum will catch a mismatch.
+    fn resumable_bytes(&self, url: &str) -> (u64, Option<PartialMeta>) {
+        let size = self.partial_path.metadata().map(|m| m.len()).unwrap_or(0);
+        if size == 0 {
+            remove_partial(self.partial_path);
+            return (0, None);
+        }
+
+        let expected = self.expected_sha256.map(str::to_string);
+        match read_partial_meta(self.partial_path) {
+            Some(meta) if meta.url == url && meta.sha256 == expected => (size, Some(meta)),
+            Some(meta) if expected.is_some() && meta.sha256 == expected => (size, None),
+            // Partial files from before the sidecar existed came from the primary URL.
+            None if self.sources.first().map(String::as_str) == Some(url) => (size, None),
+            _ => {
+                info!("Discarding partial download that came from another source");
+                remove_partial(self.partial_path);
+                (0, None)
+            }
+        }
+    }
+
+    async fn send(
+        &self,
+        url: &str,
+        resume_from: u64,
+        meta: Option<&PartialMeta>,
+    ) -> Result<reqwest::Response, DownloadFailure> {
+        let mut request = self.client.get(url);
+        if resume_from > 0 {
+            request = request.header(reqwest::header::RANGE, format!("bytes={}-", resume_from));
+            // If the file changed on the server, If-Range makes it send the new
+            // file in full instead of a range of it.
+            if let Some(validator) = meta.and_then(|m| m.etag.clone().or(m.last_modified.clone())) {
+                request = request.header(reqwest::header::IF_RANGE, validator);
+            }
+        }
+
+        match timeout(DOWNLOAD_REQUEST_TIMEOUT, request.send()).await {
+            Ok(Ok(response)) => Ok(response),
+            Ok(Err(error)) => Err(DownloadFailure::new(
+                "network_error",
+                format!("Failed to start download: {}", error),
+            )),
+            Err(_) => Err(DownloadFailure::new(
+                "network_timeout",
+                format!(
+                    "Download request timed out after {:?}",
+                    DOWNLOAD_REQUEST_TIMEOUT
+                ),
+            )),
+        }
+    }
+
+    async fn fetch(
+        &self,
+        url: &str,
+        on_progress: &mut impl FnMut(u64, u64),
+    ) -> Result<TransferOutcome, DownloadFailure> {
+        let (mut resume_from, meta) = self.resumable_bytes(url);
+        if resume_from > 0 {
+            info!("Resuming download from byte {} via {}", resume_from, url);
+        } else {
+            info!("Starting download from {}", url);
+        }
+
+        let response = self.send(url, resume_from, meta.as_ref()).await?;
+        let status = response.status();
+
+        if resume_from > 0 && status == reqwest::StatusCode::RANGE_NOT_SATISFIABLE {
+            // The partial file already holds everything the server has.
+            let complete = meta
+                .as_ref()
+                .is_some_and(|meta| meta.total_size == resume_from);
+            if complete {
+                on_progress(resume_from, resume_from);
+                return Ok(TransferOutcome::Completed { size: resume_from });
+            }
+            remove_partial(self.partial_path);
+            return Err(DownloadFailure::new(
+                "http_error",
+                "Server rejected the resume range; the partial download was discarded".to_string(),
+            ));
+        }
+
+        if !status.is_success() {
+            return Err(DownloadFailure::new(
+                "http_error",
+                format!("Failed to download model: HTTP {}", status),
+            ));
+        }
+
+        let total_size = if status == reqwest::StatusCode::PARTIAL_CONTENT {
+            let content_range = header_string(&response, reqwest::header::CONTENT_RANGE);
+            match content_range.and_then(|value| content_range_total(&value, resume_from)) {
+                Some(total) => total,
+                None => {
+                    remove_partial(self.partial_path);
+                    return Err(DownloadFailure::new(
+                        "http_error",
+                        "Server answered the resume request with a different range".to_string(),
+                    ));
+                }
+            }
+        } else {
+            // A 200 to a range request means the server ignored the range or the
+            // file changed, so the body is the whole file: start over.
+            if resume_from > 0 {
+                warn!("Server sent the full file for a resume request; restarting download");
+                resume_from = 0;
+            }
+            response.content_length().unwrap_or(0)
+        };
+
+        let meta = PartialMeta {
+            url: url.to_string(),
+            etag: header_string(&response, reqwest::header::ETAG),
+            last_modified: header_string(&response, reqwest::header::LAST_MODIFIED),
+            total_size,
+            sha256: self.expected_sha256.map(str::to_string),
+        };
+
+        let open_result = if resume_from > 0 {
+            fs::OpenOptions::new().append(true).open(self.partial_path)
+        } else {
+            File::create(self.partial_path)
+        };
+        let mut file = open_result.map_err(|error| {
+            DownloadFailure::new(
+                "io_error",
+                format!("Failed to open partial download: {}", error),
+            )
+        })?;
+        write_partial_meta(self.partial_path, &meta).map_err(|error| {
+            DownloadFailure::new(
+                "io_error",
+                format!("Failed to record download source: {}", error),
+            )
+        })?;
+
+        let mut downloaded = resume_from;
+        on_progress(downloaded, total_size);
+
+        let mut stream = response.bytes_stream();
+        let mut last_chunk_at = std::time::Instant::now();
+
+        loop {
+            if self.cancel_flag.load(Ordering::Relaxed) {
+                return Ok(TransferOutcome::Cancelled);
+            }
+
+            let next_chunk = match timeout(DOWNLOAD_POLL_INTERVAL, stream.next()).await {
+                Ok(next_chunk) => next_chunk,
+                Err(_) => {
+                    if last_chunk_at.elapsed() >= DOWNLOAD_STALL_TIMEOUT {
+                        return Err(DownloadFailure::new(
+                            "stalled",
+                            format!(
+                                "Download stalled: no data received for {:?}",
+                                DOWNLOAD_STALL_TIMEOUT
+                            ),
+                        ));
+                    }
+                    continue;
+                }
+            };
+
+            let Some(chunk) = next_chunk else {
+                break;
+            };
+
+            let chunk = chunk.map_err(|error| {
+                DownloadFailure::new(
+                    "network_error",
+                    format!("Failed while downloading model: {}", error),
+                )
+            })?;
+
+            file.write_all(&chunk).map_err(|error| {
+                DownloadFailure::new(
+                    "io_error",
+                    format!("Failed to write model chunk: {}", error),
+                )
+            })?;
+
+            last_chunk_at = std::time::Instant::now();
+            downloaded += chunk.len() as u64;
+            on_progress(downloaded, total_size);
+        }
+
+        file.flush().map_err(|error| {
+            DownloadFailure::new(
+                "io_error",
+                format!("Failed to write model chunk: {}", error),
+            )
+        })?;
+        drop(file);
+
+        let actual_size = self.partial_path.metadata().map(|m| m.len()).unwrap_or(0);
+        if total_size > 0 && actual_size != total_size {
+            return Err(DownloadFailure::new(
+                "network_error",
+                format!(
+                    "Download incomplete: expected {} bytes, got {} bytes",
+                    total_size, actual_size
+                ),
+            ));
+        }
+
+        Ok(TransferOutcome::Completed { size: actual_size })
+    }
+
+    /// Checks the finished partial file against the pinned checksum, moving it
+    /// to quarantine on mismatch.
+    async fn verify(&self) -> Result<(), DownloadFailure> {
+        let Some(expected) = self.expected_sha256.map(str::to_ascii_lowercase) else {
+            warn!(
+                "No pinned SHA-256 for {}; skipping verification",
+                self.partial_path.display()
+            );
+            return Ok(());
+        };
+
+        let path = self.partial_path.to_path_buf();
+        let actual = tauri::async_runtime::spawn_blocking(move || sha256_file(&path))
+            .await
+            .map_err(|error| DownloadFailure::new("io_error", error.to_string()))?
+            .map_err(|error| {
+                DownloadFailure::new(
+                    "io_error",
+                    format!("Failed to checksum download: {}", error),
+                )
+            })?;
+
+        if actual == expected {
+            info!("Verified SHA-256 of {}", self.partial_path.display());
+            return Ok(());
+        }
+
+        let _ = fs::remove_file(partial_meta_path(self.partial_path));
+
+        let message = match quarantine_file(self.partial_path, self.quarantine_dir) {
+            Ok(target) => format!(
+                "Checksum mismatch: expected {}, got {}. The file was moved to {}",
+                expected,
+                actual,
+                target.display()
+            ),
+            Err(error) => {
+                let _ = fs::remove_file(self.partial_path);
+                format!(
+                    "Checksum mismatch: expected {}, got {} (quarantine failed: {})",
+                    expected, actual, error
+                )
+            }
+        };
+        Err(DownloadFailure::new("checksum_mismatch", message))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader};
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+    use tempfile::TempDir;
+
+    #[derive(Clone, Copy, Debug)]
+    enum Serve {
+        /// Honors Range requests.
+        Full,
+        /// Announces the whole body but closes after this many bytes.
+        Truncated(usize),
+        /// Flips one byte of the body.
+        Tampered,
+        /// Ignores Range and always sends the whole body with 200.
+        IgnoreRange,
+        NotFound,
+    }
+
+    /// Minimal HTTP/1.1 stand-in that serves `body` at every path, answering
+    /// the n-th connection with `modes[n]` (the last mode repeats), and records
+    /// the Range start of each request.
+    fn serve(body: Vec<u8>, modes: &[Serve]) -> (String, Arc<Mutex<Vec<Option<usize>>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/model.bin", listener.local_addr().unwrap());
+        let ranges = Arc::new(Mutex::new(Vec::new()));
+        let seen = ranges.clone();
+        let modes = modes.to_vec();
+
+        std::thread::spawn(move || {
+            for (index, stream) in listener.incoming().enumerate() {
+                let Ok(mut stream) = stream else { break };
+                let mode = modes[index.min(modes.len() - 1)];
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut range = None;
+                loop {
+                    let mut line = String::new();
+                    if reader.read_line(&mut line).unwrap_or(0) == 0 || line == "\r\n" {
+                        break;
+                    }
+                    if let Some(value) = line.to_ascii_lowercase().strip_prefix("range: bytes=") {
+                        range = value.trim().trim_end_matches('-').parse::<usize>().ok();
+                    }
+                }
+                seen.lock().unwrap().push(range);
+
+                let mut payload = body.clone();
+                if let Serve::Tampered = mode {
+                    payload[0] ^= 0xff;
+                }
+                let (status, start) = match (mode, range) {
+                    (Serve::NotFound, _) => {
+                        let _ = stream.write_all(
+                            b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                        );
+                        continue;
+                    }
+                    (Serve::Full, Some(start)) if start >= payload.len() => {
+                        let _ = stream.write_all(
+                            format!(
+                                "HTTP/1.1 416 Range Not Satisfiable\r\nContent-Range: bytes */{}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                                payload.len()
+                            )
+                            .as_bytes(),
+                        );
+                        continue;
+                    }
+                    (Serve::Full, Some(start)) => ("206 Partial Content", start),
+                    _ => ("200 OK", 0),
+                };
+                let mut head = format!(
+                    "HTTP/1.1 {}\r\nContent-Length: {}\r\nETag: \"v1\"\r\nConnection: close\r\n",
+                    status,
+                    payload.len() - start
+                );
+                if start > 0 {
+                    head.push_str(&format!(
+                        "Content-Range: bytes {}-{}/{}\r\n",
+                        start,
+                        payload.len() - 1,
+                        payload.len()
+                    ));
+                }
+                head.push_str("\r\n");
+                let _ = stream.write_all(head.as_bytes());
+                let end = match mode {
+                    Serve::Truncated(limit) => limit.min(payload.len()),
+                    _ => payload.len(),
+                };
+                let _ = stream.write_all(&payload[start..end]);
+            }
+        });
+
+        (url, ranges)
+    }
+
+    fn body() -> Vec<u8> {
+        (0..200_000u32).map(|i| (i * 31 % 251) as u8).collect()
+    }
+
+    fn partial_path(dir: &TempDir) -> PathBuf {
+        dir.path().join("model.bin.partial")
+    }
+
+    fn checksum(bytes: &[u8]) -> String {
+        format!("{:x}", Sha256::digest(bytes))
+    }
+
+    fn transfer(
+        dir: &TempDir,
+        sources: Vec<String>,
+        sha256: Option<&str>,
+    ) -> Result<TransferOutcome, DownloadFailure> {
+        let client = reqwest::Client::new();
+        let partial_path = partial_path(dir);
+        let quarantine_dir = dir.path().join(QUARANTINE_DIR_NAME);
+        let cancel_flag = AtomicBool::new(false);
+        let transfer = ModelTransfer {
+            client: &client,
+            sources,
+            partial_path: &partial_path,
+            expected_sha256: sha256,
+            quarantine_dir: &quarantine_dir,
+            cancel_flag: &cancel_flag,
+        };
+        tauri::async_runtime::block_on(transfer.run(|_, _| {}))
+    }
+
+    #[test]
+    fn tampered_file_is_quarantined_and_mirror_is_used() {
+        let dir = TempDir::new().unwrap();
+        let body = body();
+        let sha = checksum(&body);
+        let (tampered, _) = serve(body.clone(), &[Serve::Tampered]);
+        let (mirror_url, _) = serve(body.clone(), &[Serve::Full]);
+
+        let outcome = transfer(&dir, vec![tampered, mirror_url.clone()], Some(&sha)).unwrap();
+        assert_eq!(
+            outcome,
+            TransferOutcome::Completed {
+                size: body.len() as u64
+            }
+        );
+        assert_eq!(fs::read(partial_path(&dir)).unwrap(), body);
+        // The sidecar stays until the caller activates the file, so a retry
+        // after a failed extraction completes without downloading again.
+        let meta = read_partial_meta(&partial_path(&dir)).unwrap();
+        assert_eq!(meta.url, mirror_url);
+
+        let quarantined = fs::read_dir(dir.path().join(QUARANTINE_DIR_NAME)).unwrap();
+        assert_eq!(quarantined.count(), 1);
+    }
+
+    #[test]
+    fn truncated_download_resumes_from_mirror_when_checksum_is_pinned() {
+        let dir = TempDir::new().unwrap();
+        let body = body();
+        let sha = checksum(&body);
+        let (truncated, _) = serve(body.clone(), &[Serve::Truncated(70_000)]);
+        let (mirror, mirror_ranges) = serve(body.clone(), &[Serve::Full]);
+
+        transfer(&dir, vec![truncated, mirror], Some(&sha)).unwrap();
+        assert_eq!(fs::read(partial_path(&dir)).unwrap(), body);
+        assert_eq!(*mirror_ranges.lock().unwrap(), vec![Some(70_000)]);
+    }
+
+    #[test]
+    fn interrupted_download_resumes_after_restart() {
+        let dir = TempDir::new().unwrap();
+        let body = body();
+        let (url, ranges) = serve(body.clone(), &[Serve::Truncated(50_000), Serve::Full]);
+
+        let failure = transfer(&dir, vec![url.clone()], None).unwrap_err();
+        assert_eq!(failure.reason, "network_error");
+        assert!(partial_meta_path(&partial_path(&dir)).exists());
+
+        // A new transfer (as after an app restart) picks up the partial file.
+        transfer(&dir, vec![url], None).unwrap();
+        assert_eq!(fs::read(partial_path(&dir)).unwrap(), body);
+        assert_eq!(*ranges.lock().unwrap(), vec![None, Some(50_000)]);
+    }
+
+    #[test]
+    fn unverified_partial_from_another_source_is_discarded() {
+        let dir = TempDir::new().unwrap();
+        let body = body();
+        let (truncated, _) = serve(body.clone(), &[Serve::Truncated(50_000)]);
+        let (mirror, mirror_ranges) = serve(body.clone(), &[Serve::Full]);
+
+        transfer(&dir, vec![truncated, mirror], None).unwrap();
+        assert_eq!(fs::read(partial_path(&dir)).unwrap(), body);
+        assert_eq!(*mirror_ranges.lock().unwrap(), vec![None]);
+    }
+
+    #[test]
+    fn server_ignoring_range_restarts_from_zero() {
+        let dir = TempDir::new().unwrap();
+        let body = body();
+        fs::write(partial_path(&dir), &body[..1000]).unwrap();
+        let (url, ranges) = serve(body.clone(), &[Serve::IgnoreRange]);
+
+        transfer(&dir, vec![url], Some(&checksum(&body))).unwrap();
+        assert_eq!(fs::read(partial_path(&dir)).unwrap(), body);
+        assert_eq!(*ranges.lock().unwrap(), vec![Some(1000)]);
+    }
+
+    #[test]
+    fn complete_partial_is_verified_without_downloading_again() {
+        let dir = TempDir::new().unwrap();
+        let body = body();
+        let sha = checksum(&body);
+        let (url, ranges) = serve(body.clone(), &[Serve::Full]);
+
+        transfer(&dir, vec![url.clone()], Some(&sha)).unwrap();
+        let outcome = transfer(&dir, vec![url], Some(&sha)).unwrap();
+        assert_eq!(
+            outcome,
+            TransferOutcome::Completed {
+                size: body.len() as u64
+            }
+        );
+        assert_eq!(*ranges.lock().unwrap(), vec![None, Some(body.len())]);
+    }
+
+    #[test]
+    fn reports_last_failure_when_every_source_fails() {
+        let dir = TempDir::new().unwrap();
+        let body = body();
+        let (missing, _) = serve(body.clone(), &[Serve::NotFound]);
+        let (tampered, _) = serve(body.clone(), &[Serve::Tampered]);
+
+        let failure = transfer(&dir, vec![missing, tampered], Some(&checksum(&body))).unwrap_err();
+        assert_eq!(failure.reason, "checksum_mismatch");
+        assert!(!partial_path(&dir).exists());
+    }
+
+    #[test]
+    fn content_range_must_start_at_the_partial_size() {
+        assert_eq!(content_range_total("bytes 100-199/200", 100), Some(200));
+        assert_eq!(content_range_total("bytes 0-199/200", 100), None);
+        assert_eq!(content_range_total("bytes 100-199/*", 100), None);
+    }
+}
@@ -1,16 +1,29 @@
+use crate::audio_toolkit::audio::{
+    load_audio_file_for_transcription, CapturedAudio, RecordedChunk, SpeechMap,
+};
+use crate::audio_toolkit::diarization::Diarizer;
 use crate::audio_toolkit::{
-    apply_custom_words_with_thresholds, filter_and_count_filler_words, filter_hallucinations,
+    apply_custom_words_with_thresholds, convert_spoken_punctuation, filter_and_count_filler_words,
+    filter_hallucinations, spoken_punctuation_language, SpeechSegment,
+};
+use crate::dictionary_bias;
+use crate::managers::audio::{create_vad, resolve_vad_path, AudioRecordingManager};
+use crate::managers::custom_models::{self, MoonshineVariant};
+use crate::managers::history::{
+    HistorySpeakerTurn, RecordingAudio, TimingGranularity, TranscriptTiming,
 };
-use crate::managers::audio::AudioRecordingManager;
+use crate::managers::long_form::LongFormSession;
 use crate::managers::model::{EngineType, ModelManager};
 use crate::sentry_observability::{capture_handled_error, HandledErrorMeta};
-use crate::settings::{get_settings, ModelUnloadTimeout};
-use crate::user_dictionary;
+use crate::settings::{get_settings, AppSettings, ModelUnloadTimeout};
+use crate::user_dictionary::{self, CustomWordEntry};
 use anyhow::Result;
 use serde::Serialize;
+use specta::Type;
 use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::Path;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
-use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::sync::{mpsc, Arc, Condvar, Mutex, MutexGuard, TryLockError};
 use std::thread;
 use std::time::{Duration, Instant, SystemTime};
 use tauri::{AppHandle, Emitter, Manager};
@@ -42,7 +55,36 @@ pub struct ModelStateEvent {
     pub error: Option<String>,
 }
 
-enum LoadedEngine {
+/// Final transcript of a recording together with the engine timings for it.
+#[derive(Clone, Debug, Default)]
+pub struct TranscriptionOutput {
+    pub text: String,
+    pub filler_words_removed: usize,
+    /// Raw-engine timings, relative to the start of the transcribed samples.
+    pub timings: Vec<TranscriptTiming>,
+    /// Dictionary terms hinted to the engine; empty when biasing was off or the
+    /// engine takes no hints.
+    pub bias_terms: Vec<String>,
+}
+
+/// Payload of the `transcription-partial` event emitted while recording.
+#[derive(Clone, Debug, Serialize, Type)]
+pub struct TranscriptionPartialEvent {
+    pub session_id: String,
+    /// Text of utterances that have ended; it will not change for this session.
+    pub stable_text: String,
+    /// Best guess for the utterance still being spoken (streaming engines only).
+    pub interim_text: String,
+}
+
+/// Streaming partial-transcript worker for one recording session.
+struct PartialSession {
+    session_id: String,
+    segment_tx: mpsc::Sender<SpeechSegment>,
+    cancelled: Arc<AtomicBool>,
+}
+
+pub(crate) enum LoadedEngine {
     Whisper(WhisperEngine),
     Parakeet(ParakeetEngine),
     Moonshine(MoonshineEngine),
@@ -51,6 +93,475 @@ enum LoadedEngine {
     GigaAM(GigaAMEngine),
 }
 
+/// Per-call inference options shared by every engine.
+///
+/// Resolved once from settings (or CLI flags in headless mode) so engine
+/// dispatch does not depend on the app handle.
+#[derive(Clone, Debug)]
+pub(crate) struct EngineInferenceOptions {
+    pub language: String,
+    pub translate_to_english: bool,
+    /// Dictionary terms to hint to engines that accept them.
+    pub bias_terms: Vec<String>,
+}
+
+impl EngineInferenceOptions {
+    pub(crate) fn from_settings(settings: &AppSettings) -> Self {
+        Self {
+            language: settings.selected_language.clone(),
+            translate_to_english: settings.translate_to_english,
+            bias_terms: Vec::new(),
+        }
+    }
+
+    /// Adds dictionary hints when biasing is enabled and `engine` accepts them;
+    /// other engines rely on correction after recognition alone.
+    pub(crate) fn with_dictionary_bias(
+        mut self,
+        engine: &LoadedEngine,
+        settings: &AppSettings,
+        dictionary_entries: &[CustomWordEntry],
+    ) -> Self {
+        if settings.dictionary_biasing_enabled && engine.supports_prompt_biasing() {
+            self.bias_terms =
+                dictionary_bias::select_bias_terms(dictionary_entries, &self.language);
+        }
+        self
+    }
+}
+
+impl LoadedEngine {
+    /// Load the engine for `engine_type` from `model_path`.
+    pub(crate) fn load(
+        engine_type: &EngineType,
+        model_id: &str,
+        model_path: &Path,
+    ) -> Result<Self> {
+        let loaded_engine = match engine_type {
+            EngineType::Whisper => {
+                let mut engine = WhisperEngine::new();
+                engine.load_model(model_path).map_err(|e| {
+                    anyhow::anyhow!("Failed to load whisper model {}: {}", model_id, e)
+                })?;
+                LoadedEngine::Whisper(engine)
+            }
+            EngineType::Parakeet => {
+                let mut engine = ParakeetEngine::new();
+                engine
+                    .load_model_with_params(model_path, ParakeetModelParams::int8())
+                    .map_err(|e| {
+                        anyhow::anyhow!("Failed to load parakeet model {}: {}", model_id, e)
+                    })?;
+                LoadedEngine::Parakeet(engine)
+            }
+            EngineType::Moonshine => {
+                let variant = match custom_models::moonshine_variant(model_path)? {
+                    MoonshineVariant::Tiny => ModelVariant::Tiny,
+                    MoonshineVariant::Base => ModelVariant::Base,
+                };
+                let mut engine = MoonshineEngine::new();
+                engine
+                    .load_model_with_params(model_path, MoonshineModelParams::variant(variant))
+                    .map_err(|e| {
+                        anyhow::anyhow!("Failed to load moonshine model {}: {}", model_id, e)
+                    })?;
+                LoadedEngine::Moonshine(engine)
+            }
+            EngineType::MoonshineStreaming => {
+                let mut engine = MoonshineStreamingEngine::new();
+                engine
+                    .load_model_with_params(model_path, StreamingModelParams::default())
+                    .map_err(|e| {
+                        anyhow::anyhow!(
+                            "Failed to load moonshine streaming model {}: {}",
+                            model_id,
+                            e
+                        )
+                    })?;
+                LoadedEngine::MoonshineStreaming(engine)
+            }
+            EngineType::SenseVoice => {
+                let mut engine = SenseVoiceEngine::new();
+                engine
+                    .load_model_with_params(model_path, SenseVoiceModelParams::int8())
+                    .map_err(|e| {
+                        anyhow::anyhow!("Failed to load SenseVoice model {}: {}", model_id, e)
+                    })?;
+                LoadedEngine::SenseVoice(engine)
+            }
+            EngineType::GigaAM => {
+                let mut engine = GigaAMEngine::new();
+                engine.load_model(model_path).map_err(|e| {
+                    anyhow::anyhow!("Failed to load gigaam model {}: {}", model_id, e)
+                })?;
+                LoadedEngine::GigaAM(engine)
+            }
+        };
+
+        Ok(loaded_engine)
+    }
+
+    /// Whether the engine can be steered towards dictionary terms. Whisper takes
+    /// them as an initial prompt; the other engines have no such input.
+    pub(crate) fn supports_prompt_biasing(&self) -> bool {
+        matches!(self, LoadedEngine::Whisper(_))
+    }
+
+    /// Whether the engine is cheap enough to re-run on a growing utterance.
+    /// Other engines only see complete utterances to avoid stalling the final pass.
+    ///
+    /// Moonshine streaming is still driven through one-shot `transcribe_samples`:
+    /// each interim pass re-decodes the utterance so far instead of feeding the
+    /// engine's incremental decoder.
+    pub(crate) fn supports_interim_partials(&self) -> bool {
+        matches!(self, LoadedEngine::MoonshineStreaming(_))
+    }
+
+    pub(crate) fn unload(&mut self) {
+        match self {
+            LoadedEngine::Whisper(ref mut e) => e.unload_model(),
+            LoadedEngine::Parakeet(ref mut e) => e.unload_model(),
+            LoadedEngine::Moonshine(ref mut e) => e.unload_model(),
+            LoadedEngine::MoonshineStreaming(ref mut e) => e.unload_model(),
+            LoadedEngine::SenseVoice(ref mut e) => e.unload_model(),
+            LoadedEngine::GigaAM(ref mut e) => e.unload_model(),
+        }
+    }
+
+    /// Granularity of the segments this engine returns; Parakeet is asked for
+    /// word timestamps, every other engine reports phrase-level segments.
+    pub(crate) fn timing_granularity(&self) -> TimingGranularity {
+        match self {
+            LoadedEngine::Parakeet(_) => TimingGranularity::Word,
+            _ => TimingGranularity::Segment,
+        }
+    }
+
+    pub(crate) fn transcribe_samples(
+        &mut self,
+        audio: Vec<f32>,
+        options: &EngineInferenceOptions,
+    ) -> Result<transcribe_rs::TranscriptionResult> {
+        match self {
+            LoadedEngine::Whisper(whisper_engine) => {
+                let params = WhisperInferenceParams {
+                    language: normalized_whisper_language(&options.language),
+                    translate: options.translate_to_english,
+                    initial_prompt: dictionary_bias::whisper_initial_prompt(&options.bias_terms),
+                    ..Default::default()
+                };
+
+                whisper_engine
+                    .transcribe_samples(audio, Some(params))
+                    .map_err(|e| anyhow::anyhow!("Whisper transcription failed: {}", e))
+            }
+            LoadedEngine::Parakeet(parakeet_engine) => {
+                let params = ParakeetInferenceParams {
+                    timestamp_granularity: TimestampGranularity::Word,
+                    ..Default::default()
+                };
+                parakeet_engine
+                    .transcribe_samples(audio, Some(params))
+                    .map_err(|e| anyhow::anyhow!("Parakeet transcription failed: {}", e))
+            }
+            LoadedEngine::Moonshine(moonshine_engine) => moonshine_engine
+                .transcribe_samples(audio, None)
+                .map_err(|e| anyhow::anyhow!("Moonshine transcription failed: {}", e)),
+            LoadedEngine::MoonshineStreaming(streaming_engine) => streaming_engine
+                .transcribe_samples(audio, None)
+                .map_err(|e| anyhow::anyhow!("Moonshine streaming transcription failed: {}", e)),
+            LoadedEngine::SenseVoice(sense_voice_engine) => {
+                let params = SenseVoiceInferenceParams {
+                    language: sense_voice_language_for(&options.language),
+                    use_itn: true,
+                };
+                sense_voice_engine
+                    .transcribe_samples(audio, Some(params))
+                    .map_err(|e| anyhow::anyhow!("SenseVoice transcription failed: {}", e))
+            }
+            LoadedEngine::GigaAM(gigaam_engine) => gigaam_engine
+                .transcribe_samples(audio, None)
+                .map_err(|e| anyhow::anyhow!("GigaAM transcription failed: {}", e)),
+        }
+    }
+}
+
+/// Convert engine segments (seconds) into millisecond timings, dropping empty
+/// text and spans with non-finite or inverted bounds.
+pub(crate) fn timings_from_result(
+    result: &transcribe_rs::TranscriptionResult,
+    granularity: TimingGranularity,
+) -> Vec<TranscriptTiming> {
+    let Some(segments) = result.segments.as_ref() else {
+        return Vec::new();
+    };
+
+    segments
+        .iter()
+        .filter_map(|segment| {
+            let text = segment.text.trim();
+            if text.is_empty() || !segment.start.is_finite() || !segment.end.is_finite() {
+                return None;
+            }
+            let start_ms = (segment.start.max(0.0) * 1000.0).round() as i64;
+            let end_ms = (segment.end.max(0.0) * 1000.0).round() as i64;
+            if end_ms < start_ms {
+                return None;
+            }
+            Some(TranscriptTiming {
+                start_ms,
+                end_ms,
+                text: text.to_string(),
+                granularity,
+            })
+        })
+        .collect()
+}
+
+/// Move timings measured on the VAD-trimmed audio onto the full capture.
+pub(crate) fn map_timings_to_capture(timings: &mut [TranscriptTiming], speech_map: &SpeechMap) {
+    for timing in timings {
+        timing.start_ms = speech_map.capture_ms(timing.start_ms, false);
+        timing.end_ms = speech_map
+            .capture_ms(timing.end_ms, true)
+            .max(timing.start_ms);
+    }
+}
+
+/// Run each timing's text through the same corrections and filters as the
+/// transcript, dropping timings left without words (e.g. removed filler
+/// words), so the timings describe the text that was kept.
+pub(crate) fn filter_timings(
+    timings: Vec<TranscriptTiming>,
+    settings: &AppSettings,
+    dictionary_entries: &[CustomWordEntry],
+) -> Vec<TranscriptTiming> {
+    timings
+        .into_iter()
+        .filter_map(|timing| {
+            let (text, _) = filter_text(&timing.text, settings, dictionary_entries);
+            let text = text.trim();
+            if !text.chars().any(char::is_alphanumeric) {
+                return None;
+            }
+            Some(TranscriptTiming {
+                text: text.to_string(),
+                ..timing
+            })
+        })
+        .collect()
+}
+
+/// Apply dictionary correction, filler-word and hallucination filtering and
+/// spoken punctuation to raw engine output. Returns the filtered text and the
+/// number of filler words removed.
+pub(crate) fn apply_text_pipeline(
+    raw_text: &str,
+    settings: &AppSettings,
+    dictionary_entries: &[CustomWordEntry],
+) -> (String, usize) {
+    if !dictionary_entries.is_empty() {
+        info!(
+            dictionary_entries = dictionary_entries.len(),
+            threshold = settings.word_correction_threshold,
+            split_threshold = settings.word_correction_split_threshold,
+            "Applying custom word correction"
+        );
+    }
+    let (filtered_result, filler_words_removed) =
+        filter_text(raw_text, settings, dictionary_entries);
+    if filtered_result != raw_text {
+        info!("After filtering: '{}'", filtered_result);
+    }
+    (filtered_result, filler_words_removed)
+}
+
+/// `apply_text_pipeline` without the logging, for pieces of a transcript and
+/// interim partials.
+fn filter_text(
+    raw_text: &str,
+    settings: &AppSettings,
+    dictionary_entries: &[CustomWordEntry],
+) -> (String, usize) {
+    // Apply word correction if custom words are configured
+    let mut filtered_result = if !dictionary_entries.is_empty() {
+        apply_custom_words_with_thresholds(
+            raw_text,
+            dictionary_entries,
+            settings.word_correction_threshold,
+            settings.word_correction_split_threshold,
+        )
+    } else {
+        raw_text.to_string()
+    };
+
+    // Filter out repeated words / hallucinations, then filler words
+    let mut filler_words_removed: usize = 0;
+
+    if settings.enable_filler_word_filter {
+        let (new_text, count) = filter_and_count_filler_words(
+            &filtered_result,
+            &settings.app_language,
+            &settings.extra_filler_words,
+        );
+        filtered_result = new_text;
+        filler_words_removed = count;
+    }
+    if settings.enable_hallucination_filter {
+        filtered_result = filter_hallucinations(&filtered_result);
+    }
+    if settings.spoken_punctuation_enabled {
+        let lang =
+            spoken_punctuation_language(&settings.selected_language, &settings.app_language);
+        filtered_result = convert_spoken_punctuation(&filtered_result, lang);
+    }
+
+    (filtered_result, filler_words_removed)
+}
+
+/// Drop interim segments that a later segment already supersedes, keeping every
+/// complete utterance in order.
+fn coalesce_partial_segments(batch: Vec<SpeechSegment>) -> Vec<SpeechSegment> {
+    let mut coalesced: Vec<SpeechSegment> = Vec::with_capacity(batch.len());
+    for segment in batch {
+        if coalesced
+            .last()
+            .is_some_and(|previous| !previous.utterance_complete)
+        {
+            coalesced.pop();
+        }
+        coalesced.push(segment);
+    }
+    coalesced
+}
+
+fn join_partial_text(stable: &[String], interim: &str) -> (String, String) {
+    let stable_text = stable
+        .iter()
+        .map(|text| text.trim())
+        .filter(|text| !text.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    (stable_text, interim.trim().to_string())
+}
+
+struct PartialWorker {
+    engine: Arc<Mutex<Option<LoadedEngine>>>,
+    app_handle: AppHandle,
+    session_id: String,
+    cancelled: Arc<AtomicBool>,
+    options: EngineInferenceOptions,
+    dictionary: Arc<Vec<CustomWordEntry>>,
+    settings: AppSettings,
+}
+
+impl PartialWorker {
+    fn run(self, segment_rx: mpsc::Receiver<SpeechSegment>) {
+        let mut stable: Vec<String> = Vec::new();
+
+        while let Ok(first) = segment_rx.recv() {
+            let mut batch = vec![first];
+            batch.extend(segment_rx.try_iter());
+
+            for segment in coalesce_partial_segments(batch) {
+                if self.cancelled.load(Ordering::Relaxed) {
+                    return;
+                }
+
+                let utterance_complete = segment.utterance_complete;
+                let Some(text) = self.transcribe_segment(segment) else {
+                    continue;
+                };
+
+                let interim = if utterance_complete {
+                    stable.push(text);
+                    String::new()
+                } else {
+                    text
+                };
+
+                if self.cancelled.load(Ordering::Relaxed) {
+                    return;
+                }
+                let (stable_text, interim_text) = join_partial_text(&stable, &interim);
+                crate::overlay::emit_transcription_partial(
+                    &self.app_handle,
+                    &TranscriptionPartialEvent {
+                        session_id: self.session_id.clone(),
+                        stable_text,
+                        interim_text,
+                    },
+                );
+            }
+        }
+    }
+
+    /// Transcribe one segment while holding the engine lock. Unlike the final pass the
+    /// engine stays in the mutex, so `transcribe` never sees an unloaded model.
+    ///
+    /// Partials never queue for the engine: if the final pass (or a model load) holds
+    /// it, the segment is skipped. An inference that is already running cannot be
+    /// interrupted, so the final pass waits for at most one segment, which the
+    /// recorder caps at a few seconds of speech.
+    fn transcribe_segment(&self, segment: SpeechSegment) -> Option<String> {
+        let mut engine_guard = match self.engine.try_lock() {
+            Ok(guard) => guard,
+            Err(TryLockError::Poisoned(poisoned)) => poisoned.into_inner(),
+            Err(TryLockError::WouldBlock) => {
+                trace!(
+                    session = %self.session_id,
+                    "Engine busy; skipping partial segment"
+                );
+                return None;
+            }
+        };
+        // The session may have ended while we were picking the segment up; the final
+        // pass is about to need the engine.
+        if self.cancelled.load(Ordering::Relaxed) {
+            return None;
+        }
+        let engine = engine_guard.as_mut()?;
+        if !segment.utterance_complete && !engine.supports_interim_partials() {
+            return None;
+        }
+
+        let options = self.options.clone().with_dictionary_bias(
+            engine,
+            &self.settings,
+            self.dictionary.as_ref(),
+        );
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            engine.transcribe_samples(segment.samples, &options)
+        }));
+        match result {
+            Ok(Ok(result)) => {
+                // Interim text is thrown away by the final pass, so keep it out of the log.
+                let (text, _) = filter_text(&result.text, &self.settings, self.dictionary.as_ref());
+                Some(text)
+            }
+            Ok(Err(err)) => {
+                debug!(
+                    session = %self.session_id,
+                    error = %err,
+                    event_code = "partial_transcription_failed",
+                    "Partial transcription failed; waiting for the final pass"
+                );
+                None
+            }
+            Err(_) => {
+                // Same policy as the final pass: a panicked engine is not reused.
+                error!(
+                    session = %self.session_id,
+                    event_code = "partial_transcription_panicked",
+                    "Transcription engine panicked during a partial pass. Model has been unloaded."
+                );
+                *engine_guard = None;
+                None
+            }
+        }
+    }
+}
+
 pub struct TranscriptionManager {
     engine: Arc<Mutex<Option<LoadedEngine>>>,
     model_manager: Arc<ModelManager>,
@@ -62,6 +573,11 @@ pub struct TranscriptionManager {
     is_loading: Arc<Mutex<bool>>,
     loading_condvar: Arc<Condvar>,
     active_session_id: Arc<Mutex<Option<String>>>,
+    partial_session: Arc<Mutex<Option<PartialSession>>>,
+    long_form_session: Arc<Mutex<Option<LongFormSession>>>,
+    /// Held while a temporary engine re-transcribes, so only one is loaded
+    /// next to the main engine at a time.
+    retranscription_lock: Arc<Mutex<()>>,
     is_primary_instance: bool,
 }
 
@@ -156,6 +672,10 @@ fn should_attempt_transcription_reload(engine_missing: bool, selected_model_empt
     engine_missing && !selected_model_empty
 }
 
+fn should_swap_transcription_model(current_model: Option<&str>, selected_model: &str) -> bool {
+    !selected_model.is_empty() && current_model != Some(selected_model)
+}
+
 impl Clone for TranscriptionManager {
     fn clone(&self) -> Self {
         Self {
@@ -169,6 +689,9 @@ impl Clone for TranscriptionManager {
             is_loading: self.is_loading.clone(),
             loading_condvar: self.loading_condvar.clone(),
             active_session_id: self.active_session_id.clone(),
+            partial_session: self.partial_session.clone(),
+            long_form_session: self.long_form_session.clone(),
+            retranscription_lock: self.retranscription_lock.clone(),
             is_primary_instance: false,
         }
     }
@@ -192,6 +715,9 @@ impl TranscriptionManager {
             is_loading: Arc::new(Mutex::new(false)),
             loading_condvar: Arc::new(Condvar::new()),
             active_session_id: Arc::new(Mutex::new(None)),
+            partial_session: Arc::new(Mutex::new(None)),
+            long_form_session: Arc::new(Mutex::new(None)),
+            retranscription_lock: Arc::new(Mutex::new(())),
             is_primary_instance: true,
         };
 
@@ -319,14 +845,7 @@ impl TranscriptionManager {
         {
             let mut engine = self.lock_engine();
             if let Some(ref mut loaded_engine) = *engine {
-                match loaded_engine {
-                    LoadedEngine::Whisper(ref mut e) => e.unload_model(),
-                    LoadedEngine::Parakeet(ref mut e) => e.unload_model(),
-                    LoadedEngine::Moonshine(ref mut e) => e.unload_model(),
-                    LoadedEngine::MoonshineStreaming(ref mut e) => e.unload_model(),
-                    LoadedEngine::SenseVoice(ref mut e) => e.unload_model(),
-                    LoadedEngine::GigaAM(ref mut e) => e.unload_model(),
-                }
+                loaded_engine.unload();
             }
             *engine = None; // Drop the engine to free memory
         }
@@ -402,130 +921,28 @@ impl TranscriptionManager {
             return Err(anyhow::anyhow!(error_msg));
         }
 
-        let model_path = self.model_manager.get_model_path(model_id)?;
-
         // Create appropriate engine based on model type
-        let loaded_engine = match model_info.engine_type {
-            EngineType::Whisper => {
-                let mut engine = WhisperEngine::new();
-                engine.load_model(&model_path).map_err(|e| {
-                    let error_msg = format!("Failed to load whisper model {}: {}", model_id, e);
-                    let _ = self.app_handle.emit(
-                        "model-state-changed",
-                        ModelStateEvent {
-                            event_type: "loading_failed".to_string(),
-                            model_id: Some(model_id.to_string()),
-                            model_name: Some(model_info.name.clone()),
-                            error: Some(error_msg.clone()),
-                        },
-                    );
-                    anyhow::anyhow!(error_msg)
-                })?;
-                LoadedEngine::Whisper(engine)
-            }
-            EngineType::Parakeet => {
-                let mut engine = ParakeetEngine::new();
-                engine
-                    .load_model_with_params(&model_path, ParakeetModelParams::int8())
-                    .map_err(|e| {
-                        let error_msg =
-                            format!("Failed to load parakeet model {}: {}", model_id, e);
-                        let _ = self.app_handle.emit(
-                            "model-state-changed",
-                            ModelStateEvent {
-                                event_type: "loading_failed".to_string(),
-                                model_id: Some(model_id.to_string()),
-                                model_name: Some(model_info.name.clone()),
-                                error: Some(error_msg.clone()),
-                            },
-                        );
-                        anyhow::anyhow!(error_msg)
-                    })?;
-                LoadedEngine::Parakeet(engine)
-            }
-            EngineType::Moonshine => {
-                let mut engine = MoonshineEngine::new();
-                engine
-                    .load_model_with_params(
-                        &model_path,
-                        MoonshineModelParams::variant(ModelVariant::Base),
-                    )
-                    .map_err(|e| {
-                        let error_msg =
-                            format!("Failed to load moonshine model {}: {}", model_id, e);
-                        let _ = self.app_handle.emit(
-                            "model-state-changed",
-                            ModelStateEvent {
-                                event_type: "loading_failed".to_string(),
-                                model_id: Some(model_id.to_string()),
-                                model_name: Some(model_info.name.clone()),
-                                error: Some(error_msg.clone()),
-                            },
-                        );
-                        anyhow::anyhow!(error_msg)
-                    })?;
-                LoadedEngine::Moonshine(engine)
-            }
-            EngineType::MoonshineStreaming => {
-                let mut engine = MoonshineStreamingEngine::new();
-                engine
-                    .load_model_with_params(&model_path, StreamingModelParams::default())
-                    .map_err(|e| {
-                        let error_msg = format!(
-                            "Failed to load moonshine streaming model {}: {}",
-                            model_id, e
-                        );
-                        let _ = self.app_handle.emit(
-                            "model-state-changed",
-                            ModelStateEvent {
-                                event_type: "loading_failed".to_string(),
-                                model_id: Some(model_id.to_string()),
-                                model_name: Some(model_info.name.clone()),
-                                error: Some(error_msg.clone()),
-                            },
-                        );
-                        anyhow::anyhow!(error_msg)
-                    })?;
-                LoadedEngine::MoonshineStreaming(engine)
-            }
-            EngineType::SenseVoice => {
-                let mut engine = SenseVoiceEngine::new();
-                engine
-                    .load_model_with_params(&model_path, SenseVoiceModelParams::int8())
-                    .map_err(|e| {
-                        let error_msg =
-                            format!("Failed to load SenseVoice model {}: {}", model_id, e);
-                        let _ = self.app_handle.emit(
-                            "model-state-changed",
-                            ModelStateEvent {
-                                event_type: "loading_failed".to_string(),
-                                model_id: Some(model_id.to_string()),
-                                model_name: Some(model_info.name.clone()),
-                                error: Some(error_msg.clone()),
-                            },
-                        );
-                        anyhow::anyhow!(error_msg)
-                    })?;
-                LoadedEngine::SenseVoice(engine)
-            }
-            EngineType::GigaAM => {
-                let mut engine = GigaAMEngine::new();
-                engine.load_model(&model_path).map_err(|e| {
-                    let error_msg = format!("Failed to load gigaam model {}: {}", model_id, e);
-                    let _ = self.app_handle.emit(
-                        "model-state-changed",
-                        ModelStateEvent {
-                            event_type: "loading_failed".to_string(),
-                            model_id: Some(model_id.to_string()),
-                            model_name: Some(model_info.name.clone()),
-                            error: Some(error_msg.clone()),
-                        },
-                    );
-                    anyhow::anyhow!(error_msg)
-                })?;
-                LoadedEngine::GigaAM(engine)
-            }
-        };
+        let loaded_engine = self
+            .model_manager
+            .get_model_path(model_id)
+            .and_then(|model_path| {
+                LoadedEngine::load(&model_info.engine_type, model_id, &model_path)
+            })
+            .map_err(|e| {
+                let error_msg = e.to_string();
+                self.model_manager
+                    .set_load_error(model_id, Some(error_msg.clone()));
+                let _ = self.app_handle.emit(
+                    "model-state-changed",
+                    ModelStateEvent {
+                        event_type: "loading_failed".to_string(),
+                        model_id: Some(model_id.to_string()),
+                        model_name: Some(model_info.name.clone()),
+                        error: Some(error_msg.clone()),
+                    },
+                );
+                anyhow::anyhow!(error_msg)
+            })?;
 
         // Update the current engine and model ID
         {
@@ -536,6 +953,7 @@ impl TranscriptionManager {
             let mut current_model = self.current_model_id.lock().unwrap();
             *current_model = Some(model_id.to_string());
         }
+        self.model_manager.set_load_error(model_id, None);
 
         // Emit loading completed event
         let _ = self.app_handle.emit(
@@ -721,12 +1139,249 @@ impl TranscriptionManager {
         is_active
     }
 
+    /// Settings of the session being transcribed. A newer recording may
+    /// already have started with another mode or profile.
+    fn transcription_settings(&self) -> AppSettings {
+        let session_id = self.active_session_id.lock().unwrap().clone();
+        match session_id {
+            Some(session_id) => {
+                crate::app_profiles::settings_for_session(&self.app_handle, &session_id)
+            }
+            None => crate::app_profiles::session_settings(&self.app_handle),
+        }
+    }
+
     pub fn is_any_session_active(&self) -> bool {
         let active = self.active_session_id.lock().unwrap();
         active.is_some()
     }
 
-    pub fn transcribe(&self, audio: Vec<f32>) -> Result<(String, usize)> {
+    /// Start streaming partial transcripts for `session_id` if enabled in settings.
+    ///
+    /// Partials are advisory: the final text still comes from `transcribe` on the
+    /// full recording so history, post-processing and undo see a single result.
+    pub fn begin_partial_session(&self, session_id: &str) {
+        let settings = crate::app_profiles::session_settings(&self.app_handle);
+        if !settings.streaming_partials_enabled {
+            return;
+        }
+
+        self.end_partial_session();
+
+        let (segment_tx, segment_rx) = mpsc::channel::<SpeechSegment>();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let worker = PartialWorker {
+            engine: Arc::clone(&self.engine),
+            app_handle: self.app_handle.clone(),
+            session_id: session_id.to_string(),
+            cancelled: Arc::clone(&cancelled),
+            options: EngineInferenceOptions::from_settings(&settings),
+            dictionary: user_dictionary::get_dictionary_snapshot(&self.app_handle),
+            settings,
+        };
+        thread::spawn(move || worker.run(segment_rx));
+
+        debug!(
+            session = session_id,
+            event_code = "partial_session_started",
+            "Streaming partial transcription started"
+        );
+        *self
+            .partial_session
+            .lock()
+            .unwrap_or_else(|e| e.into_inner()) = Some(PartialSession {
+            session_id: session_id.to_string(),
+            segment_tx,
+            cancelled,
+        });
+    }
+
+    /// Stop the partial worker; queued segments are dropped so the final pass is not delayed.
+    pub fn end_partial_session(&self) {
+        let session = self
+            .partial_session
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take();
+        if let Some(session) = session {
+            session.cancelled.store(true, Ordering::Relaxed);
+            debug!(
+                session = %session.session_id,
+                event_code = "partial_session_ended",
+                "Streaming partial transcription ended"
+            );
+        }
+    }
+
+    /// Queue a recorder speech segment for the active partial session, if any.
+    pub fn submit_partial_segment(&self, segment: SpeechSegment) {
+        let guard = self
+            .partial_session
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        if let Some(session) = guard.as_ref() {
+            let _ = session.segment_tx.send(segment);
+        }
+    }
+
+    /// Start spooling and background transcription for a long-form recording
+    /// if enabled in settings. Returns whether the recorder should cut the
+    /// recording into chunks; otherwise it is transcribed in one pass at stop.
+    pub fn begin_long_form_session(&self, session_id: &str) -> bool {
+        self.cancel_long_form_session();
+        let settings = crate::app_profiles::session_settings(&self.app_handle);
+        if !settings.long_form_recording_enabled {
+            return false;
+        }
+
+        match LongFormSession::begin(&self.app_handle, self.clone(), session_id) {
+            Ok(session) => {
+                *self
+                    .long_form_session
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner()) = Some(session);
+                true
+            }
+            Err(err) => {
+                warn!(
+                    session = session_id,
+                    error = %err,
+                    event_code = "long_form_session_failed",
+                    "Could not start long-form spooling; recording will be transcribed at stop"
+                );
+                false
+            }
+        }
+    }
+
+    /// Queue a chunk cut by the recorder for the active long-form session.
+    pub fn submit_long_form_chunk(&self, chunk: RecordedChunk) {
+        let mut guard = self
+            .long_form_session
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        if let Some(session) = guard.as_mut() {
+            session.submit(chunk);
+        }
+    }
+
+    /// Speech already handed to the long-form session, if one is running.
+    pub fn long_form_speech_ms(&self) -> Option<i64> {
+        self.long_form_session
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .as_ref()
+            .map(LongFormSession::speech_ms)
+    }
+
+    /// Discard the long-form session and its spooled audio, if any.
+    pub fn cancel_long_form_session(&self) {
+        let session = self
+            .long_form_session
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take();
+        if let Some(session) = session {
+            session.cancel();
+        }
+    }
+
+    /// Transcribe a stopped recording. A running long-form session is finished
+    /// with `samples` as its last chunk and its chunk transcripts are stitched;
+    /// otherwise `samples` are transcribed in one pass. With the recorder's
+    /// `captured` audio, history keeps the recording with its pauses and the
+    /// timings are moved onto it.
+    pub fn transcribe_recording(
+        &self,
+        samples: Vec<f32>,
+        captured: Option<CapturedAudio>,
+    ) -> Result<(TranscriptionOutput, RecordingAudio)> {
+        let session = self
+            .long_form_session
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take();
+        match session {
+            Some(session) => {
+                let finished = session.finish(samples, captured)?;
+                Ok((finished.output, RecordingAudio::Spooled(finished.recording)))
+            }
+            None => match captured.filter(|captured| !captured.samples.is_empty()) {
+                Some(captured) => {
+                    let mut output = self.transcribe(samples)?;
+                    map_timings_to_capture(&mut output.timings, &captured.speech_map);
+                    Ok((output, RecordingAudio::Samples(captured.samples)))
+                }
+                None => {
+                    let output = self.transcribe(samples.clone())?;
+                    Ok((output, RecordingAudio::Samples(samples)))
+                }
+            },
+        }
+    }
+
+    /// Speaker turns of a transcribed recording when speaker diarization is on,
+    /// otherwise empty. `audio` is the stored recording: the full capture
+    /// before the VAD trimmed it, which is the clock transcript timings are
+    /// mapped onto, so pauses between speakers are heard and turns line up
+    /// with the timings. Failures are logged and leave the entry without turns.
+    pub fn diarize_recording(
+        &self,
+        audio: &RecordingAudio,
+        settings: &AppSettings,
+    ) -> Vec<HistorySpeakerTurn> {
+        if !settings.speaker_diarization_enabled {
+            return Vec::new();
+        }
+
+        let started = Instant::now();
+        let vad = match resolve_vad_path(&self.app_handle) {
+            Ok(path) => create_vad(&path.to_string_lossy(), settings),
+            Err(e) => {
+                warn!("Skipping speaker diarization: {}", e);
+                return Vec::new();
+            }
+        };
+        let mut diarizer = Diarizer::new(vad, settings.diarization_speaker_limit());
+        match audio {
+            RecordingAudio::Samples(samples) => diarizer.push(samples),
+            RecordingAudio::Spooled(recording) => {
+                // Chunks are read one at a time so long recordings stay on disk.
+                for (path, overlap) in recording.parts() {
+                    match load_audio_file_for_transcription(path) {
+                        Ok(samples) => diarizer.push(samples.get(*overlap..).unwrap_or_default()),
+                        Err(e) => {
+                            warn!(
+                                "Skipping speaker diarization, failed to read {:?}: {}",
+                                path, e
+                            );
+                            return Vec::new();
+                        }
+                    }
+                }
+            }
+        }
+
+        let turns: Vec<HistorySpeakerTurn> = diarizer
+            .finish()
+            .into_iter()
+            .map(|turn| HistorySpeakerTurn {
+                start_ms: turn.start_ms,
+                end_ms: turn.end_ms,
+                speaker: turn.speaker as i64,
+            })
+            .collect();
+        info!(
+            speakers = turns.iter().map(|turn| turn.speaker).max().unwrap_or(0),
+            turns = turns.len(),
+            elapsed_ms = started.elapsed().as_millis() as u64,
+            event_code = "speaker_diarization_finished",
+            "Speaker diarization finished"
+        );
+        turns
+    }
+
+    pub fn transcribe(&self, audio: Vec<f32>) -> Result<TranscriptionOutput> {
         self.touch_activity();
 
         let st = std::time::Instant::now();
@@ -736,7 +1391,7 @@ impl TranscriptionManager {
         if audio.is_empty() {
             debug!("Empty audio vector");
             self.maybe_unload_immediately("empty audio");
-            return Ok((String::new(), 0));
+            return Ok(TranscriptionOutput::default());
         }
 
         // Calculate and log RMS (Root Mean Square) to detect if input is silent
@@ -765,7 +1420,7 @@ impl TranscriptionManager {
             let engine_guard = self.lock_engine();
             if engine_guard.is_none() {
                 drop(engine_guard);
-                let selected_model = get_settings(&self.app_handle).selected_model;
+                let selected_model = self.transcription_settings().selected_model;
                 if !should_attempt_transcription_reload(true, selected_model.is_empty()) {
                     return Err(anyhow::anyhow!("Model is not loaded for transcription."));
                 }
@@ -781,16 +1436,32 @@ impl TranscriptionManager {
                 if engine_guard.is_none() {
                     return Err(anyhow::anyhow!("Model is not loaded for transcription."));
                 }
+            } else {
+                drop(engine_guard);
+                // A mode or app profile may select a different model than the
+                // one left loaded by the previous session; swap it in.
+                let selected_model = self.transcription_settings().selected_model;
+                let current_model = self.get_current_model();
+                if should_swap_transcription_model(current_model.as_deref(), &selected_model) {
+                    info!(
+                        from = ?current_model,
+                        to = %selected_model,
+                        event_code = "transcription_model_swap",
+                        "Session requires a different model, swapping before transcription"
+                    );
+                    self.ensure_model_loaded(&selected_model)?;
+                }
             }
         }
 
         // Get current settings for configuration
-        let settings = get_settings(&self.app_handle);
+        let settings = self.transcription_settings();
 
         // Perform transcription with the appropriate engine.
         // We use catch_unwind to prevent engine panics from poisoning the mutex,
         // which would make the app hang indefinitely on subsequent operations.
-        let result = {
+        let dictionary_entries = user_dictionary::get_dictionary_snapshot(&self.app_handle);
+        let (result, granularity, bias_terms) = {
             let mut engine_guard = self.lock_engine();
 
             // Take the engine out so we own it during transcription.
@@ -808,67 +1479,19 @@ impl TranscriptionManager {
             // Release the lock before transcribing — no mutex held during the engine call
             drop(engine_guard);
 
-            let transcribe_result = catch_unwind(AssertUnwindSafe(
-                || -> Result<transcribe_rs::TranscriptionResult> {
-                    match &mut engine {
-                        LoadedEngine::Whisper(whisper_engine) => {
-                            let params = WhisperInferenceParams {
-                                language: normalized_whisper_language(
-                                    &settings.selected_language,
-                                ),
-                                translate: settings.translate_to_english,
-                                ..Default::default()
-                            };
-
-                            whisper_engine
-                                .transcribe_samples(audio, Some(params))
-                                .map_err(|e| anyhow::anyhow!("Whisper transcription failed: {}", e))
-                        }
-                        LoadedEngine::Parakeet(parakeet_engine) => {
-                            let params = ParakeetInferenceParams {
-                                timestamp_granularity: TimestampGranularity::Segment,
-                                ..Default::default()
-                            };
-                            parakeet_engine
-                                .transcribe_samples(audio, Some(params))
-                                .map_err(|e| {
-                                    anyhow::anyhow!("Parakeet transcription failed: {}", e)
-                                })
-                        }
-                        LoadedEngine::Moonshine(moonshine_engine) => moonshine_engine
-                            .transcribe_samples(audio, None)
-                            .map_err(|e| anyhow::anyhow!("Moonshine transcription failed: {}", e)),
-                        LoadedEngine::MoonshineStreaming(streaming_engine) => streaming_engine
-                            .transcribe_samples(audio, None)
-                            .map_err(|e| {
-                                anyhow::anyhow!("Moonshine streaming transcription failed: {}", e)
-                            }),
-                        LoadedEngine::SenseVoice(sense_voice_engine) => {
-                            let params = SenseVoiceInferenceParams {
-                                language: sense_voice_language_for(
-                                    &settings.selected_language,
-                                ),
-                                use_itn: true,
-                            };
-                            sense_voice_engine
-                                .transcribe_samples(audio, Some(params))
-                                .map_err(|e| {
-                                    anyhow::anyhow!("SenseVoice transcription failed: {}", e)
-                                })
-                        }
-                        LoadedEngine::GigaAM(gigaam_engine) => gigaam_engine
-                            .transcribe_samples(audio, None)
-                            .map_err(|e| anyhow::anyhow!("GigaAM transcription failed: {}", e)),
-                    }
-                },
-            ));
+            let granularity = engine.timing_granularity();
+            let inference_options = EngineInferenceOptions::from_settings(&settings)
+                .with_dictionary_bias(&engine, &settings, dictionary_entries.as_ref());
+            let transcribe_result = catch_unwind(AssertUnwindSafe(|| {
+                engine.transcribe_samples(audio, &inference_options)
+            }));
 
             match transcribe_result {
                 Ok(inner_result) => {
                     // Success or normal error — put the engine back
                     let mut engine_guard = self.lock_engine();
                     *engine_guard = Some(engine);
-                    inner_result?
+                    (inner_result?, granularity, inference_options.bias_terms)
                 }
                 Err(panic_payload) => {
                     // Engine panicked — do NOT put it back (it's in an unknown state).
@@ -914,52 +1537,15 @@ impl TranscriptionManager {
 
         // Log raw result before any processing
         info!("Raw transcription output: '{}'", result.text);
-
-        let dictionary_entries = user_dictionary::get_dictionary_snapshot(&self.app_handle);
-
-        // Apply word correction if custom words are configured
-        let corrected_result = if !dictionary_entries.is_empty() {
-            info!(
-                dictionary_entries = dictionary_entries.len(),
-                threshold = settings.word_correction_threshold,
-                split_threshold = settings.word_correction_split_threshold,
-                "Applying custom word correction"
-            );
-            let corrected = apply_custom_words_with_thresholds(
-                &result.text,
-                dictionary_entries.as_ref(),
-                settings.word_correction_threshold,
-                settings.word_correction_split_threshold,
-            );
-            if corrected != result.text {
-                info!("After custom words: '{}'", corrected);
-            }
-            corrected
-        } else {
-            result.text
-        };
-
-        // Filter out repeated words / hallucinations, then filler words
-        let pre_filter = corrected_result;
-        let mut filtered_result = pre_filter.clone();
-        let mut filler_words_removed: usize = 0;
-
-        if settings.enable_filler_word_filter {
-            let (new_text, count) = filter_and_count_filler_words(
-                &filtered_result,
-                &settings.app_language,
-                &settings.extra_filler_words,
+        if !bias_terms.is_empty() {
+            debug!(
+                bias_terms = bias_terms.len(),
+                "Transcribed with dictionary recognition hints"
             );
-            filtered_result = new_text;
-            filler_words_removed = count;
-        }
-        if settings.enable_hallucination_filter {
-            filtered_result = filter_hallucinations(&filtered_result);
         }
 
-        if filtered_result != pre_filter {
-            info!("After filtering: '{}'", filtered_result);
-        }
+        let (filtered_result, filler_words_removed) =
+            apply_text_pipeline(&result.text, &settings, dictionary_entries.as_ref());
 
         let et = std::time::Instant::now();
         let translation_note = if settings.translate_to_english {
@@ -983,7 +1569,94 @@ impl TranscriptionManager {
 
         self.maybe_unload_immediately("transcription");
 
-        Ok((final_result, filler_words_removed))
+        Ok(TranscriptionOutput {
+            text: final_result,
+            filler_words_removed,
+            timings: filter_timings(
+                timings_from_result(&result, granularity),
+                &settings,
+                dictionary_entries.as_ref(),
+            ),
+            bias_terms,
+        })
+    }
+
+    /// Transcribe `audio` with `model_id` on a temporary engine, leaving the
+    /// loaded model and `selected_model` untouched. Calls run one at a time.
+    /// Returns the pipeline output and the time spent in the engine.
+    pub fn transcribe_with_model(
+        &self,
+        model_id: &str,
+        audio: Vec<f32>,
+    ) -> Result<(TranscriptionOutput, std::time::Duration)> {
+        let model_info = self
+            .model_manager
+            .get_model_info(model_id)
+            .ok_or_else(|| anyhow::anyhow!("Model not found: {}", model_id))?;
+        if !model_info.is_downloaded {
+            return Err(anyhow::anyhow!("Model not downloaded: {}", model_info.name));
+        }
+        if audio.is_empty() {
+            return Ok((TranscriptionOutput::default(), std::time::Duration::ZERO));
+        }
+
+        let _retranscription = self
+            .retranscription_lock
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let model_path = self.model_manager.get_model_path(model_id)?;
+        let mut engine = LoadedEngine::load(&model_info.engine_type, model_id, &model_path)?;
+        info!(
+            model = %model_id,
+            samples = audio.len(),
+            event_code = "retranscription_start",
+            "Re-transcribing with a temporary engine"
+        );
+
+        let settings = crate::app_profiles::session_settings(&self.app_handle);
+        let granularity = engine.timing_granularity();
+        let dictionary_entries = user_dictionary::get_dictionary_snapshot(&self.app_handle);
+        let inference_options = EngineInferenceOptions::from_settings(&settings)
+            .with_dictionary_bias(&engine, &settings, dictionary_entries.as_ref());
+        let st = std::time::Instant::now();
+        let transcribe_result = catch_unwind(AssertUnwindSafe(|| {
+            engine.transcribe_samples(audio, &inference_options)
+        }));
+        let elapsed = st.elapsed();
+        let result = match transcribe_result {
+            Ok(inner_result) => {
+                engine.unload();
+                inner_result?
+            }
+            Err(_) => {
+                return Err(anyhow::anyhow!(
+                    "Transcription engine panicked while re-transcribing with {}",
+                    model_id
+                ));
+            }
+        };
+
+        let (filtered_result, filler_words_removed) =
+            apply_text_pipeline(&result.text, &settings, dictionary_entries.as_ref());
+        info!(
+            "Re-transcription with {} completed in {}ms",
+            model_id,
+            elapsed.as_millis()
+        );
+
+        Ok((
+            TranscriptionOutput {
+                text: filtered_result.trim().to_string(),
+                filler_words_removed,
+                timings: filter_timings(
+                    timings_from_result(&result, granularity),
+                    &settings,
+                    dictionary_entries.as_ref(),
+                ),
+                bias_terms: inference_options.bias_terms,
+            },
+            elapsed,
+        ))
     }
 }
 
@@ -1012,10 +1685,17 @@ impl Drop for TranscriptionManager {
 #[cfg(test)]
 mod tests {
     use super::{
-        classify_idle_unload_decision, normalized_whisper_language, sense_voice_language_for,
-        should_attempt_transcription_reload, IdleUnloadDecision,
+        classify_idle_unload_decision, coalesce_partial_segments, filter_timings,
+        join_partial_text, map_timings_to_capture, normalized_whisper_language,
+        sense_voice_language_for, should_attempt_transcription_reload,
+        should_swap_transcription_model, timings_from_result, IdleUnloadDecision,
     };
+    use crate::audio_toolkit::audio::SpeechMap;
+    use crate::audio_toolkit::SpeechSegment;
+    use crate::managers::history::{TimingGranularity, TranscriptTiming};
+    use crate::settings::get_default_settings;
     use transcribe_rs::engines::sense_voice::Language as SenseVoiceLanguage;
+    use transcribe_rs::{TranscriptionResult, TranscriptionSegment};
 
     #[test]
     fn idle_unload_allows_stale_loaded_idle_model() {
@@ -1056,6 +1736,14 @@ mod tests {
         assert!(!should_attempt_transcription_reload(false, false));
     }
 
+    #[test]
+    fn transcription_model_swap_only_when_session_model_differs() {
+        assert!(should_swap_transcription_model(Some("small"), "large"));
+        assert!(should_swap_transcription_model(None, "large"));
+        assert!(!should_swap_transcription_model(Some("large"), "large"));
+        assert!(!should_swap_transcription_model(Some("small"), ""));
+    }
+
     #[test]
     fn whisper_language_normalizes_chinese_variants() {
         assert_eq!(normalized_whisper_language("auto"), None);
@@ -1071,4 +1759,124 @@ mod tests {
         assert_eq!(sense_voice_language_for("yue"), SenseVoiceLanguage::Cantonese);
         assert_eq!(sense_voice_language_for("auto"), SenseVoiceLanguage::Auto);
     }
+
+    fn segment(len: usize, utterance_complete: bool) -> SpeechSegment {
+        SpeechSegment {
+            samples: vec![0.0; len],
+            utterance_complete,
+        }
+    }
+
+    #[test]
+    fn coalesce_keeps_complete_segments_and_latest_interim() {
+        let coalesced = coalesce_partial_segments(vec![
+            segment(1, false),
+            segment(2, false),
+            segment(3, true),
+            segment(4, false),
+            segment(5, false),
+        ]);
+
+        let lengths: Vec<usize> = coalesced.iter().map(|s| s.samples.len()).collect();
+        assert_eq!(lengths, vec![3, 5]);
+        assert!(coalesced[0].utterance_complete);
+        assert!(!coalesced[1].utterance_complete);
+    }
+
+    #[test]
+    fn coalesce_drops_interim_superseded_by_complete_utterance() {
+        let coalesced = coalesce_partial_segments(vec![segment(1, false), segment(2, true)]);
+        assert_eq!(coalesced.len(), 1);
+        assert!(coalesced[0].utterance_complete);
+    }
+
+    #[test]
+    fn join_partial_text_skips_empty_utterances() {
+        let stable = vec![
+            "Hello there.".to_string(),
+            "  ".to_string(),
+            "Next".to_string(),
+        ];
+        let (stable_text, interim_text) = join_partial_text(&stable, " and then ");
+        assert_eq!(stable_text, "Hello there. Next");
+        assert_eq!(interim_text, "and then");
+    }
+
+    #[test]
+    fn timings_from_result_converts_seconds_and_skips_invalid_segments() {
+        let segment = |start: f32, end: f32, text: &str| TranscriptionSegment {
+            start,
+            end,
+            text: text.to_string(),
+        };
+        let result = TranscriptionResult {
+            text: "hello world".to_string(),
+            segments: Some(vec![
+                segment(0.0, 0.42, " hello"),
+                segment(0.5, f32::NAN, "broken"),
+                segment(0.9, 0.8, "inverted"),
+                segment(0.95, 1.2, "   "),
+                segment(1.25, 1.9, "world"),
+            ]),
+        };
+
+        let timings = timings_from_result(&result, TimingGranularity::Word);
+        let spans: Vec<(i64, i64, &str)> = timings
+            .iter()
+            .map(|timing| (timing.start_ms, timing.end_ms, timing.text.as_str()))
+            .collect();
+        assert_eq!(spans, vec![(0, 420, "hello"), (1250, 1900, "world")]);
+        assert!(timings
+            .iter()
+            .all(|timing| timing.granularity == TimingGranularity::Word));
+    }
+
+    fn word(start_ms: i64, end_ms: i64, text: &str) -> TranscriptTiming {
+        TranscriptTiming {
+            start_ms,
+            end_ms,
+            text: text.to_string(),
+            granularity: TimingGranularity::Word,
+        }
+    }
+
+    #[test]
+    fn filter_timings_drops_words_the_transcript_filtered_out() {
+        let mut settings = get_default_settings();
+        settings.app_language = "en".to_string();
+        settings.enable_filler_word_filter = true;
+
+        let timings = filter_timings(
+            vec![
+                word(0, 200, "um,"),
+                word(250, 500, "hello"),
+                word(600, 900, " uh"),
+                word(950, 1300, "world."),
+            ],
+            &settings,
+            &[],
+        );
+        let spans: Vec<(i64, &str)> = timings
+            .iter()
+            .map(|timing| (timing.start_ms, timing.text.as_str()))
+            .collect();
+        assert_eq!(spans, vec![(250, "hello"), (950, "world.")]);
+    }
+
+    #[test]
+    fn map_timings_to_capture_restores_dropped_pauses() {
+        // 16 kHz: kept [0, 8000) is capture [16000, 24000), the rest follows
+        // a pause at capture 40000.
+        let mut speech_map = SpeechMap::default();
+        speech_map.record(0, 16_000, 8_000);
+        speech_map.record(8_000, 40_000, 8_000);
+
+        let mut timings = vec![word(100, 500, "first"), word(500, 900, "second")];
+        map_timings_to_capture(&mut timings, &speech_map);
+        let spans: Vec<(i64, i64)> = timings
+            .iter()
+            .map(|timing| (timing.start_ms, timing.end_ms))
+            .collect();
+        assert_eq!(spans, vec![(1100, 1500), (2500, 2900)]);
+    }
 }
@@ -0,0 +1,263 @@
+//! Microphone diagnostics shared by the `run_microphone_diagnostics` command and
+//! `codictate diagnose-mic`.
+//!
+//! Records a quiet sample and a spoken sample straight from the device, before
+//! any of the recorder's processing, and turns the measurements into issues
+//! that the app and the CLI each put into words.
+
+use crate::audio_toolkit::audio::{
+    amplitude_to_dbfs, percentile, vad_speech_ratio, DiagnosticCapture, SILENCE_DBFS,
+};
+use crate::audio_toolkit::constants::WHISPER_SAMPLE_RATE;
+use crate::audio_toolkit::VoiceActivityDetector;
+use crate::settings::AppSettings;
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::time::Duration;
+
+/// Speech peaking below this level means the device is effectively silent.
+const NO_SIGNAL_PEAK_DBFS: f32 = -50.0;
+/// Loud speech frames below this level are too quiet for reliable recognition.
+const LOW_SPEECH_DBFS: f32 = -35.0;
+const MAX_CLIPPING_RATIO: f32 = 0.001;
+/// Room noise above this level is audible in every recording.
+const NOISY_FLOOR_DBFS: f32 = -50.0;
+const MIN_SNR_DB: f32 = 15.0;
+const MAX_DC_OFFSET: f32 = 0.01;
+/// Share of the spoken sample the VAD should keep at the very least.
+const MIN_VAD_SPEECH_RATIO: f32 = 0.25;
+/// Share of the quiet sample the VAD may mistake for speech.
+const MAX_VAD_NOISE_RATIO: f32 = 0.2;
+
+pub const DEFAULT_SILENCE_DURATION: Duration = Duration::from_secs(3);
+pub const DEFAULT_SPEECH_DURATION: Duration = Duration::from_secs(5);
+
+/// The step the diagnostic is in; the user should stay quiet during
+/// `Silence` and talk normally during `Speech`.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, Type, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticPhase {
+    Silence,
+    Speech,
+    Analyzing,
+}
+
+/// Something about the microphone that needs attention. The measurements
+/// behind it are in the report; settings that change the advice are carried
+/// along.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, Type, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DiagnosticIssue {
+    /// The device delivered almost no sound; nothing else is reported.
+    NoSignal,
+    LowSampleRate,
+    Clipping,
+    LowLevel,
+    NoisyEnvironment {
+        noise_suppression: bool,
+    },
+    LowSnr,
+    DcOffset {
+        high_pass_filter: bool,
+    },
+    VadMissesSpeech,
+    VadTriggersOnNoise,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Type)]
+pub struct MicrophoneDiagnostics {
+    pub device_name: String,
+    /// Device config chosen the same way as for recording.
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub sample_format: String,
+    /// Median 30 ms level of the quiet sample.
+    pub noise_floor_dbfs: f32,
+    /// 90th percentile 30 ms level of the spoken sample.
+    pub speech_level_dbfs: f32,
+    pub speech_peak_dbfs: f32,
+    pub snr_db: f32,
+    /// Share of spoken-sample values at full scale.
+    pub clipping_ratio: f32,
+    /// Mean of the quiet sample on its worst channel, as a fraction of full scale.
+    pub dc_offset: f32,
+    /// Share of the spoken sample the configured VAD keeps.
+    pub vad_speech_ratio: f32,
+    /// Share of the quiet sample the configured VAD keeps.
+    pub vad_noise_ratio: f32,
+    /// Empty when nothing needs attention.
+    pub issues: Vec<DiagnosticIssue>,
+}
+
+/// Records the quiet sample, then the spoken one, reporting each phase through
+/// `on_phase` before it starts.
+pub fn run(
+    device: &cpal::Device,
+    device_name: &str,
+    vad: &mut dyn VoiceActivityDetector,
+    settings: &AppSettings,
+    silence_duration: Duration,
+    speech_duration: Duration,
+    mut on_phase: impl FnMut(DiagnosticPhase),
+) -> Result<MicrophoneDiagnostics> {
+    on_phase(DiagnosticPhase::Silence);
+    let silence = DiagnosticCapture::record(device, silence_duration)
+        .map_err(|e| anyhow!("Failed to record quiet sample: {}", e))?;
+    on_phase(DiagnosticPhase::Speech);
+    let speech = DiagnosticCapture::record(device, speech_duration)
+        .map_err(|e| anyhow!("Failed to record speech sample: {}", e))?;
+    on_phase(DiagnosticPhase::Analyzing);
+
+    Ok(analyze(device_name, &silence, &speech, vad, settings))
+}
+
+fn analyze(
+    device_name: &str,
+    silence: &DiagnosticCapture,
+    speech: &DiagnosticCapture,
+    vad: &mut dyn VoiceActivityDetector,
+    settings: &AppSettings,
+) -> MicrophoneDiagnostics {
+    let noise_floor_dbfs = percentile(&silence.frame_levels_dbfs(), 0.5).unwrap_or(SILENCE_DBFS);
+    let speech_level_dbfs = percentile(&speech.frame_levels_dbfs(), 0.9).unwrap_or(SILENCE_DBFS);
+    let speech_levels = speech.levels();
+
+    // The quiet sample goes first so adaptive detectors learn the room, as
+    // they would from the open stream before a real recording.
+    vad.reset();
+    let noise_ratio = vad_speech_ratio(vad, &silence.mono_16k());
+    let speech_ratio = vad_speech_ratio(vad, &speech.mono_16k());
+    vad.reset();
+
+    let mut report = MicrophoneDiagnostics {
+        device_name: device_name.to_string(),
+        sample_rate: speech.sample_rate,
+        channels: speech.channels,
+        sample_format: speech.sample_format.to_string(),
+        noise_floor_dbfs,
+        speech_level_dbfs,
+        speech_peak_dbfs: amplitude_to_dbfs(speech_levels.peak),
+        snr_db: speech_level_dbfs - noise_floor_dbfs,
+        clipping_ratio: speech_levels.clipping_ratio,
+        dc_offset: silence.levels().dc_offset,
+        vad_speech_ratio: speech_ratio,
+        vad_noise_ratio: noise_ratio,
+        issues: Vec::new(),
+    };
+    report.issues = issues(&report, settings);
+    report
+}
+
+fn issues(report: &MicrophoneDiagnostics, settings: &AppSettings) -> Vec<DiagnosticIssue> {
+    if report.speech_peak_dbfs < NO_SIGNAL_PEAK_DBFS {
+        // Nothing else is meaningful without a signal.
+        return vec![DiagnosticIssue::NoSignal];
+    }
+
+    let mut out = Vec::new();
+    if report.sample_rate < WHISPER_SAMPLE_RATE {
+        out.push(DiagnosticIssue::LowSampleRate);
+    }
+    if report.clipping_ratio > MAX_CLIPPING_RATIO {
+        out.push(DiagnosticIssue::Clipping);
+    } else if report.speech_level_dbfs < LOW_SPEECH_DBFS {
+        out.push(DiagnosticIssue::LowLevel);
+    }
+    if report.noise_floor_dbfs > NOISY_FLOOR_DBFS {
+        out.push(DiagnosticIssue::NoisyEnvironment {
+            noise_suppression: settings.audio_noise_suppression,
+        });
+    }
+    if report.snr_db < MIN_SNR_DB {
+        out.push(DiagnosticIssue::LowSnr);
+    }
+    if report.dc_offset.abs() > MAX_DC_OFFSET {
+        out.push(DiagnosticIssue::DcOffset {
+            high_pass_filter: settings.audio_high_pass_filter,
+        });
+    }
+    if report.vad_speech_ratio < MIN_VAD_SPEECH_RATIO && report.snr_db >= MIN_SNR_DB {
+        out.push(DiagnosticIssue::VadMissesSpeech);
+    }
+    if report.vad_noise_ratio > MAX_VAD_NOISE_RATIO {
+        out.push(DiagnosticIssue::VadTriggersOnNoise);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio_toolkit::vad::test_signals::{noise, voiced};
+    use crate::audio_toolkit::vad::EnergyVad;
+    use crate::settings::get_default_settings;
+
+    fn capture(interleaved: Vec<f32>) -> DiagnosticCapture {
+        DiagnosticCapture {
+            sample_rate: WHISPER_SAMPLE_RATE,
+            channels: 1,
+            sample_format: cpal::SampleFormat::F32,
+            interleaved,
+        }
+    }
+
+    #[test]
+    fn healthy_microphone_has_no_issues() {
+        let mut seed = 7;
+        let silence = capture(noise(0.0005, 16_000, &mut seed));
+        let speech = capture(voiced(0.3, 16_000, 0));
+        let mut vad = EnergyVad::new(0.5);
+
+        let report = analyze("Mic", &silence, &speech, &mut vad, &get_default_settings());
+        assert!(report.snr_db > 40.0, "snr {}", report.snr_db);
+        assert_eq!(report.issues, Vec::new());
+    }
+
+    #[test]
+    fn silent_device_only_reports_missing_signal() {
+        let silence = capture(vec![0.0; 16_000]);
+        let speech = capture(vec![0.0; 16_000]);
+        let mut vad = EnergyVad::new(0.5);
+
+        let report = analyze("Mic", &silence, &speech, &mut vad, &get_default_settings());
+        assert_eq!(report.issues, vec![DiagnosticIssue::NoSignal]);
+    }
+
+    #[test]
+    fn clipping_noise_and_dc_offset_are_flagged() {
+        let mut seed = 11;
+        let silence = capture(
+            noise(0.02, 16_000, &mut seed)
+                .into_iter()
+                .map(|s| s + 0.05)
+                .collect(),
+        );
+        let speech = capture(
+            voiced(2.0, 16_000, 0)
+                .into_iter()
+                .map(|s| s.clamp(-1.0, 1.0))
+                .collect(),
+        );
+        let mut vad = EnergyVad::new(0.5);
+
+        let report = analyze("Mic", &silence, &speech, &mut vad, &get_default_settings());
+        let found = &report.issues;
+        assert!(found.contains(&DiagnosticIssue::Clipping), "{:?}", found);
+        assert!(
+            found
+                .iter()
+                .any(|issue| matches!(issue, DiagnosticIssue::NoisyEnvironment { .. })),
+            "{:?}",
+            found
+        );
+        assert!(
+            found
+                .iter()
+                .any(|issue| matches!(issue, DiagnosticIssue::DcOffset { .. })),
+            "{:?}",
+            found
+        );
+        assert!(!found.contains(&DiagnosticIssue::LowLevel), "{:?}", found);
+    }
+}
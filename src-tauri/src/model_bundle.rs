@@ -0,0 +1,802 @@
+//! Offline model bundles for machines without internet access.
+//!
+//! A bundle is a ZIP holding `manifest.json`, an Ed25519 signature of the
+//! manifest bytes in `manifest.sig`, and each model's files under `models/`.
+//! The manifest lists every file with its size and SHA-256, so an import can
+//! verify the payload without network access before registering the models.
+
+use crate::managers::custom_models::CUSTOM_MODEL_METADATA_FILE_NAME;
+use crate::managers::model::{EngineType, ModelInfo, ModelManager};
+use crate::managers::model_download;
+use anyhow::{anyhow, bail, Context, Result};
+use ring::rand::SystemRandom;
+use ring::signature::{self, Ed25519KeyPair, KeyPair, UnparsedPublicKey};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use specta::Type;
+use std::collections::{BTreeSet, HashMap};
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+pub const MODEL_BUNDLE_EXTENSION: &str = "codictatemodels";
+/// Ed25519 key (PKCS#8) used to sign exports, created on first export.
+pub const SIGNING_KEY_FILE_NAME: &str = "model_bundle_signing.key";
+
+const BUNDLE_FORMAT: &str = "codictate-model-bundle";
+const BUNDLE_FORMAT_VERSION: u32 = 1;
+const MANIFEST_ENTRY: &str = "manifest.json";
+const SIGNATURE_ENTRY: &str = "manifest.sig";
+const PAYLOAD_PREFIX: &str = "models/";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct BundleManifest {
+    format: String,
+    format_version: u32,
+    created_at: String,
+    app_version: String,
+    /// Hex Ed25519 public key that signed this manifest.
+    signer: String,
+    models: Vec<BundledModel>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct BundledModel {
+    id: String,
+    name: String,
+    engine_type: EngineType,
+    filename: String,
+    is_directory: bool,
+    files: Vec<BundledFile>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct BundledFile {
+    /// Path relative to the models directory, `/`-separated.
+    path: String,
+    size: u64,
+    sha256: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ModelBundleExportReport {
+    pub path: String,
+    /// Hex public key to add to `trusted_model_bundle_keys` on target machines.
+    pub signer: String,
+    pub models: Vec<String>,
+    pub total_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ModelBundleFailure {
+    pub model_id: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ModelBundleImportReport {
+    pub signer: String,
+    pub imported: Vec<String>,
+    /// Models that were already installed.
+    pub skipped: Vec<String>,
+    pub failed: Vec<ModelBundleFailure>,
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn from_hex(value: &str) -> Option<Vec<u8>> {
+    value
+        .trim()
+        .as_bytes()
+        .chunks(2)
+        .map(|pair| {
+            let pair = std::str::from_utf8(pair).ok()?;
+            if pair.len() != 2 || !pair.chars().all(|c| c.is_ascii_hexdigit()) {
+                return None;
+            }
+            u8::from_str_radix(pair, 16).ok()
+        })
+        .collect()
+}
+
+/// Whether `key` looks like a hex Ed25519 public key.
+pub(crate) fn is_valid_public_key(key: &str) -> bool {
+    from_hex(key).is_some_and(|bytes| bytes.len() == 32)
+}
+
+/// Loads the signing key at `path`, creating one if the file doesn't exist.
+pub(crate) fn load_or_create_signing_key(path: &Path) -> Result<Ed25519KeyPair> {
+    if path.exists() {
+        let pkcs8 = fs::read(path)
+            .with_context(|| format!("Failed to read signing key {}", path.display()))?;
+        return Ed25519KeyPair::from_pkcs8(&pkcs8)
+            .map_err(|e| anyhow!("Invalid signing key {}: {}", path.display(), e));
+    }
+
+    let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new())
+        .map_err(|_| anyhow!("Failed to generate a signing key"))?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options
+        .open(path)
+        .and_then(|mut file| file.write_all(pkcs8.as_ref()))
+        .with_context(|| format!("Failed to save signing key {}", path.display()))?;
+    info!("Created model bundle signing key at {}", path.display());
+
+    Ed25519KeyPair::from_pkcs8(pkcs8.as_ref())
+        .map_err(|e| anyhow!("Failed to load the generated signing key: {}", e))
+}
+
+pub(crate) fn public_key_hex(key: &Ed25519KeyPair) -> String {
+    to_hex(key.public_key().as_ref())
+}
+
+/// Files of an installed model as `(bundle path, disk path)` pairs, sorted.
+fn model_files(models_dir: &Path, model: &ModelInfo) -> Result<Vec<(String, PathBuf)>> {
+    let root = ModelManager::resolve_model_path(models_dir, model)?;
+    if !model.is_directory {
+        return Ok(vec![(model.filename.clone(), root)]);
+    }
+
+    let mut files = Vec::new();
+    let mut pending = vec![(model.filename.clone(), root)];
+    while let Some((prefix, dir)) = pending.pop() {
+        for entry in fs::read_dir(&dir)? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            let relative = format!("{}/{}", prefix, name);
+            let file_type = entry.file_type()?;
+            if file_type.is_dir() {
+                pending.push((relative, entry.path()));
+            } else if file_type.is_file() {
+                files.push((relative, entry.path()));
+            } else {
+                warn!("Skipping {} in model bundle: not a regular file", relative);
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Writes `models` (which must be installed in `models_dir`) to a signed bundle
+/// at `output`.
+pub(crate) fn export_bundle(
+    models_dir: &Path,
+    models: &[ModelInfo],
+    signing_key: &Ed25519KeyPair,
+    output: &Path,
+) -> Result<ModelBundleExportReport> {
+    if models.is_empty() {
+        bail!("Select at least one model to export");
+    }
+
+    let temp_output = output.with_extension(format!("{}.tmp", MODEL_BUNDLE_EXTENSION));
+    let _ = fs::remove_file(&temp_output);
+
+    let write_result = (|| -> Result<BundleManifest> {
+        let mut zip = ZipWriter::new(File::create(&temp_output)?);
+        let mut manifest = BundleManifest {
+            format: BUNDLE_FORMAT.to_string(),
+            format_version: BUNDLE_FORMAT_VERSION,
+            created_at: chrono::Utc::now().to_rfc3339(),
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            signer: public_key_hex(signing_key),
+            models: Vec::new(),
+        };
+        let mut buffer = vec![0_u8; 1024 * 1024];
+
+        for model in models {
+            let mut bundled = BundledModel {
+                id: model.id.clone(),
+                name: model.name.clone(),
+                engine_type: model.engine_type.clone(),
+                filename: model.filename.clone(),
+                is_directory: model.is_directory,
+                files: Vec::new(),
+            };
+
+            for (relative, path) in model_files(models_dir, model)
+                .with_context(|| format!("Model '{}' is not installed", model.id))?
+            {
+                let mut source = File::open(&path)?;
+                let size = source.metadata()?.len();
+                // Model weights are already dense; deflate would only cost time.
+                let options = SimpleFileOptions::default()
+                    .compression_method(CompressionMethod::Stored)
+                    .large_file(size >= u32::MAX as u64);
+                zip.start_file(format!("{}{}", PAYLOAD_PREFIX, relative), options)?;
+
+                let mut hasher = Sha256::new();
+                loop {
+                    let read = source.read(&mut buffer)?;
+                    if read == 0 {
+                        break;
+                    }
+                    hasher.update(&buffer[..read]);
+                    zip.write_all(&buffer[..read])?;
+                }
+                bundled.files.push(BundledFile {
+                    path: relative,
+                    size,
+                    sha256: format!("{:x}", hasher.finalize()),
+                });
+            }
+            manifest.models.push(bundled);
+        }
+
+        let manifest_bytes = serde_json::to_vec_pretty(&manifest)?;
+        let signature = signing_key.sign(&manifest_bytes);
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        zip.start_file(MANIFEST_ENTRY, options)?;
+        zip.write_all(&manifest_bytes)?;
+        zip.start_file(SIGNATURE_ENTRY, options)?;
+        zip.write_all(to_hex(signature.as_ref()).as_bytes())?;
+        zip.finish()?;
+        Ok(manifest)
+    })();
+
+    let manifest = match write_result {
+        Ok(manifest) => manifest,
+        Err(e) => {
+            let _ = fs::remove_file(&temp_output);
+            return Err(e.context("Failed to write model bundle"));
+        }
+    };
+    fs::rename(&temp_output, output)
+        .with_context(|| format!("Failed to move model bundle to {}", output.display()))?;
+
+    let total_bytes = manifest
+        .models
+        .iter()
+        .flat_map(|model| &model.files)
+        .map(|file| file.size)
+        .sum();
+    info!(
+        "Exported {} model(s), {} bytes, to {}",
+        manifest.models.len(),
+        total_bytes,
+        output.display()
+    );
+
+    Ok(ModelBundleExportReport {
+        path: output.to_string_lossy().to_string(),
+        signer: manifest.signer,
+        models: manifest.models.into_iter().map(|model| model.id).collect(),
+        total_bytes,
+    })
+}
+
+fn read_entry(archive: &mut ZipArchive<File>, name: &str, limit: u64) -> Result<Vec<u8>> {
+    let entry = archive
+        .by_name(name)
+        .map_err(|_| anyhow!("Model bundle is missing {}", name))?;
+    let mut bytes = Vec::new();
+    entry.take(limit).read_to_end(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Parses the manifest and checks its signature. The signer must be one of
+/// `trusted_keys`; with no trusted keys configured, a valid signature from any
+/// key is accepted only when `allow_untrusted` is set.
+fn verified_manifest(
+    archive: &mut ZipArchive<File>,
+    trusted_keys: &[String],
+    allow_untrusted: bool,
+) -> Result<BundleManifest> {
+    let manifest_bytes = read_entry(archive, MANIFEST_ENTRY, 16 * 1024 * 1024)?;
+    let signature_hex = read_entry(archive, SIGNATURE_ENTRY, 1024)?;
+
+    let manifest: BundleManifest = serde_json::from_slice(&manifest_bytes)
+        .map_err(|e| anyhow!("Invalid model bundle manifest: {}", e))?;
+    if manifest.format != BUNDLE_FORMAT || manifest.format_version != BUNDLE_FORMAT_VERSION {
+        bail!(
+            "Unsupported model bundle format {} v{}",
+            manifest.format,
+            manifest.format_version
+        );
+    }
+
+    let signer = from_hex(&manifest.signer)
+        .ok_or_else(|| anyhow!("Model bundle signer is not a hex public key"))?;
+    let signature = std::str::from_utf8(&signature_hex)
+        .ok()
+        .and_then(from_hex)
+        .ok_or_else(|| anyhow!("Model bundle signature is not hex"))?;
+    UnparsedPublicKey::new(&signature::ED25519, &signer)
+        .verify(&manifest_bytes, &signature)
+        .map_err(|_| anyhow!("Model bundle signature is invalid; the bundle was modified"))?;
+
+    let signer_hex = manifest.signer.to_ascii_lowercase();
+    if !trusted_keys.is_empty()
+        && !trusted_keys
+            .iter()
+            .any(|key| key.trim().eq_ignore_ascii_case(&signer_hex))
+    {
+        bail!("Model bundle is signed by an untrusted key {}", signer_hex);
+    }
+    if trusted_keys.is_empty() {
+        if !allow_untrusted {
+            bail!(
+                "No trusted model bundle keys are configured; trust the signer {} or allow untrusted bundles",
+                signer_hex
+            );
+        }
+        warn!(
+            "No trusted model bundle keys configured; accepting bundle signed by {}",
+            signer_hex
+        );
+    }
+
+    Ok(manifest)
+}
+
+/// Checks that every file of `model` stays inside its own file or directory.
+fn validate_model_paths(model: &BundledModel) -> Result<()> {
+    let filename_ok = !model.filename.is_empty()
+        && !model.filename.starts_with('.')
+        && !model.filename.contains(['/', '\\']);
+    if !filename_ok {
+        bail!("invalid filename '{}'", model.filename);
+    }
+    if model.files.is_empty() {
+        bail!("no files");
+    }
+
+    for file in &model.files {
+        let segments: Vec<&str> = file.path.split('/').collect();
+        let safe = !file.path.contains('\\')
+            && segments.iter().all(|segment| {
+                !segment.is_empty()
+                    && *segment != "."
+                    && *segment != ".."
+                    && !segment.chars().any(|c| c.is_control())
+            });
+        let inside = if model.is_directory {
+            segments.len() > 1 && segments[0] == model.filename
+        } else {
+            file.path == model.filename
+        };
+        if !safe || !inside {
+            bail!("unsafe file path '{}'", file.path);
+        }
+    }
+    if !model.is_directory && model.files.len() != 1 {
+        bail!("a single-file model must contain exactly one file");
+    }
+    Ok(())
+}
+
+/// The local catalog entry for a bundled model. Models the catalog doesn't
+/// know are only accepted as custom models discovery registers after import:
+/// Whisper `.bin` files, or directories carrying the custom model sidecar.
+fn local_model_info(
+    bundled: &BundledModel,
+    catalog: &HashMap<String, ModelInfo>,
+) -> Result<ModelInfo> {
+    if let Some(model) = catalog.get(&bundled.id) {
+        if model.filename != bundled.filename || model.is_directory != bundled.is_directory {
+            bail!("the bundle's files don't match this machine's catalog entry");
+        }
+        // A single-file model is the file the catalog pins; directory models
+        // pin their download archive, which the bundle doesn't carry.
+        if let (Some(expected), false) = (&model.sha256, model.is_directory) {
+            if !bundled
+                .files
+                .iter()
+                .all(|file| file.sha256.eq_ignore_ascii_case(expected))
+            {
+                bail!("the bundled file doesn't match the checksum in this machine's catalog");
+            }
+        }
+        return Ok(model.clone());
+    }
+
+    let is_custom_whisper = matches!(bundled.engine_type, EngineType::Whisper)
+        && !bundled.is_directory
+        && bundled.filename == format!("{}.bin", bundled.id);
+    let metadata_path = format!("{}/{}", bundled.filename, CUSTOM_MODEL_METADATA_FILE_NAME);
+    let is_custom_directory = bundled.is_directory
+        && bundled.filename == bundled.id
+        && bundled.files.iter().any(|file| file.path == metadata_path);
+    if !is_custom_whisper && !is_custom_directory {
+        bail!("not in this machine's model catalog; add it to model_catalog.json first");
+    }
+    Ok(ModelInfo {
+        id: bundled.id.clone(),
+        name: bundled.name.clone(),
+        description: String::new(),
+        filename: bundled.filename.clone(),
+        url: None,
+        mirrors: vec![],
+        sha256: None,
+        size_mb: 0,
+        is_downloaded: false,
+        is_downloading: false,
+        partial_size: 0,
+        is_directory: bundled.is_directory,
+        engine_type: bundled.engine_type.clone(),
+        accuracy_score: 0.0,
+        speed_score: 0.0,
+        supports_translation: false,
+        is_recommended: false,
+        supported_languages: vec![],
+        is_custom: true,
+        load_error: None,
+    })
+}
+
+/// Extracts one model into a staging path, verifying every file, then moves
+/// it into place.
+fn install_model(
+    archive: &mut ZipArchive<File>,
+    models_dir: &Path,
+    model: &BundledModel,
+) -> Result<()> {
+    let staging = models_dir.join(format!("{}.importing", model.filename));
+    let _ = fs::remove_dir_all(&staging);
+    let _ = fs::remove_file(&staging);
+
+    let extract = |archive: &mut ZipArchive<File>| -> Result<()> {
+        for file in &model.files {
+            let target = if model.is_directory {
+                let relative = file
+                    .path
+                    .split_once('/')
+                    .map(|(_, rest)| rest)
+                    .unwrap_or("");
+                staging.join(relative)
+            } else {
+                staging.clone()
+            };
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+
+            let entry_name = format!("{}{}", PAYLOAD_PREFIX, file.path);
+            let entry = archive
+                .by_name(&entry_name)
+                .map_err(|_| anyhow!("{} is missing from the bundle", file.path))?;
+            let mut output = File::create(&target)?;
+            let mut hasher = Sha256::new();
+            let mut written = 0_u64;
+            let mut reader = entry.take(file.size + 1);
+            let mut buffer = vec![0_u8; 1024 * 1024];
+            loop {
+                let read = reader.read(&mut buffer)?;
+                if read == 0 {
+                    break;
+                }
+                hasher.update(&buffer[..read]);
+                output.write_all(&buffer[..read])?;
+                written += read as u64;
+            }
+            output.flush()?;
+
+            if written != file.size {
+                bail!(
+                    "{} has {} bytes, expected {}",
+                    file.path,
+                    written,
+                    file.size
+                );
+            }
+            let actual = format!("{:x}", hasher.finalize());
+            if !actual.eq_ignore_ascii_case(&file.sha256) {
+                bail!("{} failed checksum verification", file.path);
+            }
+        }
+        Ok(())
+    };
+
+    if let Err(e) = extract(archive) {
+        let _ = fs::remove_dir_all(&staging);
+        let _ = fs::remove_file(&staging);
+        return Err(e);
+    }
+
+    let final_path = models_dir.join(&model.filename);
+    if final_path.is_dir() {
+        fs::remove_dir_all(&final_path)?;
+    } else if final_path.exists() {
+        fs::remove_file(&final_path)?;
+    }
+    model_download::remove_partial(&models_dir.join(format!("{}.partial", model.filename)));
+    fs::rename(&staging, &final_path)?;
+    Ok(())
+}
+
+/// Verifies the bundle at `bundle_path` and installs its models into
+/// `models_dir`. Models already installed are skipped; a model whose files
+/// fail verification is reported and left out. See `verified_manifest` for
+/// `allow_untrusted`.
+pub(crate) fn import_bundle(
+    bundle_path: &Path,
+    models_dir: &Path,
+    catalog: &HashMap<String, ModelInfo>,
+    trusted_keys: &[String],
+    allow_untrusted: bool,
+) -> Result<ModelBundleImportReport> {
+    let file = File::open(bundle_path)
+        .with_context(|| format!("Failed to open model bundle {}", bundle_path.display()))?;
+    let mut archive = ZipArchive::new(file).map_err(|e| anyhow!("Not a model bundle: {}", e))?;
+    let manifest = verified_manifest(&mut archive, trusted_keys, allow_untrusted)?;
+
+    // Every payload entry must be listed in the signed manifest.
+    let listed: BTreeSet<String> = manifest
+        .models
+        .iter()
+        .flat_map(|model| &model.files)
+        .map(|file| format!("{}{}", PAYLOAD_PREFIX, file.path))
+        .collect();
+    for name in archive.file_names() {
+        if name.starts_with(PAYLOAD_PREFIX) && !name.ends_with('/') && !listed.contains(name) {
+            bail!("Model bundle contains unlisted file {}", name);
+        }
+    }
+
+    fs::create_dir_all(models_dir)?;
+    let mut report = ModelBundleImportReport {
+        signer: manifest.signer.to_ascii_lowercase(),
+        imported: Vec::new(),
+        skipped: Vec::new(),
+        failed: Vec::new(),
+    };
+
+    for bundled in &manifest.models {
+        let result = validate_model_paths(bundled)
+            .and_then(|()| local_model_info(bundled, catalog))
+            .and_then(|local| {
+                if ModelManager::resolve_model_path(models_dir, &local).is_ok() {
+                    return Ok(false);
+                }
+                install_model(&mut archive, models_dir, bundled)?;
+                Ok(true)
+            });
+
+        match result {
+            Ok(true) => {
+                info!("Imported model {} from bundle", bundled.id);
+                report.imported.push(bundled.id.clone());
+            }
+            Ok(false) => report.skipped.push(bundled.id.clone()),
+            Err(e) => {
+                warn!("Failed to import model {}: {}", bundled.id, e);
+                report.failed.push(ModelBundleFailure {
+                    model_id: bundled.id.clone(),
+                    message: e.to_string(),
+                });
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn model(id: &str, filename: &str, is_directory: bool, engine_type: EngineType) -> ModelInfo {
+        ModelInfo {
+            id: id.to_string(),
+            name: id.to_string(),
+            description: String::new(),
+            filename: filename.to_string(),
+            url: Some(format!("https://example.com/{}", filename)),
+            mirrors: vec![],
+            sha256: None,
+            size_mb: 1,
+            is_downloaded: false,
+            is_downloading: false,
+            partial_size: 0,
+            is_directory,
+            engine_type,
+            accuracy_score: 0.5,
+            speed_score: 0.5,
+            supports_translation: false,
+            is_recommended: false,
+            supported_languages: vec!["en".to_string()],
+            is_custom: false,
+            load_error: None,
+        }
+    }
+
+    fn catalog() -> HashMap<String, ModelInfo> {
+        [
+            model("small", "ggml-small.bin", false, EngineType::Whisper),
+            model("parakeet", "parakeet-int8", true, EngineType::Parakeet),
+        ]
+        .into_iter()
+        .map(|model| (model.id.clone(), model))
+        .collect()
+    }
+
+    /// Installs `small` and `parakeet` in a fresh models directory.
+    fn source_models_dir() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("ggml-small.bin"), vec![7_u8; 4096]).unwrap();
+        let parakeet = dir.path().join("parakeet-int8");
+        fs::create_dir_all(parakeet.join("nested")).unwrap();
+        fs::write(parakeet.join("encoder.onnx"), b"encoder weights").unwrap();
+        fs::write(parakeet.join("nested/vocab.txt"), b"a\nb\n").unwrap();
+        dir
+    }
+
+    fn export(source: &TempDir, key: &Ed25519KeyPair) -> PathBuf {
+        let catalog = catalog();
+        let models = vec![catalog["small"].clone(), catalog["parakeet"].clone()];
+        let output = source.path().join("fleet.codictatemodels");
+        export_bundle(source.path(), &models, key, &output).unwrap();
+        output
+    }
+
+    /// Rewrites the bundle with `edit` applied to each (name, bytes) entry.
+    fn rewrite(bundle: &Path, edit: impl Fn(&str, &mut Vec<u8>)) {
+        let mut archive = ZipArchive::new(File::open(bundle).unwrap()).unwrap();
+        let mut entries = Vec::new();
+        for index in 0..archive.len() {
+            let mut entry = archive.by_index(index).unwrap();
+            let mut bytes = Vec::new();
+            entry.read_to_end(&mut bytes).unwrap();
+            let name = entry.name().to_string();
+            edit(&name, &mut bytes);
+            entries.push((name, bytes));
+        }
+        let mut zip = ZipWriter::new(File::create(bundle).unwrap());
+        for (name, bytes) in entries {
+            zip.start_file(name, SimpleFileOptions::default()).unwrap();
+            zip.write_all(&bytes).unwrap();
+        }
+        zip.finish().unwrap();
+    }
+
+    #[test]
+    fn round_trip_installs_file_and_directory_models() {
+        let source = source_models_dir();
+        let key = load_or_create_signing_key(&source.path().join(SIGNING_KEY_FILE_NAME)).unwrap();
+        let bundle = export(&source, &key);
+
+        let target = TempDir::new().unwrap();
+        let trusted = vec![public_key_hex(&key).to_ascii_uppercase()];
+        let report = import_bundle(&bundle, target.path(), &catalog(), &trusted, false).unwrap();
+        assert_eq!(report.imported, vec!["small", "parakeet"]);
+        assert!(report.failed.is_empty());
+        assert_eq!(
+            fs::read(target.path().join("parakeet-int8/nested/vocab.txt")).unwrap(),
+            b"a\nb\n"
+        );
+        assert_eq!(
+            fs::read(target.path().join("ggml-small.bin"))
+                .unwrap()
+                .len(),
+            4096
+        );
+
+        let again = import_bundle(&bundle, target.path(), &catalog(), &trusted, false).unwrap();
+        assert_eq!(again.skipped, vec!["small", "parakeet"]);
+    }
+
+    #[test]
+    fn signing_key_is_reused_across_exports() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join(SIGNING_KEY_FILE_NAME);
+        let first = public_key_hex(&load_or_create_signing_key(&path).unwrap());
+        let second = public_key_hex(&load_or_create_signing_key(&path).unwrap());
+        assert_eq!(first, second);
+        assert!(is_valid_public_key(&first));
+    }
+
+    #[test]
+    fn rejects_untrusted_signer_and_modified_manifest() {
+        let source = source_models_dir();
+        let key = load_or_create_signing_key(&source.path().join(SIGNING_KEY_FILE_NAME)).unwrap();
+        let bundle = export(&source, &key);
+        let target = TempDir::new().unwrap();
+
+        let other = TempDir::new().unwrap();
+        let other_key =
+            load_or_create_signing_key(&other.path().join(SIGNING_KEY_FILE_NAME)).unwrap();
+        let error = import_bundle(
+            &bundle,
+            target.path(),
+            &catalog(),
+            &[public_key_hex(&other_key)],
+            true,
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("untrusted"));
+        let error = import_bundle(&bundle, target.path(), &catalog(), &[], false).unwrap_err();
+        assert!(error.to_string().contains("No trusted model bundle keys"));
+        assert!(!target.path().join("ggml-small.bin").exists());
+
+        rewrite(&bundle, |name, bytes| {
+            if name == MANIFEST_ENTRY {
+                let text = String::from_utf8(bytes.clone()).unwrap();
+                *bytes = text
+                    .replacen("\"size\": 4096", "\"size\": 4095", 1)
+                    .into_bytes();
+            }
+        });
+        let error = import_bundle(&bundle, target.path(), &catalog(), &[], true).unwrap_err();
+        assert!(error.to_string().contains("signature is invalid"));
+    }
+
+    #[test]
+    fn tampered_payload_fails_only_that_model() {
+        let source = source_models_dir();
+        let key = load_or_create_signing_key(&source.path().join(SIGNING_KEY_FILE_NAME)).unwrap();
+        let bundle = export(&source, &key);
+        rewrite(&bundle, |name, bytes| {
+            if name == "models/parakeet-int8/encoder.onnx" {
+                bytes[0] ^= 0xff;
+            }
+        });
+
+        let target = TempDir::new().unwrap();
+        let report = import_bundle(&bundle, target.path(), &catalog(), &[], true).unwrap();
+        assert_eq!(report.imported, vec!["small"]);
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(report.failed[0].model_id, "parakeet");
+        assert!(!target.path().join("parakeet-int8").exists());
+        assert!(!target.path().join("parakeet-int8.importing").exists());
+    }
+
+    #[test]
+    fn single_file_models_must_match_the_catalog_checksum() {
+        let source = source_models_dir();
+        let key = load_or_create_signing_key(&source.path().join(SIGNING_KEY_FILE_NAME)).unwrap();
+        let bundle = export(&source, &key);
+        let trusted = vec![public_key_hex(&key)];
+
+        let mut pinned = catalog();
+        pinned.get_mut("small").unwrap().sha256 = Some("ab".repeat(32));
+        let target = TempDir::new().unwrap();
+        let report = import_bundle(&bundle, target.path(), &pinned, &trusted, false).unwrap();
+        assert_eq!(report.imported, vec!["parakeet"]);
+        assert_eq!(report.failed[0].model_id, "small");
+        assert!(!target.path().join("ggml-small.bin").exists());
+
+        let actual = format!("{:x}", Sha256::digest(vec![7_u8; 4096]));
+        pinned.get_mut("small").unwrap().sha256 = Some(actual.to_ascii_uppercase());
+        let report = import_bundle(&bundle, target.path(), &pinned, &trusted, false).unwrap();
+        assert_eq!(report.imported, vec!["small"]);
+    }
+
+    #[test]
+    fn unknown_directory_models_and_unsafe_paths_are_rejected() {
+        let bundled = |id: &str, filename: &str, is_directory: bool, path: &str| BundledModel {
+            id: id.to_string(),
+            name: id.to_string(),
+            engine_type: EngineType::Parakeet,
+            filename: filename.to_string(),
+            is_directory,
+            files: vec![BundledFile {
+                path: path.to_string(),
+                size: 1,
+                sha256: String::new(),
+            }],
+        };
+
+        assert!(validate_model_paths(&bundled("p", "p", true, "p/../../etc/passwd")).is_err());
+        assert!(validate_model_paths(&bundled("p", "p", true, "other/file")).is_err());
+        assert!(validate_model_paths(&bundled("p", "p", true, "p/model.onnx")).is_ok());
+        assert!(local_model_info(&bundled("p", "p", true, "p/model.onnx"), &catalog()).is_err());
+        let custom = bundled("p", "p", true, "p/codictate-model.json");
+        assert!(local_model_info(&custom, &catalog()).unwrap().is_custom);
+    }
+}
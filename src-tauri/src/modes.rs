@@ -0,0 +1,186 @@
+//! Modes: named presets bundling transcription and refinement settings.
+//!
+//! The active mode (`AppSettings::active_mode_id`) applies to recordings started
+//! from the main shortcuts. A mode may also own a dedicated shortcut, stored in
+//! `AppSettings::bindings` under [`mode_binding_id`], which records with that
+//! mode regardless of the active one. Mode settings are applied per recording
+//! session through [`crate::app_profiles::session_settings`].
+
+use crate::managers::transcription::TranscriptionManager;
+use crate::settings::{self, AppSettings, Mode, ShortcutBinding};
+use crate::tray;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::{debug, info};
+
+/// Prefix of the shortcut binding ids owned by modes.
+pub const MODE_BINDING_PREFIX: &str = "transcribe_mode:";
+
+/// Prefix of the tray menu item ids used to switch modes.
+pub const TRAY_MODE_ITEM_PREFIX: &str = "mode:";
+/// Tray menu item id that clears the active mode.
+pub const TRAY_NO_MODE_ITEM_ID: &str = "mode_none";
+
+pub fn mode_binding_id(mode_id: &str) -> String {
+    format!("{MODE_BINDING_PREFIX}{mode_id}")
+}
+
+pub fn mode_id_from_binding(binding_id: &str) -> Option<&str> {
+    binding_id
+        .strip_prefix(MODE_BINDING_PREFIX)
+        .filter(|id| !id.is_empty())
+}
+
+pub fn is_mode_binding(binding_id: &str) -> bool {
+    mode_id_from_binding(binding_id).is_some()
+}
+
+/// The mode that owns `binding_id`, if it is a mode shortcut.
+pub fn mode_for_binding<'a>(settings: &'a AppSettings, binding_id: &str) -> Option<&'a Mode> {
+    mode_id_from_binding(binding_id).and_then(|mode_id| settings.mode(mode_id))
+}
+
+/// Builds the shortcut binding for a mode's dedicated hotkey.
+pub fn mode_binding(mode: &Mode, shortcut: &str) -> ShortcutBinding {
+    ShortcutBinding {
+        id: mode_binding_id(&mode.id),
+        name: mode.name.clone(),
+        description: format!("Transcribe using the '{}' mode.", mode.name),
+        default_binding: String::new(),
+        current_binding: shortcut.to_string(),
+    }
+}
+
+pub fn tray_item_id(mode_id: &str) -> String {
+    format!("{TRAY_MODE_ITEM_PREFIX}{mode_id}")
+}
+
+/// Handles a tray menu click. Returns `false` if the item is not a mode item.
+pub fn handle_tray_menu_event(app: &AppHandle, item_id: &str) -> bool {
+    let mode_id = if item_id == TRAY_NO_MODE_ITEM_ID {
+        None
+    } else if let Some(mode_id) = item_id.strip_prefix(TRAY_MODE_ITEM_PREFIX) {
+        Some(mode_id.to_string())
+    } else {
+        return false;
+    };
+
+    if let Err(e) = set_active_mode(app, mode_id) {
+        tracing::warn!("Failed to switch mode from tray: {}", e);
+    }
+    true
+}
+
+/// Persists the active mode, preloads its model and refreshes the tray.
+pub fn set_active_mode(app: &AppHandle, mode_id: Option<String>) -> Result<(), String> {
+    let mut settings = settings::get_settings(app);
+    if let Some(id) = mode_id.as_deref() {
+        if settings.mode(id).is_none() {
+            return Err(format!("Mode with id '{}' not found", id));
+        }
+    }
+
+    settings.active_mode_id = mode_id.clone();
+    let selected_model = settings
+        .active_mode()
+        .and_then(|mode| mode.settings.selected_model.clone())
+        .filter(|model| !model.is_empty())
+        .unwrap_or_else(|| settings.selected_model.clone());
+    settings::write_settings(app, settings);
+    info!("Active mode set to {:?}", mode_id);
+
+    preload_model(app, selected_model);
+    refresh_tray(app);
+
+    let _ = app.emit(
+        "settings-changed",
+        serde_json::json!({
+            "setting": "active_mode_id",
+            "value": mode_id
+        }),
+    );
+
+    Ok(())
+}
+
+/// Starts loading `model_id` in the background so the next recording does not
+/// wait on a model swap. Skipped while a session is using the current model.
+fn preload_model(app: &AppHandle, model_id: String) {
+    let Some(tm) = app.try_state::<Arc<TranscriptionManager>>() else {
+        return;
+    };
+    if tm.is_any_session_active() {
+        debug!("Skipping mode model preload while a session is active");
+        return;
+    }
+    tm.initiate_model_load_for_model(model_id);
+}
+
+pub fn refresh_tray(app: &AppHandle) {
+    let app_clone = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tray::update_tray_menu_async(&app_clone, &tray::TrayIconState::Idle, None).await;
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::{get_default_settings, ModeSettings};
+
+    fn mode(id: &str) -> Mode {
+        Mode {
+            id: id.to_string(),
+            name: id.to_string(),
+            icon: None,
+            settings: ModeSettings::default(),
+        }
+    }
+
+    #[test]
+    fn mode_binding_ids_round_trip() {
+        let binding_id = mode_binding_id("mode_1");
+        assert_eq!(binding_id, "transcribe_mode:mode_1");
+        assert_eq!(mode_id_from_binding(&binding_id), Some("mode_1"));
+        assert!(is_mode_binding(&binding_id));
+        assert!(!is_mode_binding("transcribe"));
+        assert!(!is_mode_binding(MODE_BINDING_PREFIX));
+    }
+
+    #[test]
+    fn mode_for_binding_resolves_existing_modes_only() {
+        let mut settings = get_default_settings();
+        settings.modes = vec![mode("mode_1")];
+
+        assert_eq!(
+            mode_for_binding(&settings, &mode_binding_id("mode_1")).map(|m| m.id.as_str()),
+            Some("mode_1")
+        );
+        assert!(mode_for_binding(&settings, &mode_binding_id("mode_2")).is_none());
+        assert!(mode_for_binding(&settings, "transcribe").is_none());
+    }
+
+    #[test]
+    fn mode_settings_override_provider_model_for_mode_provider() {
+        let mut settings = get_default_settings();
+        settings.post_process_provider_id = "openai".to_string();
+        settings.selected_model = "global-model".to_string();
+
+        let mode_settings = ModeSettings {
+            selected_model: Some("mode-model".to_string()),
+            post_process_provider_id: Some("groq".to_string()),
+            post_process_model: Some("llama".to_string()),
+            auto_refine_enabled: Some(true),
+            ..Default::default()
+        };
+        mode_settings.apply_to(&mut settings);
+
+        assert_eq!(settings.selected_model, "mode-model");
+        assert_eq!(settings.post_process_provider_id, "groq");
+        assert_eq!(
+            settings.post_process_models.get("groq").map(String::as_str),
+            Some("llama")
+        );
+        assert!(settings.auto_refine_enabled);
+    }
+}
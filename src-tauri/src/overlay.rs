@@ -2051,6 +2051,15 @@ pub fn emit_levels(app_handle: &AppHandle, levels: &Vec<f32>) {
     }
 }
 
+/// Emit a streaming partial transcript to the main app and the recording overlay
+pub fn emit_transcription_partial<S: Serialize + Clone>(app_handle: &AppHandle, payload: &S) {
+    let _ = app_handle.emit("transcription-partial", payload);
+
+    if let Some(overlay_window) = app_handle.get_webview_window("recording_overlay") {
+        let _ = overlay_window.emit("transcription-partial", payload);
+    }
+}
+
 /// Emit recording time progress to the overlay
 /// elapsed_secs: seconds elapsed since recording started
 /// max_secs: maximum allowed recording time in seconds
@@ -2060,6 +2069,16 @@ pub fn emit_recording_time(app_handle: &AppHandle, elapsed_secs: u32, max_secs:
     }
 }
 
+/// Tell the main app and the overlay whether the always-on microphone is
+/// keeping recent audio, so both can show it. `secs` is 0 when the buffer is off.
+pub fn emit_recent_audio_buffer(app_handle: &AppHandle, secs: u32) {
+    let _ = app_handle.emit("recent-audio-buffer", secs);
+
+    if let Some(overlay_window) = app_handle.get_webview_window("recording_overlay") {
+        let _ = overlay_window.emit("recent-audio-buffer", secs);
+    }
+}
+
 /// Creates the recording overlay window and keeps it hidden by default
 #[cfg(not(target_os = "macos"))]
 pub fn create_recording_overlay(app_handle: &AppHandle) {
@@ -13,6 +13,14 @@ pub const APPLE_INTELLIGENCE_DEFAULT_MODEL_ID: &str = "Apple Intelligence";
 #[cfg(all(target_os = "macos", target_arch = "aarch64"))]
 pub const LOCAL_MLX_PROVIDER_ID: &str = "local_mlx";
 
+/// Bounds for the always-on recent-audio buffer.
+pub const MIN_RECENT_AUDIO_SECS: u32 = 5;
+pub const MAX_RECENT_AUDIO_SECS: u32 = 60;
+
+/// Bounds for the number of speakers diarization may find in one recording.
+pub const MIN_DIARIZATION_SPEAKERS: u32 = 2;
+pub const MAX_DIARIZATION_SPEAKERS: u32 = 10;
+
 #[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, Type)]
 #[serde(rename_all = "lowercase")]
 pub enum LogLevel {
@@ -84,6 +92,127 @@ pub struct LLMPrompt {
     pub prompt: String,
 }
 
+/// Settings an application profile may override. `None` keeps the global value.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Type)]
+#[serde(default)]
+pub struct AppProfileOverrides {
+    pub selected_model: Option<String>,
+    pub selected_language: Option<String>,
+    pub translate_to_english: Option<bool>,
+    pub paste_method: Option<PasteMethod>,
+    pub post_process_selected_prompt_id: Option<String>,
+    pub auto_refine_enabled: Option<bool>,
+    pub auto_submit: Option<bool>,
+    pub auto_submit_key: Option<AutoSubmitKey>,
+}
+
+impl AppProfileOverrides {
+    pub fn apply_to(&self, settings: &mut AppSettings) {
+        if let Some(model) = self.selected_model.as_ref().filter(|m| !m.is_empty()) {
+            settings.selected_model = model.clone();
+        }
+        if let Some(language) = self.selected_language.as_ref().filter(|l| !l.is_empty()) {
+            settings.selected_language = language.clone();
+        }
+        if let Some(translate) = self.translate_to_english {
+            settings.translate_to_english = translate;
+        }
+        if let Some(method) = self.paste_method {
+            settings.paste_method = method;
+        }
+        if let Some(prompt_id) = self.post_process_selected_prompt_id.as_ref() {
+            settings.post_process_selected_prompt_id = Some(prompt_id.clone());
+        }
+        if let Some(auto_refine) = self.auto_refine_enabled {
+            settings.auto_refine_enabled = auto_refine;
+        }
+        if let Some(auto_submit) = self.auto_submit {
+            settings.auto_submit = auto_submit;
+        }
+        if let Some(key) = self.auto_submit_key {
+            settings.auto_submit_key = key;
+        }
+    }
+}
+
+/// Per-application settings applied to a recording session when the matching
+/// application is frontmost at recording start.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Type)]
+pub struct AppProfile {
+    pub id: String,
+    pub name: String,
+    /// Matched case-insensitively against the macOS bundle id, X11 `WM_CLASS`
+    /// (instance or class), Wayland app-id, or Windows executable name.
+    pub app_identifiers: Vec<String>,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub overrides: AppProfileOverrides,
+}
+
+/// Settings bundled by a mode. `None` keeps the global value.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Type)]
+#[serde(default)]
+pub struct ModeSettings {
+    pub selected_model: Option<String>,
+    pub selected_language: Option<String>,
+    pub post_process_provider_id: Option<String>,
+    /// LLM model for the mode's provider (or the global provider if unset).
+    pub post_process_model: Option<String>,
+    pub post_process_selected_prompt_id: Option<String>,
+    pub auto_refine_enabled: Option<bool>,
+    pub paste_method: Option<PasteMethod>,
+    pub auto_submit: Option<bool>,
+}
+
+impl ModeSettings {
+    pub fn apply_to(&self, settings: &mut AppSettings) {
+        if let Some(model) = self.selected_model.as_ref().filter(|m| !m.is_empty()) {
+            settings.selected_model = model.clone();
+        }
+        if let Some(language) = self.selected_language.as_ref().filter(|l| !l.is_empty()) {
+            settings.selected_language = language.clone();
+        }
+        if let Some(provider_id) = self
+            .post_process_provider_id
+            .as_ref()
+            .filter(|id| !id.is_empty())
+        {
+            settings.post_process_provider_id = provider_id.clone();
+        }
+        if let Some(model) = self.post_process_model.as_ref().filter(|m| !m.is_empty()) {
+            settings
+                .post_process_models
+                .insert(settings.post_process_provider_id.clone(), model.clone());
+        }
+        if let Some(prompt_id) = self.post_process_selected_prompt_id.as_ref() {
+            settings.post_process_selected_prompt_id = Some(prompt_id.clone());
+        }
+        if let Some(auto_refine) = self.auto_refine_enabled {
+            settings.auto_refine_enabled = auto_refine;
+        }
+        if let Some(method) = self.paste_method {
+            settings.paste_method = method;
+        }
+        if let Some(auto_submit) = self.auto_submit {
+            settings.auto_submit = auto_submit;
+        }
+    }
+}
+
+/// A named preset bundling transcription and refinement settings. The active
+/// mode applies to the main shortcuts; a mode with its own shortcut (stored in
+/// `bindings` under `transcribe_mode:<id>`) applies whenever that shortcut is used.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Type)]
+pub struct Mode {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub icon: Option<String>,
+    #[serde(default)]
+    pub settings: ModeSettings,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, Type)]
 pub struct PostProcessProvider {
     pub id: String,
@@ -154,6 +283,32 @@ pub enum RecordingRetentionPeriod {
     Months3,
 }
 
+/// Container used for new recordings. FLAC is lossless and roughly half the
+/// size of WAV for speech.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordingFormat {
+    Wav,
+    Flac,
+}
+
+impl Default for RecordingFormat {
+    fn default() -> Self {
+        RecordingFormat::Wav
+    }
+}
+
+impl RecordingFormat {
+    pub const ALL: [RecordingFormat; 2] = [RecordingFormat::Wav, RecordingFormat::Flac];
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            RecordingFormat::Wav => "wav",
+            RecordingFormat::Flac => "flac",
+        }
+    }
+}
+
 impl Default for ModelUnloadTimeout {
     fn default() -> Self {
         ModelUnloadTimeout::Min2
@@ -170,6 +325,51 @@ impl Default for PasteMethod {
     }
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum VadBackend {
+    Silero,
+    Gmm,
+    Energy,
+}
+
+impl Default for VadBackend {
+    fn default() -> Self {
+        VadBackend::Silero
+    }
+}
+
+/// What gets recorded. System audio is the monitor of an output device
+/// (PulseAudio / PipeWire, Linux only).
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum CaptureSource {
+    Microphone,
+    SystemAudio,
+    Mixed,
+}
+
+impl Default for CaptureSource {
+    fn default() -> Self {
+        CaptureSource::Microphone
+    }
+}
+
+/// How mixed capture treats its two sources: summed into one channel, or
+/// additionally kept apart for telling the local speaker from the others.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum MixedChannelLayout {
+    Downmix,
+    Separate,
+}
+
+impl Default for MixedChannelLayout {
+    fn default() -> Self {
+        MixedChannelLayout::Downmix
+    }
+}
+
 impl Default for ClipboardHandling {
     fn default() -> Self {
         ClipboardHandling::DontModify
@@ -276,6 +476,13 @@ pub struct AppSettings {
     #[serde(default)]
     pub clamshell_microphone: Option<String>,
     #[serde(default)]
+    pub capture_source: CaptureSource,
+    /// Monitor source for system audio; `None` follows the default output.
+    #[serde(default)]
+    pub loopback_source: Option<String>,
+    #[serde(default)]
+    pub mixed_channel_layout: MixedChannelLayout,
+    #[serde(default)]
     pub selected_output_device: Option<String>,
     #[serde(default = "default_translate_to_english")]
     pub translate_to_english: bool,
@@ -295,11 +502,17 @@ pub struct AppSettings {
     pub word_correction_threshold: f64,
     #[serde(default = "default_word_correction_split_threshold")]
     pub word_correction_split_threshold: f64,
+    /// Hint dictionary terms to engines that accept them (Whisper's initial
+    /// prompt) so they are recognized correctly, not just corrected afterwards.
+    #[serde(default = "default_true")]
+    pub dictionary_biasing_enabled: bool,
     #[serde(default = "default_history_limit")]
     pub history_limit: usize,
     #[serde(default = "default_recording_retention_period")]
     pub recording_retention_period: RecordingRetentionPeriod,
     #[serde(default)]
+    pub recording_format: RecordingFormat,
+    #[serde(default)]
     pub paste_method: PasteMethod,
     #[serde(default)]
     pub clipboard_handling: ClipboardHandling,
@@ -327,6 +540,62 @@ pub struct AppSettings {
     pub auto_refine_enabled: bool,
     #[serde(default)]
     pub mute_while_recording: bool,
+    /// Recorder DSP: high-pass filter against rumble and DC offset.
+    #[serde(default)]
+    pub audio_high_pass_filter: bool,
+    /// Recorder DSP: spectral-gating suppression of steady background noise.
+    #[serde(default)]
+    pub audio_noise_suppression: bool,
+    /// Recorder DSP: automatic gain control with a peak limiter.
+    #[serde(default)]
+    pub audio_auto_gain: bool,
+    /// Voice activity detector used to trim silence. Silero falls back to the
+    /// GMM detector when its model cannot be loaded.
+    #[serde(default)]
+    pub vad_backend: VadBackend,
+    /// 0.0..=1.0; higher values detect quieter speech.
+    #[serde(default = "default_vad_sensitivity")]
+    pub vad_sensitivity: f32,
+    /// Frames of audio kept before detected speech.
+    #[serde(default = "default_vad_prefill_frames")]
+    pub vad_prefill_frames: usize,
+    /// Non-speech frames kept after speech ends.
+    #[serde(default = "default_vad_hangover_frames")]
+    pub vad_hangover_frames: usize,
+    /// Consecutive speech frames needed to start a speech segment.
+    #[serde(default = "default_vad_onset_frames")]
+    pub vad_onset_frames: usize,
+    /// Hands-free mode: stop recording automatically once the speaker goes quiet.
+    #[serde(default)]
+    pub auto_stop_on_silence: bool,
+    /// Trailing silence (after the VAD hangover) that ends a hands-free recording.
+    #[serde(default = "default_auto_stop_silence_ms")]
+    pub auto_stop_silence_ms: u32,
+    /// Speech required before silence may end the recording, so a cough alone
+    /// never triggers a paste.
+    #[serde(default = "default_auto_stop_min_speech_ms")]
+    pub auto_stop_min_speech_ms: u32,
+    /// Meetings and lectures: lift the recording limit and transcribe the
+    /// recording in chunks while it runs instead of all at once at the end.
+    #[serde(default)]
+    pub long_form_recording_enabled: bool,
+    /// With the always-on microphone, keep the last few seconds of audio in
+    /// memory so "transcribe recent audio" can pick up what was just said.
+    #[serde(default)]
+    pub recent_audio_buffer_enabled: bool,
+    #[serde(default = "default_recent_audio_buffer_secs")]
+    pub recent_audio_buffer_secs: u32,
+    /// Interviews and meetings: label who spoke when, so history can show
+    /// "Speaker 1", "Speaker 2", ... turns next to the transcript.
+    #[serde(default)]
+    pub speaker_diarization_enabled: bool,
+    #[serde(default = "default_diarization_max_speakers")]
+    pub diarization_max_speakers: u32,
+    /// Hex Ed25519 public keys whose offline model bundles may be imported.
+    /// When empty, bundles are refused unless the import allows untrusted
+    /// signers.
+    #[serde(default)]
+    pub trusted_model_bundle_keys: Vec<String>,
     #[serde(default = "default_append_trailing_space")]
     pub append_trailing_space: bool,
     #[serde(default = "default_paste_last_use_smart_insertion")]
@@ -343,6 +612,26 @@ pub struct AppSettings {
     /// When true, collapses repeated/stuttered words caused by ASR hallucinations.
     #[serde(default = "default_true")]
     pub enable_hallucination_filter: bool,
+    /// When true, spoken punctuation names ("comma", "open paren") are
+    /// converted to symbols without an LLM.
+    #[serde(default)]
+    pub spoken_punctuation_enabled: bool,
+    /// Base language codes for which spoken editing commands ("new line",
+    /// "scratch that", ...) are executed instead of pasted. Empty disables them.
+    #[serde(default)]
+    pub voice_command_languages: Vec<String>,
+    /// When true, speech is transcribed incrementally while recording and
+    /// `transcription-partial` events are emitted. The final text is unchanged.
+    #[serde(default)]
+    pub streaming_partials_enabled: bool,
+    /// Per-application overrides, checked in order; the first enabled match wins.
+    #[serde(default)]
+    pub app_profiles: Vec<AppProfile>,
+    #[serde(default)]
+    pub modes: Vec<Mode>,
+    /// Mode used by the main transcription shortcuts; `None` uses global settings.
+    #[serde(default)]
+    pub active_mode_id: Option<String>,
 
     #[serde(default = "default_show_tray_icon")]
     pub show_tray_icon: bool,
@@ -441,6 +730,38 @@ fn default_recording_retention_period() -> RecordingRetentionPeriod {
     RecordingRetentionPeriod::Never
 }
 
+fn default_vad_sensitivity() -> f32 {
+    0.5
+}
+
+fn default_vad_prefill_frames() -> usize {
+    15
+}
+
+fn default_vad_hangover_frames() -> usize {
+    15
+}
+
+fn default_vad_onset_frames() -> usize {
+    2
+}
+
+fn default_auto_stop_silence_ms() -> u32 {
+    2000
+}
+
+fn default_auto_stop_min_speech_ms() -> u32 {
+    1000
+}
+
+fn default_recent_audio_buffer_secs() -> u32 {
+    30
+}
+
+fn default_diarization_max_speakers() -> u32 {
+    4
+}
+
 fn default_audio_feedback_volume() -> f32 {
     1.0
 }
@@ -828,6 +1149,28 @@ pub fn get_default_settings() -> AppSettings {
         },
     );
 
+    // Transcribe recent audio: what was said just before the shortcut, taken
+    // from the always-on microphone's rolling buffer
+    #[cfg(target_os = "macos")]
+    let recent_audio_shortcut = "control+command+r";
+    #[cfg(target_os = "windows")]
+    let recent_audio_shortcut = "ctrl+alt+r";
+    #[cfg(target_os = "linux")]
+    let recent_audio_shortcut = "ctrl+alt+r";
+    #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+    let recent_audio_shortcut = "ctrl+alt+r";
+
+    bindings.insert(
+        "transcribe_recent_audio".to_string(),
+        ShortcutBinding {
+            id: "transcribe_recent_audio".to_string(),
+            name: "Transcribe recent audio".to_string(),
+            description: "Transcribe what you said just before pressing the shortcut.".to_string(),
+            default_binding: recent_audio_shortcut.to_string(),
+            current_binding: recent_audio_shortcut.to_string(),
+        },
+    );
+
     AppSettings {
         bindings,
 
@@ -842,6 +1185,9 @@ pub fn get_default_settings() -> AppSettings {
         always_on_microphone: false,
         selected_microphone: None,
         clamshell_microphone: None,
+        capture_source: CaptureSource::default(),
+        loopback_source: None,
+        mixed_channel_layout: MixedChannelLayout::default(),
         selected_output_device: None,
         translate_to_english: false,
         selected_language: default_selected_language(),
@@ -853,8 +1199,10 @@ pub fn get_default_settings() -> AppSettings {
         model_unload_timeout: ModelUnloadTimeout::Min2,
         word_correction_threshold: default_word_correction_threshold(),
         word_correction_split_threshold: default_word_correction_split_threshold(),
+        dictionary_biasing_enabled: true,
         history_limit: default_history_limit(),
         recording_retention_period: default_recording_retention_period(),
+        recording_format: RecordingFormat::default(),
         paste_method: PasteMethod::default(),
         clipboard_handling: ClipboardHandling::default(),
         auto_submit: default_auto_submit(),
@@ -868,12 +1216,35 @@ pub fn get_default_settings() -> AppSettings {
         post_process_selected_prompt_id: None,
         auto_refine_enabled: false,
         mute_while_recording: false,
+        audio_high_pass_filter: false,
+        audio_noise_suppression: false,
+        audio_auto_gain: false,
+        vad_backend: VadBackend::default(),
+        vad_sensitivity: default_vad_sensitivity(),
+        vad_prefill_frames: default_vad_prefill_frames(),
+        vad_hangover_frames: default_vad_hangover_frames(),
+        vad_onset_frames: default_vad_onset_frames(),
+        auto_stop_on_silence: false,
+        auto_stop_silence_ms: default_auto_stop_silence_ms(),
+        auto_stop_min_speech_ms: default_auto_stop_min_speech_ms(),
+        long_form_recording_enabled: false,
+        recent_audio_buffer_enabled: false,
+        recent_audio_buffer_secs: default_recent_audio_buffer_secs(),
+        speaker_diarization_enabled: false,
+        diarization_max_speakers: default_diarization_max_speakers(),
+        trusted_model_bundle_keys: Vec::new(),
         append_trailing_space: true,
         paste_last_use_smart_insertion: false,
         app_language: default_app_language(),
         enable_filler_word_filter: true,
         extra_filler_words: Vec::new(),
         enable_hallucination_filter: true,
+        spoken_punctuation_enabled: false,
+        voice_command_languages: Vec::new(),
+        streaming_partials_enabled: false,
+        app_profiles: Vec::new(),
+        modes: Vec::new(),
+        active_mode_id: None,
         show_tray_icon: default_show_tray_icon(),
         show_unload_model_in_tray: false,
 
@@ -905,6 +1276,31 @@ impl AppSettings {
             .iter_mut()
             .find(|provider| provider.id == provider_id)
     }
+
+    pub fn mode(&self, mode_id: &str) -> Option<&Mode> {
+        self.modes.iter().find(|mode| mode.id == mode_id)
+    }
+
+    pub fn active_mode(&self) -> Option<&Mode> {
+        self.active_mode_id.as_deref().and_then(|id| self.mode(id))
+    }
+
+    /// Seconds of audio kept for "transcribe recent audio", or 0 when the
+    /// buffer is off. It only ever runs with the always-on microphone.
+    pub fn recent_audio_secs(&self) -> u32 {
+        if self.always_on_microphone && self.recent_audio_buffer_enabled {
+            self.recent_audio_buffer_secs
+                .clamp(MIN_RECENT_AUDIO_SECS, MAX_RECENT_AUDIO_SECS)
+        } else {
+            0
+        }
+    }
+
+    /// Most speakers diarization may split a recording into.
+    pub fn diarization_speaker_limit(&self) -> usize {
+        self.diarization_max_speakers
+            .clamp(MIN_DIARIZATION_SPEAKERS, MAX_DIARIZATION_SPEAKERS) as usize
+    }
 }
 
 pub fn load_or_create_app_settings(app: &AppHandle) -> AppSettings {
@@ -6,7 +6,7 @@ use tauri::{AppHandle, Emitter, Manager};
 use tauri_plugin_autostart::ManagerExt;
 use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
 
-use crate::actions::ACTION_MAP;
+use crate::actions::action_for_binding;
 
 use crate::managers::audio::AudioRecordingManager;
 use crate::managers::transcription::TranscriptionManager;
@@ -90,6 +90,15 @@ impl ShortcutInitReport {
     }
 }
 
+const RECENT_AUDIO_BINDING_ID: &str = "transcribe_recent_audio";
+
+/// Whether the shortcut for `id` should be registered. Transcribing recent
+/// audio only has something to transcribe while the rolling buffer is on, so
+/// its shortcut is not held until the user enables it.
+fn binding_is_enabled(settings: &settings::AppSettings, id: &str) -> bool {
+    id != RECENT_AUDIO_BINDING_ID || settings.recent_audio_buffer_enabled
+}
+
 fn is_fn_managed_shortcut(binding: &str) -> bool {
     let binding_lower = binding.to_lowercase();
     binding_lower == "fn"
@@ -231,6 +240,16 @@ pub fn init_shortcuts(app: &AppHandle) -> ShortcutInitReport {
             .unwrap_or(default_binding);
         (id, binding)
     });
+    // Mode shortcuts only exist in user settings, for modes that still exist
+    let mode_bindings = user_settings
+        .bindings
+        .iter()
+        .filter(|(id, _)| crate::modes::mode_for_binding(&user_settings, id).is_some())
+        .map(|(id, binding)| (id.clone(), binding.clone()))
+        .collect::<Vec<_>>();
+    let resolved_bindings = resolved_bindings
+        .chain(mode_bindings)
+        .filter(|(id, _)| binding_is_enabled(&user_settings, id));
 
     let report = init_shortcuts_with(
         resolved_bindings,
@@ -394,8 +413,10 @@ pub fn change_binding(
     let mut updated_binding = binding_to_modify;
     updated_binding.current_binding = binding.clone();
 
-    // Register the new binding
-    if let Err(e) = register_shortcut(&app, updated_binding.clone()) {
+    // Register the new binding; disabled bindings are only saved
+    if !binding_is_enabled(&settings, &id) {
+        debug!("change_binding: '{}' is disabled, saving without registering", id);
+    } else if let Err(e) = register_shortcut(&app, updated_binding.clone()) {
         let error_msg = format!("Failed to register shortcut: {}", e);
         error!("change_binding error: {}", error_msg);
         return Ok(BindingResponse {
@@ -502,6 +523,10 @@ pub fn reset_bindings(app: AppHandle, ids: Vec<String>) -> Result<Vec<BindingRes
                 );
                 continue;
             }
+            if !binding_is_enabled(&current_settings, &binding.id) {
+                debug!("[reset_bindings] Skipping disabled shortcut for {}", binding.id);
+                continue;
+            }
 
             if let Err(e) = register_shortcut(&app, binding.clone()) {
                 // Log but don't fail - the binding is already saved
@@ -713,6 +738,18 @@ pub fn change_word_correction_threshold_setting(
     Ok(())
 }
 
+#[tauri::command]
+#[specta::specta]
+pub fn change_dictionary_biasing_enabled_setting(
+    app: AppHandle,
+    enabled: bool,
+) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.dictionary_biasing_enabled = enabled;
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
 #[tauri::command]
 #[specta::specta]
 pub fn change_paste_method_setting(app: AppHandle, method: String) -> Result<(), String> {
@@ -1015,6 +1052,283 @@ pub fn delete_post_process_prompt(app: AppHandle, id: String) -> Result<(), Stri
     Ok(())
 }
 
+fn normalize_app_identifiers(app_identifiers: Vec<String>) -> Result<Vec<String>, String> {
+    let identifiers: Vec<String> = app_identifiers
+        .into_iter()
+        .map(|id| id.trim().to_string())
+        .filter(|id| !id.is_empty())
+        .collect();
+    if identifiers.is_empty() {
+        return Err("An app profile needs at least one application identifier".to_string());
+    }
+    Ok(identifiers)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn add_app_profile(
+    app: AppHandle,
+    name: String,
+    app_identifiers: Vec<String>,
+    overrides: settings::AppProfileOverrides,
+) -> Result<settings::AppProfile, String> {
+    let mut settings = settings::get_settings(&app);
+
+    let profile = settings::AppProfile {
+        id: format!("profile_{}", chrono::Utc::now().timestamp_millis()),
+        name,
+        app_identifiers: normalize_app_identifiers(app_identifiers)?,
+        enabled: true,
+        overrides,
+    };
+
+    settings.app_profiles.push(profile.clone());
+    settings::write_settings(&app, settings);
+
+    Ok(profile)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn update_app_profile(app: AppHandle, profile: settings::AppProfile) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    let app_identifiers = normalize_app_identifiers(profile.app_identifiers.clone())?;
+
+    if let Some(existing) = settings
+        .app_profiles
+        .iter_mut()
+        .find(|p| p.id == profile.id)
+    {
+        *existing = settings::AppProfile {
+            app_identifiers,
+            ..profile
+        };
+        settings::write_settings(&app, settings);
+        Ok(())
+    } else {
+        Err(format!("App profile with id '{}' not found", profile.id))
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn delete_app_profile(app: AppHandle, id: String) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+
+    let original_len = settings.app_profiles.len();
+    settings.app_profiles.retain(|p| p.id != id);
+
+    if settings.app_profiles.len() == original_len {
+        return Err(format!("App profile with id '{}' not found", id));
+    }
+
+    settings::write_settings(&app, settings);
+    Ok(())
+}
+
+fn validate_mode_references(
+    settings: &settings::AppSettings,
+    mode_settings: &settings::ModeSettings,
+) -> Result<(), String> {
+    if let Some(provider_id) = mode_settings
+        .post_process_provider_id
+        .as_deref()
+        .filter(|id| !id.is_empty())
+    {
+        validate_provider_exists(settings, provider_id)?;
+    }
+    if let Some(prompt_id) = mode_settings.post_process_selected_prompt_id.as_deref() {
+        if !settings
+            .post_process_prompts
+            .iter()
+            .any(|p| p.id == prompt_id)
+        {
+            return Err(format!("Prompt with id '{}' not found", prompt_id));
+        }
+    }
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn add_mode(
+    app: AppHandle,
+    name: String,
+    icon: Option<String>,
+    mode_settings: settings::ModeSettings,
+) -> Result<settings::Mode, String> {
+    let mut settings = settings::get_settings(&app);
+    validate_mode_references(&settings, &mode_settings)?;
+
+    let mode = settings::Mode {
+        id: format!("mode_{}", chrono::Utc::now().timestamp_millis()),
+        name,
+        icon,
+        settings: mode_settings,
+    };
+
+    settings.modes.push(mode.clone());
+    settings::write_settings(&app, settings);
+    crate::modes::refresh_tray(&app);
+
+    Ok(mode)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn update_mode(app: AppHandle, mode: settings::Mode) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    validate_mode_references(&settings, &mode.settings)?;
+
+    let Some(existing) = settings.modes.iter_mut().find(|m| m.id == mode.id) else {
+        return Err(format!("Mode with id '{}' not found", mode.id));
+    };
+    *existing = mode.clone();
+
+    // Keep the dedicated shortcut's display name in sync with the mode
+    let binding_id = crate::modes::mode_binding_id(&mode.id);
+    if let Some(binding) = settings.bindings.get(&binding_id).cloned() {
+        settings.bindings.insert(
+            binding_id,
+            crate::modes::mode_binding(&mode, &binding.current_binding),
+        );
+    }
+
+    settings::write_settings(&app, settings);
+    crate::modes::refresh_tray(&app);
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn delete_mode(app: AppHandle, id: String) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+
+    let original_len = settings.modes.len();
+    settings.modes.retain(|m| m.id != id);
+
+    if settings.modes.len() == original_len {
+        return Err(format!("Mode with id '{}' not found", id));
+    }
+
+    if let Some(binding) = settings
+        .bindings
+        .remove(&crate::modes::mode_binding_id(&id))
+    {
+        if let Err(e) = unregister_shortcut(&app, binding) {
+            debug!("No mode shortcut to unregister for '{}': {}", id, e);
+        }
+    }
+
+    if settings.active_mode_id.as_ref() == Some(&id) {
+        settings.active_mode_id = None;
+    }
+
+    settings::write_settings(&app, settings);
+    crate::modes::refresh_tray(&app);
+    Ok(())
+}
+
+/// Select the mode used by the main transcription shortcuts (`None` for no mode).
+#[tauri::command]
+#[specta::specta]
+pub fn set_active_mode(app: AppHandle, id: Option<String>) -> Result<(), String> {
+    crate::modes::set_active_mode(&app, id)
+}
+
+/// Assign (or clear, with `None`) the dedicated shortcut of a mode.
+#[tauri::command]
+#[specta::specta]
+pub fn change_mode_binding(
+    app: AppHandle,
+    id: String,
+    binding: Option<String>,
+) -> Result<BindingResponse, String> {
+    let mut settings = settings::get_settings(&app);
+    let mode = settings
+        .mode(&id)
+        .cloned()
+        .ok_or_else(|| format!("Mode with id '{}' not found", id))?;
+    let binding_id = crate::modes::mode_binding_id(&id);
+    let previous = settings.bindings.get(&binding_id).cloned();
+
+    let Some(binding) = binding
+        .map(|b| b.trim().to_string())
+        .filter(|b| !b.is_empty())
+    else {
+        if let Some(previous) = previous {
+            if let Err(e) = unregister_shortcut(&app, previous) {
+                debug!("No mode shortcut to unregister for '{}': {}", id, e);
+            }
+            settings.bindings.remove(&binding_id);
+            settings::write_settings(&app, settings);
+        }
+        return Ok(BindingResponse {
+            success: true,
+            binding: None,
+            error: None,
+        });
+    };
+
+    let reject = |error: String| -> Result<BindingResponse, String> {
+        warn!("change_mode_binding error: {}", error);
+        Ok(BindingResponse {
+            success: false,
+            binding: None,
+            error: Some(error),
+        })
+    };
+
+    // Mode shortcuts are toggles registered as global shortcuts; the Fn key
+    // monitor only dispatches press events, so Fn combinations are not supported.
+    if is_fn_managed_shortcut(&binding) {
+        return reject("Mode shortcuts cannot use the Fn key".to_string());
+    }
+    if let Err(reason) = reserved::check_reserved_shortcut(&binding) {
+        return reject(reason);
+    }
+    validate_shortcut_string(&binding)?;
+
+    let binding_lower = binding.to_lowercase();
+    if settings.bindings.iter().any(|(other_id, other)| {
+        other_id != &binding_id && other.current_binding.to_lowercase() == binding_lower
+    }) {
+        return reject(format!("Shortcut '{}' is already in use", binding));
+    }
+
+    if let Some(previous) = previous.clone() {
+        if let Err(e) = unregister_shortcut(&app, previous) {
+            debug!("No mode shortcut to unregister for '{}': {}", id, e);
+        }
+    }
+
+    let updated_binding = crate::modes::mode_binding(&mode, &binding);
+    if let Err(e) = register_shortcut(&app, updated_binding.clone()) {
+        if let Some(previous) = previous {
+            let _ = register_shortcut(&app, previous);
+        }
+        return reject(format!("Failed to register shortcut: {}", e));
+    }
+
+    settings
+        .bindings
+        .insert(binding_id, updated_binding.clone());
+    settings::write_settings(&app, settings);
+
+    Ok(BindingResponse {
+        success: true,
+        binding: Some(updated_binding),
+        error: None,
+    })
+}
+
+/// Identifiers of the currently frontmost application, for building profiles.
+#[tauri::command]
+#[specta::specta]
+pub fn get_frontmost_app_identifiers() -> Vec<String> {
+    crate::app_profiles::detect_frontmost_app()
+}
+
 #[tauri::command]
 #[specta::specta]
 pub async fn fetch_post_process_models(
@@ -1085,6 +1399,126 @@ pub fn change_mute_while_recording_setting(app: AppHandle, enabled: bool) -> Res
     Ok(())
 }
 
+#[tauri::command]
+#[specta::specta]
+pub fn change_audio_high_pass_filter_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.audio_high_pass_filter = enabled;
+    settings::write_settings(&app, settings);
+
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_audio_noise_suppression_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.audio_noise_suppression = enabled;
+    settings::write_settings(&app, settings);
+
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_audio_auto_gain_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.audio_auto_gain = enabled;
+    settings::write_settings(&app, settings);
+
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_auto_stop_on_silence_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.auto_stop_on_silence = enabled;
+    settings::write_settings(&app, settings);
+
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_auto_stop_timing_setting(
+    app: AppHandle,
+    silence_ms: u32,
+    min_speech_ms: u32,
+) -> Result<(), String> {
+    crate::audio_toolkit::audio::validate_auto_stop(silence_ms, min_speech_ms)?;
+    let mut settings = settings::get_settings(&app);
+    settings.auto_stop_silence_ms = silence_ms;
+    settings.auto_stop_min_speech_ms = min_speech_ms;
+    settings::write_settings(&app, settings);
+
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_long_form_recording_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.long_form_recording_enabled = enabled;
+    settings::write_settings(&app, settings);
+
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_speaker_diarization_setting(
+    app: AppHandle,
+    enabled: bool,
+    max_speakers: u32,
+) -> Result<(), String> {
+    if !(settings::MIN_DIARIZATION_SPEAKERS..=settings::MAX_DIARIZATION_SPEAKERS)
+        .contains(&max_speakers)
+    {
+        return Err(format!(
+            "Maximum speakers must be between {} and {}, got {}",
+            settings::MIN_DIARIZATION_SPEAKERS,
+            settings::MAX_DIARIZATION_SPEAKERS,
+            max_speakers
+        ));
+    }
+    let mut settings = settings::get_settings(&app);
+    settings.speaker_diarization_enabled = enabled;
+    settings.diarization_max_speakers = max_speakers;
+    settings::write_settings(&app, settings);
+
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_trusted_model_bundle_keys_setting(
+    app: AppHandle,
+    keys: Vec<String>,
+) -> Result<(), String> {
+    let mut trusted = Vec::new();
+    for key in keys {
+        let key = key.trim().to_ascii_lowercase();
+        if key.is_empty() {
+            continue;
+        }
+        if !crate::model_bundle::is_valid_public_key(&key) {
+            return Err(format!(
+                "'{}' is not a model bundle signing key (64 hex characters)",
+                key
+            ));
+        }
+        if !trusted.contains(&key) {
+            trusted.push(key);
+        }
+    }
+    let mut settings = settings::get_settings(&app);
+    settings.trusted_model_bundle_keys = trusted;
+    settings::write_settings(&app, settings);
+
+    Ok(())
+}
+
 #[tauri::command]
 #[specta::specta]
 pub fn change_append_trailing_space_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
@@ -1118,6 +1552,49 @@ pub fn change_filler_word_filter_setting(app: AppHandle, enabled: bool) -> Resul
     Ok(())
 }
 
+#[tauri::command]
+#[specta::specta]
+pub fn change_spoken_punctuation_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.spoken_punctuation_enabled = enabled;
+    settings::write_settings(&app, settings);
+
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn get_voice_command_languages() -> Vec<String> {
+    crate::audio_toolkit::voice_commands::SUPPORTED_VOICE_COMMAND_LANGUAGES
+        .iter()
+        .map(|lang| lang.to_string())
+        .collect()
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn change_voice_command_languages_setting(
+    app: AppHandle,
+    languages: Vec<String>,
+) -> Result<(), String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for lang in languages {
+        let lang = lang.trim().to_lowercase();
+        if !crate::audio_toolkit::voice_commands::supports_voice_commands(&lang) {
+            return Err(format!("Voice commands are not available for '{}'", lang));
+        }
+        if !normalized.contains(&lang) {
+            normalized.push(lang);
+        }
+    }
+
+    let mut settings = settings::get_settings(&app);
+    settings.voice_command_languages = normalized;
+    settings::write_settings(&app, settings);
+
+    Ok(())
+}
+
 #[tauri::command]
 #[specta::specta]
 pub fn change_hallucination_filter_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
@@ -1128,6 +1605,16 @@ pub fn change_hallucination_filter_setting(app: AppHandle, enabled: bool) -> Res
     Ok(())
 }
 
+#[tauri::command]
+#[specta::specta]
+pub fn change_streaming_partials_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut settings = settings::get_settings(&app);
+    settings.streaming_partials_enabled = enabled;
+    settings::write_settings(&app, settings);
+
+    Ok(())
+}
+
 #[tauri::command]
 #[specta::specta]
 pub fn change_app_language_setting(app: AppHandle, language: String) -> Result<(), String> {
@@ -1211,6 +1698,11 @@ pub fn resume_binding(app: AppHandle, id: String) -> Result<(), String> {
             return Ok(());
         }
 
+        if !binding_is_enabled(&settings::get_settings(&app), &id) {
+            debug!("resume_binding: '{}' is disabled, skipping", id);
+            return Ok(());
+        }
+
         // Check if already registered (idempotency)
         if let Ok(shortcut) = b.current_binding.parse::<Shortcut>() {
             if app.global_shortcut().is_registered(shortcut) {
@@ -1337,7 +1829,7 @@ pub fn register_shortcut(app: &AppHandle, binding: ShortcutBinding) -> Result<()
                 let shortcut_string = scut.into_string();
 
 
-                if let Some(action) = ACTION_MAP.get(&binding_id_for_closure) {
+                if let Some(action) = action_for_binding(&binding_id_for_closure) {
                     debug!("Global Shortcut Event: id='{}' state={:?} shortcut='{}'", binding_id_for_closure, event.state, shortcut_string);
                     if binding_id_for_closure == "cancel" {
                         if event.state == ShortcutState::Pressed {
@@ -1350,6 +1842,7 @@ pub fn register_shortcut(app: &AppHandle, binding: ShortcutBinding) -> Result<()
                             | "undo_last_transcript"
                             | "refine_last_transcript"
                             | "correct_text"
+                            | "transcribe_recent_audio"
                     ) {
                         // One-shot actions always trigger on key press.
                         if event.state == ShortcutState::Pressed {
@@ -1366,8 +1859,10 @@ pub fn register_shortcut(app: &AppHandle, binding: ShortcutBinding) -> Result<()
                         } else if event.state == ShortcutState::Released {
                             action.stop(ah, &binding_id_for_closure, &shortcut_string);
                         }
-                    } else if binding_id_for_closure == "transcribe_handsfree" {
-                        // Hands-free shortcut is ALWAYS Toggle (Press to start/stop)
+                    } else if binding_id_for_closure == "transcribe_handsfree"
+                        || crate::modes::is_mode_binding(&binding_id_for_closure)
+                    {
+                        // Hands-free and mode shortcuts are ALWAYS Toggle (Press to start/stop)
                         // Ignore Release events
                         if event.state == ShortcutState::Pressed {
                             let Some(should_start) =
@@ -1420,7 +1915,7 @@ pub fn register_shortcut(app: &AppHandle, binding: ShortcutBinding) -> Result<()
                     }
                 } else {
                     warn!(
-                        "No action defined in ACTION_MAP for shortcut ID '{}'. Shortcut: '{}', State: {:?}",
+                        "No action defined for shortcut ID '{}'. Shortcut: '{}', State: {:?}",
                         binding_id_for_closure, shortcut_string, event.state
                     );
                 }
@@ -1460,6 +1955,26 @@ pub fn unregister_shortcut(app: &AppHandle, binding: ShortcutBinding) -> Result<
     Ok(())
 }
 
+/// Registers or unregisters the recent-audio shortcut after its buffer was
+/// turned on or off.
+pub fn sync_recent_audio_shortcut(app: &AppHandle, enabled: bool) {
+    let settings = settings::get_settings(app);
+    let Some(binding) = settings.bindings.get(RECENT_AUDIO_BINDING_ID).cloned() else {
+        return;
+    };
+    if is_fn_managed_shortcut(&binding.current_binding) {
+        return;
+    }
+    let result = if enabled {
+        register_shortcut(app, binding)
+    } else {
+        unregister_shortcut(app, binding)
+    };
+    if let Err(e) = result {
+        warn!("Failed to update the recent audio shortcut: {}", e);
+    }
+}
+
 #[tauri::command]
 #[specta::specta]
 pub fn change_show_tray_icon_setting(app: AppHandle, enabled: bool) -> Result<(), String> {
@@ -1535,6 +2050,16 @@ mod tests {
         assert_eq!(report.failed_ids(), vec!["bad".to_string()]);
     }
 
+    #[test]
+    fn recent_audio_shortcut_is_only_enabled_with_its_buffer() {
+        let mut settings = settings::get_default_settings();
+        assert!(!binding_is_enabled(&settings, "transcribe_recent_audio"));
+        assert!(binding_is_enabled(&settings, "transcribe"));
+
+        settings.recent_audio_buffer_enabled = true;
+        assert!(binding_is_enabled(&settings, "transcribe_recent_audio"));
+    }
+
     #[test]
     fn init_shortcuts_skips_already_registered_bindings_for_retry_safety() {
         let bindings = vec![
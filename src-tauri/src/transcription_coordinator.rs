@@ -1,4 +1,4 @@
-use crate::actions::ACTION_MAP;
+use crate::actions::action_for_binding;
 use crate::managers::audio::AudioRecordingManager;
 use tracing::{debug, error, warn};
 use std::sync::mpsc::{self, Sender};
@@ -20,6 +20,16 @@ enum Command {
     Cancel {
         recording_was_active: bool,
     },
+    /// Hands-free stop requested after trailing silence.
+    AutoStop {
+        binding_id: String,
+    },
+    /// Transcribe what the recent-audio buffer holds, as if a recording of it
+    /// had just been stopped.
+    RecentAudio {
+        binding_id: String,
+        hotkey_string: String,
+    },
     ProcessingFinished,
 }
 
@@ -97,6 +107,25 @@ impl TranscriptionCoordinator {
                                 stage = Stage::Idle;
                             }
                         }
+                        Command::AutoStop { binding_id } => {
+                            // Ignore stale requests: the user may already have stopped
+                            // or started a different binding.
+                            if matches!(&stage, Stage::Recording(id) if id == &binding_id) {
+                                stop(&app, &mut stage, &binding_id, "silence_auto_stop");
+                            } else {
+                                debug!("Ignoring auto-stop for '{binding_id}': not recording");
+                            }
+                        }
+                        Command::RecentAudio {
+                            binding_id,
+                            hotkey_string,
+                        } => {
+                            if matches!(stage, Stage::Idle) {
+                                stop(&app, &mut stage, &binding_id, &hotkey_string);
+                            } else {
+                                debug!("Ignoring recent audio for '{binding_id}': pipeline busy");
+                            }
+                        }
                         Command::ProcessingFinished => {
                             stage = Stage::Idle;
                         }
@@ -147,6 +176,35 @@ impl TranscriptionCoordinator {
         }
     }
 
+    /// Stop a recording the same way a shortcut would, so the usual
+    /// transcribe/paste flow runs.
+    pub fn request_auto_stop(&self, binding_id: &str) {
+        if self
+            .tx
+            .send(Command::AutoStop {
+                binding_id: binding_id.to_string(),
+            })
+            .is_err()
+        {
+            warn!("Transcription coordinator channel closed");
+        }
+    }
+
+    /// Transcribe the recent-audio buffer through the usual stop flow once
+    /// the pipeline is idle; ignored while recording or processing.
+    pub fn request_recent_audio(&self, binding_id: &str, hotkey_string: &str) {
+        if self
+            .tx
+            .send(Command::RecentAudio {
+                binding_id: binding_id.to_string(),
+                hotkey_string: hotkey_string.to_string(),
+            })
+            .is_err()
+        {
+            warn!("Transcription coordinator channel closed");
+        }
+    }
+
     pub fn notify_processing_finished(&self) {
         if self.tx.send(Command::ProcessingFinished).is_err() {
             warn!("Transcription coordinator channel closed");
@@ -163,8 +221,8 @@ fn start(app: &AppHandle, stage: &mut Stage, binding_id: &str, hotkey_string: &s
         return;
     }
 
-    let Some(action) = ACTION_MAP.get(binding_id) else {
-        warn!("No action in ACTION_MAP for '{binding_id}'");
+    let Some(action) = action_for_binding(binding_id) else {
+        warn!("No action for binding '{binding_id}'");
         return;
     };
     action.start(app, binding_id, hotkey_string);
@@ -179,8 +237,8 @@ fn start(app: &AppHandle, stage: &mut Stage, binding_id: &str, hotkey_string: &s
 }
 
 fn stop(app: &AppHandle, stage: &mut Stage, binding_id: &str, hotkey_string: &str) {
-    let Some(action) = ACTION_MAP.get(binding_id) else {
-        warn!("No action in ACTION_MAP for '{binding_id}'");
+    let Some(action) = action_for_binding(binding_id) else {
+        warn!("No action for binding '{binding_id}'");
         return;
     };
     action.stop(app, binding_id, hotkey_string);
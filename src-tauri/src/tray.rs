@@ -4,7 +4,7 @@ use crate::settings;
 use crate::tray_i18n::get_tray_translations;
 use std::sync::Arc;
 use tauri::image::Image;
-use tauri::menu::{Menu, MenuItem, PredefinedMenuItem};
+use tauri::menu::{CheckMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu};
 use tauri::tray::TrayIcon;
 use tauri::{AppHandle, Manager, Theme};
 use tauri_plugin_clipboard_manager::ClipboardExt;
@@ -13,6 +13,8 @@ use tauri_plugin_clipboard_manager::ClipboardExt;
 #[derive(Clone, Debug, PartialEq)]
 pub enum TrayIconState {
     Idle,
+    /// Idle while the recent-audio buffer keeps the microphone open.
+    Listening,
     Recording,
     Transcribing,
 }
@@ -48,20 +50,36 @@ pub fn get_icon_path(theme: AppTheme, state: TrayIconState) -> &'static str {
     match (theme, state) {
         // Dark theme uses light icons
         (AppTheme::Dark, TrayIconState::Idle) => "resources/tray_idle.png",
+        (AppTheme::Dark, TrayIconState::Listening) => "resources/tray_listening.png",
         (AppTheme::Dark, TrayIconState::Recording) => "resources/tray_recording.png",
         (AppTheme::Dark, TrayIconState::Transcribing) => "resources/tray_transcribing.png",
         // Light theme uses dark icons
         (AppTheme::Light, TrayIconState::Idle) => "resources/tray_idle_dark.png",
+        (AppTheme::Light, TrayIconState::Listening) => "resources/tray_listening_dark.png",
         (AppTheme::Light, TrayIconState::Recording) => "resources/tray_recording_dark.png",
         (AppTheme::Light, TrayIconState::Transcribing) => "resources/tray_transcribing_dark.png",
         // Colored theme uses pink icons (for Linux)
         (AppTheme::Colored, TrayIconState::Idle) => "resources/codictate.png",
+        (AppTheme::Colored, TrayIconState::Listening) => "resources/listening.png",
         (AppTheme::Colored, TrayIconState::Recording) => "resources/recording.png",
         (AppTheme::Colored, TrayIconState::Transcribing) => "resources/transcribing.png",
     }
 }
 
+/// The idle state to show: `Listening` while the recent-audio buffer is on.
+pub fn idle_state(app: &AppHandle) -> TrayIconState {
+    if settings::get_settings(app).recent_audio_secs() > 0 {
+        TrayIconState::Listening
+    } else {
+        TrayIconState::Idle
+    }
+}
+
 pub fn change_tray_icon(app: &AppHandle, icon: TrayIconState) {
+    let icon = match icon {
+        TrayIconState::Idle => idle_state(app),
+        icon => icon,
+    };
     let tray = app.state::<TrayIcon>();
     let theme = get_current_theme(app);
 
@@ -89,10 +107,10 @@ pub fn change_tray_icon(app: &AppHandle, icon: TrayIconState) {
     // For Idle state, spawn the menu update in a background task to avoid blocking
     // (the Idle menu may query history, which requires async DB access)
     match icon {
-        TrayIconState::Idle => {
+        TrayIconState::Idle | TrayIconState::Listening => {
             let app_clone = app.clone();
             tauri::async_runtime::spawn(async move {
-                update_tray_menu_async(&app_clone, &TrayIconState::Idle, None).await;
+                update_tray_menu_async(&app_clone, &icon, None).await;
             });
         }
         _ => {
@@ -104,7 +122,7 @@ pub fn change_tray_icon(app: &AppHandle, icon: TrayIconState) {
 
 /// Async version of update_tray_menu - use this when calling from async context
 pub async fn update_tray_menu_async(app: &AppHandle, state: &TrayIconState, locale: Option<&str>) {
-    let has_history = if matches!(state, TrayIconState::Idle) {
+    let has_history = if matches!(state, TrayIconState::Idle | TrayIconState::Listening) {
         has_history_entries_async(app).await
     } else {
         false
@@ -181,6 +199,14 @@ fn build_and_set_tray_menu(
     .expect("failed to create unload model item");
     let quit_i = MenuItem::with_id(app, "quit", &strings.quit, true, quit_accelerator)
         .expect("failed to create quit item");
+    // Privacy indicator: the always-on microphone is keeping recent audio.
+    let recent_audio_secs = settings.recent_audio_secs();
+    let recent_audio_label = (recent_audio_secs > 0)
+        .then(|| format!("{} ({} s)", strings.recent_audio_buffer, recent_audio_secs));
+    let recent_audio_i = recent_audio_label.as_ref().map(|label| {
+        MenuItem::with_id(app, "recent_audio_buffer", label, false, None::<&str>)
+            .expect("failed to create recent audio item")
+    });
     let separator = || PredefinedMenuItem::separator(app).expect("failed to create separator");
 
     let menu = match state {
@@ -204,15 +230,22 @@ fn build_and_set_tray_menu(
             )
             .expect("failed to create menu")
         }
-        TrayIconState::Idle => {
+        TrayIconState::Idle | TrayIconState::Listening => {
             let sep1 = separator();
             let sep2 = separator();
             let sep3 = separator();
+            let sep_modes = separator();
+            let modes_submenu = build_modes_submenu(app, &settings, &strings);
 
-            let mut items: Vec<&dyn tauri::menu::IsMenuItem<tauri::Wry>> = vec![
-                &version_i,
-                &sep1,
-            ];
+            let mut items: Vec<&dyn tauri::menu::IsMenuItem<tauri::Wry>> = vec![&version_i];
+            if let Some(recent_audio_i) = recent_audio_i.as_ref() {
+                items.push(recent_audio_i);
+            }
+            items.push(&sep1);
+            if let Some(modes_submenu) = modes_submenu.as_ref() {
+                items.push(modes_submenu);
+                items.push(&sep_modes);
+            }
             if has_history {
                 items.push(&copy_last_transcript_i);
             }
@@ -234,13 +267,62 @@ fn build_and_set_tray_menu(
     let tray = app.state::<TrayIcon>();
     let _ = tray.set_menu(Some(menu));
     let _ = tray.set_icon_as_template(true);
+    let _ = tray.set_tooltip(recent_audio_label.as_deref());
+}
+
+/// Builds the mode switcher submenu, or `None` when no modes are defined.
+fn build_modes_submenu(
+    app: &AppHandle,
+    settings: &settings::AppSettings,
+    strings: &crate::tray_i18n::TrayStrings,
+) -> Option<Submenu<tauri::Wry>> {
+    if settings.modes.is_empty() {
+        return None;
+    }
+
+    let active_mode = settings.active_mode().map(|mode| mode.id.as_str());
+    let mut mode_items = vec![CheckMenuItem::with_id(
+        app,
+        crate::modes::TRAY_NO_MODE_ITEM_ID,
+        &strings.no_mode,
+        true,
+        active_mode.is_none(),
+        None::<&str>,
+    )
+    .expect("failed to create no mode item")];
+    for mode in &settings.modes {
+        let label = match mode.icon.as_deref().filter(|icon| !icon.is_empty()) {
+            Some(icon) => format!("{} {}", icon, mode.name),
+            None => mode.name.clone(),
+        };
+        mode_items.push(
+            CheckMenuItem::with_id(
+                app,
+                crate::modes::tray_item_id(&mode.id),
+                &label,
+                true,
+                active_mode == Some(mode.id.as_str()),
+                None::<&str>,
+            )
+            .expect("failed to create mode item"),
+        );
+    }
+
+    let item_refs: Vec<&dyn tauri::menu::IsMenuItem<tauri::Wry>> = mode_items
+        .iter()
+        .map(|item| item as &dyn tauri::menu::IsMenuItem<tauri::Wry>)
+        .collect();
+    Some(
+        Submenu::with_items(app, &strings.modes, true, &item_refs)
+            .expect("failed to create modes submenu"),
+    )
 }
 
 /// Async check if there are any history entries
 async fn has_history_entries_async(app: &AppHandle) -> bool {
     if let Some(history_manager) = app.try_state::<Arc<HistoryManager>>() {
         let manager = history_manager.inner().clone();
-        match manager.get_history_entries(1, 0, None, false, None).await {
+        match manager.get_history_entries(1, 0, None, false, None, None).await {
             Ok(entries) => !entries.is_empty(),
             Err(e) => {
                 tracing::warn!("Failed to check history entries: {}", e);
@@ -318,6 +400,11 @@ mod tests {
             duration_ms: 0,
             file_path: String::new(),
             audio_file_exists: false,
+            timings: Vec::new(),
+            tags: Vec::new(),
+            speaker_turns: Vec::new(),
+            speakers: Vec::new(),
+            bias_terms: Vec::new(),
         }
     }
 
@@ -206,7 +206,7 @@ fn write_entries_to_path(path: &Path, entries: &[CustomWordEntry]) -> Result<(),
     write_entries_to_path_with_dir_sync(path, entries, parent_dir_fsync)
 }
 
-fn load_entries_from_path(path: &Path) -> (Vec<CustomWordEntry>, DictionaryLoadStatus) {
+pub(crate) fn load_entries_from_path(path: &Path) -> (Vec<CustomWordEntry>, DictionaryLoadStatus) {
     if !path.exists() {
         return (Vec::new(), DictionaryLoadStatus::MissingFile);
     }
@@ -122,6 +122,7 @@ pub fn cancel_current_operation(app: &AppHandle) {
     // CRITICAL: Clear active session IMMEDIATELY to prevent pending transcriptions from pasting
     // Do not defer this to the background thread!
     tm.clear_active_session();
+    crate::app_profiles::clear_active_profile();
 
     // Show cancelling state on overlay IMMEDIATELY to prevent race conditions
     // where other threads (e.g. action.stop) might try to hide the overlay.
@@ -0,0 +1,157 @@
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// Above this many LCS cells the differing middle is reported as one deletion
+/// followed by one insertion instead of being aligned word by word.
+const MAX_ALIGNMENT_CELLS: usize = 4_000_000;
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, Type, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WordDiffKind {
+    Equal,
+    /// Only in the original transcript.
+    Delete,
+    /// Only in the revision.
+    Insert,
+}
+
+/// A run of consecutive words with the same diff kind, joined by single spaces.
+#[derive(Clone, Debug, Serialize, Deserialize, Type, PartialEq, Eq)]
+pub struct WordDiffSpan {
+    pub kind: WordDiffKind,
+    pub text: String,
+}
+
+/// Word-level diff from `original` to `revised`, splitting on whitespace.
+pub fn diff_words(original: &str, revised: &str) -> Vec<WordDiffSpan> {
+    let old: Vec<&str> = original.split_whitespace().collect();
+    let new: Vec<&str> = revised.split_whitespace().collect();
+
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut spans = Vec::new();
+    push_words(&mut spans, WordDiffKind::Equal, &old[..prefix]);
+    align_middle(
+        &mut spans,
+        &old[prefix..old.len() - suffix],
+        &new[prefix..new.len() - suffix],
+    );
+    push_words(&mut spans, WordDiffKind::Equal, &old[old.len() - suffix..]);
+    spans
+}
+
+/// Aligns the differing middle with a longest-common-subsequence table.
+fn align_middle(spans: &mut Vec<WordDiffSpan>, old: &[&str], new: &[&str]) {
+    if old.is_empty() || new.is_empty() || old.len() * new.len() > MAX_ALIGNMENT_CELLS {
+        push_words(spans, WordDiffKind::Delete, old);
+        push_words(spans, WordDiffKind::Insert, new);
+        return;
+    }
+
+    // lcs[i][j] = length of the LCS of old[i..] and new[j..].
+    let width = new.len() + 1;
+    let mut lcs = vec![0u32; (old.len() + 1) * width];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i * width + j] = if old[i] == new[j] {
+                lcs[(i + 1) * width + j + 1] + 1
+            } else {
+                lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            push_words(spans, WordDiffKind::Equal, &old[i..=i]);
+            i += 1;
+            j += 1;
+        } else if lcs[(i + 1) * width + j] >= lcs[i * width + j + 1] {
+            push_words(spans, WordDiffKind::Delete, &old[i..=i]);
+            i += 1;
+        } else {
+            push_words(spans, WordDiffKind::Insert, &new[j..=j]);
+            j += 1;
+        }
+    }
+    push_words(spans, WordDiffKind::Delete, &old[i..]);
+    push_words(spans, WordDiffKind::Insert, &new[j..]);
+}
+
+fn push_words(spans: &mut Vec<WordDiffSpan>, kind: WordDiffKind, words: &[&str]) {
+    if words.is_empty() {
+        return;
+    }
+    match spans.last_mut() {
+        Some(last) if last.kind == kind => {
+            for word in words {
+                last.text.push(' ');
+                last.text.push_str(word);
+            }
+        }
+        _ => spans.push(WordDiffSpan {
+            kind,
+            text: words.join(" "),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn span(kind: WordDiffKind, text: &str) -> WordDiffSpan {
+        WordDiffSpan {
+            kind,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn identical_text_is_one_equal_span() {
+        assert_eq!(
+            diff_words("the quick  fox", "the quick fox"),
+            vec![span(WordDiffKind::Equal, "the quick fox")]
+        );
+    }
+
+    #[test]
+    fn substitutions_and_insertions_are_aligned() {
+        assert_eq!(
+            diff_words(
+                "send the report to Ana today",
+                "send a report to Anna today please"
+            ),
+            vec![
+                span(WordDiffKind::Equal, "send"),
+                span(WordDiffKind::Delete, "the"),
+                span(WordDiffKind::Insert, "a"),
+                span(WordDiffKind::Equal, "report to"),
+                span(WordDiffKind::Delete, "Ana"),
+                span(WordDiffKind::Insert, "Anna"),
+                span(WordDiffKind::Equal, "today"),
+                span(WordDiffKind::Insert, "please"),
+            ]
+        );
+    }
+
+    #[test]
+    fn empty_sides_diff_as_whole_insert_or_delete() {
+        assert_eq!(
+            diff_words("", "hello there"),
+            vec![span(WordDiffKind::Insert, "hello there")]
+        );
+        assert_eq!(
+            diff_words("hello there", " "),
+            vec![span(WordDiffKind::Delete, "hello there")]
+        );
+        assert!(diff_words("", "").is_empty());
+    }
+}
@@ -309,6 +309,14 @@ async changeHallucinationFilterSetting(enabled: boolean) : Promise<Result<null,
     else return { status: "error", error: e  as any };
 }
 },
+async changeStreamingPartialsSetting(enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("change_streaming_partials_setting", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async changeAppLanguageSetting(language: string) : Promise<Result<null, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("change_app_language_setting", { language }) };
@@ -731,6 +739,14 @@ async getRecommendedFirstModel() : Promise<Result<string, string>> {
     else return { status: "error", error: e  as any };
 }
 },
+async importModelBundle(path: string, allowUntrusted: boolean) : Promise<Result<ModelBundleImportReport, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("import_model_bundle", { path, allowUntrusted }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async updateMicrophoneMode(alwaysOn: boolean) : Promise<Result<null, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("update_microphone_mode", { alwaysOn }) };
@@ -820,6 +836,19 @@ async getClamshellMicrophone() : Promise<Result<string, string>> {
 async isRecording() : Promise<boolean> {
     return await TAURI_INVOKE("is_recording");
 },
+/**
+ * Record a quiet sample, then a spoken one, and report levels, noise, clipping
+ * and voice detection with recommendations. Emits `mic-diagnostics-phase` as
+ * each step starts so the UI can tell the user when to speak.
+ */
+async runMicrophoneDiagnostics(silenceMs: number | null, speechMs: number | null) : Promise<Result<MicrophoneDiagnostics, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("run_microphone_diagnostics", { silenceMs, speechMs }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
  * Start microphone preview mode - opens the mic stream to emit levels without recording
  */
@@ -1180,7 +1209,13 @@ export type AppSettings = { bindings: Partial<{ [key in string]: ShortcutBinding
  * When true, all transcriptions are automatically refined (adds delay).
  * When false (default), user must manually trigger refinement with hotkey.
  */
-auto_refine_enabled?: boolean; mute_while_recording?: boolean; append_trailing_space?: boolean; paste_last_use_smart_insertion?: boolean; app_language?: string; 
+auto_refine_enabled?: boolean; mute_while_recording?: boolean; 
+/**
+ * Hex Ed25519 public keys whose offline model bundles may be imported.
+ * When empty, bundles are refused unless the import allows untrusted
+ * signers.
+ */
+trusted_model_bundle_keys?: string[]; append_trailing_space?: boolean; paste_last_use_smart_insertion?: boolean; app_language?: string; 
 /**
  * When true, removes filler words (um, uh, hmm, etc.) from transcriptions.
  */
@@ -1192,7 +1227,7 @@ extra_filler_words?: string[];
 /**
  * When true, collapses repeated/stuttered words caused by ASR hallucinations.
  */
-enable_hallucination_filter?: boolean; show_tray_icon?: boolean; show_unload_model_in_tray?: boolean; paste_delay_ms?: number; paste_restore_delay_ms?: number; typing_tool?: TypingTool; external_script_path: string | null }
+enable_hallucination_filter?: boolean; streaming_partials_enabled?: boolean; show_tray_icon?: boolean; show_unload_model_in_tray?: boolean; paste_delay_ms?: number; paste_restore_delay_ms?: number; typing_tool?: TypingTool; external_script_path: string | null }
 export type ApplyRestoreReport = { warnings: string[]; counts: BackupCounts }
 export type ApplyRestoreRequest = { archive_path: string }
 export type AudioDevice = { index: string; name: string; is_default: boolean; is_bluetooth: boolean }
@@ -1206,12 +1241,60 @@ export type CreateBackupReport = { output_path: string; counts: BackupCounts; wa
 export type CreateBackupRequest = { scope: BackupScope; output_path: string }
 export type CustomSounds = { start: boolean; stop: boolean }
 export type CustomWordEntry = { input: string; aliases?: string[]; replacement: string; is_replacement: boolean; fuzzy_enabled?: boolean | null }
+/**
+ * Something about the microphone that needs attention. The measurements
+ * behind it are in the report; settings that change the advice are carried
+ * along.
+ */
+export type DiagnosticIssue = 
+/**
+ * The device delivered almost no sound; nothing else is reported.
+ */
+{ kind: "no_signal" } | { kind: "low_sample_rate" } | { kind: "clipping" } | { kind: "low_level" } | { kind: "noisy_environment"; noise_suppression: boolean } | { kind: "low_snr" } | { kind: "dc_offset"; high_pass_filter: boolean } | { kind: "vad_misses_speech" } | { kind: "vad_triggers_on_noise" }
+/**
+ * The step the diagnostic is in; the user should stay quiet during
+ * `Silence` and talk normally during `Speech`.
+ */
+export type DiagnosticPhase = "silence" | "speech" | "analyzing"
 export type EngineType = "Whisper" | "Parakeet" | "Moonshine" | "MoonshineStreaming" | "SenseVoice" | "GigaAM"
 export type HistoryEntry = { id: number; file_name: string; timestamp: number; saved: boolean; title: string; transcription_text: string; post_processed_text: string | null; inserted_text: string | null; effective_text: string; raw_text: string; post_process_prompt: string | null; duration_ms: number; file_path: string; audio_file_exists: boolean }
 export type HistoryStats = { total_size_bytes: number; total_entries: number }
 export type HomeStats = { total_words: number; total_duration_minutes: number; wpm: number; time_saved_minutes: number; streak_days: number; faster_than_typing_percentage: number; total_filler_words_removed: number; filler_filter_active: boolean }
 export type LLMPrompt = { id: string; name: string; prompt: string }
 export type LogLevel = "trace" | "debug" | "info" | "warn" | "error"
+export type MicrophoneDiagnostics = { device_name: string; 
+/**
+ * Device config chosen the same way as for recording.
+ */
+sample_rate: number; channels: number; sample_format: string; 
+/**
+ * Median 30 ms level of the quiet sample.
+ */
+noise_floor_dbfs: number; 
+/**
+ * 90th percentile 30 ms level of the spoken sample.
+ */
+speech_level_dbfs: number; speech_peak_dbfs: number; snr_db: number; 
+/**
+ * Share of spoken-sample values at full scale.
+ */
+clipping_ratio: number; 
+/**
+ * Mean of the quiet sample on its worst channel, as a fraction of full scale.
+ */
+dc_offset: number; 
+/**
+ * Share of the spoken sample the configured VAD keeps.
+ */
+vad_speech_ratio: number; 
+/**
+ * Share of the quiet sample the configured VAD keeps.
+ */
+vad_noise_ratio: number; 
+/**
+ * Empty when nothing needs attention.
+ */
+issues: DiagnosticIssue[] }
 /**
  * Information about an available MLX model
  */
@@ -1284,6 +1367,12 @@ export type MlxModelStatus =
  * Model loading failed
  */
 "load_failed"
+export type ModelBundleFailure = { model_id: string; message: string }
+export type ModelBundleImportReport = { signer: string; imported: string[]; 
+/**
+ * Models that were already installed.
+ */
+skipped: string[]; failed: ModelBundleFailure[] }
 export type ModelInfo = { id: string; name: string; description: string; filename: string; url: string | null; size_mb: number; is_downloaded: boolean; is_downloading: boolean; partial_size: number; is_directory: boolean; engine_type: EngineType; accuracy_score: number; speed_score: number; supports_translation: boolean; is_recommended: boolean; supported_languages: string[]; is_custom: boolean }
 export type ModelLoadStatus = { is_loaded: boolean; is_loading: boolean; is_warmed: boolean; is_warming: boolean; current_model: string | null }
 export type ModelUnloadTimeout = "never" | "immediately" | "min_2" | "min_5" | "min_10" | "min_15" | "hour_1" | "sec_5"
@@ -0,0 +1,129 @@
+import React, { useEffect, useState } from "react";
+import { listen } from "@tauri-apps/api/event";
+import { useTranslation } from "react-i18next";
+import { toast } from "sonner";
+import {
+  commands,
+  type DiagnosticIssue,
+  type DiagnosticPhase,
+  type MicrophoneDiagnostics,
+} from "@/bindings";
+import { SettingsRow } from "../ui/SettingsRow";
+import { logError } from "@/utils/logging";
+
+const percent = (ratio: number, digits = 0) => (ratio * 100).toFixed(digits);
+
+/**
+ * Records a quiet and a spoken sample from the selected microphone and lists
+ * what needs attention. The backend reports issues; the wording lives here so
+ * it follows the app language.
+ */
+export const MicrophoneCheck: React.FC = () => {
+  const { t } = useTranslation();
+  const [phase, setPhase] = useState<DiagnosticPhase | null>(null);
+  const [report, setReport] = useState<MicrophoneDiagnostics | null>(null);
+
+  useEffect(() => {
+    const unlistenPromise = listen<DiagnosticPhase>(
+      "mic-diagnostics-phase",
+      (event) => {
+        setPhase(event.payload);
+      },
+    );
+
+    return () => {
+      unlistenPromise.then((unlisten) => unlisten());
+    };
+  }, []);
+
+  const runCheck = async () => {
+    setReport(null);
+    setPhase("silence");
+    try {
+      const result = await commands.runMicrophoneDiagnostics(null, null);
+      if (result.status === "error") {
+        toast.error(t("settings.sound.microphoneCheck.failed"), {
+          description: result.error,
+        });
+        return;
+      }
+      setReport(result.data);
+    } catch (error) {
+      logError(`Microphone check failed: ${error}`, "fe-mic-diagnostics");
+      toast.error(t("settings.sound.microphoneCheck.failed"));
+    } finally {
+      setPhase(null);
+    }
+  };
+
+  const describeIssue = (
+    issue: DiagnosticIssue,
+    data: MicrophoneDiagnostics,
+  ): string => {
+    const key = "settings.sound.microphoneCheck.issues";
+    switch (issue.kind) {
+      case "no_signal":
+        return t(`${key}.noSignal`, {
+          device: data.device_name,
+          appName: t("appName"),
+        });
+      case "low_sample_rate":
+        return t(`${key}.lowSampleRate`, { rate: data.sample_rate });
+      case "clipping":
+        return t(`${key}.clipping`, { percent: percent(data.clipping_ratio, 1) });
+      case "low_level":
+        return t(`${key}.lowLevel`, { level: data.speech_level_dbfs.toFixed(0) });
+      case "noisy_environment":
+        return t(
+          issue.noise_suppression
+            ? `${key}.noisyEnvironment`
+            : `${key}.noisyEnvironmentSuppressionOff`,
+          { level: data.noise_floor_dbfs.toFixed(0) },
+        );
+      case "low_snr":
+        return t(`${key}.lowSnr`, { snr: data.snr_db.toFixed(0) });
+      case "dc_offset":
+        return t(
+          issue.high_pass_filter
+            ? `${key}.dcOffset`
+            : `${key}.dcOffsetHighPassOff`,
+          { percent: percent(data.dc_offset, 1) },
+        );
+      case "vad_misses_speech":
+        return t(`${key}.vadMissesSpeech`, {
+          percent: percent(data.vad_speech_ratio),
+        });
+      case "vad_triggers_on_noise":
+        return t(`${key}.vadTriggersOnNoise`, {
+          percent: percent(data.vad_noise_ratio),
+        });
+    }
+  };
+
+  const description = () => {
+    if (phase) return t(`settings.sound.microphoneCheck.phase.${phase}`);
+    if (report && report.issues.length === 0) {
+      return t("settings.sound.microphoneCheck.noIssues");
+    }
+    return t("settings.sound.microphoneCheck.description");
+  };
+
+  return (
+    <div className="flex flex-col w-full">
+      <SettingsRow
+        title={t("settings.sound.microphoneCheck.title")}
+        description={description()}
+        buttonLabel={t("settings.sound.microphoneCheck.run")}
+        onButtonClick={runCheck}
+        disabled={phase !== null}
+      />
+      {report && report.issues.length > 0 && (
+        <ul className="list-disc pl-5 pb-4 space-y-1 text-[13px] text-muted-foreground/80">
+          {report.issues.map((issue) => (
+            <li key={issue.kind}>{describeIssue(issue, report)}</li>
+          ))}
+        </ul>
+      )}
+    </div>
+  );
+};
@@ -0,0 +1,30 @@
+import { useTranslation } from "react-i18next";
+import { ToggleSwitch } from "../ui/ToggleSwitch";
+import { useSettings } from "../../hooks/useSettings";
+
+interface StreamingPartialsProps {
+  descriptionMode?: "tooltip" | "inline";
+  grouped?: boolean;
+}
+
+export const StreamingPartials: React.FC<StreamingPartialsProps> = ({
+  descriptionMode = "tooltip",
+  grouped = false,
+}) => {
+  const { t } = useTranslation();
+  const { settings, updateSetting, isUpdating } = useSettings();
+
+  const enabled = settings?.streaming_partials_enabled ?? false;
+
+  return (
+    <ToggleSwitch
+      checked={enabled}
+      onChange={(value) => updateSetting("streaming_partials_enabled", value)}
+      isUpdating={isUpdating("streaming_partials_enabled")}
+      label={t("settings.debug.streamingPartials.label")}
+      description={t("settings.debug.streamingPartials.description")}
+      descriptionMode={descriptionMode}
+      className={grouped ? "border-t-0" : ""}
+    />
+  );
+};
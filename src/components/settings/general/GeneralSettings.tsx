@@ -3,6 +3,7 @@ import { useTranslation } from "react-i18next";
 import { type } from "@tauri-apps/plugin-os";
 import { MicrophoneSelector } from "../MicrophoneSelector";
 import { ClamshellMicrophoneSelector } from "../ClamshellMicrophoneSelector";
+import { MicrophoneCheck } from "../MicrophoneCheck";
 import { SettingsGroup } from "../../ui/SettingsGroup";
 import { SettingsRow } from "../../ui/SettingsRow";
 import { OutputDeviceSelector } from "../OutputDeviceSelector";
@@ -18,6 +19,7 @@ import { Globe } from "lucide-react";
 import { useEffect, useRef } from "react";
 import { useUpdateStore } from "../../../stores/updateStore";
 import { ShowOverlay } from "../ShowOverlay";
+import { StreamingPartials } from "../StreamingPartials";
 import { TranslateToEnglish } from "../TranslateToEnglish";
 import { ModelUnloadTimeoutSetting } from "../ModelUnloadTimeout";
 import { ShowUnloadModelInTray } from "../ShowUnloadModelInTray";
@@ -131,6 +133,7 @@ export const GeneralSettings: React.FC = () => {
         <MuteWhileRecording descriptionMode="tooltip" grouped={true} />
         {/* Clamshell mode microphone - only shown on laptops */}
         <ClamshellMicrophoneSelector descriptionMode="tooltip" grouped={true} />
+        <MicrophoneCheck />
         <AudioFeedback descriptionMode="tooltip" grouped={true} />
         <OutputDeviceSelector disabled={!audioFeedbackEnabled} />
         <VolumeSlider disabled={!audioFeedbackEnabled} />
@@ -149,6 +152,7 @@ export const GeneralSettings: React.FC = () => {
         <StartHidden descriptionMode="tooltip" grouped={true} />
         <AutostartToggle descriptionMode="tooltip" grouped={true} />
         <ShowOverlay descriptionMode="tooltip" grouped={true} />
+        <StreamingPartials descriptionMode="tooltip" grouped={true} />
         <PasteMethodSetting descriptionMode="tooltip" grouped={true} />
         <PasteLastSmartInsertion descriptionMode="tooltip" grouped={true} />
         <TypingToolSetting descriptionMode="tooltip" grouped={true} />
@@ -9,6 +9,7 @@ export { ModelsSettings } from "./models/ModelsSettings";
 // Individual setting components
 export { MicrophoneSelector } from "./MicrophoneSelector";
 export { ClamshellMicrophoneSelector } from "./ClamshellMicrophoneSelector";
+export { MicrophoneCheck } from "./MicrophoneCheck";
 export { OutputDeviceSelector } from "./OutputDeviceSelector";
 export { AlwaysOnMicrophone } from "./AlwaysOnMicrophone";
 export { AudioFeedback } from "./AudioFeedback";
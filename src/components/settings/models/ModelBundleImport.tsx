@@ -0,0 +1,98 @@
+import React, { useState } from "react";
+import { useTranslation } from "react-i18next";
+import { ask, open } from "@tauri-apps/plugin-dialog";
+import { toast } from "sonner";
+import { commands } from "@/bindings";
+import { useModelStore } from "@/stores/modelStore";
+import { useSettings } from "@/hooks/useSettings";
+import { SettingsRow } from "../ui/SettingsRow";
+import { logError } from "@/utils/logging";
+
+/**
+ * Installs models from an offline `.codictatemodels` bundle. Without trusted
+ * signer keys the backend refuses bundles, so the user has to confirm an
+ * untrusted import explicitly.
+ */
+export const ModelBundleImport: React.FC = () => {
+  const { t } = useTranslation();
+  const { getSetting } = useSettings();
+  const { loadModels } = useModelStore();
+  const [importing, setImporting] = useState(false);
+
+  const importBundle = async () => {
+    const path = await open({
+      title: t("settings.models.bundleImport.select"),
+      directory: false,
+      multiple: false,
+      filters: [
+        {
+          name: t("settings.models.bundleImport.fileType"),
+          extensions: ["codictatemodels"],
+        },
+      ],
+    });
+    if (typeof path !== "string") return;
+
+    const trustedKeys = getSetting("trusted_model_bundle_keys") ?? [];
+    let allowUntrusted = false;
+    if (trustedKeys.length === 0) {
+      allowUntrusted = await ask(
+        t("settings.models.bundleImport.untrustedConfirm"),
+        {
+          title: t("settings.models.bundleImport.untrustedTitle"),
+          kind: "warning",
+        },
+      );
+      if (!allowUntrusted) return;
+    }
+
+    setImporting(true);
+    try {
+      const result = await commands.importModelBundle(path, allowUntrusted);
+      if (result.status === "error") {
+        toast.error(t("settings.models.bundleImport.failed"), {
+          description: result.error,
+        });
+        return;
+      }
+
+      const report = result.data;
+      if (report.failed.length > 0) {
+        toast.warning(
+          t("settings.models.bundleImport.partial", {
+            imported: report.imported.length,
+            failed: report.failed.length,
+          }),
+          {
+            description: report.failed
+              .map((failure) => `${failure.model_id}: ${failure.message}`)
+              .join("\n"),
+          },
+        );
+      } else {
+        toast.success(
+          t("settings.models.bundleImport.imported", {
+            imported: report.imported.length,
+            skipped: report.skipped.length,
+          }),
+        );
+      }
+      await loadModels();
+    } catch (error) {
+      logError(`Model bundle import failed: ${error}`, "fe-model-bundle");
+      toast.error(t("settings.models.bundleImport.failed"));
+    } finally {
+      setImporting(false);
+    }
+  };
+
+  return (
+    <SettingsRow
+      title={t("settings.models.bundleImport.title")}
+      description={t("settings.models.bundleImport.description")}
+      buttonLabel={t("settings.models.bundleImport.button")}
+      onButtonClick={importBundle}
+      disabled={importing}
+    />
+  );
+};
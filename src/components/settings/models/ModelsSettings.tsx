@@ -6,6 +6,7 @@ import type { ModelCardStatus } from "@/components/onboarding";
 import { ModelCard } from "@/components/onboarding";
 import { LlmModelCard, type LlmModelCardStatus } from "@/components/onboarding/LlmModelCard";
 import { useModelStore } from "@/stores/modelStore";
+import { ModelBundleImport } from "./ModelBundleImport";
 import { useMlxModels } from "@/hooks/useMlxModels";
 import { useSettings } from "@/hooks/useSettings";
 import { LANGUAGES } from "@/lib/constants/languages.ts";
@@ -806,6 +807,8 @@ export const ModelsSettings: React.FC = () => {
           </div>
         )}
       </div>
+
+      <ModelBundleImport />
     </div>
   );
 };
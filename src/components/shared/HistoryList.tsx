@@ -29,6 +29,7 @@ import { type HistoryEntry } from "@/bindings";
 import { logError, logInfo } from "@/utils/logging";
 import { GroupedVirtuoso, GroupedVirtuosoHandle } from "react-virtuoso";
 import { convertFileSrc } from "@tauri-apps/api/core";
+import { recordingMimeType } from "@/lib/utils/format";
 import { useDictionary } from "@/hooks/useDictionary";
 import {
   dictionaryEntryIdentity,
@@ -448,7 +449,9 @@ const TimelineItem: React.FC<TimelineItemProps> = React.memo(
               // Dynamic import to avoid issues on non-Tauri envs (though we are in Tauri)
               const { readFile } = await import("@tauri-apps/plugin-fs");
               const fileData = await readFile(entry.file_path);
-              const blob = new Blob([fileData], { type: "audio/wav" });
+              const blob = new Blob([fileData], {
+                type: recordingMimeType(entry.file_path),
+              });
               return URL.createObjectURL(blob);
             } catch (e) {
               logError(`Failed to load audio on Linux: ${e}`, "fe-history");
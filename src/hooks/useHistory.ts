@@ -7,6 +7,7 @@ import { commands, type HistoryEntry } from "@/bindings";
 import { logError, logInfo } from "@/utils/logging";
 import { useTranslation } from "react-i18next";
 import { formatDate } from "@/utils/dateFormat";
+import { recordingMimeType } from "@/lib/utils/format";
 
 const IS_LINUX = platform() === "linux";
 
@@ -202,7 +203,9 @@ export const useHistory = (): UseHistoryReturn => {
       if (result.status === "ok") {
         if (IS_LINUX) {
           const fileData = await readFile(result.data);
-          const blob = new Blob([fileData], { type: "audio/wav" });
+          const blob = new Blob([fileData], {
+            type: recordingMimeType(result.data),
+          });
           return URL.createObjectURL(blob);
         }
         return convertFileSrc(result.data, "asset");
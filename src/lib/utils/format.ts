@@ -57,3 +57,8 @@ export function formatEta(remainingBytes: number, speedBytesPerSec: number): str
   const mins = Math.ceil((seconds % 3600) / 60);
   return `${hours}h ${mins}m left`;
 }
+
+/** MIME type for a stored recording, based on its file extension. */
+export function recordingMimeType(path: string): string {
+  return path.toLowerCase().endsWith(".flac") ? "audio/flac" : "audio/wav";
+}
@@ -30,6 +30,12 @@ interface UndoOverlayEventPayload {
   shortcut?: string | null;
 }
 
+interface TranscriptionPartialPayload {
+  session_id: string;
+  stable_text: string;
+  interim_text: string;
+}
+
 interface OverlayClientRect {
   x: number;
   y: number;
@@ -66,6 +72,18 @@ const TOOLTIP_EDGE_PADDING_PX = 6;
 const TOOLTIP_VERTICAL_GAP_PX = 6;
 const TOOLTIP_HORIZONTAL_GAP_PX = 0;
 const TOOLTIP_ESTIMATED_HEIGHT_PX = 24;
+// Only the end of a live transcript fits the message lane.
+const PARTIAL_TAIL_CHARS = 40;
+
+const partialTranscriptTail = (payload: TranscriptionPartialPayload): string => {
+  const text = [payload.stable_text, payload.interim_text]
+    .filter((part) => part.length > 0)
+    .join(" ");
+  if (text.length <= PARTIAL_TAIL_CHARS) {
+    return text;
+  }
+  return `…${text.slice(-PARTIAL_TAIL_CHARS).trimStart()}`;
+};
 
 const toClientRect = (element: HTMLElement | null): OverlayClientRect | null => {
   if (!element) {
@@ -125,6 +143,7 @@ const RecordingOverlay: React.FC = () => {
   const [correctionData, setCorrectionData] = useState<CorrectionResult | null>(
     null,
   );
+  const [partialText, setPartialText] = useState("");
   const [undoCard, setUndoCard] = useState<UndoOverlayEventPayload | null>(
     null,
   );
@@ -151,6 +170,8 @@ const RecordingOverlay: React.FC = () => {
   // Recording time state
   const [elapsedSecs, setElapsedSecs] = useState(0);
   const [maxSecs, setMaxSecs] = useState(480); // Default 8 min
+  // Seconds the always-on microphone keeps as recent audio; 0 when off.
+  const [recentAudioSecs, setRecentAudioSecs] = useState(0);
 
   // Calculate progress (0 to 1, where 1 = full, 0 = empty)
   const progress = maxSecs > 0 ? Math.max(0, 1 - elapsedSecs / maxSecs) : 1;
@@ -665,6 +686,7 @@ const RecordingOverlay: React.FC = () => {
         // Reset time when showing overlay in recording state
         if (overlayState === "recording") {
           setElapsedSecs(0);
+          setPartialText("");
           agcRef.current.reset();
         }
 
@@ -708,6 +730,7 @@ const RecordingOverlay: React.FC = () => {
         setIsSettled(false);
         setElapsedSecs(0);
         setCorrectionData(null);
+        setPartialText("");
 
         // Wait for fade-out animation (300ms) to complete before resetting state
         // This prevents the overlay from switching back to "recording" (audio bars)
@@ -864,6 +887,37 @@ const RecordingOverlay: React.FC = () => {
       }
       cleanupFns.push(unlistenCursorIntent);
 
+      // Live transcript preview; only shown while still recording.
+      const unlistenPartial = await listen<TranscriptionPartialPayload>(
+        "transcription-partial",
+        (event) => {
+          if (ignore) return;
+          if (overlayStateRef.current !== "recording") return;
+          setPartialText(partialTranscriptTail(event.payload));
+        },
+      );
+
+      if (ignore) {
+        unlistenPartial();
+        return;
+      }
+      cleanupFns.push(unlistenPartial);
+
+      // Privacy badge while the recent-audio buffer keeps the microphone open.
+      const unlistenRecentAudio = await listen<number>(
+        "recent-audio-buffer",
+        (event) => {
+          if (ignore) return;
+          setRecentAudioSecs(event.payload);
+        },
+      );
+
+      if (ignore) {
+        unlistenRecentAudio();
+        return;
+      }
+      cleanupFns.push(unlistenRecentAudio);
+
       // Signal full readiness after replay-sensitive listeners are registered.
       // Backend can safely re-emit the current overlay state now.
       try {
@@ -1019,6 +1073,15 @@ const RecordingOverlay: React.FC = () => {
           hideActionTooltip();
         }}
       >
+      {recentAudioSecs > 0 && (
+        <div
+          className="recent-audio-badge"
+          role="img"
+          aria-label={`${t("tray.recentAudioBuffer")} (${recentAudioSecs} s)`}
+          title={`${t("tray.recentAudioBuffer")} (${recentAudioSecs} s)`}
+        />
+      )}
+
       {/* SVG countdown border - uniform animation along perimeter */}
       {state === "recording" && !undoCard && (
         <svg
@@ -1092,7 +1155,10 @@ const RecordingOverlay: React.FC = () => {
             renderMessageLane(undoCardMessage, true, "undo", discoverabilityActive)
           ) : (
             <>
-              {visualVariant === "bars" && (
+              {visualVariant === "bars" && partialText && (
+                renderMessageLane(partialText, true)
+              )}
+              {visualVariant === "bars" && !partialText && (
                 <div className="bars-container">
                   {levels.map((v, i) => (
                     <div
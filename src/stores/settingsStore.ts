@@ -140,6 +140,8 @@ const settingUpdaters: {
     commands.changeFillerWordFilterSetting(value as boolean),
   enable_hallucination_filter: (value) =>
     commands.changeHallucinationFilterSetting(value as boolean),
+  streaming_partials_enabled: (value) =>
+    commands.changeStreamingPartialsSetting(value as boolean),
   log_level: (value) => commands.setLogLevel(value as any),
   app_language: (value) => commands.changeAppLanguageSetting(value as string),
   show_tray_icon: (value) =>